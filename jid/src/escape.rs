@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! JID Escaping, as defined in
+//! [XEP-0106](https://xmpp.org/extensions/xep-0106.html).
+//!
+//! This maps characters which are forbidden in a localpart, such as the @ of
+//! an email address, to a backslash followed by their hexadecimal code, and
+//! back.
+
+use crate::JidParseError;
+
+/// Characters which get escaped, along with their escape sequence.
+const ESCAPES: [(char, &str); 10] = [
+    (' ', "\\20"),
+    ('"', "\\22"),
+    ('&', "\\26"),
+    ('\'', "\\27"),
+    ('/', "\\2f"),
+    (':', "\\3a"),
+    ('<', "\\3c"),
+    ('>', "\\3e"),
+    ('@', "\\40"),
+    ('\\', "\\5c"),
+];
+
+fn escape_sequence(c: char) -> Option<&'static str> {
+    ESCAPES
+        .iter()
+        .find(|(unescaped, _)| *unescaped == c)
+        .map(|(_, escaped)| *escaped)
+}
+
+fn unescaped_char(s: &str) -> Option<char> {
+    ESCAPES
+        .iter()
+        .find(|(_, escaped)| s.starts_with(escaped))
+        .map(|(unescaped, _)| *unescaped)
+}
+
+/// Escapes a localpart, so that it can be used in a JID.
+///
+/// A backslash is only escaped when it is followed by something which would otherwise be
+/// unescaped, so that escaping an already escaped localpart is detectable.
+///
+/// # Examples
+///
+/// ```
+/// use jid::escape_node;
+///
+/// assert_eq!(escape_node("space cadet").unwrap(), "space\\20cadet");
+/// assert_eq!(escape_node("c:\\net").unwrap(), "c\\3a\\net");
+/// assert_eq!(escape_node("c:\\5commas").unwrap(), "c\\3a\\5c5commas");
+/// ```
+pub fn escape_node(node: &str) -> Result<String, JidParseError> {
+    if node.is_empty() {
+        return Err(JidParseError::EmptyNode);
+    }
+    if node.starts_with(' ') || node.ends_with(' ') {
+        return Err(JidParseError::SpaceAtEdgeOfNode);
+    }
+    let mut escaped = String::with_capacity(node.len());
+    for (index, c) in node.char_indices() {
+        match escape_sequence(c) {
+            Some(_) if c == '\\' && unescaped_char(&node[index..]).is_none() => escaped.push(c),
+            Some(sequence) => escaped.push_str(sequence),
+            None => escaped.push(c),
+        }
+    }
+    Ok(escaped)
+}
+
+/// Unescapes a localpart, to display it to a human or to pass it to a legacy system.
+///
+/// Backslashes which aren’t followed by a known escape sequence are kept as is.
+///
+/// # Examples
+///
+/// ```
+/// use jid::unescape_node;
+///
+/// assert_eq!(unescape_node("space\\20cadet"), "space cadet");
+/// assert_eq!(unescape_node("c\\3a\\net"), "c:\\net");
+/// ```
+pub fn unescape_node(node: &str) -> String {
+    let mut unescaped = String::with_capacity(node.len());
+    let mut rest = node;
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+        rest = &rest[index..];
+        match unescaped_char(rest) {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[3..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Table 3 of XEP-0106.
+    const EXAMPLES: [(&str, &str); 12] = [
+        ("space cadet", "space\\20cadet"),
+        ("call me \"ishmael\"", "call\\20me\\20\\22ishmael\\22"),
+        ("at&t guy", "at\\26t\\20guy"),
+        ("d'artagnan", "d\\27artagnan"),
+        ("/.fanboy", "\\2f.fanboy"),
+        ("::foo::", "\\3a\\3afoo\\3a\\3a"),
+        ("<foo>", "\\3cfoo\\3e"),
+        ("user@host", "user\\40host"),
+        ("c:\\net", "c\\3a\\net"),
+        ("c:\\\\net", "c\\3a\\\\net"),
+        ("c:\\cool stuff", "c\\3a\\cool\\20stuff"),
+        ("c:\\5commas", "c\\3a\\5c5commas"),
+    ];
+
+    #[test]
+    fn escape() {
+        for (unescaped, escaped) in EXAMPLES.iter() {
+            assert_eq!(escape_node(unescaped).unwrap(), *escaped);
+        }
+    }
+
+    #[test]
+    fn unescape() {
+        for (unescaped, escaped) in EXAMPLES.iter() {
+            assert_eq!(unescape_node(escaped), *unescaped);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for (unescaped, _) in EXAMPLES.iter() {
+            assert_eq!(unescape_node(&escape_node(unescaped).unwrap()), *unescaped);
+        }
+    }
+
+    #[test]
+    fn unknown_sequences() {
+        assert_eq!(unescape_node("\\"), "\\");
+        assert_eq!(unescape_node("a\\"), "a\\");
+        assert_eq!(unescape_node("\\2"), "\\2");
+        assert_eq!(unescape_node("\\2F"), "\\2F");
+        assert_eq!(unescape_node("\\41"), "\\41");
+        assert_eq!(unescape_node("\\5c40"), "\\40");
+        assert_eq!(escape_node("\\41").unwrap(), "\\41");
+        assert_eq!(escape_node("é\\").unwrap(), "é\\");
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(escape_node(""), Err(JidParseError::EmptyNode));
+        assert_eq!(escape_node(" a"), Err(JidParseError::SpaceAtEdgeOfNode));
+        assert_eq!(escape_node("a "), Err(JidParseError::SpaceAtEdgeOfNode));
+    }
+}
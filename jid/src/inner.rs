@@ -0,0 +1,118 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Compact storage shared by `BareJid` and `FullJid`.
+//!
+//! The whole normalised JID is kept in a single `String`, along with the
+//! offsets of the @ and / separators, so that each part can be borrowed
+//! without any further allocation.
+
+use crate::prep;
+use crate::JidParseError;
+use std::convert::TryFrom;
+use std::num::NonZeroU16;
+
+#[derive(Clone)]
+pub(crate) struct InnerJid {
+    pub(crate) normalized: String,
+    at: Option<NonZeroU16>,
+    slash: Option<NonZeroU16>,
+}
+
+impl InnerJid {
+    pub(crate) fn new(s: &str) -> Result<InnerJid, JidParseError> {
+        // The resource is everything after the first /, then the node is everything before the
+        // first @ of what remains, see RFC 7622 §3.1.
+        let (bare, resource) = match s.find('/') {
+            Some(index) => (&s[..index], Some(&s[index + 1..])),
+            None => (s, None),
+        };
+        let (node, domain) = match bare.find('@') {
+            Some(index) => (Some(&bare[..index]), &bare[index + 1..]),
+            None => (None, bare),
+        };
+        InnerJid::from_parts(node, domain, resource)
+    }
+
+    pub(crate) fn from_parts(
+        node: Option<&str>,
+        domain: &str,
+        resource: Option<&str>,
+    ) -> Result<InnerJid, JidParseError> {
+        let node = node.map(prep::node).transpose()?;
+        let domain = prep::domain(domain)?;
+        let resource = resource.map(prep::resource).transpose()?;
+
+        let len = node.as_ref().map_or(0, |node| node.len() + 1)
+            + domain.len()
+            + resource.as_ref().map_or(0, |resource| resource.len() + 1);
+        let mut normalized = String::with_capacity(len);
+        let at = node.map(|node| {
+            normalized.push_str(&node);
+            let at = offset(&normalized);
+            normalized.push('@');
+            at
+        });
+        normalized.push_str(&domain);
+        let slash = resource.map(|resource| {
+            let slash = offset(&normalized);
+            normalized.push('/');
+            normalized.push_str(&resource);
+            slash
+        });
+
+        Ok(InnerJid {
+            normalized,
+            at,
+            slash,
+        })
+    }
+
+    pub(crate) fn node(&self) -> Option<&str> {
+        self.at.map(|at| &self.normalized[..at.get() as usize])
+    }
+
+    pub(crate) fn domain(&self) -> &str {
+        let start = self.at.map_or(0, |at| at.get() as usize + 1);
+        match self.slash {
+            Some(slash) => &self.normalized[start..slash.get() as usize],
+            None => &self.normalized[start..],
+        }
+    }
+
+    pub(crate) fn resource(&self) -> Option<&str> {
+        self.slash
+            .map(|slash| &self.normalized[slash.get() as usize + 1..])
+    }
+
+    pub(crate) fn bare(&self) -> &str {
+        match self.slash {
+            Some(slash) => &self.normalized[..slash.get() as usize],
+            None => &self.normalized,
+        }
+    }
+
+    /// Keep only the domain, which is already normalised.
+    pub(crate) fn to_domain(&self) -> InnerJid {
+        InnerJid {
+            normalized: String::from(self.domain()),
+            at: None,
+            slash: None,
+        }
+    }
+
+    /// Drop the resource, without reallocating.
+    pub(crate) fn into_bare(mut self) -> InnerJid {
+        if let Some(slash) = self.slash.take() {
+            self.normalized.truncate(slash.get() as usize);
+        }
+        self
+    }
+}
+
+/// Offset of the next separator, which can’t be zero because the part before it can’t be empty,
+/// and which fits in 16 bits because each part is at most 1023 bytes long.
+fn offset(normalized: &str) -> NonZeroU16 {
+    NonZeroU16::new(u16::try_from(normalized.len()).unwrap()).unwrap()
+}
@@ -13,15 +13,30 @@
 //! Provides a type for Jabber IDs.
 //!
 //! For usage, check the documentation on the `Jid` struct.
+//!
+//! Every JID parsed from a string is checked against the rules of
+//! [RFC 7622](https://tools.ietf.org/html/rfc7622).  When the `precis` feature
+//! is enabled, each part is additionally normalised using the PRECIS profiles
+//! and IDNA, so that two strings denoting the same JID parse to equal values.
 
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::convert::{Into, TryFrom};
 use std::error::Error as StdError;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
 #[cfg(feature = "serde")]
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+mod escape;
+mod inner;
+mod prep;
+
+pub use escape::{escape_node, unescape_node};
+use inner::InnerJid;
+
 /// An error that signifies that a `Jid` cannot be parsed from a string.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum JidParseError {
@@ -37,6 +52,46 @@ pub enum JidParseError {
 
     /// Happens when the resource is empty, that is the string ends with a /.
     EmptyResource,
+
+    /// Happens when the node is longer than 1023 bytes once prepared.
+    NodeTooLong,
+
+    /// Happens when the domain is longer than 1023 bytes once prepared.
+    DomainTooLong,
+
+    /// Happens when the resource is longer than 1023 bytes once prepared.
+    ResourceTooLong,
+
+    /// Happens when the node contains one of the characters forbidden by RFC 7622, that is
+    /// `"`, `&`, `'`, `/`, `:`, `<`, `>` or `@`.
+    ForbiddenCharInNode,
+
+    /// Happens when the domain contains a @ or a /.
+    ForbiddenCharInDomain,
+
+    /// Happens when a localpart to escape starts or ends with a space, which XEP-0106 forbids.
+    SpaceAtEdgeOfNode,
+
+    /// Happens when a node is present in what should be a domain JID.
+    UnexpectedNode,
+
+    /// Happens when a resource is present in what should be a bare or domain JID.
+    UnexpectedResource,
+
+    /// Happens when the node doesn’t conform to the UsernameCaseMapped PRECIS profile.
+    ///
+    /// Only returned when the `precis` feature is enabled.
+    NodePrep,
+
+    /// Happens when the domain can’t be processed by IDNA.
+    ///
+    /// Only returned when the `precis` feature is enabled.
+    DomainPrep,
+
+    /// Happens when the resource doesn’t conform to the OpaqueString PRECIS profile.
+    ///
+    /// Only returned when the `precis` feature is enabled.
+    ResourcePrep,
 }
 
 impl StdError for JidParseError {}
@@ -51,14 +106,28 @@ impl fmt::Display for JidParseError {
                 JidParseError::NoResource => "no resource found in this full JID",
                 JidParseError::EmptyNode => "nodepart empty despite the presence of a @",
                 JidParseError::EmptyResource => "resource empty despite the presence of a /",
+                JidParseError::NodeTooLong => "localpart longer than 1023 bytes",
+                JidParseError::DomainTooLong => "domain longer than 1023 bytes",
+                JidParseError::ResourceTooLong => "resource longer than 1023 bytes",
+                JidParseError::ForbiddenCharInNode => "localpart contains a forbidden character",
+                JidParseError::ForbiddenCharInDomain => "domain contains a forbidden character",
+                JidParseError::SpaceAtEdgeOfNode => "localpart starts or ends with a space",
+                JidParseError::UnexpectedNode => "localpart found in a domain JID",
+                JidParseError::UnexpectedResource => "resource found in a bare JID",
+                JidParseError::NodePrep => "localpart doesn’t pass the UsernameCaseMapped profile",
+                JidParseError::DomainPrep => "domain doesn’t pass IDNA processing",
+                JidParseError::ResourcePrep => "resource doesn’t pass the OpaqueString profile",
             }
         )
     }
 }
 
 /// An enum representing a Jabber ID. It can be either a `FullJid` or a `BareJid`.
+///
+/// Like theirs, its equality, ordering and hashing are those of its normalised string, so a
+/// `HashMap<Jid, _>` can be queried with a `&str`.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub enum Jid {
     /// Bare Jid
     Bare(BareJid),
@@ -71,17 +140,10 @@ impl FromStr for Jid {
     type Err = JidParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (ns, ds, rs): StringJid = _from_str(s)?;
-        Ok(match rs {
-            Some(rs) => Jid::Full(FullJid {
-                node: ns,
-                domain: ds,
-                resource: rs,
-            }),
-            None => Jid::Bare(BareJid {
-                node: ns,
-                domain: ds,
-            }),
+        let inner = InnerJid::new(s)?;
+        Ok(match inner.resource() {
+            Some(_) => Jid::Full(FullJid { inner }),
+            None => Jid::Bare(BareJid { inner }),
         })
     }
 }
@@ -109,24 +171,117 @@ impl From<FullJid> for Jid {
 
 impl fmt::Display for Jid {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        fmt.write_str(String::from(self.clone()).as_ref())
+        match self {
+            Jid::Bare(bare) => fmt::Display::fmt(bare, fmt),
+            Jid::Full(full) => fmt::Display::fmt(full, fmt),
+        }
+    }
+}
+
+impl PartialEq for Jid {
+    fn eq(&self, other: &Jid) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Jid {}
+
+impl PartialOrd for Jid {
+    fn partial_cmp(&self, other: &Jid) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Jid {
+    fn cmp(&self, other: &Jid) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for Jid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl Borrow<str> for Jid {
+    fn borrow(&self) -> &str {
+        self.as_str()
     }
 }
 
 impl Jid {
     /// The node part of the Jabber ID, if it exists, else None.
-    pub fn node(self) -> Option<String> {
+    pub fn node(&self) -> Option<&str> {
         match self {
-            Jid::Bare(BareJid { node, .. }) | Jid::Full(FullJid { node, .. }) => node,
+            Jid::Bare(bare) => bare.node(),
+            Jid::Full(full) => full.node(),
         }
     }
 
     /// The domain of the Jabber ID.
-    pub fn domain(self) -> String {
+    pub fn domain(&self) -> &str {
+        match self {
+            Jid::Bare(bare) => bare.domain(),
+            Jid::Full(full) => full.domain(),
+        }
+    }
+
+    /// The resource of the Jabber ID, if it exists, else None.
+    pub fn resource(&self) -> Option<&str> {
+        match self {
+            Jid::Bare(_) => None,
+            Jid::Full(full) => Some(full.resource()),
+        }
+    }
+
+    /// The whole normalised Jabber ID, as a string.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Jid::Bare(bare) => bare.as_str(),
+            Jid::Full(full) => full.as_str(),
+        }
+    }
+
+    /// The bare part of this Jabber ID, as a string, without allocating.
+    pub fn as_bare_str(&self) -> &str {
+        match self {
+            Jid::Bare(bare) => bare.as_str(),
+            Jid::Full(full) => full.as_bare_str(),
+        }
+    }
+
+    /// Constructs the bare Jabber ID of this one.
+    pub fn to_bare(&self) -> BareJid {
+        match self {
+            Jid::Bare(bare) => bare.clone(),
+            Jid::Full(full) => full.to_bare(),
+        }
+    }
+
+    /// Constructs the domain Jabber ID of this one, that is the address of its server.
+    pub fn to_domain(&self) -> DomainJid {
         match self {
-            Jid::Bare(BareJid { domain, .. }) | Jid::Full(FullJid { domain, .. }) => domain,
+            Jid::Bare(bare) => bare.to_domain(),
+            Jid::Full(full) => full.to_domain(),
         }
     }
+
+    /// Whether this Jabber ID contains a resource.
+    pub fn is_full(&self) -> bool {
+        matches!(self, Jid::Full(_))
+    }
+
+    /// Whether this Jabber ID doesn’t contain a resource.
+    pub fn is_bare(&self) -> bool {
+        matches!(self, Jid::Bare(_))
+    }
+
+    /// Whether this Jabber ID is only a domain, such as the address of a server or of a
+    /// component.
+    pub fn is_domain(&self) -> bool {
+        matches!(self, Jid::Bare(bare) if bare.node().is_none())
+    }
 }
 
 impl From<Jid> for BareJid {
@@ -138,6 +293,42 @@ impl From<Jid> for BareJid {
     }
 }
 
+impl From<DomainJid> for Jid {
+    fn from(domain_jid: DomainJid) -> Jid {
+        Jid::Bare(domain_jid.into())
+    }
+}
+
+impl From<DomainJid> for BareJid {
+    fn from(domain_jid: DomainJid) -> BareJid {
+        BareJid {
+            inner: domain_jid.inner,
+        }
+    }
+}
+
+impl TryFrom<Jid> for DomainJid {
+    type Error = JidParseError;
+
+    fn try_from(jid: Jid) -> Result<Self, Self::Error> {
+        match jid {
+            Jid::Full(_) => Err(JidParseError::UnexpectedResource),
+            Jid::Bare(bare) => DomainJid::try_from(bare),
+        }
+    }
+}
+
+impl TryFrom<BareJid> for DomainJid {
+    type Error = JidParseError;
+
+    fn try_from(bare: BareJid) -> Result<Self, Self::Error> {
+        match bare.node() {
+            Some(_) => Err(JidParseError::UnexpectedNode),
+            None => Ok(DomainJid { inner: bare.inner }),
+        }
+    }
+}
+
 impl TryFrom<Jid> for FullJid {
     type Error = JidParseError;
 
@@ -167,6 +358,30 @@ impl PartialEq<Jid> for BareJid {
     }
 }
 
+impl PartialEq<Jid> for DomainJid {
+    fn eq(&self, other: &Jid) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<DomainJid> for Jid {
+    fn eq(&self, other: &DomainJid) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<BareJid> for DomainJid {
+    fn eq(&self, other: &BareJid) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<DomainJid> for BareJid {
+    fn eq(&self, other: &DomainJid) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
 impl PartialEq<FullJid> for Jid {
     fn eq(&self, other: &FullJid) -> bool {
         match self {
@@ -195,14 +410,13 @@ impl PartialEq<BareJid> for Jid {
 ///
 /// Unlike a `BareJid`, it always contains a resource, and should only be used when you are certain
 /// there is no case where a resource can be missing.  Otherwise, use a `Jid` enum.
-#[derive(Clone, PartialEq, Eq, Hash)]
+///
+/// It is stored as a single normalised string, from which each part can be borrowed.  Equality,
+/// ordering and hashing are those of this string, so a `HashMap<FullJid, _>` can be queried with
+/// a `&str`.
+#[derive(Clone)]
 pub struct FullJid {
-    /// The node part of the Jabber ID, if it exists, else None.
-    pub node: Option<String>,
-    /// The domain of the Jabber ID.
-    pub domain: String,
-    /// The resource of the Jabber ID.
-    pub resource: String,
+    inner: InnerJid,
 }
 
 /// A struct representing a bare Jabber ID.
@@ -214,58 +428,86 @@ pub struct FullJid {
 ///
 /// Unlike a `FullJid`, it can’t contain a resource, and should only be used when you are certain
 /// there is no case where a resource can be set.  Otherwise, use a `Jid` enum.
-#[derive(Clone, PartialEq, Eq, Hash)]
+///
+/// It is stored as a single normalised string, from which each part can be borrowed.  Equality,
+/// ordering and hashing are those of this string, so a `HashMap<BareJid, _>` can be queried with
+/// the `FullJid::as_bare_str()` of a full JID without any allocation.
+#[derive(Clone)]
 pub struct BareJid {
-    /// The node part of the Jabber ID, if it exists, else None.
-    pub node: Option<String>,
-    /// The domain of the Jabber ID.
-    pub domain: String,
+    inner: InnerJid,
 }
 
-impl From<FullJid> for String {
-    fn from(jid: FullJid) -> String {
-        String::from(&jid)
-    }
+/// A struct representing a domain Jabber ID, such as the address of a server or of a component.
+///
+/// It only contains a domain, and converts losslessly into a `BareJid` or a `Jid`, while the
+/// reverse conversions fail if a node or a resource is present.  Like a `BareJid`, it never
+/// converts into a `FullJid`, so a server can’t be mistaken for a session.
+#[derive(Clone)]
+pub struct DomainJid {
+    inner: InnerJid,
 }
 
-impl From<&FullJid> for String {
-    fn from(jid: &FullJid) -> String {
-        let mut string = String::new();
-        if let Some(ref node) = jid.node {
-            string.push_str(node);
-            string.push('@');
+macro_rules! impl_normalized_traits {
+    ($type:ty) => {
+        impl PartialEq for $type {
+            fn eq(&self, other: &$type) -> bool {
+                self.as_str() == other.as_str()
+            }
         }
-        string.push_str(&jid.domain);
-        string.push('/');
-        string.push_str(&jid.resource);
-        string
-    }
-}
 
-impl From<BareJid> for String {
-    fn from(jid: BareJid) -> String {
-        String::from(&jid)
-    }
-}
+        impl Eq for $type {}
 
-impl From<&BareJid> for String {
-    fn from(jid: &BareJid) -> String {
-        let mut string = String::new();
-        if let Some(ref node) = jid.node {
-            string.push_str(node);
-            string.push('@');
+        impl PartialOrd for $type {
+            fn partial_cmp(&self, other: &$type) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
         }
-        string.push_str(&jid.domain);
-        string
-    }
+
+        impl Ord for $type {
+            fn cmp(&self, other: &$type) -> Ordering {
+                self.as_str().cmp(other.as_str())
+            }
+        }
+
+        impl Hash for $type {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state)
+            }
+        }
+
+        impl Borrow<str> for $type {
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl AsRef<str> for $type {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl From<$type> for String {
+            fn from(jid: $type) -> String {
+                jid.inner.normalized
+            }
+        }
+
+        impl From<&$type> for String {
+            fn from(jid: &$type) -> String {
+                String::from(jid.as_str())
+            }
+        }
+    };
 }
 
+impl_normalized_traits!(FullJid);
+impl_normalized_traits!(BareJid);
+impl_normalized_traits!(DomainJid);
+
 impl From<FullJid> for BareJid {
     fn from(full: FullJid) -> BareJid {
-        BareJid {
-            node: full.node,
-            domain: full.domain,
-        }
+        full.into_bare()
     }
 }
 
@@ -281,15 +523,27 @@ impl fmt::Debug for BareJid {
     }
 }
 
+impl fmt::Debug for DomainJid {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "DomainJID({})", self)
+    }
+}
+
+impl fmt::Display for DomainJid {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt.write_str(self.as_str())
+    }
+}
+
 impl fmt::Display for FullJid {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        fmt.write_str(String::from(self.clone()).as_ref())
+        fmt.write_str(self.as_str())
     }
 }
 
 impl fmt::Display for BareJid {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        fmt.write_str(String::from(self.clone()).as_ref())
+        fmt.write_str(self.as_str())
     }
 }
 
@@ -299,7 +553,7 @@ impl Serialize for FullJid {
     where
         S: Serializer,
     {
-        serializer.serialize_str(String::from(self).as_str())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -309,98 +563,38 @@ impl Serialize for BareJid {
     where
         S: Serializer,
     {
-        serializer.serialize_str(String::from(self).as_str())
-    }
-}
-
-enum ParserState {
-    Node,
-    Domain,
-    Resource,
-}
-
-type StringJid = (Option<String>, String, Option<String>);
-fn _from_str(s: &str) -> Result<StringJid, JidParseError> {
-    // TODO: very naive, may need to do it differently
-    let iter = s.chars();
-    let mut buf = String::with_capacity(s.len());
-    let mut state = ParserState::Node;
-    let mut node = None;
-    let mut domain = None;
-    let mut resource = None;
-    for c in iter {
-        match state {
-            ParserState::Node => {
-                match c {
-                    '@' => {
-                        if buf.is_empty() {
-                            return Err(JidParseError::EmptyNode);
-                        }
-                        state = ParserState::Domain;
-                        node = Some(buf.clone()); // TODO: performance tweaks, do not need to copy it
-                        buf.clear();
-                    }
-                    '/' => {
-                        if buf.is_empty() {
-                            return Err(JidParseError::NoDomain);
-                        }
-                        state = ParserState::Resource;
-                        domain = Some(buf.clone()); // TODO: performance tweaks
-                        buf.clear();
-                    }
-                    c => {
-                        buf.push(c);
-                    }
-                }
-            }
-            ParserState::Domain => {
-                match c {
-                    '/' => {
-                        if buf.is_empty() {
-                            return Err(JidParseError::NoDomain);
-                        }
-                        state = ParserState::Resource;
-                        domain = Some(buf.clone()); // TODO: performance tweaks
-                        buf.clear();
-                    }
-                    c => {
-                        buf.push(c);
-                    }
-                }
-            }
-            ParserState::Resource => {
-                buf.push(c);
-            }
-        }
-    }
-    if !buf.is_empty() {
-        match state {
-            ParserState::Node => {
-                domain = Some(buf);
-            }
-            ParserState::Domain => {
-                domain = Some(buf);
-            }
-            ParserState::Resource => {
-                resource = Some(buf);
-            }
-        }
-    } else if let ParserState::Resource = state {
-        return Err(JidParseError::EmptyResource);
+        serializer.serialize_str(self.as_str())
     }
-    Ok((node, domain.ok_or(JidParseError::NoDomain)?, resource))
 }
 
 impl FromStr for FullJid {
     type Err = JidParseError;
 
     fn from_str(s: &str) -> Result<FullJid, JidParseError> {
-        let (ns, ds, rs): StringJid = _from_str(s)?;
-        Ok(FullJid {
-            node: ns,
-            domain: ds,
-            resource: rs.ok_or(JidParseError::NoResource)?,
-        })
+        let inner = InnerJid::new(s)?;
+        inner.resource().ok_or(JidParseError::NoResource)?;
+        Ok(FullJid { inner })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for DomainJid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DomainJid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        DomainJid::from_str(&s).map_err(de::Error::custom)
     }
 }
 
@@ -431,6 +625,10 @@ impl FullJid {
     ///
     /// This is of the form `node`@`domain`/`resource`.
     ///
+    /// # Panics
+    ///
+    /// Panics if any of the parts is invalid, use `FullJid::from_parts()` to handle this case.
+    ///
     /// # Examples
     ///
     /// ```
@@ -438,25 +636,150 @@ impl FullJid {
     ///
     /// let jid = FullJid::new("node", "domain", "resource");
     ///
-    /// assert_eq!(jid.node, Some("node".to_owned()));
-    /// assert_eq!(jid.domain, "domain".to_owned());
-    /// assert_eq!(jid.resource, "resource".to_owned());
+    /// assert_eq!(jid.node(), Some("node"));
+    /// assert_eq!(jid.domain(), "domain");
+    /// assert_eq!(jid.resource(), "resource");
     /// ```
     pub fn new<NS, DS, RS>(node: NS, domain: DS, resource: RS) -> FullJid
     where
-        NS: Into<String>,
-        DS: Into<String>,
-        RS: Into<String>,
+        NS: AsRef<str>,
+        DS: AsRef<str>,
+        RS: AsRef<str>,
     {
-        FullJid {
-            node: Some(node.into()),
-            domain: domain.into(),
-            resource: resource.into(),
+        FullJid::from_parts(Some(node.as_ref()), domain.as_ref(), resource.as_ref()).unwrap()
+    }
+
+    /// Constructs a full Jabber ID from its parts, checking and normalising each of them the same
+    /// way as when parsing a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::{FullJid, JidParseError};
+    ///
+    /// let jid = FullJid::from_parts(Some("node"), "domain.", "resource").unwrap();
+    ///
+    /// assert_eq!(jid, FullJid::new("node", "domain", "resource"));
+    ///
+    /// let error = FullJid::from_parts(Some("no<de"), "domain", "resource").unwrap_err();
+    ///
+    /// assert_eq!(error, JidParseError::ForbiddenCharInNode);
+    /// ```
+    pub fn from_parts(
+        node: Option<&str>,
+        domain: &str,
+        resource: &str,
+    ) -> Result<FullJid, JidParseError> {
+        Ok(FullJid {
+            inner: InnerJid::from_parts(node, domain, Some(resource))?,
+        })
+    }
+
+    /// Constructs a full Jabber ID from a localpart which hasn’t been escaped yet, such as an
+    /// email address, escaping it as per XEP-0106.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::FullJid;
+    ///
+    /// let jid = FullJid::from_unescaped_node("juliet@example.com", "gateway", "balcony").unwrap();
+    ///
+    /// assert_eq!(jid.as_str(), "juliet\\40example.com@gateway/balcony");
+    /// assert_eq!(jid.unescaped_node().unwrap(), "juliet@example.com");
+    /// ```
+    pub fn from_unescaped_node(
+        node: &str,
+        domain: &str,
+        resource: &str,
+    ) -> Result<FullJid, JidParseError> {
+        FullJid::from_parts(Some(&escape_node(node)?), domain, resource)
+    }
+
+    /// The node part of the Jabber ID, if it exists, else None.
+    pub fn node(&self) -> Option<&str> {
+        self.inner.node()
+    }
+
+    /// The node part of the Jabber ID unescaped as per XEP-0106, if it exists, else None.
+    pub fn unescaped_node(&self) -> Option<String> {
+        self.node().map(unescape_node)
+    }
+
+    /// The domain of the Jabber ID.
+    pub fn domain(&self) -> &str {
+        self.inner.domain()
+    }
+
+    /// The resource of the Jabber ID.
+    pub fn resource(&self) -> &str {
+        self.inner.resource().unwrap()
+    }
+
+    /// The whole normalised Jabber ID, as a string.
+    pub fn as_str(&self) -> &str {
+        &self.inner.normalized
+    }
+
+    /// The bare part of this Jabber ID, as a string, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::{BareJid, FullJid};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut roster = HashMap::new();
+    /// roster.insert(BareJid::new("node", "domain"), "Node");
+    ///
+    /// let jid = FullJid::new("node", "domain", "resource");
+    ///
+    /// assert_eq!(jid.as_bare_str(), "node@domain");
+    /// assert_eq!(roster.get(jid.as_bare_str()), Some(&"Node"));
+    /// ```
+    pub fn as_bare_str(&self) -> &str {
+        self.inner.bare()
+    }
+
+    /// Constructs the bare Jabber ID of this one, with a single allocation.
+    ///
+    /// Use `BareJid::from()` instead to reuse the allocation of a `FullJid` you don’t need
+    /// anymore.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::{BareJid, FullJid};
+    ///
+    /// let jid = FullJid::new("node", "domain", "resource");
+    ///
+    /// assert_eq!(jid.to_bare(), BareJid::new("node", "domain"));
+    /// ```
+    pub fn to_bare(&self) -> BareJid {
+        BareJid {
+            inner: self.inner.clone().into_bare(),
+        }
+    }
+
+    fn into_bare(self) -> BareJid {
+        BareJid {
+            inner: self.inner.into_bare(),
+        }
+    }
+
+    /// Constructs the domain Jabber ID of this one, that is the address of its server.
+    pub fn to_domain(&self) -> DomainJid {
+        DomainJid {
+            inner: self.inner.to_domain(),
         }
     }
 
     /// Constructs a new Jabber ID from an existing one, with the node swapped out with a new one.
     ///
+    /// # Panics
+    ///
+    /// Panics if the node is invalid.
+    ///
     /// # Examples
     ///
     /// ```
@@ -464,25 +787,25 @@ impl FullJid {
     ///
     /// let jid = FullJid::new("node", "domain", "resource");
     ///
-    /// assert_eq!(jid.node, Some("node".to_owned()));
+    /// assert_eq!(jid.node(), Some("node"));
     ///
     /// let new_jid = jid.with_node("new_node");
     ///
-    /// assert_eq!(new_jid.node, Some("new_node".to_owned()));
+    /// assert_eq!(new_jid.node(), Some("new_node"));
     /// ```
     pub fn with_node<NS>(&self, node: NS) -> FullJid
     where
-        NS: Into<String>,
+        NS: AsRef<str>,
     {
-        FullJid {
-            node: Some(node.into()),
-            domain: self.domain.clone(),
-            resource: self.resource.clone(),
-        }
+        FullJid::new(node, self.domain(), self.resource())
     }
 
     /// Constructs a new Jabber ID from an existing one, with the domain swapped out with a new one.
     ///
+    /// # Panics
+    ///
+    /// Panics if the domain is invalid.
+    ///
     /// # Examples
     ///
     /// ```
@@ -490,25 +813,25 @@ impl FullJid {
     ///
     /// let jid = FullJid::new("node", "domain", "resource");
     ///
-    /// assert_eq!(jid.domain, "domain".to_owned());
+    /// assert_eq!(jid.domain(), "domain");
     ///
-    /// let new_jid = jid.with_domain("new_domain");
+    /// let new_jid = jid.with_domain("new-domain");
     ///
-    /// assert_eq!(new_jid.domain, "new_domain");
+    /// assert_eq!(new_jid.domain(), "new-domain");
     /// ```
     pub fn with_domain<DS>(&self, domain: DS) -> FullJid
     where
-        DS: Into<String>,
+        DS: AsRef<str>,
     {
-        FullJid {
-            node: self.node.clone(),
-            domain: domain.into(),
-            resource: self.resource.clone(),
-        }
+        FullJid::from_parts(self.node(), domain.as_ref(), self.resource()).unwrap()
     }
 
     /// Constructs a full Jabber ID from a bare Jabber ID, specifying a `resource`.
     ///
+    /// # Panics
+    ///
+    /// Panics if the resource is invalid.
+    ///
     /// # Examples
     ///
     /// ```
@@ -516,21 +839,17 @@ impl FullJid {
     ///
     /// let jid = FullJid::new("node", "domain", "resource");
     ///
-    /// assert_eq!(jid.resource, "resource".to_owned());
+    /// assert_eq!(jid.resource(), "resource");
     ///
     /// let new_jid = jid.with_resource("new_resource");
     ///
-    /// assert_eq!(new_jid.resource, "new_resource");
+    /// assert_eq!(new_jid.resource(), "new_resource");
     /// ```
     pub fn with_resource<RS>(&self, resource: RS) -> FullJid
     where
-        RS: Into<String>,
+        RS: AsRef<str>,
     {
-        FullJid {
-            node: self.node.clone(),
-            domain: self.domain.clone(),
-            resource: resource.into(),
-        }
+        FullJid::from_parts(self.node(), self.domain(), resource.as_ref()).unwrap()
     }
 }
 
@@ -538,10 +857,8 @@ impl FromStr for BareJid {
     type Err = JidParseError;
 
     fn from_str(s: &str) -> Result<BareJid, JidParseError> {
-        let (ns, ds, _rs): StringJid = _from_str(s)?;
         Ok(BareJid {
-            node: ns,
-            domain: ds,
+            inner: InnerJid::new(s)?.into_bare(),
         })
     }
 }
@@ -551,6 +868,10 @@ impl BareJid {
     ///
     /// This is of the form `node`@`domain`.
     ///
+    /// # Panics
+    ///
+    /// Panics if any of the parts is invalid, use `BareJid::from_parts()` to handle this case.
+    ///
     /// # Examples
     ///
     /// ```
@@ -558,96 +879,176 @@ impl BareJid {
     ///
     /// let jid = BareJid::new("node", "domain");
     ///
-    /// assert_eq!(jid.node, Some("node".to_owned()));
-    /// assert_eq!(jid.domain, "domain".to_owned());
+    /// assert_eq!(jid.node(), Some("node"));
+    /// assert_eq!(jid.domain(), "domain");
     /// ```
     pub fn new<NS, DS>(node: NS, domain: DS) -> BareJid
     where
-        NS: Into<String>,
-        DS: Into<String>,
+        NS: AsRef<str>,
+        DS: AsRef<str>,
     {
-        BareJid {
-            node: Some(node.into()),
-            domain: domain.into(),
-        }
+        BareJid::from_parts(Some(node.as_ref()), domain.as_ref()).unwrap()
+    }
+
+    /// Constructs a bare Jabber ID from its parts, checking and normalising each of them the same
+    /// way as when parsing a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::{BareJid, JidParseError};
+    ///
+    /// let jid = BareJid::from_parts(None, "domain").unwrap();
+    ///
+    /// assert_eq!(jid, BareJid::from_domain("domain"));
+    ///
+    /// let error = BareJid::from_parts(Some(""), "domain").unwrap_err();
+    ///
+    /// assert_eq!(error, JidParseError::EmptyNode);
+    /// ```
+    pub fn from_parts(node: Option<&str>, domain: &str) -> Result<BareJid, JidParseError> {
+        Ok(BareJid {
+            inner: InnerJid::from_parts(node, domain, None)?,
+        })
     }
 
     /// Constructs a bare Jabber ID containing only a `domain`.
     ///
     /// This is of the form `domain`.
     ///
+    /// # Panics
+    ///
+    /// Panics if the domain is invalid.
+    ///
     /// # Examples
     ///
     /// ```
     /// use jid::BareJid;
     ///
-    /// let jid = BareJid::domain("domain");
+    /// let jid = BareJid::from_domain("domain");
     ///
-    /// assert_eq!(jid.node, None);
-    /// assert_eq!(jid.domain, "domain".to_owned());
+    /// assert_eq!(jid.node(), None);
+    /// assert_eq!(jid.domain(), "domain");
     /// ```
-    pub fn domain<DS>(domain: DS) -> BareJid
+    pub fn from_domain<DS>(domain: DS) -> BareJid
     where
-        DS: Into<String>,
+        DS: AsRef<str>,
     {
-        BareJid {
-            node: None,
-            domain: domain.into(),
+        BareJid::from_parts(None, domain.as_ref()).unwrap()
+    }
+
+    /// Constructs a bare Jabber ID from a localpart which hasn’t been escaped yet, such as an
+    /// email address, escaping it as per XEP-0106.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::BareJid;
+    ///
+    /// let jid = BareJid::from_unescaped_node("at&t guy", "gateway").unwrap();
+    ///
+    /// assert_eq!(jid.as_str(), "at\\26t\\20guy@gateway");
+    /// assert_eq!(jid.unescaped_node().unwrap(), "at&t guy");
+    /// ```
+    pub fn from_unescaped_node(node: &str, domain: &str) -> Result<BareJid, JidParseError> {
+        BareJid::from_parts(Some(&escape_node(node)?), domain)
+    }
+
+    /// The node part of the Jabber ID, if it exists, else None.
+    pub fn node(&self) -> Option<&str> {
+        self.inner.node()
+    }
+
+    /// The node part of the Jabber ID unescaped as per XEP-0106, if it exists, else None.
+    pub fn unescaped_node(&self) -> Option<String> {
+        self.node().map(unescape_node)
+    }
+
+    /// The domain of the Jabber ID.
+    pub fn domain(&self) -> &str {
+        self.inner.domain()
+    }
+
+    /// The whole normalised Jabber ID, as a string.
+    pub fn as_str(&self) -> &str {
+        &self.inner.normalized
+    }
+
+    /// Constructs the domain Jabber ID of this one, that is the address of its server.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::{BareJid, DomainJid};
+    ///
+    /// let jid = BareJid::new("node", "domain");
+    ///
+    /// assert_eq!(jid.to_domain(), DomainJid::new("domain"));
+    /// ```
+    pub fn to_domain(&self) -> DomainJid {
+        DomainJid {
+            inner: self.inner.to_domain(),
         }
     }
 
     /// Constructs a new Jabber ID from an existing one, with the node swapped out with a new one.
     ///
+    /// # Panics
+    ///
+    /// Panics if the node is invalid.
+    ///
     /// # Examples
     ///
     /// ```
     /// use jid::BareJid;
     ///
-    /// let jid = BareJid::domain("domain");
+    /// let jid = BareJid::from_domain("domain");
     ///
-    /// assert_eq!(jid.node, None);
+    /// assert_eq!(jid.node(), None);
     ///
     /// let new_jid = jid.with_node("node");
     ///
-    /// assert_eq!(new_jid.node, Some("node".to_owned()));
+    /// assert_eq!(new_jid.node(), Some("node"));
     /// ```
     pub fn with_node<NS>(&self, node: NS) -> BareJid
     where
-        NS: Into<String>,
+        NS: AsRef<str>,
     {
-        BareJid {
-            node: Some(node.into()),
-            domain: self.domain.clone(),
-        }
+        BareJid::new(node, self.domain())
     }
 
     /// Constructs a new Jabber ID from an existing one, with the domain swapped out with a new one.
     ///
+    /// # Panics
+    ///
+    /// Panics if the domain is invalid.
+    ///
     /// # Examples
     ///
     /// ```
     /// use jid::BareJid;
     ///
-    /// let jid = BareJid::domain("domain");
+    /// let jid = BareJid::from_domain("domain");
     ///
-    /// assert_eq!(jid.domain, "domain");
+    /// assert_eq!(jid.domain(), "domain");
     ///
-    /// let new_jid = jid.with_domain("new_domain");
+    /// let new_jid = jid.with_domain("new-domain");
     ///
-    /// assert_eq!(new_jid.domain, "new_domain");
+    /// assert_eq!(new_jid.domain(), "new-domain");
     /// ```
     pub fn with_domain<DS>(&self, domain: DS) -> BareJid
     where
-        DS: Into<String>,
+        DS: AsRef<str>,
     {
-        BareJid {
-            node: self.node.clone(),
-            domain: domain.into(),
-        }
+        BareJid::from_parts(self.node(), domain.as_ref()).unwrap()
     }
 
     /// Constructs a full Jabber ID from a bare Jabber ID, specifying a `resource`.
     ///
+    /// # Panics
+    ///
+    /// Panics if the resource is invalid.
+    ///
     /// # Examples
     ///
     /// ```
@@ -656,24 +1057,139 @@ impl BareJid {
     /// let bare = BareJid::new("node", "domain");
     /// let full = bare.with_resource("resource");
     ///
-    /// assert_eq!(full.node, Some("node".to_owned()));
-    /// assert_eq!(full.domain, "domain".to_owned());
-    /// assert_eq!(full.resource, "resource".to_owned());
+    /// assert_eq!(full.node(), Some("node"));
+    /// assert_eq!(full.domain(), "domain");
+    /// assert_eq!(full.resource(), "resource");
     /// ```
     pub fn with_resource<RS>(self, resource: RS) -> FullJid
     where
-        RS: Into<String>,
+        RS: AsRef<str>,
     {
-        FullJid {
-            node: self.node,
-            domain: self.domain,
-            resource: resource.into(),
+        FullJid::from_parts(self.node(), self.domain(), resource.as_ref()).unwrap()
+    }
+}
+
+impl FromStr for DomainJid {
+    type Err = JidParseError;
+
+    fn from_str(s: &str) -> Result<DomainJid, JidParseError> {
+        let inner = InnerJid::new(s)?;
+        if inner.node().is_some() {
+            return Err(JidParseError::UnexpectedNode);
+        }
+        if inner.resource().is_some() {
+            return Err(JidParseError::UnexpectedResource);
         }
+        Ok(DomainJid { inner })
+    }
+}
+
+impl DomainJid {
+    /// Constructs a domain Jabber ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain is invalid, use `DomainJid::from_str()` to handle this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::DomainJid;
+    ///
+    /// let jid = DomainJid::new("domain");
+    ///
+    /// assert_eq!(jid.domain(), "domain");
+    /// ```
+    pub fn new<DS>(domain: DS) -> DomainJid
+    where
+        DS: AsRef<str>,
+    {
+        DomainJid {
+            inner: InnerJid::from_parts(None, domain.as_ref(), None).unwrap(),
+        }
+    }
+
+    /// The domain of the Jabber ID.
+    pub fn domain(&self) -> &str {
+        &self.inner.normalized
+    }
+
+    /// The whole normalised Jabber ID, as a string, which is the same as its domain.
+    pub fn as_str(&self) -> &str {
+        &self.inner.normalized
+    }
+
+    /// Constructs a bare Jabber ID on this domain, specifying a `node`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::{BareJid, DomainJid};
+    ///
+    /// let jid = DomainJid::new("domain").with_node("node");
+    ///
+    /// assert_eq!(jid, BareJid::new("node", "domain"));
+    /// ```
+    pub fn with_node<NS>(&self, node: NS) -> BareJid
+    where
+        NS: AsRef<str>,
+    {
+        BareJid::new(node, self.domain())
+    }
+
+    /// Constructs a full Jabber ID on this domain, specifying a `resource`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jid::DomainJid;
+    ///
+    /// let jid = DomainJid::new("domain").with_resource("resource");
+    ///
+    /// assert_eq!(jid.node(), None);
+    /// assert_eq!(jid.resource(), "resource");
+    /// ```
+    pub fn with_resource<RS>(&self, resource: RS) -> FullJid
+    where
+        RS: AsRef<str>,
+    {
+        FullJid::from_parts(None, self.domain(), resource.as_ref()).unwrap()
     }
 }
 
 #[cfg(feature = "minidom")]
-use minidom::{IntoAttributeValue, Node};
+use minidom::{FromAttributeValue, IntoAttributeValue, Node};
+
+#[cfg(feature = "minidom")]
+impl IntoAttributeValue for DomainJid {
+    fn into_attribute_value(self) -> Option<String> {
+        Some(String::from(self))
+    }
+}
+
+#[cfg(feature = "minidom")]
+impl FromAttributeValue for DomainJid {
+    type Error = JidParseError;
+
+    fn from_attribute_value(value: &str) -> Result<DomainJid, JidParseError> {
+        DomainJid::from_str(value)
+    }
+}
+
+#[cfg(feature = "minidom")]
+impl From<DomainJid> for Node {
+    fn from(jid: DomainJid) -> Node {
+        Node::Text(String::from(jid))
+    }
+}
 
 #[cfg(feature = "minidom")]
 impl IntoAttributeValue for Jid {
@@ -682,6 +1198,15 @@ impl IntoAttributeValue for Jid {
     }
 }
 
+#[cfg(feature = "minidom")]
+impl FromAttributeValue for Jid {
+    type Error = JidParseError;
+
+    fn from_attribute_value(value: &str) -> Result<Jid, JidParseError> {
+        Jid::from_str(value)
+    }
+}
+
 #[cfg(feature = "minidom")]
 impl From<Jid> for Node {
     fn from(jid: Jid) -> Node {
@@ -696,6 +1221,15 @@ impl IntoAttributeValue for FullJid {
     }
 }
 
+#[cfg(feature = "minidom")]
+impl FromAttributeValue for FullJid {
+    type Error = JidParseError;
+
+    fn from_attribute_value(value: &str) -> Result<FullJid, JidParseError> {
+        FullJid::from_str(value)
+    }
+}
+
 #[cfg(feature = "minidom")]
 impl From<FullJid> for Node {
     fn from(jid: FullJid) -> Node {
@@ -710,6 +1244,15 @@ impl IntoAttributeValue for BareJid {
     }
 }
 
+#[cfg(feature = "minidom")]
+impl FromAttributeValue for BareJid {
+    type Error = JidParseError;
+
+    fn from_attribute_value(value: &str) -> Result<BareJid, JidParseError> {
+        BareJid::from_str(value)
+    }
+}
+
 #[cfg(feature = "minidom")]
 impl From<BareJid> for Node {
     fn from(jid: BareJid) -> Node {
@@ -732,11 +1275,7 @@ mod tests {
         );
         assert_eq!(
             FullJid::from_str("b.c/d"),
-            Ok(FullJid {
-                node: None,
-                domain: "b.c".to_owned(),
-                resource: "d".to_owned(),
-            })
+            Ok(FullJid::from_parts(None, "b.c", "d").unwrap())
         );
 
         assert_eq!(FullJid::from_str("a@b.c"), Err(JidParseError::NoResource));
@@ -746,22 +1285,10 @@ mod tests {
     #[test]
     fn can_parse_bare_jids() {
         assert_eq!(BareJid::from_str("a@b.c/d"), Ok(BareJid::new("a", "b.c")));
-        assert_eq!(
-            BareJid::from_str("b.c/d"),
-            Ok(BareJid {
-                node: None,
-                domain: "b.c".to_owned(),
-            })
-        );
+        assert_eq!(BareJid::from_str("b.c/d"), Ok(BareJid::from_domain("b.c")));
 
         assert_eq!(BareJid::from_str("a@b.c"), Ok(BareJid::new("a", "b.c")));
-        assert_eq!(
-            BareJid::from_str("b.c"),
-            Ok(BareJid {
-                node: None,
-                domain: "b.c".to_owned(),
-            })
-        );
+        assert_eq!(BareJid::from_str("b.c"), Ok(BareJid::from_domain("b.c")));
     }
 
     #[test]
@@ -789,18 +1316,69 @@ mod tests {
 
     #[test]
     fn node_from_jid() {
+        assert_eq!(Jid::Full(FullJid::new("a", "b.c", "d")).node(), Some("a"));
+        assert_eq!(Jid::Bare(BareJid::from_domain("b.c")).node(), None);
+    }
+
+    #[test]
+    fn domain_from_jid() {
+        assert_eq!(Jid::Bare(BareJid::new("a", "b.c")).domain(), "b.c");
+        assert_eq!(Jid::Full(FullJid::new("a", "b.c", "d")).domain(), "b.c");
+    }
+
+    #[test]
+    fn resource_from_jid() {
         assert_eq!(
-            Jid::Full(FullJid::new("a", "b.c", "d")).node(),
-            Some(String::from("a")),
+            Jid::Full(FullJid::new("a", "b.c", "d")).resource(),
+            Some("d")
         );
+        assert_eq!(Jid::Bare(BareJid::new("a", "b.c")).resource(), None);
     }
 
     #[test]
-    fn domain_from_jid() {
+    fn domain_jids() {
+        let domain = DomainJid::from_str("b.c").unwrap();
+        assert_eq!(domain.domain(), "b.c");
+        assert_eq!(
+            DomainJid::from_str("a@b.c"),
+            Err(JidParseError::UnexpectedNode)
+        );
+        assert_eq!(
+            DomainJid::from_str("b.c/d"),
+            Err(JidParseError::UnexpectedResource)
+        );
+
+        let jid = Jid::from(domain.clone());
+        assert!(jid.is_domain());
+        assert!(jid.is_bare());
+        assert!(!jid.is_full());
+        assert_eq!(jid, Jid::Bare(BareJid::from_domain("b.c")));
+        assert_eq!(jid, domain);
+        assert_eq!(domain, BareJid::from_domain("b.c"));
+        assert_ne!(Jid::from_str("b.c/d").unwrap(), domain);
+        assert_eq!(DomainJid::try_from(jid), Ok(domain.clone()));
+        assert_eq!(
+            FullJid::try_from(Jid::from(domain.clone())),
+            Err(JidParseError::NoResource)
+        );
+
+        let jid = Jid::from_str("a@b.c/d").unwrap();
+        assert!(!jid.is_domain());
+        assert_eq!(jid.to_domain(), domain);
+        assert_eq!(jid.to_bare(), BareJid::new("a", "b.c"));
+        assert_eq!(jid.as_bare_str(), "a@b.c");
+        assert_eq!(
+            DomainJid::try_from(jid),
+            Err(JidParseError::UnexpectedResource)
+        );
         assert_eq!(
-            Jid::Bare(BareJid::new("a", "b.c")).domain(),
-            String::from("b.c"),
+            DomainJid::try_from(BareJid::new("a", "b.c")),
+            Err(JidParseError::UnexpectedNode)
         );
+
+        assert_eq!(domain.with_node("a"), BareJid::new("a", "b.c"));
+        assert_eq!(domain.with_resource("d").as_str(), "b.c/d");
+        assert_eq!(BareJid::from(domain), BareJid::from_domain("b.c"));
     }
 
     #[test]
@@ -831,6 +1409,93 @@ mod tests {
         let _map: HashMap<Jid, String> = HashMap::new();
     }
 
+    #[test]
+    fn accessors() {
+        let full = FullJid::from_str("a@b.c/d@e/f").unwrap();
+        assert_eq!(full.node(), Some("a"));
+        assert_eq!(full.domain(), "b.c");
+        assert_eq!(full.resource(), "d@e/f");
+        assert_eq!(full.as_str(), "a@b.c/d@e/f");
+        assert_eq!(full.as_bare_str(), "a@b.c");
+
+        let bare = full.to_bare();
+        assert_eq!(bare.node(), Some("a"));
+        assert_eq!(bare.domain(), "b.c");
+        assert_eq!(bare.as_str(), "a@b.c");
+        assert_eq!(bare, BareJid::from(full));
+
+        let bare = BareJid::from_str("b.c").unwrap();
+        assert_eq!(bare.node(), None);
+        assert_eq!(bare.domain(), "b.c");
+
+        let full = bare.with_resource("d");
+        assert_eq!(full.node(), None);
+        assert_eq!(full.domain(), "b.c");
+        assert_eq!(full.resource(), "d");
+    }
+
+    #[test]
+    fn escaped_nodes() {
+        // Examples from XEP-0106, §5.1.
+        let jid = BareJid::from_unescaped_node("space cadet", "example.com").unwrap();
+        assert_eq!(
+            jid,
+            BareJid::from_str("space\\20cadet@example.com").unwrap()
+        );
+        let jid = BareJid::from_unescaped_node("user@host", "example.com").unwrap();
+        assert_eq!(jid, BareJid::from_str("user\\40host@example.com").unwrap());
+        let jid = FullJid::from_unescaped_node("c:\\cool stuff", "example.com", "d").unwrap();
+        assert_eq!(jid.as_str(), "c\\3a\\cool\\20stuff@example.com/d");
+        assert_eq!(jid.unescaped_node().unwrap(), "c:\\cool stuff");
+
+        assert_eq!(BareJid::from_domain("example.com").unescaped_node(), None);
+        assert_eq!(
+            BareJid::from_unescaped_node(" space", "example.com"),
+            Err(JidParseError::SpaceAtEdgeOfNode)
+        );
+    }
+
+    #[test]
+    fn borrow_normalized() {
+        let mut map: HashMap<BareJid, u32> = HashMap::new();
+        map.insert(BareJid::new("a", "b.c"), 1);
+        map.insert(BareJid::from_domain("b.c"), 2);
+
+        let full = FullJid::new("a", "b.c", "d");
+        assert_eq!(map.get(full.as_bare_str()), Some(&1));
+        assert_eq!(map.get("b.c"), Some(&2));
+        assert_eq!(map.get("a@b.c."), None);
+
+        let mut map: HashMap<Jid, u32> = HashMap::new();
+        map.insert(Jid::Full(full.clone()), 1);
+        map.insert(Jid::Bare(full.to_bare()), 2);
+        assert_eq!(map.get("a@b.c/d"), Some(&1));
+        assert_eq!(map.get(full.as_bare_str()), Some(&2));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut jids: Vec<Jid> = vec!["b@c/d", "a@c", "b@c", "a@c/d"]
+            .into_iter()
+            .map(|jid| Jid::from_str(jid).unwrap())
+            .collect();
+        jids.sort();
+        assert_eq!(
+            jids.into_iter().map(String::from).collect::<Vec<_>>(),
+            vec!["a@c", "a@c/d", "b@c", "b@c/d"]
+        );
+
+        assert!(BareJid::new("a", "c") < BareJid::new("b", "c"));
+        assert!(FullJid::new("a", "c", "d") < FullJid::new("a", "c", "e"));
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn size() {
+        assert_eq!(std::mem::size_of::<BareJid>(), 32);
+        assert_eq!(std::mem::size_of::<FullJid>(), 32);
+    }
+
     #[test]
     fn invalid_jids() {
         assert_eq!(BareJid::from_str(""), Err(JidParseError::NoDomain));
@@ -847,6 +1512,114 @@ mod tests {
         assert_eq!(FullJid::from_str("a@b"), Err(JidParseError::NoResource));
     }
 
+    #[test]
+    fn invalid_parts() {
+        let long = "a".repeat(1024);
+        assert_eq!(
+            BareJid::from_str(&format!("{}@b", long)),
+            Err(JidParseError::NodeTooLong)
+        );
+        assert_eq!(BareJid::from_str(&long), Err(JidParseError::DomainTooLong));
+        assert_eq!(
+            FullJid::from_str(&format!("a@b/{}", long)),
+            Err(JidParseError::ResourceTooLong)
+        );
+        assert!(BareJid::from_str(&"a".repeat(1023)).is_ok());
+
+        for c in &['"', '&', '\'', ':', '<', '>'] {
+            assert_eq!(
+                BareJid::from_str(&format!("a{}b@c", c)),
+                Err(JidParseError::ForbiddenCharInNode)
+            );
+        }
+        assert_eq!(
+            BareJid::from_parts(Some("a@b"), "c"),
+            Err(JidParseError::ForbiddenCharInNode)
+        );
+        assert_eq!(
+            BareJid::from_parts(Some("a"), "b/c"),
+            Err(JidParseError::ForbiddenCharInDomain)
+        );
+
+        // The @ is part of the resource here.
+        assert_eq!(
+            FullJid::from_str("b/c@d"),
+            Ok(FullJid::from_parts(None, "b", "c@d").unwrap())
+        );
+    }
+
+    #[test]
+    fn trailing_dot() {
+        assert_eq!(BareJid::from_str("a@b.c."), Ok(BareJid::new("a", "b.c")));
+        assert_eq!(
+            FullJid::from_str("a@b.c./d"),
+            Ok(FullJid::new("a", "b.c", "d"))
+        );
+        assert_eq!(BareJid::from_str("a@."), Err(JidParseError::NoDomain));
+    }
+
+    #[cfg(feature = "precis")]
+    #[test]
+    fn precis() {
+        // Case mapping and width mapping of the localpart.
+        assert_eq!(
+            BareJid::from_str("JuLiEt@example.com"),
+            Ok(BareJid::new("juliet", "example.com"))
+        );
+        assert_eq!(
+            BareJid::from_str("ｊｕｌｉｅｔ@example.com"),
+            Ok(BareJid::new("juliet", "example.com"))
+        );
+        assert_eq!(
+            BareJid::from_str("juliet@example.com"),
+            BareJid::from_str("JULIET@EXAMPLE.COM")
+        );
+
+        // Spaces aren’t allowed in localparts, but are in resources.
+        assert_eq!(
+            BareJid::from_str("jul iet@example.com"),
+            Err(JidParseError::NodePrep)
+        );
+        assert_eq!(
+            FullJid::from_str("juliet@example.com/Home Balcony"),
+            Ok(FullJid::new("juliet", "example.com", "Home Balcony"))
+        );
+
+        // Resources are case sensitive, but still get normalised.
+        assert_eq!(
+            FullJid::from_str("juliet@example.com/Balcony"),
+            Ok(FullJid::new("juliet", "example.com", "Balcony"))
+        );
+        assert_eq!(
+            FullJid::from_str("juliet@example.com/\u{0}"),
+            Err(JidParseError::ResourcePrep)
+        );
+
+        // Domains go through IDNA.
+        assert_eq!(
+            BareJid::from_str("juliet@XN--BCHER-KVA.example"),
+            Ok(BareJid::new("juliet", "bücher.example"))
+        );
+        assert_eq!(
+            BareJid::from_str("juliet@Bücher.example"),
+            Ok(BareJid::new("juliet", "bücher.example"))
+        );
+        assert_eq!(
+            BareJid::from_str("juliet@exa mple.com"),
+            Err(JidParseError::DomainPrep)
+        );
+
+        // IP literals are kept as is.
+        assert_eq!(
+            BareJid::from_str("juliet@[::1]"),
+            Ok(BareJid::new("juliet", "[::1]"))
+        );
+        assert_eq!(
+            BareJid::from_str("juliet@127.0.0.1"),
+            Ok(BareJid::new("juliet", "127.0.0.1"))
+        );
+    }
+
     #[test]
     fn display_jids() {
         assert_eq!(
@@ -884,6 +1657,25 @@ mod tests {
         assert_eq!(to, BareJid::new("a", "b"));
     }
 
+    #[cfg(feature = "minidom")]
+    #[test]
+    fn minidom_attr_as() {
+        let elem: minidom::Element = "<message xmlns='ns1' from='a@b/c' to='@b'/>"
+            .parse()
+            .unwrap();
+        let from: Option<FullJid> = elem.attr_as("from").unwrap();
+        assert_eq!(from, Some(FullJid::new("a", "b", "c")));
+        let to = elem.required_attr::<Jid>("to");
+        assert_eq!(
+            to,
+            Err(minidom::AttributeError::Invalid {
+                element: String::from("message"),
+                attribute: String::from("to"),
+                error: JidParseError::EmptyNode,
+            })
+        );
+    }
+
     #[cfg(feature = "minidom")]
     #[test]
     fn minidom_into_attr() {
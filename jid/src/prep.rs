@@ -0,0 +1,102 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Preparation and enforcement of each part of a JID, following
+//! [RFC 7622](https://tools.ietf.org/html/rfc7622).
+//!
+//! Length and structural rules are always checked, while the PRECIS profiles
+//! and IDNA processing require the `precis` feature, as they pull in large
+//! Unicode tables.
+
+use crate::JidParseError;
+use std::borrow::Cow;
+
+#[cfg(feature = "precis")]
+use precis_core::profile::PrecisFastInvocation;
+#[cfg(feature = "precis")]
+use precis_profiles::{OpaqueString, UsernameCaseMapped};
+#[cfg(feature = "precis")]
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Maximum length in bytes of each part of a JID, RFC 7622 §3.
+const MAX_PART_LENGTH: usize = 1023;
+
+/// Characters which are forbidden in a localpart, RFC 7622 §3.3.1.
+const FORBIDDEN_NODE_CHARS: [char; 8] = ['"', '&', '\'', '/', ':', '<', '>', '@'];
+
+/// Enforce the UsernameCaseMapped profile on a localpart.
+pub(crate) fn node(node: &str) -> Result<Cow<'_, str>, JidParseError> {
+    if node.is_empty() {
+        return Err(JidParseError::EmptyNode);
+    }
+    #[cfg(feature = "precis")]
+    let node = UsernameCaseMapped::enforce(node).map_err(|_| JidParseError::NodePrep)?;
+    #[cfg(not(feature = "precis"))]
+    let node = Cow::Borrowed(node);
+    if node.len() > MAX_PART_LENGTH {
+        return Err(JidParseError::NodeTooLong);
+    }
+    if node.contains(&FORBIDDEN_NODE_CHARS[..]) {
+        return Err(JidParseError::ForbiddenCharInNode);
+    }
+    Ok(node)
+}
+
+/// Process a domainpart through IDNA, unless it is an IP address literal.
+pub(crate) fn domain(domain: &str) -> Result<Cow<'_, str>, JidParseError> {
+    // A single trailing dot is removed before any other processing.
+    let domain = domain.strip_suffix('.').unwrap_or(domain);
+    if domain.is_empty() {
+        return Err(JidParseError::NoDomain);
+    }
+    if domain.contains(&['@', '/'][..]) {
+        return Err(JidParseError::ForbiddenCharInDomain);
+    }
+    #[cfg(feature = "precis")]
+    let domain = if is_ip_literal(domain) {
+        Cow::Borrowed(domain)
+    } else {
+        let (domain, result) = idna::Config::default()
+            .use_std3_ascii_rules(true)
+            .to_unicode(domain);
+        result.map_err(|_| JidParseError::DomainPrep)?;
+        if domain.is_empty() {
+            return Err(JidParseError::NoDomain);
+        }
+        Cow::Owned(domain)
+    };
+    #[cfg(not(feature = "precis"))]
+    let domain = Cow::Borrowed(domain);
+    if domain.len() > MAX_PART_LENGTH {
+        return Err(JidParseError::DomainTooLong);
+    }
+    Ok(domain)
+}
+
+/// Enforce the OpaqueString profile on a resourcepart.
+pub(crate) fn resource(resource: &str) -> Result<Cow<'_, str>, JidParseError> {
+    if resource.is_empty() {
+        return Err(JidParseError::EmptyResource);
+    }
+    #[cfg(feature = "precis")]
+    let resource = OpaqueString::enforce(resource).map_err(|_| JidParseError::ResourcePrep)?;
+    #[cfg(not(feature = "precis"))]
+    let resource = Cow::Borrowed(resource);
+    if resource.len() > MAX_PART_LENGTH {
+        return Err(JidParseError::ResourceTooLong);
+    }
+    Ok(resource)
+}
+
+#[cfg(feature = "precis")]
+fn is_ip_literal(domain: &str) -> bool {
+    if let Some(ipv6) = domain
+        .strip_prefix('[')
+        .and_then(|domain| domain.strip_suffix(']'))
+    {
+        ipv6.parse::<Ipv6Addr>().is_ok()
+    } else {
+        domain.parse::<Ipv4Addr>().is_ok()
+    }
+}
@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Measures the allocations and time spent parsing typical stanzas from a stream, then
+//! dispatching them the way a client does, by checking their name and namespace.
+//!
+//! Each measurement is compared with the same work done with owned names and namespaces: parsing
+//! each stanza on its own with `Element::from_reader()`, which allocates them for every element,
+//! and dispatching with a copy of the namespace as `Element::ns()` used to return.
+//!
+//! Run with `cargo bench -p minidom --bench dispatch`.
+
+use bytes::{BufMut, BytesMut};
+use minidom::{Element, Parser, ParserResult};
+use quick_xml::Reader;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const STREAM_START: &[u8] = b"<stream:stream xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' version='1.0' id='abc' from='example.org'>";
+
+const MESSAGE: &[u8] = b"<message from='juliet@example.org/balcony' to='romeo@example.org' type='chat' id='a1'><body>Art thou not Romeo, and a Montague?</body><active xmlns='http://jabber.org/protocol/chatstates'/><origin-id xmlns='urn:xmpp:sid:0' id='b2'/><request xmlns='urn:xmpp:receipts'/></message>";
+
+const PRESENCE: &[u8] = b"<presence from='juliet@example.org/balcony' to='romeo@example.org'><show>away</show><status>Be right back</status><priority>5</priority><c xmlns='http://jabber.org/protocol/caps' hash='sha-1' node='https://example.org' ver='QgayPKawpkPSDYmwT/WM94uAlu0='/><x xmlns='vcard-temp:x:update'><photo>abc</photo></x></presence>";
+
+const STANZAS: usize = 10_000;
+
+/// What a client would look at before handing the stanza to a more specific parser.
+fn dispatch(elem: &Element) -> usize {
+    let mut matched = 0;
+    if elem.is("message", "jabber:client") {
+        for child in elem.children() {
+            if child.is("body", "jabber:client")
+                || child.has_ns("http://jabber.org/protocol/chatstates")
+                || child.ns() == "urn:xmpp:sid:0"
+                || child.ns() == "urn:xmpp:receipts"
+            {
+                matched += 1;
+            }
+        }
+    } else if elem.is("presence", "jabber:client") {
+        for child in elem.children() {
+            if child.is("c", "http://jabber.org/protocol/caps")
+                || child.is("x", "vcard-temp:x:update")
+                || child.ns() == "jabber:client"
+            {
+                matched += 1;
+            }
+        }
+    }
+    matched
+}
+
+/// Same as `dispatch()`, with a copy of the namespace for every check.
+fn dispatch_owned(elem: &Element) -> usize {
+    let mut matched = 0;
+    let ns = elem.ns().to_owned();
+    if elem.name() == "message" && ns == "jabber:client" {
+        for child in elem.children() {
+            let ns = child.ns().to_owned();
+            if (child.name() == "body" && ns == "jabber:client")
+                || ns == "http://jabber.org/protocol/chatstates"
+                || ns == "urn:xmpp:sid:0"
+                || ns == "urn:xmpp:receipts"
+            {
+                matched += 1;
+            }
+        }
+    } else if elem.name() == "presence" && ns == "jabber:client" {
+        for child in elem.children() {
+            let ns = child.ns().to_owned();
+            if (child.name() == "c" && ns == "http://jabber.org/protocol/caps")
+                || (child.name() == "x" && ns == "vcard-temp:x:update")
+                || ns == "jabber:client"
+            {
+                matched += 1;
+            }
+        }
+    }
+    matched
+}
+
+fn count_elements(elem: &Element) -> usize {
+    1 + elem.children().map(count_elements).sum::<usize>()
+}
+
+/// Parses each stanza on its own, so that nothing is shared between them.
+fn parse_owned(stanzas: &[Vec<u8>]) -> Vec<Element> {
+    stanzas
+        .iter()
+        .map(|stanza| Element::from_reader(&mut Reader::from_reader(&stanza[..])).unwrap())
+        .collect()
+}
+
+/// Counts the allocations done by `f`, and the time it took.
+fn measure<T, F: FnOnce() -> T>(f: F) -> (T, usize, Duration) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let time = Instant::now();
+    let result = f();
+    let time = time.elapsed();
+    (
+        result,
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        time,
+    )
+}
+
+fn report(
+    what: &str,
+    allocations: usize,
+    time: Duration,
+    owned_allocations: usize,
+    owned_time: Duration,
+) {
+    println!(
+        "{}: {:>8.1} allocations/stanza, {:>8.2?}/stanza (owned: {:>8.1} allocations/stanza, {:>8.2?}/stanza)",
+        what,
+        allocations as f64 / STANZAS as f64,
+        time / STANZAS as u32,
+        owned_allocations as f64 / STANZAS as f64,
+        owned_time / STANZAS as u32
+    );
+}
+
+fn main() {
+    let mut data = BytesMut::new();
+    let mut owned_data = Vec::with_capacity(STANZAS);
+    for i in 0..STANZAS {
+        let stanza = if i % 2 == 0 { MESSAGE } else { PRESENCE };
+        data.put(stanza);
+        // Without the stream, the namespace has to be declared on each stanza.
+        let name_end = stanza.iter().position(|&c| c == b' ').unwrap();
+        let mut owned = stanza[..name_end].to_vec();
+        owned.extend_from_slice(b" xmlns='jabber:client'");
+        owned.extend_from_slice(&stanza[name_end..]);
+        owned_data.push(owned);
+    }
+
+    let mut parser = Parser::new();
+    let mut start = BytesMut::new();
+    start.put(STREAM_START);
+    parser.feed(start).unwrap();
+    parser.poll().unwrap().unwrap();
+
+    let (stanzas, parse_allocations, parse_time) = measure(|| {
+        parser.feed(data).unwrap();
+        let mut stanzas = Vec::with_capacity(STANZAS);
+        while let Some(result) = parser.poll().unwrap() {
+            if let ParserResult::Single(elem) = result {
+                stanzas.push(elem);
+            }
+        }
+        stanzas
+    });
+    let (owned_stanzas, owned_parse_allocations, owned_parse_time) =
+        measure(|| parse_owned(&owned_data));
+
+    let (matched, dispatch_allocations, dispatch_time) =
+        measure(|| stanzas.iter().map(dispatch).sum::<usize>());
+    let (owned_matched, owned_dispatch_allocations, owned_dispatch_time) =
+        measure(|| owned_stanzas.iter().map(dispatch_owned).sum::<usize>());
+
+    assert_eq!(stanzas, owned_stanzas);
+    assert_eq!(stanzas.len(), STANZAS);
+    assert_eq!(matched, STANZAS / 2 * 4 + STANZAS / 2 * 5);
+    assert_eq!(owned_matched, matched);
+    report(
+        "parse   ",
+        parse_allocations,
+        parse_time,
+        owned_parse_allocations,
+        owned_parse_time,
+    );
+    report(
+        "dispatch",
+        dispatch_allocations,
+        dispatch_time,
+        owned_dispatch_allocations,
+        owned_dispatch_time,
+    );
+
+    // Names and namespaces are shared between stanzas, which saves at least the allocation of
+    // both for every element, and they are never copied while dispatching.
+    let elements: usize = stanzas.iter().map(count_elements).sum();
+    assert!(parse_allocations + 2 * elements <= owned_parse_allocations);
+    assert_eq!(dispatch_allocations, 0);
+    assert!(owned_dispatch_allocations >= matched);
+}
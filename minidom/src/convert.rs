@@ -7,6 +7,11 @@
 
 //! A module which exports a few traits for converting types to elements and attributes.
 
+use std::convert::Infallible;
+use std::net::{AddrParseError, IpAddr};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
 /// A trait for types which can be converted to an attribute value.
 pub trait IntoAttributeValue {
     /// Turns this into an attribute string, or None if it shouldn't be added.
@@ -67,9 +72,61 @@ impl<T: IntoAttributeValue> IntoAttributeValue for Option<T> {
     }
 }
 
+/// A trait for types which can be parsed from an attribute value, the reverse of
+/// `IntoAttributeValue`.
+pub trait FromAttributeValue: Sized {
+    /// The error returned when the value is invalid.
+    type Error;
+
+    /// Parses this type from an attribute string.
+    fn from_attribute_value(value: &str) -> Result<Self, Self::Error>;
+}
+
+macro_rules! impl_from_attribute_value {
+    ($t:ty, $err:ty) => {
+        impl FromAttributeValue for $t {
+            type Error = $err;
+
+            fn from_attribute_value(value: &str) -> Result<$t, $err> {
+                <$t>::from_str(value)
+            }
+        }
+    };
+}
+
+macro_rules! impl_from_attribute_values {
+    ($err:ty, $($t:ty),*) => {
+        $(impl_from_attribute_value!($t, $err);)*
+    }
+}
+
+impl_from_attribute_values!(
+    ParseIntError,
+    usize,
+    u64,
+    u32,
+    u16,
+    u8,
+    isize,
+    i64,
+    i32,
+    i16,
+    i8
+);
+
+impl_from_attribute_value!(IpAddr, AddrParseError);
+
+impl FromAttributeValue for String {
+    type Error = Infallible;
+
+    fn from_attribute_value(value: &str) -> Result<String, Infallible> {
+        Ok(value.to_owned())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::IntoAttributeValue;
+    use super::{FromAttributeValue, IntoAttributeValue};
     use std::net::IpAddr;
     use std::str::FromStr;
 
@@ -91,4 +148,16 @@ mod tests {
             "::1"
         );
     }
+
+    #[test]
+    fn test_from_attribute_value() {
+        assert_eq!(u8::from_attribute_value("16").unwrap(), 16);
+        assert_eq!(i64::from_attribute_value("-19").unwrap(), -19);
+        assert!(u16::from_attribute_value("-17").is_err());
+        assert_eq!(
+            IpAddr::from_attribute_value("0000:0::1").unwrap(),
+            IpAddr::from_str("::1").unwrap()
+        );
+        assert_eq!(String::from_attribute_value("a&b").unwrap(), "a&b");
+    }
 }
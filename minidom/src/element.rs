@@ -12,17 +12,20 @@
 
 //! Provides an `Element` type, which represents DOM nodes, and a builder to create them with.
 
-use crate::convert::IntoAttributeValue;
-use crate::error::{Error, Result};
+use crate::convert::{FromAttributeValue, IntoAttributeValue};
+use crate::error::{AttributeError, Error, ParserError, Result};
 use crate::namespaces::NSChoice;
 use crate::node::Node;
+use crate::parser::{ParserLimits, XML_NS};
 use crate::prefixes::{Namespace, Prefix, Prefixes};
+use crate::query::{Match, Selector};
 
 use std::collections::{btree_map, BTreeMap};
 use std::io::Write;
 
 use std::borrow::Cow;
 use std::str;
+use std::sync::Arc;
 
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
 use quick_xml::Reader as EventReader;
@@ -77,17 +80,69 @@ pub fn escape(raw: &[u8]) -> Cow<[u8]> {
     }
 }
 
+/// Escapes text the way Canonical XML does, only replacing what would otherwise be invalid or
+/// normalised by a parser.
+fn canonical_escape(raw: &str, attribute: bool) -> Cow<'_, str> {
+    let needs_escape = |c: char| match c {
+        '&' | '<' | '\r' => true,
+        '>' => !attribute,
+        '"' | '\t' | '\n' => attribute,
+        _ => false,
+    };
+    if !raw.contains(needs_escape) {
+        return Cow::Borrowed(raw);
+    }
+    let mut escaped = String::with_capacity(raw.len() + 8);
+    for c in raw.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' if !attribute => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\t' if attribute => escaped.push_str("&#x9;"),
+            '\n' if attribute => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
 #[derive(Clone, Eq, Debug)]
 /// A struct representing a DOM Element.
 pub struct Element {
-    name: String,
-    namespace: String,
+    name: Arc<str>,
+    namespace: Arc<str>,
     /// This is only used when deserializing. If you have to use a custom prefix use
     /// `ElementBuilder::prefix`.
     prefix: Option<Prefix>,
     prefixes: Prefixes,
     attributes: BTreeMap<String, String>,
     children: Vec<Node>,
+    /// This is only set when parsing in lossless mode, and dropped as soon as this element gets
+    /// modified.
+    source: Option<Box<Source>>,
+}
+
+/// The exact markup an element has been parsed from, so that it can be written back unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Source {
+    /// The start tag, or empty element tag, as received.
+    start: Box<str>,
+    /// The end tag as received, which is still None while the element is being parsed.
+    end: Option<Box<str>>,
+    /// The namespaces the start tag uses without declaring them, which have to be declared when
+    /// writing this element in a context where they aren’t in scope.
+    inherited: Vec<(Prefix, Namespace)>,
+    /// The markup of each text node, including references and CDATA sections.
+    texts: Vec<String>,
+}
+
+impl Source {
+    /// Whether this source covers the whole element.
+    fn is_complete(&self) -> bool {
+        self.end.is_some() || self.start.ends_with("/>")
+    }
 }
 
 impl<'a> From<&'a Element> for String {
@@ -107,6 +162,29 @@ impl FromStr for Element {
     }
 }
 
+/// Elements are serialised as their XML string, so they can be stored in any format supported by
+/// serde.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Element {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&String::from(self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Element {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Element::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 impl PartialEq for Element {
     fn eq(&self, other: &Self) -> bool {
         if self.name() == other.name() && self.ns() == other.ns() && self.attrs().eq(other.attrs())
@@ -128,9 +206,9 @@ fn ensure_no_prefix<S: AsRef<str>>(s: &S) -> Result<()> {
 }
 
 impl Element {
-    fn new<P: Into<Prefixes>>(
-        name: String,
-        namespace: String,
+    pub(crate) fn new<P: Into<Prefixes>>(
+        name: Arc<str>,
+        namespace: Arc<str>,
         prefix: Option<Prefix>,
         prefixes: P,
         attributes: BTreeMap<String, String>,
@@ -145,6 +223,7 @@ impl Element {
             prefixes: prefixes.into(),
             attributes,
             children,
+            source: None,
         }
     }
 
@@ -161,15 +240,15 @@ impl Element {
     ///                    .build();
     ///
     /// assert_eq!(elem.name(), "name");
-    /// assert_eq!(elem.ns(), "namespace".to_owned());
+    /// assert_eq!(elem.ns(), "namespace");
     /// assert_eq!(elem.attr("name"), Some("value"));
     /// assert_eq!(elem.attr("inexistent"), None);
     /// assert_eq!(elem.text(), "inner");
     /// ```
-    pub fn builder<S: AsRef<str>, NS: Into<String>>(name: S, namespace: NS) -> ElementBuilder {
+    pub fn builder<S: AsRef<str>, NS: Into<Arc<str>>>(name: S, namespace: NS) -> ElementBuilder {
         ElementBuilder {
             root: Element::new(
-                name.as_ref().to_string(),
+                Arc::from(name.as_ref()),
                 namespace.into(),
                 None,
                 None,
@@ -193,7 +272,7 @@ impl Element {
     /// assert_eq!(bare.attr("name"), None);
     /// assert_eq!(bare.text(), "");
     /// ```
-    pub fn bare<S: Into<String>, NS: Into<String>>(name: S, namespace: NS) -> Element {
+    pub fn bare<S: Into<Arc<str>>, NS: Into<Arc<str>>>(name: S, namespace: NS) -> Element {
         Element::new(
             name.into(),
             namespace.into(),
@@ -210,8 +289,14 @@ impl Element {
     }
 
     /// Returns a reference to the namespace of this element.
-    pub fn ns(&self) -> String {
-        self.namespace.clone()
+    pub fn ns(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Returns the namespace declarations made on this element, keyed by their prefix, `None`
+    /// being the default namespace.
+    pub fn declared_prefixes(&self) -> &BTreeMap<Option<String>, String> {
+        self.prefixes.declared_prefixes()
     }
 
     /// Returns a reference to the value of the given attribute, if it exists, else `None`.
@@ -222,6 +307,73 @@ impl Element {
         None
     }
 
+    /// Parses the value of the given attribute, returning `None` if it doesn’t exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use minidom::{AttributeError, Element};
+    ///
+    /// let elem: Element = "<set xmlns='urn:xmpp:rsm' max='10' index='x'/>".parse().unwrap();
+    ///
+    /// assert_eq!(elem.attr_as::<u32>("max"), Ok(Some(10)));
+    /// assert_eq!(elem.attr_as::<u32>("first"), Ok(None));
+    /// match elem.attr_as::<u32>("index") {
+    ///     Err(AttributeError::Invalid { element, attribute, .. }) => {
+    ///         assert_eq!(element, "set");
+    ///         assert_eq!(attribute, "index");
+    ///     }
+    ///     _ => panic!(),
+    /// }
+    /// ```
+    pub fn attr_as<T: FromAttributeValue>(
+        &self,
+        name: &str,
+    ) -> std::result::Result<Option<T>, AttributeError<T::Error>> {
+        match self.attributes.get(name) {
+            Some(value) => match T::from_attribute_value(value) {
+                Ok(value) => Ok(Some(value)),
+                Err(error) => Err(AttributeError::Invalid {
+                    element: self.name().to_owned(),
+                    attribute: name.to_owned(),
+                    error,
+                }),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the value of the given attribute, which must exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use minidom::{AttributeError, Element};
+    ///
+    /// let elem: Element = "<set xmlns='urn:xmpp:rsm' max='10'/>".parse().unwrap();
+    ///
+    /// assert_eq!(elem.required_attr::<u32>("max"), Ok(10));
+    /// assert_eq!(
+    ///     elem.required_attr::<u32>("index"),
+    ///     Err(AttributeError::Missing {
+    ///         element: String::from("set"),
+    ///         attribute: String::from("index"),
+    ///     })
+    /// );
+    /// ```
+    pub fn required_attr<T: FromAttributeValue>(
+        &self,
+        name: &str,
+    ) -> std::result::Result<T, AttributeError<T::Error>> {
+        match self.attr_as(name)? {
+            Some(value) => Ok(value),
+            None => Err(AttributeError::Missing {
+                element: self.name().to_owned(),
+                attribute: name.to_owned(),
+            }),
+        }
+    }
+
     /// Returns an iterator over the attributes of this element.
     ///
     /// # Example
@@ -245,6 +397,7 @@ impl Element {
     /// Returns an iterator over the attributes of this element, with the value being a mutable
     /// reference.
     pub fn attrs_mut(&mut self) -> AttrsMut {
+        self.source = None;
         AttrsMut {
             iter: self.attributes.iter_mut(),
         }
@@ -252,6 +405,7 @@ impl Element {
 
     /// Modifies the value of an attribute.
     pub fn set_attr<S: Into<String>, V: IntoAttributeValue>(&mut self, name: S, val: V) {
+        self.source = None;
         let name = name.into();
         let val = val.into_attribute_value();
 
@@ -286,7 +440,7 @@ impl Element {
     /// assert_eq!(elem.is("name", NSChoice::Any), true);
     /// ```
     pub fn is<'a, N: AsRef<str>, NS: Into<NSChoice<'a>>>(&self, name: N, namespace: NS) -> bool {
-        self.name == name.as_ref() && namespace.into().compare(self.namespace.as_ref())
+        &*self.name == name.as_ref() && namespace.into().compare(&self.namespace)
     }
 
     /// Returns whether the element has the given namespace.
@@ -310,16 +464,82 @@ impl Element {
         namespace.into().compare(self.namespace.as_ref())
     }
 
-    /// Parse a document from an `EventReader`.
+    /// Parse a document from an `EventReader`, with the default `ParserLimits`.
+    ///
+    /// As the `EventReader` already wraps its input, the size of the document is only checked
+    /// after each event has been read, use `from_reader_with_limits()` to bound what gets read.
     pub fn from_reader<R: BufRead>(reader: &mut EventReader<R>) -> Result<Element> {
+        Element::from_reader_inner(reader, &ParserLimits::default(), false)
+    }
+
+    /// Parse a document from a `BufRead`, failing if it exceeds `limits`.
+    ///
+    /// The whole document is bounded by `ParserLimits::max_stanza_size`, and no more than that
+    /// gets read from `reader`, so that a huge text node or tag can’t be buffered before the
+    /// limit applies.
+    pub fn from_reader_with_limits<R: BufRead>(
+        reader: R,
+        limits: &ParserLimits,
+    ) -> Result<Element> {
+        Element::from_bounded_reader(reader, limits, false)
+    }
+
+    /// Parse a document from a `BufRead` in lossless mode, failing if it exceeds `limits`.
+    ///
+    /// Every element remembers the exact markup it has been parsed from, including its
+    /// prefixes, the order and quoting of its attributes, references, CDATA sections and
+    /// whitespace, and writing it back produces the same bytes as long as it hasn’t been
+    /// modified.  The only exceptions are the declarations of namespaces inherited from an
+    /// ancestor which isn’t written along with it, and whitespace inside end tags, which
+    /// `quick_xml` doesn’t report; `Parser::set_lossless()` keeps it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use minidom::{Element, ParserLimits};
+    ///
+    /// let xml = "<message xmlns='jabber:client' type='chat' id='1'>\n  <body>I &lt;3 XMPP</body>\n</message>";
+    /// let elem = Element::from_reader_lossless(xml.as_bytes(), &ParserLimits::default()).unwrap();
+    /// assert_eq!(String::from(&elem), xml);
+    /// ```
+    pub fn from_reader_lossless<R: BufRead>(reader: R, limits: &ParserLimits) -> Result<Element> {
+        Element::from_bounded_reader(reader, limits, true)
+    }
+
+    /// Reads at most one byte more than `ParserLimits::max_stanza_size` from `reader`, which is
+    /// enough to notice a document exceeding it.
+    fn from_bounded_reader<R: BufRead>(
+        reader: R,
+        limits: &ParserLimits,
+        lossless: bool,
+    ) -> Result<Element> {
+        let limit = (limits.max_stanza_size as u64).saturating_add(1);
+        let mut input = reader.take(limit);
+        let mut reader = EventReader::from_reader(&mut input);
+        match Element::from_reader_inner(&mut reader, limits, lossless) {
+            // The document got cut by the limit in the middle of an event.
+            Err(_) if input.limit() == 0 => Err(ParserError::StanzaTooLarge.into()),
+            result => result,
+        }
+    }
+
+    fn from_reader_inner<R: BufRead>(
+        reader: &mut EventReader<R>,
+        limits: &ParserLimits,
+        lossless: bool,
+    ) -> Result<Element> {
         let mut buf = Vec::new();
 
         let mut prefixes = BTreeMap::new();
         let root: Element = loop {
             let e = reader.read_event(&mut buf)?;
+            check_size(reader, limits)?;
             match e {
-                Event::Empty(ref e) | Event::Start(ref e) => {
-                    break build_element(reader, e, &mut prefixes)?;
+                Event::Empty(ref e) => {
+                    return build_element(reader, e, &mut prefixes, limits, lossless, true);
+                }
+                Event::Start(ref e) => {
+                    break build_element(reader, e, &mut prefixes, limits, lossless, false)?;
                 }
                 Event::Eof => {
                     return Err(Error::EndOfDocument);
@@ -327,12 +547,12 @@ impl Element {
                 Event::Comment { .. } => {
                     return Err(Error::NoComments);
                 }
+                Event::PI { .. } => return Err(ParserError::ProcessingInstruction.into()),
+                Event::DocType { .. } => return Err(ParserError::DocType.into()),
                 Event::Text { .. }
                 | Event::End { .. }
                 | Event::CData { .. }
-                | Event::Decl { .. }
-                | Event::PI { .. }
-                | Event::DocType { .. } => (), // TODO: may need more errors
+                | Event::Decl { .. } => (), // TODO: may need more errors
             }
         };
 
@@ -340,98 +560,78 @@ impl Element {
         let mut prefix_stack = vec![prefixes];
 
         loop {
-            match reader.read_event(&mut buf)? {
+            let event = reader.read_event(&mut buf)?;
+            check_size(reader, limits)?;
+            match event {
                 Event::Empty(ref e) => {
+                    if stack.len() >= limits.max_depth {
+                        return Err(ParserError::TooDeep.into());
+                    }
                     let mut prefixes = prefix_stack.last().unwrap().clone();
-                    let elem = build_element(reader, e, &mut prefixes)?;
+                    let elem = build_element(reader, e, &mut prefixes, limits, lossless, true)?;
                     // Since there is no Event::End after, directly append it to the current node
-                    stack.last_mut().unwrap().append_child(elem);
+                    stack.last_mut().unwrap().push_child(elem);
                 }
                 Event::Start(ref e) => {
+                    if stack.len() >= limits.max_depth {
+                        return Err(ParserError::TooDeep.into());
+                    }
                     let mut prefixes = prefix_stack.last().unwrap().clone();
-                    let elem = build_element(reader, e, &mut prefixes)?;
+                    let elem = build_element(reader, e, &mut prefixes, limits, lossless, false)?;
                     stack.push(elem);
                     prefix_stack.push(prefixes);
                 }
                 Event::End(ref e) => {
-                    if stack.len() <= 1 {
-                        break;
-                    }
-                    let prefixes = match prefix_stack.pop().unwrap() {
-                        x if x.is_empty() => {
-                            let mut aux: BTreeMap<Prefix, Namespace> = BTreeMap::new();
-                            aux.insert(None, "no namespace".to_string());
-                            aux
+                    prefix_stack.pop();
+                    let mut elem = stack.pop().unwrap();
+                    let name = str::from_utf8(e.name())?;
+                    let matches = match elem.prefix {
+                        Some(Some(ref prefix)) => {
+                            name.len() == prefix.len() + 1 + elem.name.len()
+                                && name.starts_with(prefix.as_str())
+                                && name[prefix.len()..].starts_with(':')
+                                && name.ends_with(&*elem.name)
                         }
-                        x => x,
+                        _ => name == &*elem.name,
                     };
-
-                    let elem = stack.pop().unwrap();
-                    if let Some(to) = stack.last_mut() {
-                        // TODO: check whether this is correct, we are comparing &[u8]s, not &strs
-                        let elem_name = e.name();
-                        let mut split_iter = elem_name.splitn(2, |u| *u == 0x3A);
-                        let possible_prefix = split_iter.next().unwrap(); // Can't be empty.
-                        let opening_prefix = {
-                            let mut tmp: Option<Option<String>> = None;
-                            for (prefix, ns) in prefixes {
-                                if ns == elem.namespace {
-                                    tmp = Some(prefix.clone());
-                                    break;
-                                }
-                            }
-                            match tmp {
-                                Some(prefix) => prefix,
-                                None => return Err(Error::InvalidPrefix),
-                            }
-                        };
-                        match split_iter.next() {
-                            // There is a prefix on the closing tag
-                            Some(name) => {
-                                // Does the closing prefix match the opening prefix?
-                                match opening_prefix {
-                                    Some(prefix) if possible_prefix == prefix.as_bytes() => (),
-                                    _ => return Err(Error::InvalidElementClosed),
-                                }
-                                // Does the closing tag name match the opening tag name?
-                                if name != elem.name().as_bytes() {
-                                    return Err(Error::InvalidElementClosed);
-                                }
-                            }
-                            // There was no prefix on the closing tag
-                            None => {
-                                // Is there a prefix on the opening tag?
-                                if opening_prefix.is_some() {
-                                    return Err(Error::InvalidElementClosed);
-                                }
-                                // Does the opening tag name match the closing one?
-                                if possible_prefix != elem.name().as_bytes() {
-                                    return Err(Error::InvalidElementClosed);
-                                }
-                            }
-                        }
-                        to.append_child(elem);
+                    if !matches {
+                        return Err(Error::InvalidElementClosed);
+                    }
+                    if lossless {
+                        elem.set_source_end(&format!("</{}>", name));
+                    }
+                    match stack.last_mut() {
+                        Some(parent) => parent.push_child(elem),
+                        None => return Ok(elem),
                     }
                 }
                 Event::Text(s) => {
                     let text = s.unescape_and_decode(reader)?;
+                    if text.len() > limits.max_text_length {
+                        return Err(ParserError::TextTooLong.into());
+                    }
                     if !text.is_empty() {
-                        let current_elem = stack.last_mut().unwrap();
-                        current_elem.append_text_node(text);
+                        let markup = reader.decode(s.escaped())?;
+                        stack.last_mut().unwrap().push_text(&text, markup);
                     }
                 }
                 Event::CData(s) => {
                     let text = s.unescape_and_decode(&reader)?;
+                    if text.len() > limits.max_text_length {
+                        return Err(ParserError::TextTooLong.into());
+                    }
                     if !text.is_empty() {
-                        let current_elem = stack.last_mut().unwrap();
-                        current_elem.append_text_node(text);
+                        let markup = format!("<![CDATA[{}]]>", text);
+                        stack.last_mut().unwrap().push_text(&text, &markup);
                     }
                 }
                 Event::Eof => {
                     break;
                 }
                 Event::Comment(_) => return Err(Error::NoComments),
-                Event::Decl { .. } | Event::PI { .. } | Event::DocType { .. } => (),
+                Event::PI { .. } => return Err(ParserError::ProcessingInstruction.into()),
+                Event::DocType { .. } => return Err(ParserError::DocType.into()),
+                Event::Decl { .. } => (),
             }
         }
         Ok(stack.pop().unwrap())
@@ -458,19 +658,268 @@ impl Element {
         self.write_to_inner(writer, &mut BTreeMap::new())
     }
 
+    /// Output a document to a `Writer`, putting each child element on its own line, indented
+    /// by one more `indent` than its parent.
+    ///
+    /// Elements containing text are written as is, to keep their content intact, but text made
+    /// only of whitespace gets replaced by the indentation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use minidom::Element;
+    ///
+    /// let elem: Element = "<message xmlns='jabber:client'><body>Hello</body><active xmlns='http://jabber.org/protocol/chatstates'/></message>".parse().unwrap();
+    ///
+    /// let mut writer = Vec::new();
+    /// elem.write_pretty(&mut writer, "  ").unwrap();
+    /// assert_eq!(String::from_utf8(writer).unwrap(), r#"<message xmlns="jabber:client">
+    ///   <body>Hello</body>
+    ///   <active xmlns="http://jabber.org/protocol/chatstates"/>
+    /// </message>"#);
+    /// ```
+    pub fn write_pretty<W: Write>(&self, writer: &mut W, indent: &str) -> Result<()> {
+        self.write_pretty_inner(
+            &mut EventWriter::new(writer),
+            &mut BTreeMap::new(),
+            indent,
+            0,
+        )
+    }
+
+    fn write_pretty_inner<W: Write>(
+        &self,
+        writer: &mut EventWriter<W>,
+        all_prefixes: &mut BTreeMap<Prefix, Namespace>,
+        indent: &str,
+        depth: usize,
+    ) -> Result<()> {
+        let mut children = self.children.iter().filter_map(Node::as_element).peekable();
+        let is_indentable = self.children.iter().all(|child| match child {
+            Node::Element(_) => true,
+            Node::Text(text) => text.trim().is_empty(),
+        });
+        if children.peek().is_none() || !is_indentable {
+            return self.write_to_inner(writer, all_prefixes);
+        }
+
+        let name = self.write_start(writer, all_prefixes, false)?;
+        let write_indent = |writer: &mut EventWriter<W>, depth| -> Result<()> {
+            writer.write(b"\n")?;
+            for _ in 0..depth {
+                writer.write(indent.as_bytes())?;
+            }
+            Ok(())
+        };
+        for child in children {
+            write_indent(writer, depth + 1)?;
+            child.write_pretty_inner(writer, &mut all_prefixes.clone(), indent, depth + 1)?;
+        }
+        write_indent(writer, depth)?;
+        writer.write_event(Event::End(BytesEnd::borrowed(name.as_bytes())))?;
+        Ok(())
+    }
+
+    /// Output a canonical form of this element to a `Writer`, suitable for hashing or signing,
+    /// following the rules of [Canonical XML](https://www.w3.org/TR/xml-c14n11/):
+    ///
+    /// - every element uses the default namespace, which is only declared where it changes,
+    /// - prefixes are only declared where an attribute uses them for the first time,
+    /// - namespace declarations come first, sorted by prefix, then attributes sorted by
+    ///   namespace and name,
+    /// - attribute values are always quoted with `"`, and text and attributes are escaped the
+    ///   same way whatever the input was,
+    /// - empty elements are written with a start and an end tag.
+    ///
+    /// Two elements comparing equal always give the same output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use minidom::Element;
+    ///
+    /// let elem: Element = "<root xmlns='ns1' b='2' a='1'><child/></root>".parse().unwrap();
+    ///
+    /// let mut writer = Vec::new();
+    /// elem.write_canonical(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     r#"<root xmlns="ns1" a="1" b="2"><child></child></root>"#,
+    /// );
+    /// ```
+    pub fn write_canonical<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.write_canonical_inner(writer, None, &BTreeMap::new(), &BTreeMap::new())
+    }
+
+    fn write_canonical_inner<W: Write>(
+        &self,
+        writer: &mut W,
+        default_namespace: Option<&str>,
+        in_scope: &BTreeMap<Prefix, Namespace>,
+        rendered: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let local_prefixes = self.prefixes.declared_prefixes();
+        let in_scope = if local_prefixes.is_empty() {
+            Cow::Borrowed(in_scope)
+        } else {
+            let mut in_scope = in_scope.clone();
+            in_scope.extend(local_prefixes.clone());
+            Cow::Owned(in_scope)
+        };
+        let mut rendered = Cow::Borrowed(rendered);
+
+        let mut declarations = BTreeMap::new();
+        let mut attributes = Vec::with_capacity(self.attributes.len());
+        for (name, value) in &self.attributes {
+            let (namespace, local) = match name.split_once(':') {
+                Some(("xml", local)) => (XML_NS, local),
+                Some((prefix, local)) => match in_scope.get(&Some(prefix.to_owned())) {
+                    Some(namespace) => {
+                        if rendered.get(prefix) != Some(namespace) {
+                            declarations.insert(prefix, namespace.as_str());
+                            rendered
+                                .to_mut()
+                                .insert(prefix.to_owned(), namespace.clone());
+                        }
+                        (namespace.as_str(), local)
+                    }
+                    None => ("", name.as_str()),
+                },
+                None => ("", name.as_str()),
+            };
+            attributes.push(((namespace, local), name, value));
+        }
+        attributes.sort_by_key(|(key, _, _)| *key);
+
+        writer.write_all(b"<")?;
+        writer.write_all(self.name.as_bytes())?;
+        if default_namespace != Some(&*self.namespace) {
+            write!(
+                writer,
+                " xmlns=\"{}\"",
+                canonical_escape(&self.namespace, true)
+            )?;
+        }
+        for (prefix, namespace) in declarations {
+            write!(
+                writer,
+                " xmlns:{}=\"{}\"",
+                prefix,
+                canonical_escape(namespace, true)
+            )?;
+        }
+        for (_, name, value) in attributes {
+            write!(writer, " {}=\"{}\"", name, canonical_escape(value, true))?;
+        }
+        writer.write_all(b">")?;
+
+        for child in &self.children {
+            match child {
+                Node::Element(child) => child.write_canonical_inner(
+                    writer,
+                    Some(&self.namespace),
+                    &in_scope,
+                    &rendered,
+                )?,
+                Node::Text(text) => writer.write_all(canonical_escape(text, false).as_bytes())?,
+            }
+        }
+
+        writer.write_all(b"</")?;
+        writer.write_all(self.name.as_bytes())?;
+        writer.write_all(b">")?;
+        Ok(())
+    }
+
     /// Like `write_to()` but without the `<?xml?>` prelude
     pub fn write_to_inner<W: Write>(
         &self,
         writer: &mut EventWriter<W>,
         all_prefixes: &mut BTreeMap<Prefix, Namespace>,
     ) -> Result<()> {
+        if let Some(ref source) = self.source {
+            if source.is_complete() {
+                return self.write_source(source, writer, all_prefixes);
+            }
+        }
+        let name = self.write_start(writer, all_prefixes, self.children.is_empty())?;
+        if self.children.is_empty() {
+            return Ok(());
+        }
+
+        for child in &self.children {
+            child.write_to_inner(writer, &mut all_prefixes.clone())?;
+        }
+
+        writer.write_event(Event::End(BytesEnd::borrowed(name.as_bytes())))?;
+        Ok(())
+    }
+
+    /// Writes this element exactly as it has been parsed, only adding the declarations of the
+    /// namespaces it inherited which aren’t in scope here.
+    fn write_source<W: Write>(
+        &self,
+        source: &Source,
+        writer: &mut EventWriter<W>,
+        all_prefixes: &mut BTreeMap<Prefix, Namespace>,
+    ) -> Result<()> {
+        let start = &*source.start;
+        let name_end = start
+            .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+            .unwrap_or(start.len());
+        writer.write(&start.as_bytes()[..name_end])?;
+        for (prefix, namespace) in &source.inherited {
+            if all_prefixes.get(prefix) == Some(namespace) {
+                continue;
+            }
+            match prefix {
+                None => writer.write(b" xmlns=\"")?,
+                Some(prefix) => {
+                    writer.write(b" xmlns:")?;
+                    writer.write(prefix.as_bytes())?;
+                    writer.write(b"=\"")?;
+                }
+            }
+            writer.write(&escape(namespace.as_bytes()))?;
+            writer.write(b"\"")?;
+            all_prefixes.insert(prefix.clone(), namespace.clone());
+        }
+        writer.write(&start.as_bytes()[name_end..])?;
+        for (prefix, namespace) in self.prefixes.declared_prefixes() {
+            all_prefixes.insert(prefix.clone(), namespace.clone());
+        }
+
+        let mut texts = source.texts.iter();
+        for node in &self.children {
+            match node {
+                Node::Element(child) => child.write_to_inner(writer, &mut all_prefixes.clone())?,
+                Node::Text(text) => match texts.next() {
+                    Some(markup) => writer.write(markup.as_bytes())?,
+                    None => writer.write(&escape(text.as_bytes()))?,
+                },
+            }
+        }
+        if let Some(ref end) = source.end {
+            writer.write(end.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the start tag of this element, declaring the prefixes which aren’t in
+    /// `all_prefixes` yet, and returns its name for the end tag.
+    fn write_start<W: Write>(
+        &self,
+        writer: &mut EventWriter<W>,
+        all_prefixes: &mut BTreeMap<Prefix, Namespace>,
+        empty: bool,
+    ) -> Result<Cow<'_, str>> {
         let local_prefixes: &BTreeMap<Option<String>, String> = self.prefixes.declared_prefixes();
 
         // Element namespace
         // If the element prefix hasn't been set yet via a custom prefix, add it.
         let mut existing_self_prefix: Option<Option<String>> = None;
         for (prefix, ns) in local_prefixes.iter().chain(all_prefixes.iter()) {
-            if ns == &self.namespace {
+            if **ns == *self.namespace {
                 existing_self_prefix = Some(prefix.clone());
             }
         }
@@ -501,7 +950,7 @@ impl Element {
 
         let name = match self_prefix {
             (Some(ref prefix), _) => Cow::Owned(format!("{}:{}", prefix, self.name)),
-            _ => Cow::Borrowed(&self.name),
+            _ => Cow::Borrowed(&*self.name),
         };
         let mut start = BytesStart::borrowed(name.as_bytes(), name.len());
 
@@ -510,12 +959,12 @@ impl Element {
             (Some(ref p), true) => {
                 let key = format!("xmlns:{}", p);
                 start.push_attribute((key.as_bytes(), self.namespace.as_bytes()));
-                all_prefixes.insert(self_prefix.0, self.namespace.clone());
+                all_prefixes.insert(self_prefix.0, self.namespace.to_string());
             }
             (None, true) => {
                 let key = String::from("xmlns");
                 start.push_attribute((key.as_bytes(), self.namespace.as_bytes()));
-                all_prefixes.insert(self_prefix.0, self.namespace.clone());
+                all_prefixes.insert(self_prefix.0, self.namespace.to_string());
             }
             _ => (),
         };
@@ -540,19 +989,12 @@ impl Element {
             start.push_attribute((key.as_bytes(), escape(value.as_bytes()).as_ref()));
         }
 
-        if self.children.is_empty() {
+        if empty {
             writer.write_event(Event::Empty(start))?;
-            return Ok(());
-        }
-
-        writer.write_event(Event::Start(start))?;
-
-        for child in &self.children {
-            child.write_to_inner(writer, &mut all_prefixes.clone())?;
+        } else {
+            writer.write_event(Event::Start(start))?;
         }
-
-        writer.write_event(Event::End(BytesEnd::borrowed(name.as_bytes())))?;
-        Ok(())
+        Ok(name)
     }
 
     /// Returns an iterator over references to every child node of this element.
@@ -581,6 +1023,7 @@ impl Element {
     /// Returns an iterator over mutable references to every child node of this element.
     #[inline]
     pub fn nodes_mut(&mut self) -> NodesMut {
+        self.source = None;
         self.children.iter_mut()
     }
 
@@ -638,6 +1081,7 @@ impl Element {
     /// Returns an iterator over mutable references to every text node of this element.
     #[inline]
     pub fn texts_mut(&mut self) -> TextsMut {
+        self.source = None;
         TextsMut {
             iter: self.children.iter_mut(),
         }
@@ -667,6 +1111,7 @@ impl Element {
     /// assert_eq!(child.name(), "new");
     /// ```
     pub fn append_child(&mut self, child: Element) -> &mut Element {
+        self.source = None;
         self.children.push(Node::Element(child));
         if let Node::Element(ref mut cld) = *self.children.last_mut().unwrap() {
             cld
@@ -691,6 +1136,7 @@ impl Element {
     /// assert_eq!(elem.text(), "text");
     /// ```
     pub fn append_text_node<S: Into<String>>(&mut self, child: S) {
+        self.source = None;
         self.children.push(Node::Text(child.into()));
     }
 
@@ -708,9 +1154,56 @@ impl Element {
     /// assert_eq!(elem.text(), "hello");
     /// ```
     pub fn append_node(&mut self, node: Node) {
+        self.source = None;
         self.children.push(node);
     }
 
+    /// Remembers the start tag this element has been parsed from, for the lossless mode.
+    pub(crate) fn set_source(&mut self, start: &str, inherited: Vec<(Prefix, Namespace)>) {
+        self.source = Some(Box::new(Source {
+            start: start.into(),
+            end: None,
+            inherited,
+            texts: Vec::new(),
+        }));
+    }
+
+    /// Remembers the end tag this element has been parsed from, for the lossless mode.
+    pub(crate) fn set_source_end(&mut self, end: &str) {
+        if let Some(ref mut source) = self.source {
+            source.end = Some(end.into());
+        }
+    }
+
+    /// Appends a parsed child, keeping the source of this element.
+    pub(crate) fn push_child(&mut self, child: Element) {
+        self.children.push(Node::Element(child));
+    }
+
+    /// Appends parsed text, merging it with the previous text node if there is one, and returns
+    /// the length of the resulting text node.
+    ///
+    /// `markup` is the text as received, kept for the lossless mode.
+    pub(crate) fn push_text(&mut self, text: &str, markup: &str) -> usize {
+        let merged = matches!(self.children.last(), Some(Node::Text(_)));
+        if let Some(ref mut source) = self.source {
+            match source.texts.last_mut() {
+                Some(previous) if merged => previous.push_str(markup),
+                _ => source.texts.push(markup.to_owned()),
+            }
+        }
+        match self.children.last_mut() {
+            Some(Node::Text(previous)) => {
+                previous.push_str(text);
+                previous.len()
+            }
+            _ => {
+                self.children.push(Node::Text(text.to_owned()));
+                text.len()
+            }
+        }
+    }
+
     /// Returns the concatenation of all text nodes in the `Element`.
     ///
     /// # Examples
@@ -776,6 +1269,27 @@ impl Element {
         None
     }
 
+    /// Returns everything `selector` matches in this `Element`, in document order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use minidom::{Element, Selector};
+    ///
+    /// let selector = Selector::new("node/a[ns=ns]/@id").unwrap();
+    /// let elem: Element = r#"<node xmlns="ns"><a id="1"/><a xmlns="other_ns" id="2"/><a id="3"/></node>"#.parse().unwrap();
+    /// let ids: Vec<_> = elem.select(&selector).iter().map(|id| id.as_str().unwrap().to_owned()).collect();
+    /// assert_eq!(ids, ["1", "3"]);
+    /// ```
+    pub fn select(&self, selector: &Selector) -> Vec<Match<'_>> {
+        selector.find_all(self)
+    }
+
+    /// Returns the first thing `selector` matches in this `Element`, if any.
+    pub fn select_first(&self, selector: &Selector) -> Option<Match<'_>> {
+        selector.find(self)
+    }
+
     /// Returns whether a specific child with this name and namespace exists in the direct
     /// descendants of the `Element`.
     ///
@@ -828,6 +1342,7 @@ impl Element {
                 false
             }
         })?;
+        self.source = None;
         self.children.remove(idx).into_element()
     }
 }
@@ -841,58 +1356,102 @@ fn split_element_name<S: AsRef<str>>(s: S) -> Result<(Option<String>, String)> {
     }
 }
 
+fn check_size<R: BufRead>(reader: &EventReader<R>, limits: &ParserLimits) -> Result<()> {
+    if reader.buffer_position() > limits.max_stanza_size {
+        return Err(ParserError::StanzaTooLarge.into());
+    }
+    Ok(())
+}
+
 fn build_element<R: BufRead>(
     reader: &EventReader<R>,
     event: &BytesStart,
     prefixes: &mut BTreeMap<Prefix, Namespace>,
+    limits: &ParserLimits,
+    lossless: bool,
+    empty: bool,
 ) -> Result<Element> {
+    if event.attributes().count() > limits.max_attributes {
+        return Err(ParserError::TooManyAttributes.into());
+    }
     let (prefix, name) = split_element_name(str::from_utf8(event.name())?)?;
     let mut local_prefixes = BTreeMap::new();
-
-    let attributes = event
-        .attributes()
-        .map(|o| {
-            let o = o?;
-            let key = str::from_utf8(o.key)?.to_owned();
-            let value = o.unescape_and_decode_value(reader)?;
-            Ok((key, value))
-        })
-        .filter(|o| match *o {
-            Ok((ref key, ref value)) if key == "xmlns" => {
-                local_prefixes.insert(None, value.clone());
-                prefixes.insert(None, value.clone());
-                false
-            }
-            Ok((ref key, ref value)) if key.starts_with("xmlns:") => {
-                local_prefixes.insert(None, value.to_owned());
-                prefixes.insert(None, value.to_owned());
-                false
-            }
-            _ => true,
-        })
-        .collect::<Result<BTreeMap<String, String>>>()?;
-
-    let namespace: String = {
-        if let Some(namespace) = local_prefixes.get(&prefix) {
-            namespace.clone()
-        } else if let Some(namespace) = prefixes.get(&prefix) {
-            namespace.clone()
+    let mut attributes = BTreeMap::new();
+    for attr in event.attributes() {
+        let attr = attr?;
+        let key = str::from_utf8(attr.key)?;
+        let value = attr.unescape_and_decode_value(reader)?;
+        if key == "xmlns" {
+            local_prefixes.insert(None, value);
+        } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+            local_prefixes.insert(Some(prefix.to_owned()), value);
         } else {
-            //return Err(Error::MissingNamespace);
-            "no namespace".to_string()
+            attributes.insert(key.to_owned(), value);
         }
+    }
+    for (prefix, namespace) in &local_prefixes {
+        prefixes.insert(prefix.clone(), namespace.clone());
+    }
+
+    let namespace = match (&prefix, prefixes.get(&prefix)) {
+        (_, Some(namespace)) => namespace.clone(),
+        (Some(prefix), None) if prefix == "xml" => String::from(XML_NS),
+        _ => return Err(Error::MissingNamespace),
     };
 
-    Ok(Element::new(
-        name,
-        namespace,
+    let inherited = if lossless {
+        inherited_namespaces(&prefix, attributes.keys(), &local_prefixes, prefixes)
+    } else {
+        Vec::new()
+    };
+
+    let mut elem = Element::new(
+        name.into(),
+        namespace.into(),
         // Note that this will always be Some(_) as we can't distinguish between the None case and
         // Some(None). At least we make sure the prefix has a namespace associated.
         Some(prefix),
         local_prefixes,
         attributes,
         Vec::new(),
-    ))
+    );
+    if lossless {
+        let content = reader.decode(event)?;
+        let start = if empty {
+            format!("<{}/>", content)
+        } else {
+            format!("<{}>", content)
+        };
+        elem.set_source(&start, inherited);
+    }
+    Ok(elem)
+}
+
+/// Lists the namespaces used by the prefix of an element or of its attributes, which haven’t
+/// been declared on this element itself but are in `scope`.
+pub(crate) fn inherited_namespaces<'a, K: Iterator<Item = &'a String>, N: AsRef<str>>(
+    prefix: &Prefix,
+    attributes: K,
+    local_prefixes: &BTreeMap<Prefix, String>,
+    scope: &BTreeMap<Prefix, N>,
+) -> Vec<(Prefix, Namespace)> {
+    let attribute_prefixes = attributes.filter_map(|key| {
+        key.split_once(':')
+            .map(|(prefix, _)| Some(prefix.to_owned()))
+    });
+    let mut inherited: Vec<(Prefix, Namespace)> = Vec::new();
+    for prefix in std::iter::once(prefix.clone()).chain(attribute_prefixes) {
+        if prefix.as_deref() == Some("xml")
+            || local_prefixes.contains_key(&prefix)
+            || inherited.iter().any(|(p, _)| *p == prefix)
+        {
+            continue;
+        }
+        if let Some(namespace) = scope.get(&prefix) {
+            inherited.push((prefix, namespace.as_ref().to_owned()));
+        }
+    }
+    inherited
 }
 
 /// An iterator over references to child elements of an `Element`.
@@ -1060,8 +1619,8 @@ mod tests {
         use std::iter::FromIterator;
 
         let elem = Element::new(
-            "name".to_owned(),
-            "namespace".to_owned(),
+            Arc::from("name"),
+            Arc::from("namespace"),
             None,
             (None, "namespace".to_owned()),
             BTreeMap::from_iter(vec![("name".to_string(), "value".to_string())].into_iter()),
@@ -1164,4 +1723,20 @@ mod tests {
         assert_eq!(elem, elem3);
         assert_eq!(elem, elem4);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() {
+        use serde::de::{value::Error as ValueError, IntoDeserializer};
+        use serde::Deserialize;
+
+        let xml = "<message xmlns='jabber:client'><body>Hello</body></message>";
+        let elem: std::result::Result<Element, ValueError> =
+            Element::deserialize(xml.into_deserializer());
+        assert_eq!(elem.unwrap(), xml.parse::<Element>().unwrap());
+
+        let elem: std::result::Result<Element, ValueError> =
+            Element::deserialize("<message>".into_deserializer());
+        assert!(elem.is_err());
+    }
 }
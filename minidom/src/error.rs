@@ -47,6 +47,114 @@ pub enum Error {
 
     /// An error which is returned when a prefixed is defined twice
     DuplicatePrefix,
+
+    /// An error which is returned by the streaming `Parser`.
+    ParserError(ParserError),
+
+    /// An error which is returned when a `Selector` can’t be compiled.
+    InvalidSelector(&'static str),
+}
+
+/// An error specific to the streaming `Parser`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParserError {
+    /// Data was fed after the root element has been closed, or after an error.
+    Closed,
+
+    /// Text which isn’t whitespace has been found outside of the root element.
+    UnexpectedText,
+
+    /// A stanza is larger than `ParserLimits::max_stanza_size`.
+    StanzaTooLarge,
+
+    /// Elements are nested deeper than `ParserLimits::max_depth`.
+    TooDeep,
+
+    /// An element has more attributes than `ParserLimits::max_attributes`.
+    TooManyAttributes,
+
+    /// A text node is longer than `ParserLimits::max_text_length`.
+    TextTooLong,
+
+    /// A document type declaration has been found, which XMPP forbids along with the entities it
+    /// could declare.
+    DocType,
+
+    /// A processing instruction other than the XML declaration has been found, which XMPP
+    /// forbids.
+    ProcessingInstruction,
+}
+
+impl StdError for ParserError {}
+
+impl std::fmt::Display for ParserError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParserError::Closed => write!(fmt, "the parser has already been closed"),
+            ParserError::UnexpectedText => write!(fmt, "text found outside of the root element"),
+            ParserError::StanzaTooLarge => write!(fmt, "the stanza is too large"),
+            ParserError::TooDeep => write!(fmt, "the elements are nested too deeply"),
+            ParserError::TooManyAttributes => write!(fmt, "the element has too many attributes"),
+            ParserError::TextTooLong => write!(fmt, "the text is too long"),
+            ParserError::DocType => write!(fmt, "document type declarations are forbidden"),
+            ParserError::ProcessingInstruction => {
+                write!(fmt, "processing instructions are forbidden")
+            }
+        }
+    }
+}
+
+/// An error returned by `Element::attr_as()` and `Element::required_attr()`, naming the element
+/// and the attribute which couldn’t be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeError<E> {
+    /// The attribute isn’t present on the element.
+    Missing {
+        /// Name of the element.
+        element: String,
+        /// Name of the missing attribute.
+        attribute: String,
+    },
+
+    /// The value of the attribute couldn’t be parsed.
+    Invalid {
+        /// Name of the element.
+        element: String,
+        /// Name of the invalid attribute.
+        attribute: String,
+        /// The error returned by `FromAttributeValue`.
+        error: E,
+    },
+}
+
+impl<E: StdError + 'static> StdError for AttributeError<E> {
+    fn cause(&self) -> Option<&dyn StdError> {
+        match self {
+            AttributeError::Missing { .. } => None,
+            AttributeError::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for AttributeError<E> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AttributeError::Missing { element, attribute } => write!(
+                fmt,
+                "required attribute '{}' missing in {} element",
+                attribute, element
+            ),
+            AttributeError::Invalid {
+                element,
+                attribute,
+                error,
+            } => write!(
+                fmt,
+                "invalid attribute '{}' in {} element: {}",
+                attribute, element, error
+            ),
+        }
+    }
 }
 
 impl StdError for Error {
@@ -62,6 +170,8 @@ impl StdError for Error {
             Error::MissingNamespace => None,
             Error::NoComments => None,
             Error::DuplicatePrefix => None,
+            Error::ParserError(e) => Some(e),
+            Error::InvalidSelector(_) => None,
         }
     }
 }
@@ -86,6 +196,8 @@ impl std::fmt::Display for Error {
                 "a comment has been found even though comments are forbidden"
             ),
             Error::DuplicatePrefix => write!(fmt, "the prefix is already defined"),
+            Error::ParserError(e) => write!(fmt, "parser error: {}", e),
+            Error::InvalidSelector(e) => write!(fmt, "invalid selector: {}", e),
         }
     }
 }
@@ -96,6 +208,12 @@ impl From<::quick_xml::Error> for Error {
     }
 }
 
+impl From<ParserError> for Error {
+    fn from(err: ParserError) -> Error {
+        Error::ParserError(err)
+    }
+}
+
 impl From<::std::str::Utf8Error> for Error {
     fn from(err: ::std::str::Utf8Error) -> Error {
         Error::Utf8Error(err)
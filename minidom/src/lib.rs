@@ -82,13 +82,19 @@ pub mod element;
 pub mod error;
 mod namespaces;
 pub mod node;
+pub mod parser;
 mod prefixes;
+pub mod query;
+pub mod writer;
 
 #[cfg(test)]
 mod tests;
 
-pub use convert::IntoAttributeValue;
+pub use convert::{FromAttributeValue, IntoAttributeValue};
 pub use element::{Children, ChildrenMut, Element, ElementBuilder};
-pub use error::{Error, Result};
+pub use error::{AttributeError, Error, ParserError, Result};
 pub use namespaces::NSChoice;
 pub use node::Node;
+pub use parser::{Parser, ParserLimits, ParserResult};
+pub use query::{Match, Selector};
+pub use writer::{AsXml, XmlWriter};
@@ -7,147 +7,549 @@
 
 //! Provides a `Parser` type, which takes bytes and returns Elements. It also keeps a hold of
 //! ascendant elements to be able to handle namespaces properly.
+//!
+//! The parser is push-based: bytes are passed to `Parser::feed()` as they arrive, in chunks of any
+//! size, and `Parser::poll()` then returns everything which could be completed so far.  Only the
+//! bytes of the token currently being received are kept around, so the whole stream is never
+//! re-parsed.
 
-use crate::element::Element;
+use crate::element::{inherited_namespaces, Element};
 use crate::error::{Error, ParserError, Result};
+use crate::prefixes::Prefix;
 
 use bytes::BytesMut;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader as EventReader;
-use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::str;
+use std::sync::Arc;
+
+/// The namespace bound to the xml prefix, which never needs to be declared.
+pub(crate) const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Maximum number of distinct element names remembered by a parser, so that a peer sending random
+/// names can’t make it grow forever.
+const MAX_INTERNED_NAMES: usize = 1024;
+
+/// Namespaces in scope for an element, shared with its children unless they declare new ones.
+type Scope = Arc<BTreeMap<Prefix, Arc<str>>>;
+
+/// The parts of a qualified element name, shared by all elements with this name.
+#[derive(Debug, Clone)]
+struct Name {
+    qualified: Arc<str>,
+    prefix: Prefix,
+    local: Arc<str>,
+}
+
+/// Bounds on what the parser accepts, so that a hostile peer can’t make it use an unbounded
+/// amount of memory.
+///
+/// Exceeding any of them makes the parser return the matching `ParserError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserLimits {
+    /// Maximum size in bytes of each child of the root element, and of the root element’s start
+    /// tag.
+    pub max_stanza_size: usize,
+
+    /// Maximum nesting depth of elements, the root element being at depth 1.
+    pub max_depth: usize,
+
+    /// Maximum number of attributes on a single element, including namespace declarations.
+    pub max_attributes: usize,
+
+    /// Maximum length in bytes of a single text node.
+    pub max_text_length: usize,
+}
+
+impl Default for ParserLimits {
+    fn default() -> ParserLimits {
+        ParserLimits {
+            max_stanza_size: 256 * 1024,
+            max_depth: 64,
+            max_attributes: 64,
+            max_text_length: 128 * 1024,
+        }
+    }
+}
 
 /// Parser
 #[derive(Debug)]
 pub struct Parser {
-    buffer: RefCell<BytesMut>,
+    buffer: BytesMut,
     state: ParserState,
+    limits: ParserLimits,
+    /// Whether elements remember the markup they have been parsed from.
+    lossless: bool,
+    /// Size of the stanza currently being received.
+    size: usize,
+    /// Qualified names of the currently open elements, including the root one.
+    names: Vec<Arc<str>>,
+    /// Namespaces in scope for each open element, including the root one.
+    scopes: Vec<Scope>,
+    /// Element names seen so far, so that they are allocated only once.
+    interned_names: HashMap<String, Name>,
+    /// Buffer used by quick-xml to read each tag.
+    tag_buffer: Vec<u8>,
+    /// Children of the root element which are still being built.
+    stack: Vec<Element>,
+    /// Results which are ready but haven't been polled yet.
+    queue: VecDeque<ParserResult>,
 }
 
 /// Describes the state of the parser.
 ///
-/// This parser will only accept one-level documents. The root element is kept for convenience, to
-/// be able to pass namespaces down to children who are themselves children.
-#[derive(Debug)]
-pub enum ParserState {
-    /// Not enough data has been processed to find the first element.
-    Empty,
-
-    /// The normal state. the root element has been identified and children are processed.
-    Root {
-        /// Root element. Kept for future reference
-        root: Element,
-
-        /// Child element
-        child: Option<Element>,
+/// This parser will only return the direct children of the root element. The root element is
+/// returned first, without any children, so that its attributes are available as soon as it has
+/// been received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParserState {
+    /// Not enough data has been processed to find the root element.
+    Prolog,
 
-        /// XXX: Weird flag to say if we've already sent what we could send or if there's more to
-        /// send. This Variant needs to be changed.
-        sent: bool,
-    },
+    /// The normal state, the root element has been opened and its children are processed.
+    Root,
 
-    /// Something was passed in the buffer that made the parser get into an error state.
-    Error,
-
-    /// The root element has been closed. No feed-ing can happen past this point.
+    /// The root element has been closed, or an error happened. No feed-ing can happen past this
+    /// point.
     Closed,
 }
 
 /// Result of polling the parser
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParserResult {
-    /// Buffer is not empty but needs more data
-    Partial,
+    /// The root element has been opened, it is returned without any children.
+    StreamStart(Element),
 
-    /// An Element has been generated from the buffer.
+    /// A complete child of the root element has been received.
     Single(Element),
+
+    /// Some text has been received between two children of the root element, usually whitespace
+    /// keep-alives.
+    Text(String),
+
+    /// The root element has been closed.
+    StreamEnd,
+}
+
+/// The kinds of tokens the buffer gets split into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Text,
+    Tag,
+    Comment,
+    CData,
+    ProcessingInstruction,
+    DocType,
 }
 
-/*
-/// Split <stream:stream> and parse it.
-fn split_stream_stream_stream_features(string: String) -> (Element, Element) {
-    let mut stuff = string.splitn(2, '>');
-    let stream_opening_str = stuff.next().unwrap().to_string() + "/>";
-    let rest = stuff.next().unwrap().to_string();
-    let stream_opening: Element = stream_opening_str.parse().unwrap();
-    let rest: Element = rest.parse().unwrap();
-    println!("opening: {}", String::from(&stream_opening));
-    println!("features: {}", String::from(&rest));
-    (stream_opening, rest)
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Finds the next complete token at the start of `buf`, along with its length, or None if more
+/// data is needed.
+fn next_token(buf: &[u8]) -> Option<(Token, usize)> {
+    if buf.is_empty() {
+        return None;
+    }
+    if buf[0] != b'<' {
+        return text_len(buf).map(|len| (Token::Text, len));
+    }
+    for (start, end, token) in [
+        (&b"<!--"[..], &b"-->"[..], Token::Comment),
+        (b"<![CDATA[", b"]]>", Token::CData),
+        (b"<?", b"?>", Token::ProcessingInstruction),
+    ]
+    .iter()
+    {
+        if buf.len() < start.len() && start.starts_with(buf) {
+            // We can’t know yet which token this is.
+            return None;
+        }
+        if buf.starts_with(start) {
+            return find(&buf[start.len()..], end).map(|i| (*token, start.len() + i + end.len()));
+        }
+    }
+    if buf.starts_with(b"<!") {
+        // Skip over the internal subset of a DTD, which may contain >.
+        let mut depth = 0usize;
+        for (i, b) in buf.iter().enumerate() {
+            match b {
+                b'[' => depth += 1,
+                b']' => depth = depth.saturating_sub(1),
+                b'>' if depth == 0 => return Some((Token::DocType, i + 1)),
+                _ => (),
+            }
+        }
+        return None;
+    }
+    // Attribute values may contain >, so skip over them.
+    let mut quote = None;
+    for (i, &b) in buf.iter().enumerate() {
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => (),
+            None => match b {
+                b'"' | b'\'' => quote = Some(b),
+                b'>' => return Some((Token::Tag, i + 1)),
+                _ => (),
+            },
+        }
+    }
+    None
 }
-*/
 
-fn maybe_split_prolog(string: &str) -> &str {
-    if string.starts_with("<?xml") {
-        let mut stuff = string.splitn(2, '>');
-        stuff.next();
-        stuff.next().unwrap()
+/// Length of the text at the start of `buf` which can already be processed: everything until the
+/// next tag, or if it hasn’t been received yet, everything except a truncated entity or UTF-8
+/// sequence.
+fn text_len(buf: &[u8]) -> Option<usize> {
+    if let Some(i) = buf.iter().position(|&b| b == b'<') {
+        return Some(i);
+    }
+    let mut len = buf.len();
+    if let Some(amp) = buf.iter().rposition(|&b| b == b'&') {
+        if !buf[amp..].contains(&b';') {
+            len = amp;
+        }
+    }
+    if let Err(e) = str::from_utf8(&buf[..len]) {
+        if e.error_len().is_none() {
+            len = e.valid_up_to();
+        }
+    }
+    if len == 0 {
+        None
     } else {
-        string
+        Some(len)
+    }
+}
+
+/// Whether this processing instruction is the XML declaration.
+fn is_decl(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"<?xml")
+        && matches!(bytes.get(5), Some(b) if b.is_ascii_whitespace() || *b == b'?')
+}
+
+fn split_name(name: &str) -> Result<(Prefix, &str)> {
+    let mut parts = name.splitn(2, ':');
+    let first = parts.next().unwrap();
+    match parts.next() {
+        Some(local) if local.contains(':') => Err(Error::InvalidElement),
+        Some(local) => Ok((Some(first.to_owned()), local)),
+        None => Ok((None, first)),
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
     }
 }
 
 impl Parser {
-    /// Creates a new Parser
+    /// Creates a new Parser, with the default limits.
     pub fn new() -> Parser {
+        Parser::with_limits(ParserLimits::default())
+    }
+
+    /// Creates a new Parser, with custom limits.
+    pub fn with_limits(limits: ParserLimits) -> Parser {
         Parser {
-            buffer: RefCell::new(BytesMut::new()),
-            state: ParserState::Empty,
+            buffer: BytesMut::new(),
+            state: ParserState::Prolog,
+            limits,
+            lossless: false,
+            size: 0,
+            names: Vec::new(),
+            scopes: Vec::new(),
+            interned_names: HashMap::new(),
+            tag_buffer: Vec::new(),
+            stack: Vec::new(),
+            queue: VecDeque::new(),
         }
     }
 
+    /// Enables or disables the lossless mode, in which every element remembers the exact markup it
+    /// has been parsed from, see `Element::from_reader_lossless()`.
+    ///
+    /// This only applies to elements started after this call.
+    pub fn set_lossless(&mut self, lossless: bool) {
+        self.lossless = lossless;
+    }
+
     /// Feed bytes to the parser.
+    ///
+    /// They don’t need to be split on any particular boundary, a tag, an entity or even a
+    /// character can be split between two calls.
     pub fn feed(&mut self, bytes: BytesMut) -> Result<()> {
-        self.buffer.borrow_mut().unsplit(bytes);
-        let state = match self.state {
-            ParserState::Empty => {
-                // TODO: Try splitting xml prolog and stream header
-                let foo = self.buffer.borrow();
-                let header = maybe_split_prolog(str::from_utf8(foo.as_ref())?);
-                println!("FOO: header: {:?}", header);
-                let mut reader = EventReader::from_str(header);
-                let root = Element::from_reader(&mut reader);
-                match root {
-                    Ok(root) => {
-                        println!("FOO: elem: {:?}", root);
-                        ParserState::Root {
-                            root,
-                            child: None,
-                            sent: false,
-                        }
-                    }
-                    Err(e) => {
-                        println!("FOO: err: {:?}", e);
-                        ParserState::Empty
-                    }
+        if self.state == ParserState::Closed {
+            return Err(Error::ParserError(ParserError::Closed));
+        }
+        self.buffer.unsplit(bytes);
+        Ok(())
+    }
+
+    /// Returns the next result which could be completed from the bytes fed so far, or None if
+    /// more data is needed.
+    ///
+    /// Once an error has been returned, the parser is closed and won’t return anything else.
+    pub fn poll(&mut self) -> Result<Option<ParserResult>> {
+        loop {
+            if let Some(result) = self.queue.pop_front() {
+                return Ok(Some(result));
+            }
+            if self.state == ParserState::Closed {
+                return Ok(None);
+            }
+            let (token, len) = match next_token(&self.buffer) {
+                Some(token) => token,
+                None if self.size + self.buffer.len() > self.limits.max_stanza_size => {
+                    self.close();
+                    return Err(ParserError::StanzaTooLarge.into());
                 }
+                None => return Ok(None),
+            };
+            self.size += len;
+            if self.size > self.limits.max_stanza_size {
+                self.close();
+                return Err(ParserError::StanzaTooLarge.into());
             }
-            ParserState::Closed => return Err(Error::ParserError(ParserError::Closed)),
-            _ => ParserState::Empty,
-        };
+            let bytes = self.buffer.split_to(len);
+            if let Err(err) = self.process(token, &bytes) {
+                self.close();
+                return Err(err);
+            }
+        }
+    }
 
-        self.state = state;
+    /// Resets the parser, keeping its limits and mode.
+    pub fn reset(&mut self) {
+        let lossless = self.lossless;
+        *self = Parser::with_limits(self.limits.clone());
+        self.lossless = lossless;
+    }
+
+    fn close(&mut self) {
+        self.state = ParserState::Closed;
+        self.buffer.clear();
+        self.names.clear();
+        self.scopes.clear();
+        self.stack.clear();
+    }
+
+    fn process(&mut self, token: Token, bytes: &[u8]) -> Result<()> {
+        match token {
+            Token::Text => {
+                let text =
+                    quick_xml::escape::unescape(bytes).map_err(quick_xml::Error::EscapeError)?;
+                self.text(str::from_utf8(&text)?, bytes)
+            }
+            Token::CData => {
+                let text = &bytes[b"<![CDATA[".len()..bytes.len() - b"]]>".len()];
+                self.text(str::from_utf8(text)?, bytes)
+            }
+            Token::Tag => self.tag(bytes),
+            Token::Comment => Err(Error::NoComments),
+            // Only the XML declaration is allowed, before the root element.
+            Token::ProcessingInstruction if self.state == ParserState::Prolog && is_decl(bytes) => {
+                self.size = 0;
+                Ok(())
+            }
+            // RFC 6120 §11.1 forbids these, DTDs are also where entities would be declared.
+            Token::ProcessingInstruction => Err(ParserError::ProcessingInstruction.into()),
+            Token::DocType => Err(ParserError::DocType.into()),
+        }
+    }
+
+    fn text(&mut self, text: &str, markup: &[u8]) -> Result<()> {
+        let max_text_length = self.limits.max_text_length;
+        if let Some(elem) = self.stack.last_mut() {
+            let len = elem.push_text(text, str::from_utf8(markup)?);
+            if len > max_text_length {
+                return Err(ParserError::TextTooLong.into());
+            }
+            return Ok(());
+        }
+        // Text outside of any stanza doesn’t count towards the next stanza’s size.
+        self.size = 0;
+        if text.len() > max_text_length {
+            return Err(ParserError::TextTooLong.into());
+        }
+        match self.state {
+            ParserState::Root => self.queue.push_back(ParserResult::Text(text.to_owned())),
+            // Whitespace is allowed around the root element.
+            _ if text.trim().is_empty() => (),
+            _ => return Err(Error::ParserError(ParserError::UnexpectedText)),
+        }
         Ok(())
     }
 
-    /// Returns Elements to the application.
-    pub fn poll(&mut self) -> Result<Option<ParserResult>> {
-        match &self.state {
-            ParserState::Empty if self.buffer.borrow().len() != 0 => {
-                Ok(Some(ParserResult::Partial))
+    fn tag(&mut self, bytes: &[u8]) -> Result<()> {
+        // Reuse the same buffer for every tag.
+        let mut buf = std::mem::take(&mut self.tag_buffer);
+        let result = self.tag_with_buffer(bytes, &mut buf);
+        buf.clear();
+        self.tag_buffer = buf;
+        result
+    }
+
+    fn tag_with_buffer(&mut self, bytes: &[u8], buf: &mut Vec<u8>) -> Result<()> {
+        let mut reader = EventReader::from_reader(bytes);
+        reader.check_end_names(false);
+        let mut event = reader.read_event(buf)?;
+        // quick-xml reports the empty text before the tag.
+        if let Event::Text(ref text) = event {
+            if text.is_empty() {
+                event = reader.read_event(buf)?;
             }
-            ParserState::Empty | ParserState::Closed | ParserState::Error => Ok(None),
-            ParserState::Root {
-                root, child: None, ..
-            } => Ok(Some(ParserResult::Single(root.clone()))),
-            ParserState::Root {
-                child: Some(child), ..
-            } => Ok(Some(ParserResult::Single(child.clone()))),
         }
+        match event {
+            Event::Start(ref start) => {
+                let (name, elem, scope) = self.build_element(&reader, start, bytes)?;
+                self.start(name, elem, scope)?;
+            }
+            Event::Empty(ref start) => {
+                let (name, elem, scope) = self.build_element(&reader, start, bytes)?;
+                self.start(name, elem, scope)?;
+                self.end(bytes)?;
+            }
+            Event::End(ref end) => {
+                let name = str::from_utf8(end.name())?;
+                if self.names.last().map(|name| &**name) != Some(name) {
+                    return Err(Error::InvalidElementClosed);
+                }
+                self.end(bytes)?;
+            }
+            _ => return Err(Error::InvalidElement),
+        }
+        Ok(())
     }
 
-    /// Resets the parser
-    pub fn reset(&mut self) {
-        *self = Parser::new();
+    fn intern_name(&mut self, qualified: &str) -> Result<Name> {
+        if let Some(name) = self.interned_names.get(qualified) {
+            return Ok(name.clone());
+        }
+        let (prefix, local) = split_name(qualified)?;
+        let name = Name {
+            qualified: Arc::from(qualified),
+            prefix,
+            local: Arc::from(local),
+        };
+        if self.interned_names.len() < MAX_INTERNED_NAMES {
+            self.interned_names
+                .insert(qualified.to_owned(), name.clone());
+        }
+        Ok(name)
+    }
+
+    fn build_element(
+        &mut self,
+        reader: &EventReader<&[u8]>,
+        start: &BytesStart,
+        markup: &[u8],
+    ) -> Result<(Arc<str>, Element, Scope)> {
+        let Name {
+            qualified,
+            prefix,
+            local,
+        } = self.intern_name(str::from_utf8(start.name())?)?;
+
+        let mut local_prefixes = BTreeMap::new();
+        let mut attributes = BTreeMap::new();
+        for (i, attr) in start.attributes().enumerate() {
+            if i >= self.limits.max_attributes {
+                return Err(ParserError::TooManyAttributes.into());
+            }
+            let attr = attr?;
+            let key = str::from_utf8(attr.key)?;
+            let value = attr.unescape_and_decode_value(reader)?;
+            if key == "xmlns" {
+                local_prefixes.insert(None, value);
+            } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+                local_prefixes.insert(Some(prefix.to_owned()), value);
+            } else {
+                attributes.insert(key.to_owned(), value);
+            }
+        }
+
+        let mut scope = self.scopes.last().cloned().unwrap_or_default();
+        if !local_prefixes.is_empty() {
+            let scope = Arc::make_mut(&mut scope);
+            for (prefix, namespace) in &local_prefixes {
+                scope.insert(prefix.clone(), Arc::from(namespace.as_str()));
+            }
+        }
+        let namespace = match (&prefix, scope.get(&prefix)) {
+            (_, Some(namespace)) => namespace.clone(),
+            (Some(prefix), None) if prefix == "xml" => Arc::from(XML_NS),
+            _ => return Err(Error::MissingNamespace),
+        };
+
+        let inherited = if self.lossless {
+            inherited_namespaces(&prefix, attributes.keys(), &local_prefixes, &scope)
+        } else {
+            Vec::new()
+        };
+        let mut elem = Element::new(
+            local,
+            namespace,
+            Some(prefix),
+            local_prefixes,
+            attributes,
+            Vec::new(),
+        );
+        if self.lossless {
+            elem.set_source(str::from_utf8(markup)?, inherited);
+        }
+        Ok((qualified, elem, scope))
+    }
+
+    fn start(&mut self, name: Arc<str>, elem: Element, scope: Scope) -> Result<()> {
+        if self.names.len() >= self.limits.max_depth {
+            return Err(ParserError::TooDeep.into());
+        }
+        self.names.push(name);
+        self.scopes.push(scope);
+        if self.state == ParserState::Prolog {
+            self.state = ParserState::Root;
+            self.size = 0;
+            self.queue.push_back(ParserResult::StreamStart(elem));
+        } else {
+            self.stack.push(elem);
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, markup: &[u8]) -> Result<()> {
+        self.names.pop();
+        self.scopes.pop();
+        match self.stack.pop() {
+            // </stream:stream>
+            None => {
+                self.close();
+                self.queue.push_back(ParserResult::StreamEnd);
+            }
+            Some(mut elem) => {
+                // The end tag of an empty element tag is the start tag itself.
+                if !markup.ends_with(b"/>") {
+                    elem.set_source_end(str::from_utf8(markup)?);
+                }
+                match self.stack.last_mut() {
+                    // </stanza>
+                    None => {
+                        self.size = 0;
+                        self.queue.push_back(ParserResult::Single(elem));
+                    }
+                    Some(parent) => {
+                        parent.push_child(elem);
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 }
 
@@ -156,29 +558,271 @@ mod tests {
     use super::*;
     use bytes::{BufMut, BytesMut};
 
+    const STREAM_START: &[u8] = b"<?xml version='1.0'?><stream:stream xmlns='jabber:client' xml:lang='en' xmlns:stream='http://etherx.jabber.org/streams' version='1.0' to='foo.bar'>";
+
+    fn feed(parser: &mut Parser, bytes: &[u8]) {
+        let mut buf = BytesMut::new();
+        buf.put(bytes);
+        parser.feed(buf).unwrap();
+    }
+
+    fn poll_all(parser: &mut Parser) -> Vec<ParserResult> {
+        let mut results = Vec::new();
+        while let Some(result) = parser.poll().unwrap() {
+            results.push(result);
+        }
+        results
+    }
+
     #[test]
     fn test_prolog() {
         let mut parser = Parser::new();
-        let mut buf = BytesMut::new();
-        buf.put(&b"<?xml version='1.0'?>"[..]);
-        buf.put(&b"<stream:stream xmlns='jabber:client' xml:lang='en' xmlns:stream='http://etherx.jabber.org/streams' version='1.0' to='foo.bar'>"[..]);
-        match parser.feed(buf) {
-            Ok(_) => (),
-            _ => panic!(),
-        }
+        feed(&mut parser, STREAM_START);
 
-        let elem = Element::builder("stream:stream", "http://etherx.jabber.org/streams")
-            .prefix_ns(None, "jabber:client")
+        let elem = Element::builder("stream", "http://etherx.jabber.org/streams")
             .attr("xml:lang", "en")
             .attr("version", "1.0")
             .attr("to", "foo.bar")
             .build();
 
-        println!("BAR: elem: {:?}", elem);
+        match parser.poll() {
+            Ok(Some(ParserResult::StreamStart(e))) => {
+                assert_eq!(e, elem);
+                let prefixes = e.declared_prefixes();
+                assert_eq!(prefixes.get(&None).unwrap(), "jabber:client");
+            }
+            _ => panic!(),
+        }
+        assert!(parser.poll().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_children() {
+        let mut parser = Parser::new();
+        feed(&mut parser, STREAM_START);
+        feed(
+            &mut parser,
+            b"<stream:features><starttls xmlns='urn:ietf:params:xml:ns:xmpp-tls'/></stream:features>\n<message type='chat'><body>a &amp; b</body></message></stream:stream>",
+        );
+
+        let results = poll_all(&mut parser);
+        assert_eq!(results.len(), 5);
+        match &results[1] {
+            ParserResult::Single(features) => {
+                assert!(features.is("features", "http://etherx.jabber.org/streams"));
+                assert!(features.has_child("starttls", "urn:ietf:params:xml:ns:xmpp-tls"));
+            }
+            _ => panic!(),
+        }
+        assert_eq!(results[2], ParserResult::Text(String::from("\n")));
+        match &results[3] {
+            ParserResult::Single(message) => {
+                assert!(message.is("message", "jabber:client"));
+                assert_eq!(message.attr("type"), Some("chat"));
+                let body = message.get_child("body", "jabber:client").unwrap();
+                assert_eq!(body.text(), "a & b");
+            }
+            _ => panic!(),
+        }
+        assert_eq!(results[4], ParserResult::StreamEnd);
+
+        match parser.feed(BytesMut::new()) {
+            Err(Error::ParserError(ParserError::Closed)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_byte_by_byte() {
+        let data = "<message xml:lang='fr'><body>é &lt; ß<![CDATA[<&>]]></body><x xmlns='urn:x' a='>'/></message>".as_bytes();
+
+        let mut parser = Parser::new();
+        feed(&mut parser, STREAM_START);
+        let mut results = poll_all(&mut parser);
+        for byte in data {
+            feed(&mut parser, &[*byte]);
+            results.extend(poll_all(&mut parser));
+        }
+
+        assert_eq!(results.len(), 2);
+        match &results[1] {
+            ParserResult::Single(message) => {
+                assert_eq!(message.attr("xml:lang"), Some("fr"));
+                let body = message.get_child("body", "jabber:client").unwrap();
+                assert_eq!(body.nodes().count(), 1);
+                assert_eq!(body.text(), "é < ß<&>");
+                let x = message.get_child("x", "urn:x").unwrap();
+                assert_eq!(x.attr("a"), Some(">"));
+            }
+            _ => panic!(),
+        }
+    }
 
+    #[test]
+    fn test_invalid() {
+        let mut parser = Parser::new();
+        feed(&mut parser, STREAM_START);
+        feed(&mut parser, b"<message></iq>");
+        assert!(parser.poll().unwrap().is_some());
+        match parser.poll() {
+            Err(Error::InvalidElementClosed) => (),
+            _ => panic!(),
+        }
+        assert!(parser.poll().unwrap().is_none());
+
+        let mut parser = Parser::new();
+        feed(&mut parser, b"<root xmlns='ns'><!-- comment -->");
+        assert!(parser.poll().unwrap().is_some());
         match parser.poll() {
-            Ok(Some(ParserResult::Single(e))) => assert_eq!(e, elem),
+            Err(Error::NoComments) => (),
             _ => panic!(),
         }
+
+        let mut parser = Parser::new();
+        feed(&mut parser, b"<root xmlns='ns'><foo:bar/>");
+        assert!(parser.poll().unwrap().is_some());
+        match parser.poll() {
+            Err(Error::MissingNamespace) => (),
+            _ => panic!(),
+        }
+
+        let mut parser = Parser::new();
+        feed(&mut parser, b"text<root xmlns='ns'>");
+        match parser.poll() {
+            Err(Error::ParserError(ParserError::UnexpectedText)) => (),
+            _ => panic!(),
+        }
+    }
+
+    fn limit_error(limits: ParserLimits, data: &[u8]) -> ParserError {
+        let mut parser = Parser::with_limits(limits);
+        feed(&mut parser, b"<root xmlns='ns'>");
+        assert!(parser.poll().unwrap().is_some());
+        feed(&mut parser, data);
+        loop {
+            match parser.poll() {
+                Ok(Some(_)) => (),
+                Ok(None) => panic!("no error"),
+                Err(Error::ParserError(err)) => return err,
+                Err(err) => panic!("wrong error: {:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = ParserLimits {
+            max_stanza_size: 64,
+            max_depth: 3,
+            max_attributes: 2,
+            max_text_length: 8,
+        };
+
+        let mut parser = Parser::with_limits(limits.clone());
+        feed(&mut parser, b"<root xmlns='ns'>");
+        feed(&mut parser, b"<a><b c='d' e='f'>12345678</b></a>   <a/>");
+        assert_eq!(poll_all(&mut parser).len(), 4);
+
+        assert_eq!(
+            limit_error(limits.clone(), &[b'a'; 65][..]),
+            ParserError::StanzaTooLarge
+        );
+        assert_eq!(
+            limit_error(
+                limits.clone(),
+                b"<message><body>01234567</body><body>01234567</body><body>01234567</body>"
+            ),
+            ParserError::StanzaTooLarge
+        );
+        assert_eq!(
+            limit_error(limits.clone(), b"<message to='0123456789012345678901234567890123456789012345678901234567890123456789'"),
+            ParserError::StanzaTooLarge
+        );
+        assert_eq!(
+            limit_error(limits.clone(), b"<a><b><c>"),
+            ParserError::TooDeep
+        );
+        assert_eq!(
+            limit_error(limits.clone(), b"<a xmlns='ns' b='c' d='e'/>"),
+            ParserError::TooManyAttributes
+        );
+        assert_eq!(
+            limit_error(limits.clone(), b"<a>123456789</a>"),
+            ParserError::TextTooLong
+        );
+        assert_eq!(
+            limit_error(limits, b"<a>12345<![CDATA[6789]]></a>"),
+            ParserError::TextTooLong
+        );
+    }
+
+    #[test]
+    fn test_forbidden() {
+        let mut parser = Parser::new();
+        feed(
+            &mut parser,
+            b"<?xml version='1.0'?><!DOCTYPE foo [<!ENTITY bar 'baz'>]><root xmlns='ns'>",
+        );
+        match parser.poll() {
+            Err(Error::ParserError(ParserError::DocType)) => (),
+            _ => panic!(),
+        }
+
+        assert_eq!(
+            limit_error(ParserLimits::default(), b"<?xml-stylesheet href='foo'?>"),
+            ParserError::ProcessingInstruction
+        );
+        assert_eq!(
+            limit_error(ParserLimits::default(), b"<?xml version='1.0'?>"),
+            ParserError::ProcessingInstruction
+        );
+
+        let mut parser = Parser::new();
+        feed(&mut parser, b"<root xmlns='ns'>&bar;");
+        assert!(parser.poll().unwrap().is_some());
+        match parser.poll() {
+            Err(Error::XmlError(_)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_lossless() {
+        let stanza = b"<message to='juliet@example.com' id='a'>\n  <body>&lt;3</body >\n  <stream:error/>\n</message>";
+        let mut parser = Parser::new();
+        parser.set_lossless(true);
+        feed(&mut parser, STREAM_START);
+        feed(&mut parser, stanza);
+        let results = poll_all(&mut parser);
+        assert_eq!(results.len(), 2);
+        match &results[1] {
+            ParserResult::Single(message) => assert_eq!(
+                String::from(message),
+                "<message xmlns=\"jabber:client\" to='juliet@example.com' id='a'>\n  <body>&lt;3</body >\n  <stream:error xmlns:stream=\"http://etherx.jabber.org/streams\"/>\n</message>"
+            ),
+            _ => panic!(),
+        }
+
+        // The mode is kept across resets.
+        parser.reset();
+        feed(&mut parser, STREAM_START);
+        feed(&mut parser, b"<presence  />");
+        match &poll_all(&mut parser)[1] {
+            ParserResult::Single(presence) => assert_eq!(
+                String::from(presence),
+                "<presence xmlns=\"jabber:client\"  />"
+            ),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut parser = Parser::new();
+        feed(&mut parser, b"<root xmlns='ns'/>");
+        assert_eq!(poll_all(&mut parser).len(), 2);
+        parser.reset();
+        feed(&mut parser, STREAM_START);
+        assert_eq!(poll_all(&mut parser).len(), 1);
     }
 }
@@ -0,0 +1,495 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Provides a `Selector` type, a small XPath-like query language to find elements, attributes
+//! or text inside of an `Element`.
+//!
+//! A selector is a list of steps separated by `/`, the first one being tested against the
+//! element it is applied to, and each following one against the children of the elements
+//! matched so far.  Using `//` instead of `/` matches any descendant instead of only direct
+//! children, and a leading `//` makes the first step match the element or any of its
+//! descendants.
+//!
+//! Each step is an element name, or `*` for any name, followed by any number of predicates:
+//!
+//! - `[ns=namespace]` requires the element to be in this namespace, which can be an alias
+//!   given to `Selector::with_namespaces()`,
+//! - `[@attr]` requires the element to have this attribute,
+//! - `[@attr=value]` requires this attribute to have this value.
+//!
+//! Values can be quoted with `'` or `"`, which is needed if they contain a `]`.  Without any
+//! `ns` predicate, elements match in any namespace.
+//!
+//! The selector can end with `/@attr` to return the value of this attribute on the matched
+//! elements, or with `/text()` to return their text, instead of the elements themselves.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use minidom::{Element, Selector};
+//!
+//! let selector = Selector::with_namespaces(
+//!     "message/event[ns=pubsub#event]/items/item/@id",
+//!     &[("pubsub#event", "http://jabber.org/protocol/pubsub#event")],
+//! )
+//! .unwrap();
+//!
+//! let message: Element = "<message xmlns='jabber:client'><event xmlns='http://jabber.org/protocol/pubsub#event'><items node='princely_musings'><item id='ae890ac52d0df67ed7cfdf51b644e901'/></items></event></message>".parse().unwrap();
+//! let id = selector.find(&message).unwrap();
+//! assert_eq!(id.as_str(), Some("ae890ac52d0df67ed7cfdf51b644e901"));
+//! ```
+
+use crate::element::Element;
+use crate::error::{Error, Result};
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    /// Only for the first step, the element the selector is applied to.
+    Itself,
+    /// Only for the first step, the element the selector is applied to or any descendant.
+    ItselfOrDescendant,
+    Child,
+    Descendant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    Namespace(String),
+    HasAttribute(String),
+    Attribute(String, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    axis: Axis,
+    /// `None` for `*`.
+    name: Option<String>,
+    predicates: Vec<Predicate>,
+}
+
+impl Step {
+    fn matches(&self, elem: &Element) -> bool {
+        if let Some(name) = &self.name {
+            if elem.name() != name {
+                return false;
+            }
+        }
+        self.predicates.iter().all(|predicate| match predicate {
+            Predicate::Namespace(ns) => elem.ns() == ns,
+            Predicate::HasAttribute(name) => elem.attr(name).is_some(),
+            Predicate::Attribute(name, value) => elem.attr(name) == Some(value),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Element,
+    Attribute(String),
+    Text,
+}
+
+/// What a `Selector` found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Match<'a> {
+    /// An element matched by the last step.
+    Element(&'a Element),
+
+    /// The value of the attribute selected by a final `/@attr`.
+    Attribute(&'a str),
+
+    /// The text of an element, selected by a final `/text()`.
+    Text(String),
+}
+
+impl<'a> Match<'a> {
+    /// Returns the matched element, if the selector didn’t select an attribute or text.
+    pub fn as_element(&self) -> Option<&'a Element> {
+        match self {
+            Match::Element(elem) => Some(elem),
+            _ => None,
+        }
+    }
+
+    /// Returns the matched attribute value or text, if the selector selected one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Match::Element(_) => None,
+            Match::Attribute(value) => Some(value),
+            Match::Text(text) => Some(text),
+        }
+    }
+}
+
+/// A compiled query, which can be reused on many elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    steps: Vec<Step>,
+    target: Target,
+}
+
+/// Reads a selector, one character at a time.
+struct SelectorParser<'a> {
+    input: &'a str,
+    namespaces: &'a [(&'a str, &'a str)],
+}
+
+impl<'a> SelectorParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input.chars().next()
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.input.starts_with(prefix) {
+            self.input = &self.input[prefix.len()..];
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, prefix: &str, error: &'static str) -> Result<()> {
+        if self.eat(prefix) {
+            Ok(())
+        } else {
+            Err(Error::InvalidSelector(error))
+        }
+    }
+
+    /// Reads a name, up to the next special character.
+    fn name(&mut self) -> Result<String> {
+        let end = self
+            .input
+            .find(&['/', '[', ']', '=', '@'][..])
+            .unwrap_or(self.input.len());
+        if end == 0 {
+            return Err(Error::InvalidSelector("a name was expected"));
+        }
+        let (name, rest) = self.input.split_at(end);
+        self.input = rest;
+        Ok(name.to_owned())
+    }
+
+    /// Reads a value, either quoted or up to the end of the predicate.
+    fn value(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(quote @ '\'') | Some(quote @ '"') => {
+                let rest = &self.input[1..];
+                let end = rest
+                    .find(quote)
+                    .ok_or(Error::InvalidSelector("unterminated quoted value"))?;
+                self.input = &rest[end + 1..];
+                Ok(rest[..end].to_owned())
+            }
+            _ => {
+                let end = self
+                    .input
+                    .find(']')
+                    .ok_or(Error::InvalidSelector("unterminated predicate"))?;
+                let (value, rest) = self.input.split_at(end);
+                self.input = rest;
+                Ok(value.to_owned())
+            }
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Predicate> {
+        let predicate = if self.eat("@") {
+            let name = self.name()?;
+            if self.eat("=") {
+                Predicate::Attribute(name, self.value()?)
+            } else {
+                Predicate::HasAttribute(name)
+            }
+        } else if self.eat("ns=") {
+            let ns = self.value()?;
+            let ns = match self.namespaces.iter().find(|(alias, _)| *alias == ns) {
+                Some((_, ns)) => (*ns).to_owned(),
+                None => ns,
+            };
+            Predicate::Namespace(ns)
+        } else {
+            return Err(Error::InvalidSelector("unknown predicate"));
+        };
+        self.expect("]", "a predicate must end with ]")?;
+        Ok(predicate)
+    }
+
+    fn step(&mut self, axis: Axis) -> Result<Step> {
+        let name = if self.eat("*") {
+            None
+        } else {
+            Some(self.name()?)
+        };
+        let mut predicates = Vec::new();
+        while self.eat("[") {
+            predicates.push(self.predicate()?);
+        }
+        Ok(Step {
+            axis,
+            name,
+            predicates,
+        })
+    }
+
+    fn selector(mut self) -> Result<Selector> {
+        let axis = if self.eat("//") {
+            Axis::ItselfOrDescendant
+        } else {
+            Axis::Itself
+        };
+        let mut steps = vec![self.step(axis)?];
+        loop {
+            let axis = if self.input.is_empty() {
+                return Ok(Selector {
+                    steps,
+                    target: Target::Element,
+                });
+            } else if self.eat("//") {
+                Axis::Descendant
+            } else if self.eat("/") {
+                Axis::Child
+            } else {
+                return Err(Error::InvalidSelector("unexpected character after a step"));
+            };
+            if axis == Axis::Child {
+                let target = if self.eat("@") {
+                    Some(Target::Attribute(self.name()?))
+                } else if self.eat("text()") {
+                    Some(Target::Text)
+                } else {
+                    None
+                };
+                if let Some(target) = target {
+                    if !self.input.is_empty() {
+                        return Err(Error::InvalidSelector(
+                            "an attribute or text() must end the selector",
+                        ));
+                    }
+                    return Ok(Selector { steps, target });
+                }
+            }
+            steps.push(self.step(axis)?);
+        }
+    }
+}
+
+impl Selector {
+    /// Compiles a selector, see the module documentation for its syntax.
+    pub fn new(selector: &str) -> Result<Selector> {
+        Selector::with_namespaces(selector, &[])
+    }
+
+    /// Compiles a selector, replacing each alias used in a `[ns=alias]` predicate with its
+    /// namespace.
+    pub fn with_namespaces(selector: &str, namespaces: &[(&str, &str)]) -> Result<Selector> {
+        SelectorParser {
+            input: selector,
+            namespaces,
+        }
+        .selector()
+    }
+
+    /// Returns everything this selector matches in `elem`, in document order.
+    pub fn find_all<'a>(&self, elem: &'a Element) -> Vec<Match<'a>> {
+        // With more than one `//`, the same element can be reached through different paths,
+        // and in an order which isn’t the document one, so walk the tree again to sort them.
+        let mut found: HashSet<*const Element> = HashSet::new();
+        self.visit(elem, 0, &mut |elem| {
+            found.insert(elem);
+            false
+        });
+        let mut elements = Vec::with_capacity(found.len());
+        let mut keep_found = |elem: &'a Element| {
+            if found.contains(&(elem as *const Element)) {
+                elements.push(elem);
+            }
+            elements.len() == found.len()
+        };
+        if !keep_found(elem) {
+            visit_descendants(elem, &mut keep_found);
+        }
+        elements
+            .into_iter()
+            .filter_map(|elem| self.extract(elem))
+            .collect()
+    }
+
+    /// Returns the first thing this selector matches in `elem`.
+    pub fn find<'a>(&self, elem: &'a Element) -> Option<Match<'a>> {
+        let mut result = None;
+        self.visit(elem, 0, &mut |found| {
+            result = self.extract(found);
+            result.is_some()
+        });
+        result
+    }
+
+    /// Returns whether this selector matches anything in `elem`.
+    pub fn matches(&self, elem: &Element) -> bool {
+        self.find(elem).is_some()
+    }
+
+    fn extract<'a>(&self, elem: &'a Element) -> Option<Match<'a>> {
+        match &self.target {
+            Target::Element => Some(Match::Element(elem)),
+            Target::Attribute(name) => elem.attr(name).map(Match::Attribute),
+            Target::Text => Some(Match::Text(elem.text())),
+        }
+    }
+
+    /// Calls `found` on each element matched by the steps starting at `index`, until it
+    /// returns true, in which case this returns true too.
+    fn visit<'a, F: FnMut(&'a Element) -> bool>(
+        &self,
+        elem: &'a Element,
+        index: usize,
+        found: &mut F,
+    ) -> bool {
+        let step = &self.steps[index];
+        let mut visit_candidate = |candidate: &'a Element| {
+            if !step.matches(candidate) {
+                false
+            } else if index + 1 == self.steps.len() {
+                found(candidate)
+            } else {
+                self.visit(candidate, index + 1, found)
+            }
+        };
+        match step.axis {
+            Axis::Itself => visit_candidate(elem),
+            Axis::ItselfOrDescendant => {
+                visit_candidate(elem) || visit_descendants(elem, &mut visit_candidate)
+            }
+            Axis::Child => elem.children().any(visit_candidate),
+            Axis::Descendant => visit_descendants(elem, &mut visit_candidate),
+        }
+    }
+}
+
+/// Calls `f` on every descendant of `elem` in document order, until it returns true.
+fn visit_descendants<'a, F: FnMut(&'a Element) -> bool>(elem: &'a Element, f: &mut F) -> bool {
+    elem.children()
+        .any(|child| f(child) || visit_descendants(child, f))
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Selector> {
+        Selector::new(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> Element {
+        "<message xmlns='jabber:client' type='chat'>\
+            <body>Hello</body>\
+            <event xmlns='http://jabber.org/protocol/pubsub#event'>\
+                <items node='a'><item id='1'/><item id='2'><entry>first</entry></item></items>\
+                <items node='b'><item id='3'/></items>\
+            </event>\
+        </message>"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_child_path() {
+        let message = message();
+        let selector = Selector::with_namespaces(
+            "message/event[ns=pubsub#event]/items/item/@id",
+            &[("pubsub#event", "http://jabber.org/protocol/pubsub#event")],
+        )
+        .unwrap();
+        let ids = selector.find_all(&message);
+        let ids: Vec<_> = ids.iter().map(|id| id.as_str().unwrap()).collect();
+        assert_eq!(ids, ["1", "2", "3"]);
+
+        let selector = Selector::new("message/event[ns=urn:xmpp:other]/items").unwrap();
+        assert!(!selector.matches(&message));
+
+        let selector = Selector::new("presence/event").unwrap();
+        assert!(!selector.matches(&message));
+    }
+
+    #[test]
+    fn test_predicates() {
+        let message = message();
+        let selector: Selector = "message[@type='chat']/*/items[@node=b]/item/@id"
+            .parse()
+            .unwrap();
+        assert_eq!(selector.find(&message), Some(Match::Attribute("3")));
+
+        let selector: Selector = "*/*/*/item[@id]".parse().unwrap();
+        assert_eq!(selector.find_all(&message).len(), 3);
+
+        let selector: Selector = "message[@type=\"groupchat\"]".parse().unwrap();
+        assert_eq!(selector.find(&message), None);
+    }
+
+    #[test]
+    fn test_descendants_and_text() {
+        let message = message();
+        let selector: Selector = "//entry/text()".parse().unwrap();
+        assert_eq!(
+            selector.find(&message),
+            Some(Match::Text(String::from("first")))
+        );
+
+        let selector: Selector = "message//item".parse().unwrap();
+        let items = selector.find_all(&message);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].as_element().unwrap().attr("id"), Some("2"));
+
+        // The same element is only returned once.
+        let selector: Selector = "//*//item".parse().unwrap();
+        assert_eq!(selector.find_all(&message).len(), 3);
+
+        let selector: Selector = "//message".parse().unwrap();
+        assert_eq!(selector.find(&message), Some(Match::Element(&message)));
+    }
+
+    #[test]
+    fn test_document_order() {
+        let elem: Element = "<a xmlns='ns'><a><b id='1'/></a><b id='2'/></a>"
+            .parse()
+            .unwrap();
+        let selector: Selector = "//a/b/@id".parse().unwrap();
+        let ids = selector.find_all(&elem);
+        let ids: Vec<_> = ids.iter().map(|id| id.as_str().unwrap()).collect();
+        assert_eq!(ids, ["1", "2"]);
+
+        let selector: Selector = "//a//b/@id".parse().unwrap();
+        let ids = selector.find_all(&elem);
+        let ids: Vec<_> = ids.iter().map(|id| id.as_str().unwrap()).collect();
+        assert_eq!(ids, ["1", "2"]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        for selector in &[
+            "",
+            "message/",
+            "message[",
+            "message[@id",
+            "message[foo]",
+            "message[@id='a]",
+            "message/@id/body",
+            "message/text()/body",
+            "message]",
+        ] {
+            match Selector::new(selector) {
+                Err(Error::InvalidSelector(_)) => (),
+                other => panic!("{:?} gave {:?}", selector, other),
+            }
+        }
+    }
+}
@@ -11,9 +11,11 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::element::Element;
-use crate::error::Error;
+use crate::error::{Error, ParserError};
+use crate::parser::ParserLimits;
 
 use quick_xml::Reader;
+use std::io::{self, BufReader, Read};
 
 const TEST_STRING: &'static str = r#"<root xmlns="root_ns" a="b" xml:lang="en">meow<child c="d"/><child xmlns="child_ns" d="e" xml:lang="fr"/>nya</root>"#;
 
@@ -255,6 +257,139 @@ fn writer_escapes_attributes() {
     );
 }
 
+#[test]
+fn pretty_writer_indents_children() {
+    let root: Element = "<root xmlns='ns1'>\n <a>text</a>  <b xmlns='ns2'><c/></b><d/></root>"
+        .parse()
+        .unwrap();
+    let mut writer = Vec::new();
+    root.write_pretty(&mut writer, "\t").unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<root xmlns=\"ns1\">\n\t<a>text</a>\n\t<b xmlns=\"ns2\">\n\t\t<c/>\n\t</b>\n\t<d/>\n</root>"
+    );
+}
+
+#[test]
+fn pretty_writer_keeps_mixed_content() {
+    let root: Element = "<root xmlns='ns1'><p>Hello <b>world</b> !</p></root>"
+        .parse()
+        .unwrap();
+    let mut writer = Vec::new();
+    root.write_pretty(&mut writer, "  ").unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<root xmlns=\"ns1\">\n  <p>Hello <b>world</b> !</p>\n</root>"
+    );
+}
+
+#[test]
+fn canonical_writer_sorts_and_normalises() {
+    let child1 = Element::builder("child", "ns2")
+        .prefix(Some(String::from("q")), "ns4")
+        .unwrap()
+        .attr("q:b", "3")
+        .attr("c", "4")
+        .build();
+    let child2 = Element::builder("child", "ns1").build();
+    let root = Element::builder("root", "ns1")
+        .prefix(Some(String::from("p")), "ns1")
+        .unwrap()
+        .prefix(None, "ns2")
+        .unwrap()
+        .prefix(Some(String::from("unused")), "ns3")
+        .unwrap()
+        .attr("z", "1")
+        .attr("xml:lang", "en")
+        .attr("a", "2")
+        .append(child1)
+        .append(child2)
+        .build();
+    let mut writer = Vec::new();
+    root.write_canonical(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        r#"<root xmlns="ns1" a="2" z="1" xml:lang="en"><child xmlns="ns2" xmlns:q="ns4" c="4" q:b="3"></child><child></child></root>"#
+    );
+}
+
+#[test]
+fn canonical_writer_is_stable() {
+    let elem1: Element = "<root xmlns='ns1' b=\"'\" a='&quot;'>a&gt;b\r</root>"
+        .parse()
+        .unwrap();
+    let elem2 = Element::builder("root", "ns1")
+        .prefix(Some(String::from("p")), "ns1")
+        .unwrap()
+        .attr("a", "\"")
+        .attr("b", "'")
+        .append("a>b\r")
+        .build();
+    let mut writer1 = Vec::new();
+    elem1.write_canonical(&mut writer1).unwrap();
+    let mut writer2 = Vec::new();
+    elem2.write_canonical(&mut writer2).unwrap();
+    assert_eq!(writer1, writer2);
+    assert_eq!(
+        String::from_utf8(writer1).unwrap(),
+        "<root xmlns=\"ns1\" a=\"&quot;\" b=\"'\">a&gt;b&#xD;</root>"
+    );
+}
+
+fn parse_lossless(xml: &str) -> Element {
+    Element::from_reader_lossless(xml.as_bytes(), &ParserLimits::default()).unwrap()
+}
+
+#[test]
+fn lossless_round_trip() {
+    let xml = "<p:root xmlns:p='ns1' xmlns=\"ns2\" z='1' a=\"&apos;\">\n\t<child  b='&#x41;' p:c='2' />\n\t<p:child>&amp;<![CDATA[<&>]]> </p:child><empty></empty></p:root>";
+    let elem = parse_lossless(xml);
+    assert_eq!(String::from(&elem), xml);
+
+    // The normal parser keeps the same information otherwise.
+    let elem2: Element = xml.parse().unwrap();
+    assert_eq!(elem, elem2);
+    assert_eq!(elem.attr("a"), Some("'"));
+    let child = elem.get_child("child", "ns1").unwrap();
+    assert_eq!(child.text(), "&<&> ");
+}
+
+#[test]
+fn lossless_declares_inherited_namespaces() {
+    let elem = parse_lossless(
+        "<root xmlns='ns1' xmlns:p='ns2'><p:child xml:lang='en' p:a='b'><c/></p:child></root>",
+    );
+    let child = elem.children().next().unwrap().clone();
+    assert_eq!(
+        String::from(&child),
+        "<p:child xmlns:p=\"ns2\" xml:lang='en' p:a='b'><c xmlns=\"ns1\"/></p:child>"
+    );
+
+    // Namespaces already in scope aren’t declared again.
+    let wrapper = Element::builder("wrapper", "ns1").append(child).build();
+    assert_eq!(
+        String::from(&wrapper),
+        "<wrapper xmlns=\"ns1\"><p:child xmlns:p=\"ns2\" xml:lang='en' p:a='b'><c/></p:child></wrapper>"
+    );
+}
+
+#[test]
+fn lossless_modified_element() {
+    let mut elem = parse_lossless("<root xmlns='ns1' a='b'><child  c='d'/>text</root>");
+    elem.set_attr("e", "f");
+    assert_eq!(
+        String::from(&elem),
+        "<root xmlns=\"ns1\" a=\"b\" e=\"f\"><child  c='d'/>text</root>"
+    );
+
+    let mut elem = parse_lossless("<root xmlns='ns1' a='b'><child  c='d'/></root>");
+    elem.append_text_node("text");
+    assert_eq!(
+        String::from(&elem),
+        "<root xmlns=\"ns1\" a=\"b\"><child  c='d'/>text</root>"
+    );
+}
+
 #[test]
 fn writer_escapes_text() {
     let root = Element::builder("root", "ns1").append("<3").build();
@@ -457,3 +592,105 @@ fn missing_namespace_error() {
         err => panic!("No or wrong error: {:?}", err),
     }
 }
+
+#[test]
+fn reader_binds_prefixes() {
+    let elem: Element =
+        "<p1:root xmlns='ns1' xmlns:p1='ns2'><child/><p1:child/><xml:child/></p1:root>"
+            .parse()
+            .unwrap();
+    assert_eq!(elem.ns(), "ns2");
+    let namespaces: Vec<_> = elem.children().map(|child| child.ns()).collect();
+    assert_eq!(
+        namespaces,
+        ["ns1", "ns2", "http://www.w3.org/XML/1998/namespace"]
+    );
+
+    match "<root xmlns='ns1'><p1:child/></root>".parse::<Element>() {
+        Err(Error::MissingNamespace) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+}
+
+#[test]
+fn reader_matches_end_tags() {
+    // The end tag has to repeat the prefix of the start tag, even when another prefix is bound
+    // to the same namespace.
+    for xml in &[
+        "<p1:root xmlns:p1='ns1' xmlns:p2='ns1'><p2:child></p2:child></p1:root>",
+        "<root xmlns='ns1' xmlns:p1='ns1'><p1:child></p1:child><child></child></root>",
+    ] {
+        let elem: Element = xml.parse().unwrap();
+        assert_eq!(elem.children().count(), xml.matches("</").count() - 1);
+    }
+
+    for xml in &[
+        "<root xmlns='ns1'><child></other></root>",
+        "<p1:root xmlns:p1='ns1'><p1:child></child></p1:root>",
+        "<p1:root xmlns:p1='ns1' xmlns:p2='ns1'><p1:child></p2:child></p1:root>",
+    ] {
+        assert!(xml.parse::<Element>().is_err(), "{} got parsed", xml);
+    }
+}
+
+#[test]
+fn forbidden_error() {
+    match "<!DOCTYPE a [<!ENTITY b 'c'>]><a xmlns='ns1'>&b;</a>".parse::<Element>() {
+        Err(Error::ParserError(ParserError::DocType)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+
+    match "<a xmlns='ns1'><?foo bar?></a>".parse::<Element>() {
+        Err(Error::ParserError(ParserError::ProcessingInstruction)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+
+    let _: Element = "<?xml version='1.0'?><a xmlns='ns1'/>".parse().unwrap();
+}
+
+#[test]
+fn limits_error() {
+    let limits = ParserLimits {
+        max_stanza_size: 64,
+        max_depth: 2,
+        max_attributes: 2,
+        max_text_length: 4,
+    };
+    let parse = |xml: &str| Element::from_reader_with_limits(xml.as_bytes(), &limits);
+
+    parse("<a xmlns='ns1' b='c'><d>1234</d></a>").unwrap();
+
+    match parse("<a xmlns='ns1'><b/><b/><b/><b/><b/><b/><b/><b/><b/><b/><b/><b/></a>") {
+        Err(Error::ParserError(ParserError::StanzaTooLarge)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+
+    match parse("<a xmlns='ns1'><b><c/></b></a>") {
+        Err(Error::ParserError(ParserError::TooDeep)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+
+    match parse("<a xmlns='ns1' b='c' d='e'/>") {
+        Err(Error::ParserError(ParserError::TooManyAttributes)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+
+    match parse("<a xmlns='ns1'>12345</a>") {
+        Err(Error::ParserError(ParserError::TextTooLong)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+
+    // An endless text node, which must not be buffered past the limit.
+    let endless = BufReader::new((&b"<a xmlns='ns1'>"[..]).chain(io::repeat(b'a')));
+    match Element::from_reader_with_limits(endless, &limits) {
+        Err(Error::ParserError(ParserError::StanzaTooLarge)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+
+    // Same for an endless tag.
+    let endless = BufReader::new((&b"<a xmlns='ns1' b='"[..]).chain(io::repeat(b'a')));
+    match Element::from_reader_with_limits(endless, &limits) {
+        Err(Error::ParserError(ParserError::StanzaTooLarge)) => (),
+        err => panic!("No or wrong error: {:?}", err),
+    }
+}
@@ -0,0 +1,253 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Provides the `AsXml` trait, for types which can write themselves as XML directly into a
+//! buffer, without building an `Element` first.
+
+use crate::element::Element;
+use crate::error::Result;
+
+use bytes::{BufMut, BytesMut};
+use quick_xml::Writer as EventWriter;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Write};
+
+/// A trait for types which can be serialised straight into a `BytesMut`, which is much cheaper
+/// than converting them into an `Element` and then serialising it.
+///
+/// # Examples
+///
+/// ```rust
+/// use minidom::{AsXml, XmlWriter, Result};
+///
+/// struct Ping {
+///     id: String,
+/// }
+///
+/// impl AsXml for Ping {
+///     fn write_xml(&self, writer: &mut XmlWriter) -> Result<()> {
+///         writer.start("iq", "jabber:client");
+///         writer.attr("id", &self.id);
+///         writer.attr("type", "get");
+///         writer.start("ping", "urn:xmpp:ping");
+///         writer.end();
+///         writer.end();
+///         Ok(())
+///     }
+/// }
+///
+/// let ping = Ping { id: String::from("a&b") };
+/// assert_eq!(
+///     &ping.to_bytes().unwrap()[..],
+///     &b"<iq xmlns=\"jabber:client\" id=\"a&amp;b\" type=\"get\"><ping xmlns=\"urn:xmpp:ping\"/></iq>"[..],
+/// );
+/// ```
+pub trait AsXml {
+    /// Writes this item, which should be a single element, using `writer`.
+    fn write_xml(&self, writer: &mut XmlWriter) -> Result<()>;
+
+    /// Serialises this item into a new buffer.
+    fn to_bytes(&self) -> Result<BytesMut> {
+        let mut buf = BytesMut::new();
+        self.write_xml(&mut XmlWriter::new(&mut buf))?;
+        Ok(buf)
+    }
+}
+
+/// Writes an `Element` as is, this is the fallback for payloads without a more specific
+/// implementation.
+impl AsXml for Element {
+    fn write_xml(&self, writer: &mut XmlWriter) -> Result<()> {
+        writer.element(self)
+    }
+}
+
+/// Escapes everything written through it, the same way `Element::write_to()` does.
+struct Escaper<'a> {
+    buf: &'a mut BytesMut,
+}
+
+impl<'a> Write for Escaper<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut start = 0;
+        for (i, c) in s.char_indices() {
+            let escaped: &[u8] = match c {
+                '<' => b"&lt;",
+                '>' => b"&gt;",
+                '\'' => b"&apos;",
+                '&' => b"&amp;",
+                '"' => b"&quot;",
+                _ => continue,
+            };
+            self.buf.extend_from_slice(&s.as_bytes()[start..i]);
+            self.buf.extend_from_slice(escaped);
+            start = i + 1;
+        }
+        self.buf.extend_from_slice(&s.as_bytes()[start..]);
+        Ok(())
+    }
+}
+
+/// Writes elements into a `BytesMut`, declaring namespaces only where they change.
+///
+/// Elements are opened with `start()`, which must be followed by their attributes, then their
+/// content, and closed with `end()`.
+pub struct XmlWriter<'a> {
+    buf: &'a mut BytesMut,
+    /// Name and namespace of each element started but not ended yet.
+    stack: Vec<(&'static str, &'static str)>,
+    /// Whether the start tag of the last started element hasn’t been closed with `>` yet.
+    in_start_tag: bool,
+}
+
+impl<'a> XmlWriter<'a> {
+    /// Creates a writer appending to `buf`.
+    pub fn new(buf: &'a mut BytesMut) -> XmlWriter<'a> {
+        XmlWriter {
+            buf,
+            stack: Vec::new(),
+            in_start_tag: false,
+        }
+    }
+
+    fn close_start_tag(&mut self) {
+        if self.in_start_tag {
+            self.buf.put_u8(b'>');
+            self.in_start_tag = false;
+        }
+    }
+
+    fn namespace(&self) -> Option<&'static str> {
+        self.stack.last().map(|(_, ns)| *ns)
+    }
+
+    /// Starts a new element, declaring its namespace if it differs from its parent’s.
+    pub fn start(&mut self, name: &'static str, namespace: &'static str) {
+        self.close_start_tag();
+        self.buf.put_u8(b'<');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.in_start_tag = true;
+        if self.namespace() != Some(namespace) {
+            self.attr("xmlns", namespace);
+        }
+        self.stack.push((name, namespace));
+    }
+
+    /// Adds an attribute to the element which just got started.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some content has already been written in this element.
+    pub fn attr<V: Display + ?Sized>(&mut self, name: &str, value: &V) {
+        assert!(
+            self.in_start_tag,
+            "attributes must be written before any content"
+        );
+        self.buf.put_u8(b' ');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.extend_from_slice(b"=\"");
+        // Writing into a BytesMut never fails.
+        let _ = write!(Escaper { buf: self.buf }, "{}", value);
+        self.buf.put_u8(b'"');
+    }
+
+    /// Adds some text to the current element.
+    pub fn text<T: Display + ?Sized>(&mut self, text: &T) {
+        self.close_start_tag();
+        let _ = write!(Escaper { buf: self.buf }, "{}", text);
+    }
+
+    /// Adds an `Element` to the current element, reusing its namespace if it is the same.
+    pub fn element(&mut self, elem: &Element) -> Result<()> {
+        self.close_start_tag();
+        let mut prefixes = BTreeMap::new();
+        if let Some(namespace) = self.namespace() {
+            prefixes.insert(None, namespace.to_owned());
+        }
+        elem.write_to_inner(
+            &mut EventWriter::new((&mut *self.buf).writer()),
+            &mut prefixes,
+        )
+    }
+
+    /// Adds anything implementing `AsXml` to the current element.
+    pub fn write<T: AsXml + ?Sized>(&mut self, item: &T) -> Result<()> {
+        item.write_xml(self)
+    }
+
+    /// Ends the current element, as an empty element if nothing got written into it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no element to end.
+    pub fn end(&mut self) {
+        let (name, _) = self.stack.pop().expect("no element to end");
+        if self.in_start_tag {
+            self.buf.extend_from_slice(b"/>");
+            self.in_start_tag = false;
+        } else {
+            self.buf.extend_from_slice(b"</");
+            self.buf.extend_from_slice(name.as_bytes());
+            self.buf.put_u8(b'>');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespaces() {
+        let mut buf = BytesMut::new();
+        let mut writer = XmlWriter::new(&mut buf);
+        writer.start("message", "jabber:client");
+        writer.start("body", "jabber:client");
+        writer.text("<3");
+        writer.end();
+        writer.start("x", "ns1");
+        writer.start("y", "ns1");
+        writer.end();
+        writer.start("body", "jabber:client");
+        writer.end();
+        writer.end();
+        writer.end();
+        assert_eq!(
+            &buf[..],
+            &b"<message xmlns=\"jabber:client\"><body>&lt;3</body><x xmlns=\"ns1\"><y/><body xmlns=\"jabber:client\"/></x></message>"[..]
+        );
+    }
+
+    #[test]
+    fn test_element_fallback() {
+        let payload = Element::builder("payload", "jabber:client")
+            .append(Element::builder("other", "ns1").attr("a", "'"))
+            .build();
+        let mut buf = BytesMut::new();
+        let mut writer = XmlWriter::new(&mut buf);
+        writer.start("message", "jabber:client");
+        writer.attr("id", &42);
+        writer.write(&payload).unwrap();
+        writer.end();
+        assert_eq!(
+            &buf[..],
+            &b"<message xmlns=\"jabber:client\" id=\"42\"><payload><other xmlns=\"ns1\" a=\"&apos;\"/></payload></message>"[..]
+        );
+
+        assert_eq!(
+            &payload.to_bytes().unwrap()[..],
+            String::from(&payload).as_bytes()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "attributes must be written before any content")]
+    fn test_attribute_after_content() {
+        let mut buf = BytesMut::new();
+        let mut writer = XmlWriter::new(&mut buf);
+        writer.start("message", "jabber:client");
+        writer.text("hello");
+        writer.attr("id", "a");
+    }
+}
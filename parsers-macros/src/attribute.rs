@@ -0,0 +1,263 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Types of attribute values: an enum of the possible values, or a newtype around another
+//! attribute type.
+
+use crate::{serde_mirror, TypeOptions, VariantOptions, XmlStruct};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Expr, Fields, Ident, Lit, LitStr, Type};
+
+pub(crate) struct AttributeVariant {
+    ident: Ident,
+    /// `None` for a default variant only used when the attribute is absent.
+    value: Option<LitStr>,
+    aliases: Vec<LitStr>,
+    default: bool,
+}
+
+pub(crate) enum XmlAttribute {
+    Enum {
+        ident: Ident,
+        name: LitStr,
+        variants: Vec<AttributeVariant>,
+    },
+    Newtype {
+        ident: Ident,
+        ty: Box<Type>,
+        default: Option<Expr>,
+    },
+}
+
+impl XmlAttribute {
+    pub(crate) fn parse(input: &DeriveInput, options: TypeOptions) -> syn::Result<XmlAttribute> {
+        let error = |message: &str| Err(syn::Error::new(input.ident.span(), message));
+        if options.name.is_some() {
+            return error("an attribute type takes no name, only #[xml(attribute = ...)]");
+        }
+        let ident = input.ident.clone();
+        match &input.data {
+            Data::Enum(data) => {
+                let name = match options.attribute {
+                    Some(Some(name)) => name,
+                    _ => return error("missing #[xml(attribute = ...)] on the enum"),
+                };
+                if options.default.is_some() {
+                    return error(
+                        "the default of an enum is given with #[xml(default)] on a variant",
+                    );
+                }
+                let mut variants = Vec::new();
+                for variant in &data.variants {
+                    let options = VariantOptions::parse(variant)?;
+                    let value = match options.value {
+                        Some(Lit::Str(value)) => Some(value),
+                        None if options.default && options.aliases.is_empty() => None,
+                        _ => {
+                            return Err(syn::Error::new(
+                                variant.ident.span(),
+                                "each variant needs a #[xml(value = \"...\")], unless it is the default",
+                            ))
+                        }
+                    };
+                    if options.name.is_some() {
+                        return Err(syn::Error::new(
+                            variant.ident.span(),
+                            "the variant of an attribute has a value, not a name",
+                        ));
+                    }
+                    variants.push(AttributeVariant {
+                        ident: variant.ident.clone(),
+                        value,
+                        aliases: options.aliases,
+                        default: options.default,
+                    });
+                }
+                if variants.iter().filter(|variant| variant.default).count() > 1 {
+                    return error("only one variant can be the default");
+                }
+                Ok(XmlAttribute::Enum {
+                    ident,
+                    name,
+                    variants,
+                })
+            }
+            Data::Struct(data) => match &data.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(XmlAttribute::Newtype {
+                    ident,
+                    ty: Box::new(fields.unnamed[0].ty.clone()),
+                    default: options.default,
+                }),
+                _ => error("an attribute type must be an enum or a newtype"),
+            },
+            Data::Union(_) => error("unions can’t be derived from xml"),
+        }
+    }
+
+    pub(crate) fn parser(&self) -> TokenStream2 {
+        let (ident, from_str) = match self {
+            XmlAttribute::Enum {
+                ident,
+                name,
+                variants,
+            } => {
+                let unknown =
+                    XmlStruct::error(format!("Unknown value for '{}' attribute.", name.value()));
+                let arms = variants.iter().filter_map(|variant| {
+                    let value = variant.value.as_ref()?;
+                    let aliases = &variant.aliases;
+                    let variant = &variant.ident;
+                    Some(quote!(#value #(| #aliases)* => #ident::#variant,))
+                });
+                (
+                    ident,
+                    quote! {
+                        ::std::result::Result::Ok(match s {
+                            #(#arms)*
+                            _ => #unknown,
+                        })
+                    },
+                )
+            }
+            XmlAttribute::Newtype { ident, ty, .. } => (
+                ident,
+                quote! {
+                    ::std::result::Result::Ok(#ident(<#ty as ::std::str::FromStr>::from_str(s)?))
+                },
+            ),
+        };
+
+        let default = match self {
+            XmlAttribute::Enum { variants, .. } => variants
+                .iter()
+                .find(|variant| variant.default)
+                .map(|variant| {
+                    let variant = &variant.ident;
+                    quote!(#ident::#variant)
+                }),
+            XmlAttribute::Newtype { default, .. } => {
+                default.as_ref().map(|default| quote!(#ident(#default)))
+            }
+        };
+        let default = default.map(|default| {
+            quote! {
+                impl ::std::default::Default for #ident {
+                    fn default() -> #ident {
+                        #default
+                    }
+                }
+            }
+        });
+
+        let serde = match self {
+            XmlAttribute::Enum { variants, .. } => serde_mirror(
+                ident,
+                variants
+                    .iter()
+                    .map(|variant| (&variant.ident, variant.value.as_ref())),
+                false,
+            ),
+            XmlAttribute::Newtype { .. } => quote!(),
+        };
+
+        quote! {
+            impl ::std::str::FromStr for #ident {
+                type Err = crate::util::error::Error;
+
+                fn from_str(s: &str) -> ::std::result::Result<#ident, crate::util::error::Error> {
+                    #from_str
+                }
+            }
+
+            impl ::minidom::FromAttributeValue for #ident {
+                type Error = crate::util::error::Error;
+
+                fn from_attribute_value(
+                    s: &str,
+                ) -> ::std::result::Result<#ident, crate::util::error::Error> {
+                    <#ident as ::std::str::FromStr>::from_str(s)
+                }
+            }
+            #default
+            #serde
+        }
+    }
+
+    pub(crate) fn serialiser(&self) -> TokenStream2 {
+        match self {
+            XmlAttribute::Enum {
+                ident, variants, ..
+            } => {
+                let idents = variants.iter().map(|variant| &variant.ident);
+                let values = variants.iter().map(|variant| match &variant.value {
+                    Some(value) if !variant.default => quote!(::std::option::Option::Some(#value)),
+                    _ => quote!(::std::option::Option::None),
+                });
+                // Only the values which are always written can be displayed.
+                let display = if variants.iter().any(|variant| variant.default) {
+                    quote!()
+                } else {
+                    let idents = variants.iter().map(|variant| &variant.ident);
+                    let values = variants.iter().map(|variant| &variant.value);
+                    quote! {
+                        impl ::std::fmt::Display for #ident {
+                            fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                                fmt.write_str(match self {
+                                    #(#ident::#idents => #values,)*
+                                })
+                            }
+                        }
+                    }
+                };
+                let serde = serde_mirror(
+                    ident,
+                    variants
+                        .iter()
+                        .map(|variant| (&variant.ident, variant.value.as_ref())),
+                    true,
+                );
+                quote! {
+                    impl #ident {
+                        /// Returns the value of this attribute, or `None` for the default one.
+                        #[allow(dead_code)]
+                        fn as_attribute_value(&self) -> ::std::option::Option<&'static str> {
+                            match self {
+                                #(#ident::#idents => #values,)*
+                            }
+                        }
+                    }
+
+                    impl ::minidom::IntoAttributeValue for #ident {
+                        fn into_attribute_value(self) -> ::std::option::Option<::std::string::String> {
+                            self.as_attribute_value().map(::std::string::String::from)
+                        }
+                    }
+                    #display
+                    #serde
+                }
+            }
+            XmlAttribute::Newtype { ident, default, .. } => {
+                let value = quote!(::minidom::IntoAttributeValue::into_attribute_value(self.0));
+                let value = match default {
+                    Some(default) => quote! {
+                        if self.0 == #default {
+                            ::std::option::Option::None
+                        } else {
+                            #value
+                        }
+                    },
+                    None => value,
+                };
+                quote! {
+                    impl ::minidom::IntoAttributeValue for #ident {
+                        fn into_attribute_value(self) -> ::std::option::Option<::std::string::String> {
+                            #value
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Elements which aren’t a struct with named fields: a newtype around their text, or an enum
+//! of empty elements told apart by their name or by the value of an attribute.
+
+use crate::{serde_mirror, xml_attributes, TypeOptions, VariantOptions, XmlStruct};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, Lit, LitStr, Path};
+
+pub(crate) enum XmlElement {
+    /// A newtype around the text of the element, which has no attribute nor child.
+    Text {
+        ident: Ident,
+        name: LitStr,
+        namespace: Path,
+    },
+    /// An enum of empty elements, each variant being a different name in the same namespace.
+    Names {
+        ident: Ident,
+        name: LitStr,
+        namespace: Path,
+        variants: Vec<(Ident, LitStr)>,
+    },
+    /// An enum of the values of a single attribute of an empty element.
+    Values {
+        ident: Ident,
+        name: LitStr,
+        namespace: Path,
+        attribute: LitStr,
+        variants: Vec<(Ident, Lit)>,
+    },
+}
+
+impl XmlElement {
+    pub(crate) fn parse(input: &DeriveInput, options: TypeOptions) -> syn::Result<XmlElement> {
+        let error = |message: &str| Err(syn::Error::new(input.ident.span(), message));
+        if options.default.is_some() {
+            return error("only an attribute type can have a default");
+        }
+        let (name, namespace) = options.element(&input.ident)?;
+        let ident = input.ident.clone();
+        match &input.data {
+            Data::Struct(data) => {
+                let field = match &data.fields {
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+                    _ => return error("a tuple struct must have a single field, its text"),
+                };
+                if options.attribute.is_some() || xml_attributes(&field.attrs).next().is_some() {
+                    return error("a tuple struct can only map the text of the element");
+                }
+                Ok(XmlElement::Text {
+                    ident,
+                    name,
+                    namespace,
+                })
+            }
+            Data::Enum(data) => {
+                let variants = data
+                    .variants
+                    .iter()
+                    .map(|variant| Ok((variant.ident.clone(), VariantOptions::parse(variant)?)));
+                let variants = variants.collect::<syn::Result<Vec<_>>>()?;
+                if variants
+                    .iter()
+                    .any(|(_, options)| options.default || !options.aliases.is_empty())
+                {
+                    return error("an element enum can’t have a default nor aliases");
+                }
+                match options.attribute {
+                    None => Ok(XmlElement::Names {
+                        ident,
+                        name,
+                        namespace,
+                        variants: variants
+                            .into_iter()
+                            .map(|(variant, options)| match options {
+                                VariantOptions {
+                                    name: Some(name),
+                                    value: None,
+                                    ..
+                                } => Ok((variant, name)),
+                                _ => Err(syn::Error::new(
+                                    variant.span(),
+                                    "each variant needs a #[xml(name = ...)]",
+                                )),
+                            })
+                            .collect::<syn::Result<_>>()?,
+                    }),
+                    Some(None) => error("the attribute of the element needs a name"),
+                    Some(Some(attribute)) => Ok(XmlElement::Values {
+                        ident,
+                        name,
+                        namespace,
+                        attribute,
+                        variants: variants
+                            .into_iter()
+                            .map(|(variant, options)| match options {
+                                VariantOptions {
+                                    name: None,
+                                    value: Some(value),
+                                    ..
+                                } => Ok((variant, value)),
+                                _ => Err(syn::Error::new(
+                                    variant.span(),
+                                    "each variant needs a #[xml(value = ...)]",
+                                )),
+                            })
+                            .collect::<syn::Result<_>>()?,
+                    }),
+                }
+            }
+            Data::Union(_) => error("unions can’t be derived from xml"),
+        }
+    }
+
+    pub(crate) fn parser(&self) -> TokenStream2 {
+        let (ident, name, namespace) = match self {
+            XmlElement::Text {
+                ident,
+                name,
+                namespace,
+                ..
+            }
+            | XmlElement::Names {
+                ident,
+                name,
+                namespace,
+                ..
+            }
+            | XmlElement::Values {
+                ident,
+                name,
+                namespace,
+                ..
+            } => (ident, name, namespace),
+        };
+        let not_self = XmlStruct::error(format!("This is not a {} element.", name.value()));
+        let unknown_attribute =
+            XmlStruct::error(format!("Unknown attribute in {} element.", name.value()));
+        let unknown_child = XmlStruct::error(format!("Unknown child in {} element.", name.value()));
+        let no_children = quote! {
+            #[cfg(not(feature = "disable-validation"))]
+            if elem.children().next().is_some() {
+                #unknown_child;
+            }
+        };
+        let no_attributes = quote! {
+            #[cfg(not(feature = "disable-validation"))]
+            if elem.attrs().next().is_some() {
+                #unknown_attribute;
+            }
+        };
+
+        let body = match self {
+            XmlElement::Text { .. } => quote! {
+                if !elem.is(#name, crate::ns::#namespace) {
+                    #not_self;
+                }
+                #no_children
+                #no_attributes
+                <#ident as ::std::str::FromStr>::from_str(&elem.text())
+            },
+            XmlElement::Names { variants, .. } => {
+                let (idents, names): (Vec<_>, Vec<_>) = variants.iter().cloned().unzip();
+                quote! {
+                    if !elem.has_ns(crate::ns::#namespace) {
+                        #not_self;
+                    }
+                    #no_children
+                    #no_attributes
+                    ::std::result::Result::Ok(match elem.name() {
+                        #(#names => #ident::#idents,)*
+                        _ => #not_self,
+                    })
+                }
+            }
+            XmlElement::Values {
+                attribute,
+                variants,
+                ..
+            } => {
+                let (idents, values): (Vec<_>, Vec<_>) = variants.iter().cloned().unzip();
+                let invalid = XmlStruct::error(format!(
+                    "Invalid {} {} value.",
+                    name.value(),
+                    attribute.value()
+                ));
+                quote! {
+                    if !elem.has_ns(crate::ns::#namespace) {
+                        #not_self;
+                    }
+                    #no_children
+                    #[cfg(not(feature = "disable-validation"))]
+                    if elem.attrs().any(|(attr, _)| attr != #attribute) {
+                        #unknown_attribute;
+                    }
+                    ::std::result::Result::Ok(match elem.required_attr(#attribute)? {
+                        #(#values => #ident::#idents,)*
+                        _ => #invalid,
+                    })
+                }
+            }
+        };
+
+        let serde = match self {
+            XmlElement::Text { .. } => quote!(),
+            XmlElement::Names { variants, .. } => serde_mirror(
+                ident,
+                variants.iter().map(|(variant, name)| (variant, Some(name))),
+                false,
+            ),
+            XmlElement::Values { variants, .. } => serde_mirror(
+                ident,
+                variants.iter().map(|(variant, _)| (variant, None)),
+                false,
+            ),
+        };
+
+        let from_str = match self {
+            XmlElement::Text { .. } => quote! {
+                impl ::std::str::FromStr for #ident {
+                    type Err = crate::util::error::Error;
+
+                    fn from_str(s: &str) -> ::std::result::Result<#ident, crate::util::error::Error> {
+                        ::std::result::Result::Ok(#ident(s.parse()?))
+                    }
+                }
+            },
+            XmlElement::Names { .. } | XmlElement::Values { .. } => quote!(),
+        };
+
+        quote! {
+            impl ::std::convert::TryFrom<crate::Element> for #ident {
+                type Error = crate::util::error::Error;
+
+                fn try_from(
+                    elem: crate::Element,
+                ) -> ::std::result::Result<#ident, crate::util::error::Error> {
+                    #body
+                }
+            }
+            #from_str
+            #serde
+        }
+    }
+
+    pub(crate) fn serialiser(&self) -> TokenStream2 {
+        match self {
+            XmlElement::Text {
+                ident,
+                name,
+                namespace,
+            } => quote! {
+                impl ::std::convert::From<#ident> for crate::Element {
+                    fn from(elem: #ident) -> crate::Element {
+                        crate::Element::builder(#name, crate::ns::#namespace)
+                            .append(::std::string::ToString::to_string(&elem.0))
+                            .build()
+                    }
+                }
+
+                impl ::minidom::AsXml for #ident {
+                    fn write_xml(&self, writer: &mut ::minidom::XmlWriter) -> ::minidom::Result<()> {
+                        writer.start(#name, crate::ns::#namespace);
+                        writer.text(&self.0);
+                        writer.end();
+                        ::std::result::Result::Ok(())
+                    }
+                }
+            },
+            XmlElement::Names {
+                ident,
+                namespace,
+                variants,
+                ..
+            } => {
+                let (idents, names): (Vec<_>, Vec<_>) = variants.iter().cloned().unzip();
+                let serde = serde_mirror(
+                    ident,
+                    variants.iter().map(|(variant, name)| (variant, Some(name))),
+                    true,
+                );
+                quote! {
+                    impl ::std::convert::From<#ident> for crate::Element {
+                        fn from(elem: #ident) -> crate::Element {
+                            let name = match elem {
+                                #(#ident::#idents => #names,)*
+                            };
+                            crate::Element::builder(name, crate::ns::#namespace).build()
+                        }
+                    }
+
+                    impl ::minidom::AsXml for #ident {
+                        fn write_xml(
+                            &self,
+                            writer: &mut ::minidom::XmlWriter,
+                        ) -> ::minidom::Result<()> {
+                            let name = match self {
+                                #(#ident::#idents => #names,)*
+                            };
+                            writer.start(name, crate::ns::#namespace);
+                            writer.end();
+                            ::std::result::Result::Ok(())
+                        }
+                    }
+                    #serde
+                }
+            }
+            XmlElement::Values {
+                ident,
+                name,
+                namespace,
+                attribute,
+                variants,
+            } => {
+                let (idents, values): (Vec<_>, Vec<_>) = variants.iter().cloned().unzip();
+                let serde = serde_mirror(
+                    ident,
+                    variants.iter().map(|(variant, _)| (variant, None)),
+                    true,
+                );
+                quote! {
+                    impl ::std::convert::From<#ident> for crate::Element {
+                        fn from(elem: #ident) -> crate::Element {
+                            let value = match elem {
+                                #(#ident::#idents => #values,)*
+                            };
+                            crate::Element::builder(#name, crate::ns::#namespace)
+                                .attr(#attribute, value)
+                                .build()
+                        }
+                    }
+                    #serde
+                }
+            }
+        }
+    }
+}
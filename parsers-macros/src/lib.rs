@@ -0,0 +1,855 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Derive macros generating the parsers and serialisers of xmpp-parsers.
+//!
+//! `#[derive(FromXml)]` implements `TryFrom<Element>`, and `#[derive(IntoXml)]` implements
+//! `From<Self> for Element`.  The generated code refers to `crate::Element`,
+//! `crate::ns` and `crate::util::error::Error`, so these derives are only meant to be used
+//! from within xmpp-parsers itself.
+//!
+//! The struct is mapped to an element with `#[xml(namespace = NS, name = "name")]`, where
+//! `NS` is a constant of `crate::ns`.  A unit struct represents an empty element, without
+//! any attribute nor child.
+//!
+//! Each field of a struct with named fields has exactly one of these mappings:
+//!
+//! - `#[xml(attribute = "name")]` maps an attribute, parsed with `FromAttributeValue` and serialised
+//!   with `IntoAttributeValue`.  It is required unless the field is an `Option<T>`, or
+//!   `default` is given in which case `Default::default()` is used when it is absent.
+//!   `empty_as_none` makes an empty optional attribute parse as `None`, and `non_empty`
+//!   rejects an empty required attribute.
+//! - `#[xml(child = "name")]` maps a child element, in the namespace of the parent unless
+//!   `namespace = NS` or `any_namespace` is given.  The field can be an `Option<T>`, a
+//!   `Vec<T>`, or a required `T` which can be made optional with `default`.  `T` is either
+//!   an `Element`, a `String` for the text of the child, or any type implementing
+//!   `TryFrom<Element>` and `Into<Element>`.
+//! - `#[xml(flag = "name")]` maps a `bool` to the presence of an empty child element,
+//!   accepting `namespace = NS` too.
+//! - `#[xml(text)]` maps the text content of the element, using the given
+//!   `codec = Codec` from `crate::util::helpers`, or `Text` by default.
+//! - `#[xml(other_children)]` collects into a `Vec<Element>` every child not mapped by another
+//!   field, instead of rejecting them, for elements meant to be extended.  They get serialised
+//!   in the position of this field.
+//!
+//! A tuple struct with a single field maps the text of an element without attribute nor
+//! child, parsed with `FromStr` and serialised with `ToString`.  An enum with
+//! `#[xml(namespace = NS, name = "name")]` maps empty elements of this namespace, each unit
+//! variant taking its element name from `#[xml(name = "name")]`.  With an additional
+//! `attribute = "name"` on the enum, all variants are instead the same element, told apart by
+//! the `#[xml(value = ...)]` of this attribute.
+//!
+//! Without a namespace, `#[xml(attribute = "name")]` on an enum makes it the type of an
+//! attribute, implementing `FromStr`, `FromAttributeValue` and `IntoAttributeValue`.  Each
+//! unit variant has a `#[xml(value = "value")]`, additional `alias = "value"` accepted when
+//! parsing, and at most one of them is the `default`, implementing `Default` and omitting the
+//! attribute when serialised; the default variant can have no value at all when it only
+//! represents an absent attribute.  A bare `#[xml(attribute)]` on a newtype wraps another
+//! attribute type, with an optional `default = expr` value for the wrapped one.
+//!
+//! Error messages are the same as those of the former `generate_*!` macros, and the checks
+//! for unknown attributes are disabled with the `disable-validation` feature.
+
+extern crate proc_macro;
+
+mod attribute;
+mod element;
+
+use attribute::XmlAttribute;
+use element::XmlElement;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, Ident, Lit,
+    LitStr, Path, PathArguments, Token, Type, Variant,
+};
+
+/// Derives `TryFrom<Element>`, or `FromStr` and `FromAttributeValue` for an attribute type,
+/// see the crate documentation for the accepted attributes.
+#[proc_macro_derive(FromXml, attributes(xml))]
+pub fn derive_from_xml(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match XmlType::parse(&input) {
+        Ok(xml) => xml.parser().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Derives `From<Self> for Element`, or `IntoAttributeValue` for an attribute type, see the
+/// crate documentation for the accepted attributes.
+#[proc_macro_derive(IntoXml, attributes(xml))]
+pub fn derive_into_xml(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match XmlType::parse(&input) {
+        Ok(xml) => xml.serialiser().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// The options given with `#[xml(...)]` on the type itself.
+#[derive(Default)]
+struct TypeOptions {
+    name: Option<LitStr>,
+    namespace: Option<Path>,
+    /// `Some(None)` for a bare `#[xml(attribute)]`.
+    attribute: Option<Option<LitStr>>,
+    default: Option<Expr>,
+}
+
+impl TypeOptions {
+    fn parse(input: &DeriveInput) -> syn::Result<TypeOptions> {
+        let mut options = TypeOptions::default();
+        for attr in xml_attributes(&input.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("namespace") {
+                    options.namespace = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("attribute") {
+                    options.attribute = Some(if meta.input.peek(Token![=]) {
+                        Some(meta.value()?.parse()?)
+                    } else {
+                        None
+                    });
+                } else if meta.path.is_ident("default") {
+                    options.default = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown xml attribute on a type"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+
+    /// Returns the name and namespace of the element this type maps to.
+    fn element(&self, ident: &Ident) -> syn::Result<(LitStr, Path)> {
+        let missing = |what| {
+            syn::Error::new(
+                ident.span(),
+                format!("missing #[xml({} = ...)] on the type", what),
+            )
+        };
+        let name = self.name.clone().ok_or_else(|| missing("name"))?;
+        let namespace = self.namespace.clone().ok_or_else(|| missing("namespace"))?;
+        Ok((name, namespace))
+    }
+}
+
+/// The options given with `#[xml(...)]` on a variant of an enum.
+#[derive(Default)]
+struct VariantOptions {
+    name: Option<LitStr>,
+    value: Option<Lit>,
+    aliases: Vec<LitStr>,
+    default: bool,
+}
+
+impl VariantOptions {
+    fn parse(variant: &Variant) -> syn::Result<VariantOptions> {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.span(),
+                "only unit variants can be derived from xml",
+            ));
+        }
+        let mut options = VariantOptions::default();
+        for attr in xml_attributes(&variant.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("value") {
+                    options.value = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("alias") {
+                    options.aliases.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default") {
+                    options.default = true;
+                } else {
+                    return Err(meta.error("unknown xml attribute on a variant"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// What a type deriving FromXml and IntoXml maps to.
+enum XmlType {
+    Struct(XmlStruct),
+    Element(XmlElement),
+    Attribute(XmlAttribute),
+}
+
+impl XmlType {
+    fn parse(input: &DeriveInput) -> syn::Result<XmlType> {
+        let options = TypeOptions::parse(input)?;
+        if options.attribute.is_some() && options.namespace.is_none() {
+            return XmlAttribute::parse(input, options).map(XmlType::Attribute);
+        }
+        match &input.data {
+            Data::Struct(data) if !matches!(data.fields, Fields::Unnamed(_)) => {
+                XmlStruct::parse(input, options).map(XmlType::Struct)
+            }
+            _ => XmlElement::parse(input, options).map(XmlType::Element),
+        }
+    }
+
+    fn parser(&self) -> TokenStream2 {
+        match self {
+            XmlType::Struct(xml) => xml.parser(),
+            XmlType::Element(xml) => xml.parser(),
+            XmlType::Attribute(xml) => xml.parser(),
+        }
+    }
+
+    fn serialiser(&self) -> TokenStream2 {
+        match self {
+            XmlType::Struct(xml) => xml.serialiser(),
+            XmlType::Element(xml) => xml.serialiser(),
+            XmlType::Attribute(xml) => xml.serialiser(),
+        }
+    }
+}
+
+/// Implements serde’s `Serialize`, or `Deserialize`, for an enum through a copy of it with
+/// its variants renamed, as `#[serde(rename)]` can’t be added from a derive.
+fn serde_mirror<'a, I: Iterator<Item = (&'a Ident, Option<&'a LitStr>)>>(
+    ident: &Ident,
+    variants: I,
+    serialise: bool,
+) -> TokenStream2 {
+    let (idents, renames): (Vec<_>, Vec<_>) = variants
+        .map(|(variant, value)| {
+            let rename = value.map(|value| quote!(#[serde(rename = #value)]));
+            (variant, rename)
+        })
+        .unzip();
+    let name = ident.to_string();
+    let (derive, implementation) = if serialise {
+        (
+            quote!(serde::Serialize),
+            quote! {
+                impl serde::Serialize for #ident {
+                    fn serialize<S: serde::Serializer>(
+                        &self,
+                        serializer: S,
+                    ) -> ::std::result::Result<S::Ok, S::Error> {
+                        let mirror = match self {
+                            #(#ident::#idents => Mirror::#idents,)*
+                        };
+                        serde::Serialize::serialize(&mirror, serializer)
+                    }
+                }
+            },
+        )
+    } else {
+        (
+            quote!(serde::Deserialize),
+            quote! {
+                impl<'de> serde::Deserialize<'de> for #ident {
+                    fn deserialize<D: serde::Deserializer<'de>>(
+                        deserializer: D,
+                    ) -> ::std::result::Result<#ident, D::Error> {
+                        let mirror = <Mirror as serde::Deserialize>::deserialize(deserializer)?;
+                        ::std::result::Result::Ok(match mirror {
+                            #(Mirror::#idents => #ident::#idents,)*
+                        })
+                    }
+                }
+            },
+        )
+    };
+    quote! {
+        #[cfg(feature = "serde")]
+        const _: () = {
+            #[derive(#derive)]
+            #[serde(rename = #name)]
+            enum Mirror {
+                #(#renames #idents,)*
+            }
+            #implementation
+        };
+    }
+}
+
+/// How an attribute gets parsed.
+enum AttributeAction {
+    Required,
+    RequiredNonEmpty,
+    Option,
+    OptionEmpty,
+    Default,
+}
+
+/// How many times a child element can appear.
+enum Cardinality {
+    Required,
+    Default,
+    Option,
+    Vec,
+    Present,
+}
+
+/// What a child element gets parsed into.
+enum ChildType {
+    Element,
+    String,
+    Other(Box<Type>),
+}
+
+enum Kind {
+    Attribute {
+        name: LitStr,
+        action: AttributeAction,
+    },
+    Child {
+        name: LitStr,
+        namespace: Option<Path>,
+        cardinality: Cardinality,
+        child_type: ChildType,
+    },
+    Text {
+        codec: Path,
+    },
+    OtherChildren,
+}
+
+struct XmlField {
+    ident: Ident,
+    kind: Kind,
+}
+
+struct XmlStruct {
+    ident: Ident,
+    name: LitStr,
+    namespace: Path,
+    /// `None` for a unit struct.
+    fields: Option<Vec<XmlField>>,
+}
+
+/// Returns the type wrapped by `wrapper`, if `ty` is `wrapper<T>`.
+fn wrapped_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_type(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.is_ident(name),
+        _ => false,
+    }
+}
+
+fn xml_attributes(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("xml"))
+}
+
+impl XmlStruct {
+    fn parse(input: &DeriveInput, options: TypeOptions) -> syn::Result<XmlStruct> {
+        if options.attribute.is_some() || options.default.is_some() {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "a struct with named fields can only take a name and a namespace",
+            ));
+        }
+        let (name, namespace) = options.element(&input.ident)?;
+
+        let data = match &input.data {
+            Data::Struct(data) => data,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "only structs can be derived from xml",
+                ))
+            }
+        };
+        let fields = match &data.fields {
+            Fields::Unit => None,
+            Fields::Named(fields) => Some(
+                fields
+                    .named
+                    .iter()
+                    .map(|field| XmlField::parse(field, &namespace))
+                    .collect::<syn::Result<Vec<_>>>()?,
+            ),
+            Fields::Unnamed(fields) => {
+                return Err(syn::Error::new(
+                    fields.span(),
+                    "tuple structs can’t be derived from xml",
+                ))
+            }
+        };
+        if let Some(fields) = &fields {
+            let mut texts = fields
+                .iter()
+                .filter(|field| matches!(field.kind, Kind::Text { .. }));
+            if let (Some(_), Some(field)) = (texts.next(), texts.next()) {
+                return Err(syn::Error::new(
+                    field.ident.span(),
+                    "only one field can contain the text of the element",
+                ));
+            }
+            let mut others = fields
+                .iter()
+                .filter(|field| matches!(field.kind, Kind::OtherChildren));
+            if let (Some(_), Some(field)) = (others.next(), others.next()) {
+                return Err(syn::Error::new(
+                    field.ident.span(),
+                    "only one field can contain the other children of the element",
+                ));
+            }
+        }
+
+        Ok(XmlStruct {
+            ident: input.ident.clone(),
+            name,
+            namespace,
+            fields,
+        })
+    }
+
+    fn error(message: String) -> TokenStream2 {
+        quote! {
+            return ::std::result::Result::Err(crate::util::error::Error::ParseError(#message))
+        }
+    }
+
+    fn parser(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let name = &self.name;
+        let namespace = &self.namespace;
+        let not_self = Self::error(format!("This is not a {} element.", name.value()));
+        let unknown_attribute =
+            Self::error(format!("Unknown attribute in {} element.", name.value()));
+        let unknown_child = Self::error(format!("Unknown child in {} element.", name.value()));
+
+        let body = match &self.fields {
+            None => quote! {
+                #[cfg(not(feature = "disable-validation"))]
+                if elem.children().next().is_some() {
+                    #unknown_child;
+                }
+                #[cfg(not(feature = "disable-validation"))]
+                if elem.attrs().next().is_some() {
+                    #unknown_attribute;
+                }
+                ::std::result::Result::Ok(#ident)
+            },
+            Some(fields) => {
+                let attribute_names = fields.iter().filter_map(|field| match &field.kind {
+                    Kind::Attribute { name, .. } => Some(name),
+                    _ => None,
+                });
+                let temporaries = fields.iter().map(|field| field.start_parse());
+                let children = fields.iter().map(|field| field.parse_child(name));
+                let values = fields.iter().map(|field| field.finish_parse(name));
+                let unknown_child = match fields
+                    .iter()
+                    .find(|field| matches!(field.kind, Kind::OtherChildren))
+                {
+                    Some(field) => {
+                        let temporary = field.temporary();
+                        quote!(#temporary.push(child.clone()))
+                    }
+                    None => unknown_child,
+                };
+                quote! {
+                    #[cfg(not(feature = "disable-validation"))]
+                    {
+                        const KNOWN_ATTRIBUTES: &[&str] = &[#(#attribute_names),*];
+                        if elem.attrs().any(|(attr, _)| !KNOWN_ATTRIBUTES.contains(&attr)) {
+                            #unknown_attribute;
+                        }
+                    }
+                    #(#temporaries)*
+                    for child in elem.children() {
+                        #(#children)*
+                        #unknown_child;
+                    }
+                    ::std::result::Result::Ok(#ident {
+                        #(#values,)*
+                    })
+                }
+            }
+        };
+
+        quote! {
+            impl ::std::convert::TryFrom<crate::Element> for #ident {
+                type Error = crate::util::error::Error;
+
+                fn try_from(
+                    elem: crate::Element,
+                ) -> ::std::result::Result<#ident, crate::util::error::Error> {
+                    if !elem.is(#name, crate::ns::#namespace) {
+                        #not_self;
+                    }
+                    #body
+                }
+            }
+        }
+    }
+
+    fn serialiser(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let name = &self.name;
+        let namespace = &self.namespace;
+        let (binding, fields) = match &self.fields {
+            None => (quote!(_), Vec::new()),
+            Some(fields) => (
+                quote!(elem),
+                fields
+                    .iter()
+                    .map(|field| field.serialise(namespace))
+                    .collect(),
+            ),
+        };
+        quote! {
+            impl ::std::convert::From<#ident> for crate::Element {
+                fn from(#binding: #ident) -> crate::Element {
+                    #[allow(unused_mut)]
+                    let mut builder = crate::Element::builder(#name, crate::ns::#namespace);
+                    #(#fields)*
+                    builder.build()
+                }
+            }
+        }
+    }
+}
+
+impl XmlField {
+    fn parse(field: &syn::Field, parent_namespace: &Path) -> syn::Result<XmlField> {
+        let ident = field.ident.clone().unwrap();
+        let mut attribute: Option<LitStr> = None;
+        let mut child: Option<LitStr> = None;
+        let mut flag: Option<LitStr> = None;
+        let mut text = false;
+        let mut codec: Option<Path> = None;
+        let mut namespace: Option<Path> = None;
+        let mut any_namespace = false;
+        let mut default = false;
+        let mut empty_as_none = false;
+        let mut non_empty = false;
+        let mut other_children = false;
+        for attr in xml_attributes(&field.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("attribute") {
+                    attribute = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("child") {
+                    child = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("flag") {
+                    flag = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("text") {
+                    text = true;
+                } else if meta.path.is_ident("codec") {
+                    codec = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("namespace") {
+                    namespace = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("any_namespace") {
+                    any_namespace = true;
+                } else if meta.path.is_ident("default") {
+                    default = true;
+                } else if meta.path.is_ident("empty_as_none") {
+                    empty_as_none = true;
+                } else if meta.path.is_ident("non_empty") {
+                    non_empty = true;
+                } else if meta.path.is_ident("other_children") {
+                    other_children = true;
+                } else {
+                    return Err(meta.error("unknown xml attribute on a field"));
+                }
+                Ok(())
+            })?;
+        }
+        let error = |message: &str| Err(syn::Error::new(ident.span(), message));
+
+        let ty = &field.ty;
+        if other_children {
+            if attribute.is_some() || child.is_some() || flag.is_some() || text {
+                return error("#[xml(other_children)] can’t be combined with another mapping");
+            }
+            if !matches!(wrapped_type(ty, "Vec"), Some(inner) if is_type(inner, "Element")) {
+                return error("#[xml(other_children)] must be a Vec<Element>");
+            }
+            return Ok(XmlField {
+                ident,
+                kind: Kind::OtherChildren,
+            });
+        }
+        let kind = match (attribute, child, flag, text) {
+            (Some(name), None, None, false) => {
+                let action = if wrapped_type(ty, "Option").is_some() {
+                    if empty_as_none {
+                        AttributeAction::OptionEmpty
+                    } else {
+                        AttributeAction::Option
+                    }
+                } else if default {
+                    AttributeAction::Default
+                } else if non_empty {
+                    AttributeAction::RequiredNonEmpty
+                } else {
+                    AttributeAction::Required
+                };
+                Kind::Attribute { name, action }
+            }
+            (None, Some(name), None, false) => {
+                let (cardinality, inner) = if let Some(inner) = wrapped_type(ty, "Vec") {
+                    (Cardinality::Vec, inner)
+                } else if let Some(inner) = wrapped_type(ty, "Option") {
+                    (Cardinality::Option, inner)
+                } else if default {
+                    (Cardinality::Default, ty)
+                } else {
+                    (Cardinality::Required, ty)
+                };
+                let child_type = if is_type(inner, "Element") {
+                    ChildType::Element
+                } else if is_type(inner, "String") {
+                    ChildType::String
+                } else {
+                    ChildType::Other(Box::new(inner.clone()))
+                };
+                if any_namespace && matches!(child_type, ChildType::String) {
+                    return error("a String child needs a namespace to be serialised");
+                }
+                Kind::Child {
+                    name,
+                    namespace: if any_namespace {
+                        None
+                    } else {
+                        Some(namespace.unwrap_or_else(|| parent_namespace.clone()))
+                    },
+                    cardinality,
+                    child_type,
+                }
+            }
+            (None, None, Some(name), false) => {
+                if !is_type(ty, "bool") {
+                    return error("a flag must be a bool");
+                }
+                if any_namespace {
+                    return error("a flag needs a namespace to be serialised");
+                }
+                Kind::Child {
+                    name,
+                    namespace: Some(namespace.unwrap_or_else(|| parent_namespace.clone())),
+                    cardinality: Cardinality::Present,
+                    child_type: ChildType::Element,
+                }
+            }
+            (None, None, None, true) => Kind::Text {
+                codec: codec.unwrap_or_else(|| syn::parse_quote!(crate::util::helpers::Text)),
+            },
+            _ => {
+                return error(
+                    "exactly one of #[xml(attribute)], #[xml(child)], #[xml(flag)] or #[xml(text)] is required",
+                )
+            }
+        };
+
+        Ok(XmlField { ident, kind })
+    }
+
+    /// The local variable in which a child gets accumulated while parsing.
+    fn temporary(&self) -> Ident {
+        format_ident!("__{}", self.ident)
+    }
+
+    fn start_parse(&self) -> TokenStream2 {
+        let temporary = self.temporary();
+        match &self.kind {
+            Kind::Child {
+                cardinality: Cardinality::Vec,
+                ..
+            }
+            | Kind::OtherChildren => quote!(let mut #temporary = ::std::vec::Vec::new();),
+            Kind::Child {
+                cardinality: Cardinality::Present,
+                ..
+            } => quote!(let mut #temporary = false;),
+            Kind::Child { .. } => quote!(let mut #temporary = ::std::option::Option::None;),
+            _ => quote!(),
+        }
+    }
+
+    fn parse_child(&self, parent: &LitStr) -> TokenStream2 {
+        let (name, namespace, cardinality, child_type) = match &self.kind {
+            Kind::Child {
+                name,
+                namespace,
+                cardinality,
+                child_type,
+            } => (name, namespace, cardinality, child_type),
+            _ => return quote!(),
+        };
+        let temporary = self.temporary();
+        let test = match namespace {
+            Some(namespace) => quote!(child.is(#name, crate::ns::#namespace)),
+            None => quote!(child.is(#name, ::minidom::NSChoice::Any)),
+        };
+        let value = match child_type {
+            ChildType::Element => quote!(child.clone()),
+            ChildType::String => quote!(child.text()),
+            ChildType::Other(ty) => {
+                quote!(<#ty as ::std::convert::TryFrom<crate::Element>>::try_from(child.clone())?)
+            }
+        };
+        let duplicate = XmlStruct::error(format!(
+            "Element {} must not have more than one {} child.",
+            parent.value(),
+            name.value()
+        ));
+        let store = match cardinality {
+            Cardinality::Vec => quote!(#temporary.push(#value);),
+            Cardinality::Present => quote! {
+                if #temporary {
+                    #duplicate;
+                }
+                #temporary = true;
+            },
+            _ => quote! {
+                if #temporary.is_some() {
+                    #duplicate;
+                }
+                #temporary = ::std::option::Option::Some(#value);
+            },
+        };
+        quote! {
+            if #test {
+                #store
+                continue;
+            }
+        }
+    }
+
+    fn finish_parse(&self, parent: &LitStr) -> TokenStream2 {
+        let ident = &self.ident;
+        let temporary = self.temporary();
+        let value = match &self.kind {
+            Kind::Attribute { name, action } => match action {
+                AttributeAction::Required => quote! {
+                    elem.required_attr(#name)?
+                },
+                AttributeAction::RequiredNonEmpty => {
+                    let empty = XmlStruct::error(format!(
+                        "Required attribute '{}' must not be empty.",
+                        name.value()
+                    ));
+                    quote! {
+                        match elem.attr(#name) {
+                            ::std::option::Option::Some("") => #empty,
+                            _ => elem.required_attr(#name)?,
+                        }
+                    }
+                }
+                AttributeAction::Option => quote! {
+                    elem.attr_as(#name)?
+                },
+                AttributeAction::OptionEmpty => quote! {
+                    match elem.attr(#name) {
+                        ::std::option::Option::Some("") => ::std::option::Option::None,
+                        _ => elem.attr_as(#name)?,
+                    }
+                },
+                AttributeAction::Default => quote! {
+                    elem.attr_as(#name)?.unwrap_or_default()
+                },
+            },
+            Kind::Child {
+                name, cardinality, ..
+            } => match cardinality {
+                Cardinality::Required => {
+                    let missing = XmlStruct::error(format!(
+                        "Missing child {} in {} element.",
+                        name.value(),
+                        parent.value()
+                    ));
+                    quote! {
+                        match #temporary {
+                            ::std::option::Option::Some(value) => value,
+                            ::std::option::Option::None => #missing,
+                        }
+                    }
+                }
+                Cardinality::Default => quote!(#temporary.unwrap_or_default()),
+                _ => quote!(#temporary),
+            },
+            Kind::Text { codec } => quote!(#codec::decode(&elem.text())?),
+            Kind::OtherChildren => quote!(#temporary),
+        };
+        quote!(#ident: #value)
+    }
+
+    fn serialise(&self, parent_namespace: &Path) -> TokenStream2 {
+        let ident = &self.ident;
+        match &self.kind {
+            Kind::Attribute { name, .. } => quote! {
+                builder = builder.attr(#name, elem.#ident);
+            },
+            Kind::Child {
+                name,
+                namespace,
+                cardinality,
+                child_type,
+            } => {
+                let namespace = namespace.as_ref().unwrap_or(parent_namespace);
+                let node = |value: TokenStream2| match child_type {
+                    ChildType::String => quote! {
+                        ::minidom::Node::Element(
+                            crate::Element::builder(#name, crate::ns::#namespace)
+                                .append(::minidom::Node::Text(#value))
+                                .build(),
+                        )
+                    },
+                    _ => quote!(::minidom::Node::Element(crate::Element::from(#value))),
+                };
+                let child = node(quote!(child));
+                match cardinality {
+                    Cardinality::Vec => quote! {
+                        builder = builder.append_all(elem.#ident.into_iter().map(|child| #child));
+                    },
+                    Cardinality::Option => quote! {
+                        builder = builder.append_all(elem.#ident.map(|child| #child));
+                    },
+                    Cardinality::Present => quote! {
+                        if elem.#ident {
+                            builder = builder.append(::minidom::Node::Element(
+                                crate::Element::builder(#name, crate::ns::#namespace).build(),
+                            ));
+                        }
+                    },
+                    Cardinality::Required | Cardinality::Default => {
+                        let child = node(quote!(elem.#ident));
+                        quote! {
+                            builder = builder.append(#child);
+                        }
+                    }
+                }
+            }
+            Kind::Text { codec } => quote! {
+                builder = builder.append_all(
+                    #codec::encode(&elem.#ident).map(::minidom::Node::Text),
+                );
+            },
+            Kind::OtherChildren => quote! {
+                builder = builder.append_all(elem.#ident.into_iter().map(::minidom::Node::Element));
+            },
+        }
+    }
+}
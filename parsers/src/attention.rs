@@ -5,13 +5,13 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// Requests the attention of the recipient.
-    Attention,
-    "attention",
-    ATTENTION
-);
+/// Requests the attention of the recipient.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = ATTENTION, name = "attention")]
+pub struct Attention;
 
 impl MessagePayload for Attention {}
 
@@ -7,50 +7,59 @@
 use crate::hashes::Sha1HexAttribute;
 use crate::pubsub::PubSubPayload;
 use crate::util::helpers::WhitespaceAwareBase64;
-
-generate_element!(
-    /// Communicates information about an avatar.
-    Metadata, "metadata", AVATAR_METADATA,
-    children: [
-        /// List of information elements describing this avatar.
-        infos: Vec<Info> = ("info", AVATAR_METADATA) => Info
-    ]
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Communicates information about an avatar.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = AVATAR_METADATA, name = "metadata")]
+pub struct Metadata {
+    /// List of information elements describing this avatar.
+    #[xml(child = "info")]
+    pub infos: Vec<Info>,
+}
 
 impl PubSubPayload for Metadata {}
 
-generate_element!(
-    /// Communicates avatar metadata.
-    Info, "info", AVATAR_METADATA,
-    attributes: [
-        /// The size of the image data in bytes.
-        bytes: Required<u16> = "bytes",
-
-        /// The width of the image in pixels.
-        width: Option<u16> = "width",
-
-        /// The height of the image in pixels.
-        height: Option<u16> = "height",
-
-        /// The SHA-1 hash of the image data for the specified content-type.
-        id: Required<Sha1HexAttribute> = "id",
-
-        /// The IANA-registered content type of the image data.
-        type_: Required<String> = "type",
-
-        /// The http: or https: URL at which the image data file is hosted.
-        url: Option<String> = "url",
-    ]
-);
-
-generate_element!(
-    /// The actual avatar data.
-    Data, "data", AVATAR_DATA,
-    text: (
-        /// Vector of bytes representing the avatar’s image.
-        data: WhitespaceAwareBase64<Vec<u8>>
-    )
-);
+/// Communicates avatar metadata.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = AVATAR_METADATA, name = "info")]
+pub struct Info {
+    /// The size of the image data in bytes.
+    #[xml(attribute = "bytes")]
+    pub bytes: u16,
+
+    /// The width of the image in pixels.
+    #[xml(attribute = "width")]
+    pub width: Option<u16>,
+
+    /// The height of the image in pixels.
+    #[xml(attribute = "height")]
+    pub height: Option<u16>,
+
+    /// The SHA-1 hash of the image data for the specified content-type.
+    #[xml(attribute = "id")]
+    pub id: Sha1HexAttribute,
+
+    /// The IANA-registered content type of the image data.
+    #[xml(attribute = "type")]
+    pub type_: String,
+
+    /// The http: or https: URL at which the image data file is hosted.
+    #[xml(attribute = "url")]
+    pub url: Option<String>,
+}
+
+/// The actual avatar data.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = AVATAR_DATA, name = "data")]
+pub struct Data {
+    /// Vector of bytes representing the avatar’s image.
+    #[xml(text, codec = WhitespaceAwareBase64)]
+    pub data: Vec<u8>,
+}
 
 impl PubSubPayload for Data {}
 
@@ -17,6 +17,7 @@ use std::str::FromStr;
 ///
 /// See https://xmpp.org/rfcs/rfc6120.html#bind
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BindQuery {
     /// Requests this resource, the server may associate another one though.
     ///
@@ -74,6 +75,7 @@ impl From<BindQuery> for Element {
 ///
 /// See https://xmpp.org/rfcs/rfc6120.html#bind
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BindResponse {
     /// The full JID returned by the server for this client.
     jid: FullJid,
@@ -143,14 +145,14 @@ mod tests {
     #[test]
     fn test_size() {
         assert_size!(BindQuery, 12);
-        assert_size!(BindResponse, 36);
+        assert_size!(BindResponse, 16);
     }
 
     #[cfg(target_pointer_width = "64")]
     #[test]
     fn test_size() {
         assert_size!(BindQuery, 24);
-        assert_size!(BindResponse, 72);
+        assert_size!(BindResponse, 32);
     }
 
     #[test]
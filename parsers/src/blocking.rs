@@ -10,14 +10,14 @@ use crate::util::error::Error;
 use crate::Element;
 use jid::Jid;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// The element requesting the blocklist, the result iq will contain a
-    /// [BlocklistResult].
-    BlocklistRequest,
-    "blocklist",
-    BLOCKING
-);
+/// The element requesting the blocklist, the result iq will contain a
+/// [BlocklistResult].
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = BLOCKING, name = "blocklist")]
+pub struct BlocklistRequest;
 
 impl IqGetPayload for BlocklistRequest {}
 
@@ -25,6 +25,7 @@ macro_rules! generate_blocking_element {
     ($(#[$meta:meta])* $elem:ident, $name:tt) => (
         $(#[$meta])*
         #[derive(Debug, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $elem {
             /// List of JIDs affected by this command.
             pub items: Vec<Jid>,
@@ -41,7 +42,7 @@ macro_rules! generate_blocking_element {
                     check_self!(child, "item", BLOCKING);
                     check_no_unknown_attributes!(child, "item", ["jid"]);
                     check_no_children!(child, "item");
-                    items.push(get_attr!(child, "jid", Required));
+                    items.push(child.required_attr("jid")?);
                 }
                 Ok($elem { items })
             }
@@ -88,12 +89,11 @@ generate_blocking_element!(
 
 impl IqSetPayload for Unblock {}
 
-generate_empty_element!(
-    /// The application-specific error condition when a message is blocked.
-    Blocked,
-    "blocked",
-    BLOCKING_ERRORS
-);
+/// The application-specific error condition when a message is blocked.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = BLOCKING_ERRORS, name = "blocked")]
+pub struct Blocked;
 
 #[cfg(test)]
 mod tests {
@@ -141,14 +141,8 @@ mod tests {
     fn test_items() {
         let elem: Element = "<blocklist xmlns='urn:xmpp:blocking'><item jid='coucou@coucou'/><item jid='domain'/></blocklist>".parse().unwrap();
         let two_items = vec![
-            Jid::Bare(BareJid {
-                node: Some(String::from("coucou")),
-                domain: String::from("coucou"),
-            }),
-            Jid::Bare(BareJid {
-                node: None,
-                domain: String::from("domain"),
-            }),
+            Jid::Bare(BareJid::new("coucou", "coucou")),
+            Jid::Bare(BareJid::from_domain("domain")),
         ];
 
         let result_elem = elem.clone();
@@ -7,14 +7,16 @@
 use crate::hashes::{Algo, Hash};
 use crate::util::error::Error;
 use crate::util::helpers::Base64;
-use minidom::IntoAttributeValue;
+use minidom::{FromAttributeValue, IntoAttributeValue};
 use std::str::FromStr;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
 /// A Content-ID, as defined in RFC2111.
 ///
 /// The text value SHOULD be of the form algo+hash@bob.xmpp.org, this struct
 /// enforces that format.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContentId {
     hash: Hash,
 }
@@ -49,6 +51,14 @@ impl FromStr for ContentId {
     }
 }
 
+impl FromAttributeValue for ContentId {
+    type Error = Error;
+
+    fn from_attribute_value(value: &str) -> Result<ContentId, Error> {
+        ContentId::from_str(value)
+    }
+}
+
 impl IntoAttributeValue for ContentId {
     fn into_attribute_value(self) -> Option<String> {
         let algo = match self.hash.algo {
@@ -60,30 +70,33 @@ impl IntoAttributeValue for ContentId {
     }
 }
 
-generate_element!(
-    /// Request for an uncached cid file.
-    Data, "data", BOB,
-    attributes: [
-        /// The cid in question.
-        cid: Required<ContentId> = "cid",
-
-        /// How long to cache it (in seconds).
-        max_age: Option<usize> = "max-age",
-
-        /// The MIME type of the data being transmitted.
-        ///
-        /// See the [IANA MIME Media Types Registry][1] for a list of
-        /// registered types, but unregistered or yet-to-be-registered are
-        /// accepted too.
-        ///
-        /// [1]: https://www.iana.org/assignments/media-types/media-types.xhtml
-        type_: Option<String> = "type"
-    ],
-    text: (
-        /// The actual data.
-        data: Base64<Vec<u8>>
-    )
-);
+/// Request for an uncached cid file.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = BOB, name = "data")]
+pub struct Data {
+    /// The cid in question.
+    #[xml(attribute = "cid")]
+    pub cid: ContentId,
+
+    /// How long to cache it (in seconds).
+    #[xml(attribute = "max-age")]
+    pub max_age: Option<usize>,
+
+    /// The MIME type of the data being transmitted.
+    ///
+    /// See the [IANA MIME Media Types Registry][1] for a list of
+    /// registered types, but unregistered or yet-to-be-registered are
+    /// accepted too.
+    ///
+    /// [1]: https://www.iana.org/assignments/media-types/media-types.xhtml
+    #[xml(attribute = "type")]
+    pub type_: Option<String>,
+
+    /// The actual data.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
 
 #[cfg(test)]
 mod tests {
@@ -5,60 +5,74 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use jid::BareJid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Whether a conference bookmark should be joined automatically.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "autojoin")]
+pub enum Autojoin {
+    /// True value, represented by either 'true' or '1'.
+    #[xml(value = "true", alias = "1")]
+    True,
+
+    /// False value, represented by either 'false' or '0'.
+    #[xml(value = "false", alias = "0", default)]
+    False,
+}
 
-generate_attribute!(
+/// A conference bookmark.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = BOOKMARKS, name = "conference")]
+pub struct Conference {
     /// Whether a conference bookmark should be joined automatically.
-    Autojoin,
-    "autojoin",
-    bool
-);
-
-generate_element!(
-    /// A conference bookmark.
-    Conference, "conference", BOOKMARKS,
-    attributes: [
-        /// Whether a conference bookmark should be joined automatically.
-        autojoin: Default<Autojoin> = "autojoin",
-
-        /// The JID of the conference.
-        jid: Required<BareJid> = "jid",
-
-        /// A user-defined name for this conference.
-        name: Option<String> = "name",
-    ],
-    children: [
-        /// The nick the user will use to join this conference.
-        nick: Option<String> = ("nick", BOOKMARKS) => String,
-
-        /// The password required to join this conference.
-        password: Option<String> = ("password", BOOKMARKS) => String
-    ]
-);
-
-generate_element!(
-    /// An URL bookmark.
-    Url, "url", BOOKMARKS,
-    attributes: [
-        /// A user-defined name for this URL.
-        name: Option<String> = "name",
-
-        /// The URL of this bookmark.
-        url: Required<String> = "url",
-    ]
-);
-
-generate_element!(
-    /// Container element for multiple bookmarks.
-    #[derive(Default)]
-    Storage, "storage", BOOKMARKS,
-    children: [
-        /// Conferences the user has expressed an interest in.
-        conferences: Vec<Conference> = ("conference", BOOKMARKS) => Conference,
-
-        /// URLs the user is interested in.
-        urls: Vec<Url> = ("url", BOOKMARKS) => Url
-    ]
-);
+    #[xml(attribute = "autojoin", default)]
+    pub autojoin: Autojoin,
+
+    /// The JID of the conference.
+    #[xml(attribute = "jid")]
+    pub jid: BareJid,
+
+    /// A user-defined name for this conference.
+    #[xml(attribute = "name")]
+    pub name: Option<String>,
+
+    /// The nick the user will use to join this conference.
+    #[xml(child = "nick")]
+    pub nick: Option<String>,
+
+    /// The password required to join this conference.
+    #[xml(child = "password")]
+    pub password: Option<String>,
+}
+
+/// An URL bookmark.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = BOOKMARKS, name = "url")]
+pub struct Url {
+    /// A user-defined name for this URL.
+    #[xml(attribute = "name")]
+    pub name: Option<String>,
+
+    /// The URL of this bookmark.
+    #[xml(attribute = "url")]
+    pub url: String,
+}
+
+/// Container element for multiple bookmarks.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = BOOKMARKS, name = "storage")]
+pub struct Storage {
+    /// Conferences the user has expressed an interest in.
+    #[xml(child = "conference")]
+    pub conferences: Vec<Conference>,
+
+    /// URLs the user is interested in.
+    #[xml(child = "url")]
+    pub urls: Vec<Url>,
+}
 
 impl Storage {
     /// Create an empty bookmarks storage.
@@ -76,7 +90,7 @@ mod tests {
     #[cfg(target_pointer_width = "32")]
     #[test]
     fn test_size() {
-        assert_size!(Conference, 64);
+        assert_size!(Conference, 56);
         assert_size!(Url, 24);
         assert_size!(Storage, 24);
     }
@@ -84,7 +98,7 @@ mod tests {
     #[cfg(target_pointer_width = "64")]
     #[test]
     fn test_size() {
-        assert_size!(Conference, 128);
+        assert_size!(Conference, 112);
         assert_size!(Url, 48);
         assert_size!(Storage, 48);
     }
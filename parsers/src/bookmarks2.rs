@@ -7,16 +7,24 @@ use crate::ns;
 use crate::util::error::Error;
 use crate::Element;
 use std::convert::TryFrom;
-
-generate_attribute!(
-    /// Whether a conference bookmark should be joined automatically.
-    Autojoin,
-    "autojoin",
-    bool
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Whether a conference bookmark should be joined automatically.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "autojoin")]
+pub enum Autojoin {
+    /// True value, represented by either 'true' or '1'.
+    #[xml(value = "true", alias = "1")]
+    True,
+
+    /// False value, represented by either 'false' or '0'.
+    #[xml(value = "false", alias = "0", default)]
+    False,
+}
 
 /// A conference bookmark.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Conference {
     /// Whether a conference bookmark should be joined automatically.
     pub autojoin: Autojoin,
@@ -49,8 +57,8 @@ impl TryFrom<Element> for Conference {
         check_no_unknown_attributes!(root, "Conference", ["autojoin", "name"]);
 
         let mut conference = Conference {
-            autojoin: get_attr!(root, "autojoin", Default),
-            name: get_attr!(root, "name", Option),
+            autojoin: root.attr_as("autojoin")?.unwrap_or_default(),
+            name: root.attr_as("name")?,
             nick: None,
             password: None,
             extensions: Vec::new(),
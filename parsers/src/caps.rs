@@ -20,6 +20,7 @@ use std::convert::TryFrom;
 
 /// Represents a capability hash for a given client.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Caps {
     /// Deprecated list of additional feature bundles.
     pub ext: Option<String>,
@@ -45,14 +46,14 @@ impl TryFrom<Element> for Caps {
         check_self!(elem, "c", CAPS, "caps");
         check_no_children!(elem, "caps");
         check_no_unknown_attributes!(elem, "caps", ["hash", "ver", "ext", "node"]);
-        let ver: String = get_attr!(elem, "ver", Required);
+        let ver: String = elem.required_attr("ver")?;
         let hash = Hash {
-            algo: get_attr!(elem, "hash", Required),
+            algo: elem.required_attr("hash")?,
             hash: base64::decode(&ver)?,
         };
         Ok(Caps {
-            ext: get_attr!(elem, "ext", Option),
-            node: get_attr!(elem, "node", Required),
+            ext: elem.attr_as("ext")?,
+            node: elem.required_attr("node")?,
             hash,
         })
     }
@@ -7,56 +7,54 @@
 use crate::forwarding::Forwarded;
 use crate::iq::IqSetPayload;
 use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// Enable carbons for this session.
-    Enable,
-    "enable",
-    CARBONS
-);
+/// Enable carbons for this session.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CARBONS, name = "enable")]
+pub struct Enable;
 
 impl IqSetPayload for Enable {}
 
-generate_empty_element!(
-    /// Disable a previously-enabled carbons.
-    Disable,
-    "disable",
-    CARBONS
-);
+/// Disable a previously-enabled carbons.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CARBONS, name = "disable")]
+pub struct Disable;
 
 impl IqSetPayload for Disable {}
 
-generate_empty_element!(
-    /// Request the enclosing message to not be copied to other carbons-enabled
-    /// resources of the user.
-    Private,
-    "private",
-    CARBONS
-);
+/// Request the enclosing message to not be copied to other carbons-enabled
+/// resources of the user.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CARBONS, name = "private")]
+pub struct Private;
 
 impl MessagePayload for Private {}
 
-generate_element!(
-    /// Wrapper for a message received on another resource.
-    Received, "received", CARBONS,
-
-    children: [
-        /// Wrapper for the enclosed message.
-        forwarded: Required<Forwarded> = ("forwarded", FORWARD) => Forwarded
-    ]
-);
+/// Wrapper for a message received on another resource.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CARBONS, name = "received")]
+pub struct Received {
+    /// Wrapper for the enclosed message.
+    #[xml(child = "forwarded", namespace = FORWARD)]
+    pub forwarded: Forwarded,
+}
 
 impl MessagePayload for Received {}
 
-generate_element!(
-    /// Wrapper for a message sent from another resource.
-    Sent, "sent", CARBONS,
-
-    children: [
-        /// Wrapper for the enclosed message.
-        forwarded: Required<Forwarded> = ("forwarded", FORWARD) => Forwarded
-    ]
-);
+/// Wrapper for a message sent from another resource.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CARBONS, name = "sent")]
+pub struct Sent {
+    /// Wrapper for the enclosed message.
+    #[xml(child = "forwarded", namespace = FORWARD)]
+    pub forwarded: Forwarded,
+}
 
 impl MessagePayload for Sent {}
 
@@ -6,113 +6,123 @@
 
 use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
 use crate::util::helpers::Base64;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The name of a certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "name")]
+pub struct Name(pub String);
+
+/// An X.509 certificate.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "x509cert")]
+pub struct Cert {
+    /// The BER X.509 data.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
 
-generate_elem_id!(
-    /// The name of a certificate.
-    Name,
-    "name",
-    SASL_CERT
-);
-
-generate_element!(
-    /// An X.509 certificate.
-    Cert, "x509cert", SASL_CERT,
-    text: (
-        /// The BER X.509 data.
-        data: Base64<Vec<u8>>
-    )
-);
-
-generate_element!(
-    /// For the client to upload an X.509 certificate.
-    Append, "append", SASL_CERT,
-    children: [
-        /// The name of this certificate.
-        name: Required<Name> = ("name", SASL_CERT) => Name,
-
-        /// The X.509 certificate to set.
-        cert: Required<Cert> = ("x509cert", SASL_CERT) => Cert,
-
-        /// This client is forbidden from managing certificates.
-        no_cert_management: Present<_> = ("no-cert-management", SASL_CERT) => bool
-    ]
-);
+/// For the client to upload an X.509 certificate.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "append")]
+pub struct Append {
+    /// The name of this certificate.
+    #[xml(child = "name")]
+    pub name: Name,
+
+    /// The X.509 certificate to set.
+    #[xml(child = "x509cert")]
+    pub cert: Cert,
+
+    /// This client is forbidden from managing certificates.
+    #[xml(flag = "no-cert-management")]
+    pub no_cert_management: bool,
+}
 
 impl IqSetPayload for Append {}
 
-generate_empty_element!(
-    /// Client requests the current list of X.509 certificates.
-    ListCertsQuery,
-    "items",
-    SASL_CERT
-);
+/// Client requests the current list of X.509 certificates.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "items")]
+pub struct ListCertsQuery;
 
 impl IqGetPayload for ListCertsQuery {}
 
-generate_elem_id!(
-    /// One resource currently using a certificate.
-    Resource,
-    "resource",
-    SASL_CERT
-);
-
-generate_element!(
-    /// A list of resources currently using this certificate.
-    Users, "users", SASL_CERT,
-    children: [
-        /// Resources currently using this certificate.
-        resources: Vec<Resource> = ("resource", SASL_CERT) => Resource
-    ]
-);
-
-generate_element!(
-    /// An X.509 certificate being set for this user.
-    Item, "item", SASL_CERT,
-    children: [
-        /// The name of this certificate.
-        name: Required<Name> = ("name", SASL_CERT) => Name,
-
-        /// The X.509 certificate to set.
-        cert: Required<Cert> = ("x509cert", SASL_CERT) => Cert,
-
-        /// This client is forbidden from managing certificates.
-        no_cert_management: Present<_> = ("no-cert-management", SASL_CERT) => bool,
-
-        /// List of resources currently using this certificate.
-        users: Option<Users> = ("users", SASL_CERT) => Users
-    ]
-);
-
-generate_element!(
-    /// Server answers with the current list of X.509 certificates.
-    ListCertsResponse, "items", SASL_CERT,
-    children: [
-        /// List of certificates.
-        items: Vec<Item> = ("item", SASL_CERT) => Item
-    ]
-);
+/// One resource currently using a certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "resource")]
+pub struct Resource(pub String);
+
+/// A list of resources currently using this certificate.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "users")]
+pub struct Users {
+    /// Resources currently using this certificate.
+    #[xml(child = "resource")]
+    pub resources: Vec<Resource>,
+}
+
+/// An X.509 certificate being set for this user.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "item")]
+pub struct Item {
+    /// The name of this certificate.
+    #[xml(child = "name")]
+    pub name: Name,
+
+    /// The X.509 certificate to set.
+    #[xml(child = "x509cert")]
+    pub cert: Cert,
+
+    /// This client is forbidden from managing certificates.
+    #[xml(flag = "no-cert-management")]
+    pub no_cert_management: bool,
+
+    /// List of resources currently using this certificate.
+    #[xml(child = "users")]
+    pub users: Option<Users>,
+}
+
+/// Server answers with the current list of X.509 certificates.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "items")]
+pub struct ListCertsResponse {
+    /// List of certificates.
+    #[xml(child = "item")]
+    pub items: Vec<Item>,
+}
 
 impl IqResultPayload for ListCertsResponse {}
 
-generate_element!(
-    /// Client disables an X.509 certificate.
-    Disable, "disable", SASL_CERT,
-    children: [
-        /// Name of the certificate to disable.
-        name: Required<Name> = ("name", SASL_CERT) => Name
-    ]
-);
+/// Client disables an X.509 certificate.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "disable")]
+pub struct Disable {
+    /// Name of the certificate to disable.
+    #[xml(child = "name")]
+    pub name: Name,
+}
 
 impl IqSetPayload for Disable {}
 
-generate_element!(
-    /// Client revokes an X.509 certificate.
-    Revoke, "revoke", SASL_CERT,
-    children: [
-        /// Name of the certificate to revoke.
-        name: Required<Name> = ("name", SASL_CERT) => Name
-    ]
-);
+/// Client revokes an X.509 certificate.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL_CERT, name = "revoke")]
+pub struct Revoke {
+    /// Name of the certificate to revoke.
+    #[xml(child = "name")]
+    pub name: Name,
+}
 
 impl IqSetPayload for Revoke {}
 
@@ -0,0 +1,127 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Requests that this message gets marked by its recipients once received, displayed or
+/// acknowledged.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CHAT_MARKERS, name = "markable")]
+pub struct Markable;
+
+impl MessagePayload for Markable {}
+
+/// Notes that the message of this id, and every previous one, has been received.
+///
+/// The message is referenced by its
+/// [reference id](../message/struct.Message.html#method.reference_id).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CHAT_MARKERS, name = "received")]
+pub struct Received {
+    /// The id of the marked message.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
+
+impl MessagePayload for Received {}
+
+/// Notes that the message of this id, and every previous one, has been displayed to the user.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CHAT_MARKERS, name = "displayed")]
+pub struct Displayed {
+    /// The id of the marked message.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
+
+impl MessagePayload for Displayed {}
+
+/// Notes that the message of this id, and every previous one, has been acknowledged by the
+/// user, for instance by replying to it.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CHAT_MARKERS, name = "acknowledged")]
+pub struct Acknowledged {
+    /// The id of the marked message.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
+
+impl MessagePayload for Acknowledged {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Markable, 0);
+        assert_size!(Received, 24);
+        assert_size!(Displayed, 24);
+        assert_size!(Acknowledged, 24);
+    }
+
+    #[test]
+    fn test_simple() {
+        let elem: Element = "<markable xmlns='urn:xmpp:chat-markers:0'/>"
+            .parse()
+            .unwrap();
+        Markable::try_from(elem).unwrap();
+
+        let elem: Element = "<received xmlns='urn:xmpp:chat-markers:0' id='message-1'/>"
+            .parse()
+            .unwrap();
+        let received = Received::try_from(elem).unwrap();
+        assert_eq!(received.id, "message-1");
+
+        let elem: Element = "<displayed xmlns='urn:xmpp:chat-markers:0' id='message-1'/>"
+            .parse()
+            .unwrap();
+        let displayed = Displayed::try_from(elem).unwrap();
+        assert_eq!(displayed.id, "message-1");
+
+        let elem: Element = "<acknowledged xmlns='urn:xmpp:chat-markers:0' id='message-1'/>"
+            .parse()
+            .unwrap();
+        let acknowledged = Acknowledged::try_from(elem).unwrap();
+        assert_eq!(acknowledged.id, "message-1");
+    }
+
+    #[test]
+    fn test_serialise() {
+        let displayed = Displayed {
+            id: String::from("message-1"),
+        };
+        let elem: Element = displayed.into();
+        assert_eq!(
+            String::from(&elem),
+            "<displayed xmlns=\"urn:xmpp:chat-markers:0\" id=\"message-1\"/>"
+        );
+    }
+
+    #[test]
+    fn test_missing_id() {
+        let elem: Element = "<displayed xmlns='urn:xmpp:chat-markers:0'/>"
+            .parse()
+            .unwrap();
+        let error = Displayed::try_from(elem).unwrap_err();
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
+            _ => panic!(),
+        };
+        assert_eq!(attribute, "id");
+    }
+}
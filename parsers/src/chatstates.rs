@@ -5,27 +5,33 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element_enum!(
-    /// Enum representing chatstate elements part of the
-    /// `http://jabber.org/protocol/chatstates` namespace.
-    ChatState, "chatstate", CHATSTATES, {
-        /// `<active xmlns='http://jabber.org/protocol/chatstates'/>`
-        Active => "active",
+/// Enum representing chatstate elements part of the
+/// `http://jabber.org/protocol/chatstates` namespace.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(namespace = CHATSTATES, name = "chatstate")]
+pub enum ChatState {
+    /// `<active xmlns='http://jabber.org/protocol/chatstates'/>`
+    #[xml(name = "active")]
+    Active,
 
-        /// `<composing xmlns='http://jabber.org/protocol/chatstates'/>`
-        Composing => "composing",
+    /// `<composing xmlns='http://jabber.org/protocol/chatstates'/>`
+    #[xml(name = "composing")]
+    Composing,
 
-        /// `<gone xmlns='http://jabber.org/protocol/chatstates'/>`
-        Gone => "gone",
+    /// `<gone xmlns='http://jabber.org/protocol/chatstates'/>`
+    #[xml(name = "gone")]
+    Gone,
 
-        /// `<inactive xmlns='http://jabber.org/protocol/chatstates'/>`
-        Inactive => "inactive",
+    /// `<inactive xmlns='http://jabber.org/protocol/chatstates'/>`
+    #[xml(name = "inactive")]
+    Inactive,
 
-        /// `<paused xmlns='http://jabber.org/protocol/chatstates'/>`
-        Paused => "paused",
-    }
-);
+    /// `<paused xmlns='http://jabber.org/protocol/chatstates'/>`
+    #[xml(name = "paused")]
+    Paused,
+}
 
 impl MessagePayload for ChatState {}
 
@@ -0,0 +1,310 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::data_forms::DataForm;
+use crate::iq::{IqResultPayload, IqSetPayload};
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// An action requested by the requester of a command.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "action")]
+pub enum Action {
+    /// Cancels the whole command.
+    #[xml(value = "cancel")]
+    Cancel,
+
+    /// Completes the command with the current data.
+    #[xml(value = "complete")]
+    Complete,
+
+    /// Starts the command, or does the default action of its current stage.
+    #[xml(value = "execute", default)]
+    Execute,
+
+    /// Goes to the next stage of the command.
+    #[xml(value = "next")]
+    Next,
+
+    /// Goes back to the previous stage of the command.
+    #[xml(value = "prev")]
+    Prev,
+}
+
+/// The status of a command, as returned by the responder.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "status")]
+pub enum Status {
+    /// The command is being executed, and expects another action.
+    #[xml(value = "executing")]
+    Executing,
+
+    /// The command has completed.
+    #[xml(value = "completed")]
+    Completed,
+
+    /// The command has been canceled.
+    #[xml(value = "canceled")]
+    Canceled,
+}
+
+/// The severity of a note.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "type")]
+pub enum NoteType {
+    /// Information about the execution of the command.
+    #[xml(value = "info", default)]
+    Info,
+
+    /// A warning, the command can continue.
+    #[xml(value = "warn")]
+    Warn,
+
+    /// An error, the command can’t continue.
+    #[xml(value = "error")]
+    Error,
+}
+
+/// The actions a requester may do at the current stage of a command.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = COMMANDS, name = "actions")]
+pub struct Actions {
+    /// The action done when the requester executes the command again, one of those allowed.
+    #[xml(attribute = "execute")]
+    pub execute: Option<Action>,
+
+    /// Whether going back to the previous stage is allowed.
+    #[xml(flag = "prev")]
+    pub prev: bool,
+
+    /// Whether going to the next stage is allowed.
+    #[xml(flag = "next")]
+    pub next: bool,
+
+    /// Whether completing the command is allowed.
+    #[xml(flag = "complete")]
+    pub complete: bool,
+}
+
+/// A note about the execution of a command, for the requester to display.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = COMMANDS, name = "note")]
+pub struct Note {
+    /// The severity of this note.
+    #[xml(attribute = "type", default)]
+    pub type_: NoteType,
+
+    /// The text of this note.
+    #[xml(text)]
+    pub text: String,
+}
+
+impl Note {
+    /// Creates a note of this severity.
+    pub fn new<T: Into<String>>(type_: NoteType, text: T) -> Note {
+        Note {
+            type_,
+            text: text.into(),
+        }
+    }
+}
+
+/// The `<command/>` element, used both to request an action on a command and to return its
+/// result.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = COMMANDS, name = "command")]
+pub struct Command {
+    /// The node identifying this command, as advertised in disco#items.
+    #[xml(attribute = "node")]
+    pub node: String,
+
+    /// The session this command is part of, given by the responder on its first answer.
+    #[xml(attribute = "sessionid")]
+    pub sessionid: Option<String>,
+
+    /// The status of this command, only set by the responder.
+    #[xml(attribute = "status")]
+    pub status: Option<Status>,
+
+    /// The action requested, only set by the requester.
+    #[xml(attribute = "action", default)]
+    pub action: Action,
+
+    /// The actions allowed at this stage.
+    #[xml(child = "actions")]
+    pub actions: Option<Actions>,
+
+    /// Notes about the execution of this command.
+    #[xml(child = "note")]
+    pub notes: Vec<Note>,
+
+    /// The form to fill, or the form filled, at this stage.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+}
+
+impl IqSetPayload for Command {}
+impl IqResultPayload for Command {}
+
+impl Command {
+    /// Creates a request executing this command.
+    pub fn new<N: Into<String>>(node: N) -> Command {
+        Command {
+            node: node.into(),
+            sessionid: None,
+            status: None,
+            action: Action::Execute,
+            actions: None,
+            notes: Vec::new(),
+            form: None,
+        }
+    }
+
+    /// Sets the session of this command.
+    pub fn with_sessionid<S: Into<String>>(mut self, sessionid: S) -> Command {
+        self.sessionid = Some(sessionid.into());
+        self
+    }
+
+    /// Sets the action requested.
+    pub fn with_action(mut self, action: Action) -> Command {
+        self.action = action;
+        self
+    }
+
+    /// Sets the form of this command.
+    pub fn with_form(mut self, form: DataForm) -> Command {
+        self.form = Some(form);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_forms::DataFormType;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_execute() {
+        let elem: Element =
+            "<command xmlns='http://jabber.org/protocol/commands' node='list' action='execute'/>"
+                .parse()
+                .unwrap();
+        let command = Command::try_from(elem).unwrap();
+        assert_eq!(command, Command::new("list"));
+
+        let elem: Element = Command::new("list").into();
+        assert_eq!(
+            String::from(&elem),
+            "<command xmlns=\"http://jabber.org/protocol/commands\" node=\"list\"/>"
+        );
+    }
+
+    #[test]
+    fn test_stage() {
+        let elem: Element = "<command xmlns='http://jabber.org/protocol/commands' sessionid='config:20020923T213616Z-700' node='config' status='executing'>
+            <actions execute='next'>
+                <next/>
+            </actions>
+            <x xmlns='jabber:x:data' type='form'>
+                <title>Configure Service</title>
+                <field var='service' type='list-single' label='Service'>
+                    <option><value>httpd</value></option>
+                </field>
+            </x>
+        </command>"
+            .parse()
+            .unwrap();
+        let command = Command::try_from(elem).unwrap();
+        assert_eq!(command.node, "config");
+        assert_eq!(
+            command.sessionid,
+            Some(String::from("config:20020923T213616Z-700"))
+        );
+        assert_eq!(command.status, Some(Status::Executing));
+        assert_eq!(
+            command.actions,
+            Some(Actions {
+                execute: Some(Action::Next),
+                next: true,
+                ..Actions::default()
+            })
+        );
+        let form = command.form.unwrap();
+        assert_eq!(form.type_, DataFormType::Form);
+        assert_eq!(form.fields.len(), 1);
+    }
+
+    #[test]
+    fn test_completed() {
+        let elem: Element = "<command xmlns='http://jabber.org/protocol/commands' node='reset' sessionid='reset:1' status='completed'><note type='warn'>Service restarted.</note><note>Done.</note></command>"
+            .parse()
+            .unwrap();
+        let command = Command::try_from(elem.clone()).unwrap();
+        assert_eq!(
+            command.notes,
+            vec![
+                Note::new(NoteType::Warn, "Service restarted."),
+                Note::new(NoteType::Info, "Done."),
+            ]
+        );
+        assert_eq!(Element::from(command), elem);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let elem: Element = "<command xmlns='http://jabber.org/protocol/commands' node='config' sessionid='config:1' action='cancel'/>"
+            .parse()
+            .unwrap();
+        let command = Command::try_from(elem.clone()).unwrap();
+        assert_eq!(
+            command,
+            Command::new("config")
+                .with_sessionid("config:1")
+                .with_action(Action::Cancel)
+        );
+        assert_eq!(Element::from(command), elem);
+    }
+
+    #[test]
+    fn test_invalid_action() {
+        let elem: Element =
+            "<command xmlns='http://jabber.org/protocol/commands' node='list' action='run'/>"
+                .parse()
+                .unwrap();
+        let error = Command::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown value for 'action' attribute.");
+    }
+
+    #[test]
+    fn test_missing_node() {
+        let elem: Element = "<command xmlns='http://jabber.org/protocol/commands'/>"
+            .parse()
+            .unwrap();
+        let error = Command::try_from(elem).unwrap_err();
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
+            _ => panic!(),
+        };
+        assert_eq!(attribute, "node");
+    }
+}
@@ -7,21 +7,22 @@
 use crate::util::helpers::PlainText;
 use digest::Digest;
 use sha1::Sha1;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// The main authentication mechanism for components.
-    #[derive(Default)]
-    Handshake, "handshake", COMPONENT,
-    text: (
-        /// If Some, contains the hex-encoded SHA-1 of the concatenation of the
-        /// stream id and the password, and is used to authenticate against the
-        /// server.
-        ///
-        /// If None, it is the successful reply from the server, the stream is now
-        /// fully established and both sides can now exchange stanzas.
-        data: PlainText<Option<String>>
-    )
-);
+/// The main authentication mechanism for components.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = COMPONENT, name = "handshake")]
+pub struct Handshake {
+    /// If Some, contains the hex-encoded SHA-1 of the concatenation of the
+    /// stream id and the password, and is used to authenticate against the
+    /// server.
+    ///
+    /// If None, it is the successful reply from the server, the stream is now
+    /// fully established and both sides can now exchange stanzas.
+    #[xml(text, codec = PlainText)]
+    pub data: Option<String>,
+}
 
 impl Handshake {
     /// Creates a successful reply from a server.
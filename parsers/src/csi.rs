@@ -4,26 +4,25 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-generate_empty_element!(
-    /// Stream:feature sent by the server to advertise it supports CSI.
-    Feature,
-    "csi",
-    CSI
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// Client indicates it is inactive.
-    Inactive,
-    "inactive",
-    CSI
-);
+/// Stream:feature sent by the server to advertise it supports CSI.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CSI, name = "csi")]
+pub struct Feature;
 
-generate_empty_element!(
-    /// Client indicates it is active again.
-    Active,
-    "active",
-    CSI
-);
+/// Client indicates it is inactive.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CSI, name = "inactive")]
+pub struct Inactive;
+
+/// Client indicates it is active again.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = CSI, name = "active")]
+pub struct Active;
 
 #[cfg(test)]
 mod tests {
@@ -9,67 +9,80 @@ use crate::ns;
 use crate::util::error::Error;
 use crate::Element;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Represents one of the possible values for a list- field.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DATA_FORMS, name = "option")]
+pub struct Option_ {
+    /// The optional label to be displayed to the user for this option.
+    #[xml(attribute = "label")]
+    pub label: Option<String>,
+
+    /// The value returned to the server when selecting this option.
+    #[xml(child = "value")]
+    pub value: String,
+}
 
-generate_element!(
-    /// Represents one of the possible values for a list- field.
-    Option_, "option", DATA_FORMS,
-    attributes: [
-        /// The optional label to be displayed to the user for this option.
-        label: Option<String> = "label"
-    ],
-    children: [
-        /// The value returned to the server when selecting this option.
-        value: Required<String> = ("value", DATA_FORMS) => String
-    ]
-);
-
-generate_attribute!(
-    /// The type of a [field](struct.Field.html) element.
-    FieldType, "type", {
-        /// This field can only take the values "0" or "false" for a false
-        /// value, and "1" or "true" for a true value.
-        Boolean => "boolean",
-
-        /// This field describes data, it must not be sent back to the
-        /// requester.
-        Fixed => "fixed",
-
-        /// This field is hidden, it should not be displayed to the user but
-        /// should be sent back to the requester.
-        Hidden => "hidden",
-
-        /// This field accepts one or more [JIDs](../../jid/struct.Jid.html).
-        /// A client may want to let the user autocomplete them based on their
-        /// contacts list for instance.
-        JidMulti => "jid-multi",
-
-        /// This field accepts one [JID](../../jid/struct.Jid.html).  A client
-        /// may want to let the user autocomplete it based on their contacts
-        /// list for instance.
-        JidSingle => "jid-single",
-
-        /// This field accepts one or more values from the list provided as
-        /// [options](struct.Option_.html).
-        ListMulti => "list-multi",
-
-        /// This field accepts one value from the list provided as
-        /// [options](struct.Option_.html).
-        ListSingle => "list-single",
-
-        /// This field accepts one or more free form text lines.
-        TextMulti => "text-multi",
-
-        /// This field accepts one free form password, a client should hide it
-        /// in its user interface.
-        TextPrivate => "text-private",
-
-        /// This field accepts one free form text line.
-        TextSingle => "text-single",
-    }, Default = TextSingle
-);
+/// The type of a [field](struct.Field.html) element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "type")]
+pub enum FieldType {
+    /// This field can only take the values "0" or "false" for a false
+    /// value, and "1" or "true" for a true value.
+    #[xml(value = "boolean")]
+    Boolean,
+
+    /// This field describes data, it must not be sent back to the
+    /// requester.
+    #[xml(value = "fixed")]
+    Fixed,
+
+    /// This field is hidden, it should not be displayed to the user but
+    /// should be sent back to the requester.
+    #[xml(value = "hidden")]
+    Hidden,
+
+    /// This field accepts one or more [JIDs](../../jid/struct.Jid.html).
+    /// A client may want to let the user autocomplete them based on their
+    /// contacts list for instance.
+    #[xml(value = "jid-multi")]
+    JidMulti,
+
+    /// This field accepts one [JID](../../jid/struct.Jid.html).  A client
+    /// may want to let the user autocomplete it based on their contacts
+    /// list for instance.
+    #[xml(value = "jid-single")]
+    JidSingle,
+
+    /// This field accepts one or more values from the list provided as
+    /// [options](struct.Option_.html).
+    #[xml(value = "list-multi")]
+    ListMulti,
+
+    /// This field accepts one value from the list provided as
+    /// [options](struct.Option_.html).
+    #[xml(value = "list-single")]
+    ListSingle,
+
+    /// This field accepts one or more free form text lines.
+    #[xml(value = "text-multi")]
+    TextMulti,
+
+    /// This field accepts one free form password, a client should hide it
+    /// in its user interface.
+    #[xml(value = "text-private")]
+    TextPrivate,
+
+    /// This field accepts one free form text line.
+    #[xml(value = "text-single", default)]
+    TextSingle,
+}
 
 /// Represents a field in a [data form](struct.DataForm.html).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Field {
     /// The unique identifier for this field, in the form.
     pub var: String,
@@ -130,9 +143,9 @@ impl TryFrom<Element> for Field {
         check_self!(elem, "field", DATA_FORMS);
         check_no_unknown_attributes!(elem, "field", ["label", "type", "var"]);
         let mut field = Field {
-            var: get_attr!(elem, "var", Required),
-            type_: get_attr!(elem, "type", Default),
-            label: get_attr!(elem, "label", Option),
+            var: elem.required_attr("var")?,
+            type_: elem.attr_as("type")?.unwrap_or_default(),
+            label: elem.attr_as("label")?,
             required: false,
             options: vec![],
             values: vec![],
@@ -192,26 +205,31 @@ impl From<Field> for Element {
     }
 }
 
-generate_attribute!(
-    /// Represents the type of a [data form](struct.DataForm.html).
-    DataFormType, "type", {
-        /// This is a cancel request for a prior type="form" data form.
-        Cancel => "cancel",
-
-        /// This is a request for the recipient to fill this form and send it
-        /// back as type="submit".
-        Form => "form",
-
-        /// This is a result form, which contains what the requester asked for.
-        Result_ => "result",
-
-        /// This is a complete response to a form received before.
-        Submit => "submit",
-    }
-);
+/// Represents the type of a [data form](struct.DataForm.html).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "type")]
+pub enum DataFormType {
+    /// This is a cancel request for a prior type="form" data form.
+    #[xml(value = "cancel")]
+    Cancel,
+
+    /// This is a request for the recipient to fill this form and send it
+    /// back as type="submit".
+    #[xml(value = "form")]
+    Form,
+
+    /// This is a result form, which contains what the requester asked for.
+    #[xml(value = "result")]
+    Result_,
+
+    /// This is a complete response to a form received before.
+    #[xml(value = "submit")]
+    Submit,
+}
 
 /// This is a form to be sent to another entity for filling.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataForm {
     /// The type of this form, telling the other party which action to execute.
     pub type_: DataFormType,
@@ -250,7 +268,7 @@ impl TryFrom<Element> for DataForm {
     fn try_from(elem: Element) -> Result<DataForm, Error> {
         check_self!(elem, "x", DATA_FORMS);
         check_no_unknown_attributes!(elem, "x", ["type"]);
-        let type_ = get_attr!(elem, "type", Required);
+        let type_ = elem.required_attr("type")?;
         let mut form = DataForm {
             type_,
             form_type: None,
@@ -360,16 +378,25 @@ mod tests {
     fn test_invalid() {
         let elem: Element = "<x xmlns='jabber:x:data'/>".parse().unwrap();
         let error = DataForm::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'type' missing.");
+        assert_eq!(attribute, "type");
 
         let elem: Element = "<x xmlns='jabber:x:data' type='coucou'/>".parse().unwrap();
         let error = DataForm::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'type' attribute.");
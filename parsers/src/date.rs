@@ -6,7 +6,7 @@
 
 use crate::util::error::Error;
 use chrono::{DateTime as ChronoDateTime, FixedOffset};
-use minidom::{IntoAttributeValue, Node};
+use minidom::{FromAttributeValue, IntoAttributeValue, Node};
 use std::str::FromStr;
 
 /// Implements the DateTime profile of XEP-0082, which represents a
@@ -40,6 +40,14 @@ impl FromStr for DateTime {
     }
 }
 
+impl FromAttributeValue for DateTime {
+    type Error = Error;
+
+    fn from_attribute_value(value: &str) -> Result<DateTime, Error> {
+        DateTime::from_str(value)
+    }
+}
+
 impl IntoAttributeValue for DateTime {
     fn into_attribute_value(self) -> Option<String> {
         Some(self.0.to_rfc3339())
@@ -52,6 +60,27 @@ impl From<DateTime> for Node {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for DateTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0.to_rfc3339())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DateTime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        DateTime::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,4 +163,19 @@ mod tests {
         let attr = date.into_attribute_value();
         assert_eq!(attr, Some(String::from("2017-05-21T20:19:55+01:00")));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::de::{value::Error as ValueError, IntoDeserializer};
+        use serde::Deserialize;
+
+        let date: Result<DateTime, ValueError> =
+            DateTime::deserialize("2017-05-21T20:19:55+01:00".into_deserializer());
+        assert_eq!(date.unwrap().0.to_rfc3339(), "2017-05-21T20:19:55+01:00");
+
+        let date: Result<DateTime, ValueError> =
+            DateTime::deserialize("2017-05-21".into_deserializer());
+        assert!(date.is_err());
+    }
 }
@@ -9,22 +9,25 @@ use crate::message::MessagePayload;
 use crate::presence::PresencePayload;
 use crate::util::helpers::PlainText;
 use jid::Jid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Notes when and by whom a message got stored for later delivery.
-    Delay, "delay", DELAY,
-    attributes: [
-        /// The entity which delayed this message.
-        from: Option<Jid> = "from",
+/// Notes when and by whom a message got stored for later delivery.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DELAY, name = "delay")]
+pub struct Delay {
+    /// The entity which delayed this message.
+    #[xml(attribute = "from")]
+    pub from: Option<Jid>,
 
-        /// The time at which this message got stored.
-        stamp: Required<DateTime> = "stamp"
-    ],
-    text: (
-        /// The optional reason this message got delayed.
-        data: PlainText<Option<String>>
-    )
-);
+    /// The time at which this message got stored.
+    #[xml(attribute = "stamp")]
+    pub stamp: DateTime,
+
+    /// The optional reason this message got delayed.
+    #[xml(text, codec = PlainText)]
+    pub data: Option<String>,
+}
 
 impl MessagePayload for Delay {}
 impl PresencePayload for Delay {}
@@ -57,7 +60,7 @@ mod tests {
                 .parse()
                 .unwrap();
         let delay = Delay::try_from(elem).unwrap();
-        assert_eq!(delay.from.unwrap(), BareJid::domain("capulet.com"));
+        assert_eq!(delay.from.unwrap(), BareJid::from_domain("capulet.com"));
         assert_eq!(
             delay.stamp,
             DateTime::from_str("2002-09-10T23:08:25Z").unwrap()
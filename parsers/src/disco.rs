@@ -11,28 +11,33 @@ use crate::util::error::Error;
 use crate::Element;
 use jid::Jid;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
 /// Structure representing a `<query xmlns='http://jabber.org/protocol/disco#info'/>` element.
 ///
 /// It should only be used in an `<iq type='get'/>`, as it can only represent
 /// the request, and not a result.
-DiscoInfoQuery, "query", DISCO_INFO,
-attributes: [
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DISCO_INFO, name = "query")]
+pub struct DiscoInfoQuery {
     /// Node on which we are doing the discovery.
-    node: Option<String> = "node",
-]);
+    #[xml(attribute = "node")]
+    pub node: Option<String>,
+}
 
 impl IqGetPayload for DiscoInfoQuery {}
 
-generate_element!(
 #[derive(Eq, Hash)]
 /// Structure representing a `<feature xmlns='http://jabber.org/protocol/disco#info'/>` element.
-Feature, "feature", DISCO_INFO,
-attributes: [
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DISCO_INFO, name = "feature")]
+pub struct Feature {
     /// Namespace of the feature we want to represent.
-    var: Required<String> = "var",
-]);
+    #[xml(attribute = "var")]
+    pub var: String,
+}
 
 impl Feature {
     /// Create a new `<feature/>` with the according `@var`.
@@ -41,25 +46,29 @@ impl Feature {
     }
 }
 
-generate_element!(
-    /// Structure representing an `<identity xmlns='http://jabber.org/protocol/disco#info'/>` element.
-    Identity, "identity", DISCO_INFO,
-    attributes: [
-        /// Category of this identity.
-        // TODO: use an enum here.
-        category: RequiredNonEmpty<String> = "category",
-
-        /// Type of this identity.
-        // TODO: use an enum here.
-        type_: RequiredNonEmpty<String> = "type",
-
-        /// Lang of the name of this identity.
-        lang: Option<String> = "xml:lang",
-
-        /// Name of this identity.
-        name: Option<String> = "name",
-    ]
-);
+/// Structure representing an `<identity xmlns='http://jabber.org/protocol/disco#info'/>` element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DISCO_INFO, name = "identity")]
+pub struct Identity {
+    /// Category of this identity.
+    // TODO: use an enum here.
+    #[xml(attribute = "category", non_empty)]
+    pub category: String,
+
+    /// Type of this identity.
+    // TODO: use an enum here.
+    #[xml(attribute = "type", non_empty)]
+    pub type_: String,
+
+    /// Lang of the name of this identity.
+    #[xml(attribute = "xml:lang")]
+    pub lang: Option<String>,
+
+    /// Name of this identity.
+    #[xml(attribute = "name")]
+    pub name: Option<String>,
+}
 
 impl Identity {
     /// Create a new `<identity/>`.
@@ -98,6 +107,7 @@ impl Identity {
 /// It should only be used in an `<iq type='result'/>`, as it can only
 /// represent the result, and not a request.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DiscoInfoResult {
     /// Node on which we have done this discovery.
     pub node: Option<String>,
@@ -122,7 +132,7 @@ impl TryFrom<Element> for DiscoInfoResult {
         check_no_unknown_attributes!(elem, "disco#info result", ["node"]);
 
         let mut result = DiscoInfoResult {
-            node: get_attr!(elem, "node", Option),
+            node: elem.attr_as("node")?,
             identities: vec![],
             features: vec![],
             extensions: vec![],
@@ -184,47 +194,56 @@ impl From<DiscoInfoResult> for Element {
     }
 }
 
-generate_element!(
 /// Structure representing a `<query xmlns='http://jabber.org/protocol/disco#items'/>` element.
 ///
 /// It should only be used in an `<iq type='get'/>`, as it can only represent
 /// the request, and not a result.
-DiscoItemsQuery, "query", DISCO_ITEMS,
-attributes: [
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DISCO_ITEMS, name = "query")]
+pub struct DiscoItemsQuery {
     /// Node on which we are doing the discovery.
-    node: Option<String> = "node",
-]);
+    #[xml(attribute = "node")]
+    pub node: Option<String>,
+}
 
 impl IqGetPayload for DiscoItemsQuery {}
 
-generate_element!(
 /// Structure representing an `<item xmlns='http://jabber.org/protocol/disco#items'/>` element.
-Item, "item", DISCO_ITEMS,
-attributes: [
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DISCO_ITEMS, name = "item")]
+pub struct Item {
     /// JID of the entity pointed by this item.
-    jid: Required<Jid> = "jid",
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
     /// Node of the entity pointed by this item.
-    node: Option<String> = "node",
+    #[xml(attribute = "node")]
+    pub node: Option<String>,
+
     /// Name of the entity pointed by this item.
-    name: Option<String> = "name",
-]);
-
-generate_element!(
-    /// Structure representing a `<query
-    /// xmlns='http://jabber.org/protocol/disco#items'/>` element.
-    ///
-    /// It should only be used in an `<iq type='result'/>`, as it can only
-    /// represent the result, and not a request.
-    DiscoItemsResult, "query", DISCO_ITEMS,
-    attributes: [
-        /// Node on which we have done this discovery.
-        node: Option<String> = "node"
-    ],
-    children: [
-        /// List of items pointed by this entity.
-        items: Vec<Item> = ("item", DISCO_ITEMS) => Item
-    ]
-);
+    #[xml(attribute = "name")]
+    pub name: Option<String>,
+}
+
+/// Structure representing a `<query
+/// xmlns='http://jabber.org/protocol/disco#items'/>` element.
+///
+/// It should only be used in an `<iq type='result'/>`, as it can only
+/// represent the result, and not a request.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DISCO_ITEMS, name = "query")]
+pub struct DiscoItemsResult {
+    /// Node on which we have done this discovery.
+    #[xml(attribute = "node")]
+    pub node: Option<String>,
+
+    /// List of items pointed by this entity.
+    #[xml(child = "item")]
+    pub items: Vec<Item>,
+}
 
 impl IqResultPayload for DiscoItemsResult {}
 
@@ -323,11 +342,15 @@ mod tests {
                 .parse()
                 .unwrap();
         let error = DiscoInfoResult::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'category' missing.");
+        assert_eq!(attribute, "category");
 
         let elem: Element =
             "<query xmlns='http://jabber.org/protocol/disco#info'><identity category=''/></query>"
@@ -342,11 +365,15 @@ mod tests {
 
         let elem: Element = "<query xmlns='http://jabber.org/protocol/disco#info'><identity category='coucou'/></query>".parse().unwrap();
         let error = DiscoInfoResult::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'type' missing.");
+        assert_eq!(attribute, "type");
 
         let elem: Element = "<query xmlns='http://jabber.org/protocol/disco#info'><identity category='coucou' type=''/></query>".parse().unwrap();
         let error = DiscoInfoResult::try_from(elem).unwrap_err();
@@ -364,11 +391,15 @@ mod tests {
                 .parse()
                 .unwrap();
         let error = DiscoInfoResult::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'var' missing.");
+        assert_eq!(attribute, "var");
     }
 
     #[test]
@@ -442,10 +473,10 @@ mod tests {
         let elem2 = Element::from(query);
         let query = DiscoItemsResult::try_from(elem2).unwrap();
         assert_eq!(query.items.len(), 2);
-        assert_eq!(query.items[0].jid, BareJid::domain("component"));
+        assert_eq!(query.items[0].jid, BareJid::from_domain("component"));
         assert_eq!(query.items[0].node, None);
         assert_eq!(query.items[0].name, None);
-        assert_eq!(query.items[1].jid, BareJid::domain("component2"));
+        assert_eq!(query.items[1].jid, BareJid::from_domain("component2"));
         assert_eq!(query.items[1].node, Some(String::from("test")));
         assert_eq!(query.items[1].name, Some(String::from("A component")));
     }
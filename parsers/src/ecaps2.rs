@@ -14,17 +14,19 @@ use blake2::Blake2bVar;
 use digest::{Digest, Update, VariableOutput};
 use sha2::{Sha256, Sha512};
 use sha3::{Sha3_256, Sha3_512};
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Represents a set of capability hashes, all of them must correspond to
-    /// the same input [disco#info](../disco/struct.DiscoInfoResult.html),
-    /// using different [algorithms](../hashes/enum.Algo.html).
-    ECaps2, "c", ECAPS2,
-    children: [
-        /// Hashes of the [disco#info](../disco/struct.DiscoInfoResult.html).
-        hashes: Vec<Hash> = ("hash", HASHES) => Hash
-    ]
-);
+/// Represents a set of capability hashes, all of them must correspond to
+/// the same input [disco#info](../disco/struct.DiscoInfoResult.html),
+/// using different [algorithms](../hashes/enum.Algo.html).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = ECAPS2, name = "c")]
+pub struct ECaps2 {
+    /// Hashes of the [disco#info](../disco/struct.DiscoInfoResult.html).
+    #[xml(child = "hash", namespace = HASHES)]
+    pub hashes: Vec<Hash>,
+}
 
 impl PresencePayload for ECaps2 {}
 
@@ -5,19 +5,22 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Structure representing an `<encryption xmlns='urn:xmpp:eme:0'/>` element.
-    ExplicitMessageEncryption, "encryption", EME,
-    attributes: [
-        /// Namespace of the encryption scheme used.
-        namespace: Required<String> = "namespace",
+/// Structure representing an `<encryption xmlns='urn:xmpp:eme:0'/>` element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = EME, name = "encryption")]
+pub struct ExplicitMessageEncryption {
+    /// Namespace of the encryption scheme used.
+    #[xml(attribute = "namespace")]
+    pub namespace: String,
 
-        /// User-friendly name for the encryption scheme, should be `None` for OTR,
-        /// legacy OpenPGP and OX.
-        name: Option<String> = "name",
-    ]
-);
+    /// User-friendly name for the encryption scheme, should be `None` for OTR,
+    /// legacy OpenPGP and OX.
+    #[xml(attribute = "name")]
+    pub name: Option<String>,
+}
 
 impl MessagePayload for ExplicitMessageEncryption {}
 
@@ -7,131 +7,159 @@
 use crate::data_forms::DataForm;
 use crate::date::DateTime;
 use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// When sending a push update, the action value indicates if the service is being added or
+/// deleted from the set of known services (or simply being modified).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "action")]
+pub enum Action {
+    /// The service is being added from the set of known services.
+    #[xml(value = "add", default)]
+    Add,
+
+    /// The service is being removed from the set of known services.
+    #[xml(value = "remove")]
+    Remove,
+
+    /// The service is being modified.
+    #[xml(value = "modify")]
+    Modify,
+}
+
+/// The underlying transport protocol to be used when communicating with the service.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "transport")]
+pub enum Transport {
+    /// Use TCP as a transport protocol.
+    #[xml(value = "tcp")]
+    Tcp,
+
+    /// Use UDP as a transport protocol.
+    #[xml(value = "udp")]
+    Udp,
+}
 
-generate_attribute!(
+/// The service type as registered with the XMPP Registrar.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "type")]
+pub enum Type {
+    /// A server that provides Session Traversal Utilities for NAT (STUN).
+    #[xml(value = "stun")]
+    Stun,
+
+    /// A server that provides Traversal Using Relays around NAT (TURN).
+    #[xml(value = "turn")]
+    Turn,
+}
+
+/// Username and password credentials are required and will need to be requested if not already
+/// provided.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "restricted")]
+pub enum Restricted {
+    /// True value, represented by either 'true' or '1'.
+    #[xml(value = "true", alias = "1")]
+    True,
+
+    /// False value, represented by either 'false' or '0'.
+    #[xml(value = "false", alias = "0", default)]
+    False,
+}
+
+/// Structure representing a `<service xmlns='urn:xmpp:extdisco:2'/>` element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = EXT_DISCO, name = "service")]
+pub struct Service {
     /// When sending a push update, the action value indicates if the service is being added or
     /// deleted from the set of known services (or simply being modified).
-    Action, "action", {
-        /// The service is being added from the set of known services.
-        Add => "add",
-
-        /// The service is being removed from the set of known services.
-        Remove => "remove",
-
-        /// The service is being modified.
-        Modify => "modify",
-    }, Default = Add
-);
-
-generate_attribute!(
-    /// The underlying transport protocol to be used when communicating with the service.
-    Transport, "transport", {
-        /// Use TCP as a transport protocol.
-        Tcp => "tcp",
-
-        /// Use UDP as a transport protocol.
-        Udp => "udp",
-    }
-);
+    #[xml(attribute = "action", default)]
+    pub action: Action,
+
+    /// A timestamp indicating when the provided username and password credentials will expire.
+    #[xml(attribute = "expires")]
+    pub expires: Option<DateTime>,
+
+    /// Either a fully qualified domain name (FQDN) or an IP address (IPv4 or IPv6).
+    #[xml(attribute = "host")]
+    pub host: String,
+
+    /// A friendly (human-readable) name or label for the service.
+    #[xml(attribute = "name")]
+    pub name: Option<String>,
+
+    /// A service- or server-generated password for use at the service.
+    #[xml(attribute = "password")]
+    pub password: Option<String>,
+
+    /// The communications port to be used at the host.
+    #[xml(attribute = "port")]
+    pub port: Option<u16>,
+
+    /// A boolean value indicating that username and password credentials are required and will
+    /// need to be requested if not already provided.
+    #[xml(attribute = "restricted", default)]
+    pub restricted: Restricted,
+
+    /// The underlying transport protocol to be used when communicating with the service (typically
+    /// either TCP or UDP).
+    #[xml(attribute = "transport")]
+    pub transport: Option<Transport>,
 
-generate_attribute!(
     /// The service type as registered with the XMPP Registrar.
-    Type, "type", {
-        /// A server that provides Session Traversal Utilities for NAT (STUN).
-        Stun => "stun",
+    #[xml(attribute = "type")]
+    pub type_: Type,
 
-        /// A server that provides Traversal Using Relays around NAT (TURN).
-        Turn => "turn",
-    }
-);
-
-generate_attribute!(
-    /// Username and password credentials are required and will need to be requested if not already
-    /// provided.
-    Restricted,
-    "restricted",
-    bool
-);
-
-generate_element!(
-    /// Structure representing a `<service xmlns='urn:xmpp:extdisco:2'/>` element.
-    Service, "service", EXT_DISCO,
-    attributes: [
-        /// When sending a push update, the action value indicates if the service is being added or
-        /// deleted from the set of known services (or simply being modified).
-        action: Default<Action> = "action",
-
-        /// A timestamp indicating when the provided username and password credentials will expire.
-        expires: Option<DateTime> = "expires",
-
-        /// Either a fully qualified domain name (FQDN) or an IP address (IPv4 or IPv6).
-        host: Required<String> = "host",
-
-        /// A friendly (human-readable) name or label for the service.
-        name: Option<String> = "name",
-
-        /// A service- or server-generated password for use at the service.
-        password: Option<String> = "password",
-
-        /// The communications port to be used at the host.
-        port: Option<u16> = "port",
-
-        /// A boolean value indicating that username and password credentials are required and will
-        /// need to be requested if not already provided.
-        restricted: Default<Restricted> = "restricted",
-
-        /// The underlying transport protocol to be used when communicating with the service (typically
-        /// either TCP or UDP).
-        transport: Option<Transport> = "transport",
-
-        /// The service type as registered with the XMPP Registrar.
-        type_: Required<Type> = "type",
-
-        /// A service- or server-generated username for use at the service.
-        username: Option<String> = "username",
-    ], children: [
-        /// Extended information
-        ext_info: Vec<DataForm> = ("x", DATA_FORMS) => DataForm
-    ]
-);
+    /// A service- or server-generated username for use at the service.
+    #[xml(attribute = "username")]
+    pub username: Option<String>,
+
+    /// Extended information
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub ext_info: Vec<DataForm>,
+}
 
 impl IqGetPayload for Service {}
 
-generate_element!(
-    /// Structure representing a `<services xmlns='urn:xmpp:extdisco:2'/>` element.
-    ServicesQuery, "services", EXT_DISCO,
-    attributes: [
-        /// TODO
-        type_: Option<Type> = "type",
-    ]
-);
+/// Structure representing a `<services xmlns='urn:xmpp:extdisco:2'/>` element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = EXT_DISCO, name = "services")]
+pub struct ServicesQuery {
+    /// TODO
+    #[xml(attribute = "type")]
+    pub type_: Option<Type>,
+}
 
 impl IqGetPayload for ServicesQuery {}
 
-generate_element!(
-    /// Structure representing a `<services xmlns='urn:xmpp:extdisco:2'/>` element.
-    ServicesResult, "services", EXT_DISCO,
-    attributes: [
-        /// TODO
-        type_: Option<Type> = "type",
-    ],
-    children: [
-        /// List of services.
-        services: Vec<Service> = ("service", EXT_DISCO) => Service
-    ]
-);
+/// Structure representing a `<services xmlns='urn:xmpp:extdisco:2'/>` element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = EXT_DISCO, name = "services")]
+pub struct ServicesResult {
+    /// TODO
+    #[xml(attribute = "type")]
+    pub type_: Option<Type>,
+
+    /// List of services.
+    #[xml(child = "service")]
+    pub services: Vec<Service>,
+}
 
 impl IqResultPayload for ServicesResult {}
 impl IqSetPayload for ServicesResult {}
 
-generate_element!(
-    /// Structure representing a `<credentials xmlns='urn:xmpp:extdisco:2'/>` element.
-    Credentials, "credentials", EXT_DISCO,
-    children: [
-        /// List of services.
-        services: Vec<Service> = ("service", EXT_DISCO) => Service
-    ]
-);
+/// Structure representing a `<credentials xmlns='urn:xmpp:extdisco:2'/>` element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = EXT_DISCO, name = "credentials")]
+pub struct Credentials {
+    /// List of services.
+    #[xml(child = "service")]
+    pub services: Vec<Service>,
+}
 
 impl IqGetPayload for Credentials {}
 impl IqResultPayload for Credentials {}
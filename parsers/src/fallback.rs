@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// A range of the body which is only a fallback, in Unicode code points.
+///
+/// Without any bound, the whole body is a fallback.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = FALLBACK, name = "body")]
+pub struct BodyRange {
+    /// The first code point of the range, or the start of the body.
+    #[xml(attribute = "start")]
+    pub start: Option<usize>,
+
+    /// The code point after the end of the range, or the end of the body.
+    #[xml(attribute = "end")]
+    pub end: Option<usize>,
+}
+
+/// A range of the subject which is only a fallback, in Unicode code points.
+///
+/// Without any bound, the whole subject is a fallback.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = FALLBACK, name = "subject")]
+pub struct SubjectRange {
+    /// The first code point of the range, or the start of the subject.
+    #[xml(attribute = "start")]
+    pub start: Option<usize>,
+
+    /// The code point after the end of the range, or the end of the subject.
+    #[xml(attribute = "end")]
+    pub end: Option<usize>,
+}
+
+/// Marks parts of this message as a fallback for clients not supporting the specification of
+/// this namespace, which should then be hidden by those supporting it.
+///
+/// Without any range, the whole body is a fallback.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = FALLBACK, name = "fallback")]
+pub struct Fallback {
+    /// The namespace of the specification this fallback is for.
+    #[xml(attribute = "for")]
+    pub for_: String,
+
+    /// The fallback ranges of the body.
+    #[xml(child = "body")]
+    pub bodies: Vec<BodyRange>,
+
+    /// The fallback ranges of the subject.
+    #[xml(child = "subject")]
+    pub subjects: Vec<SubjectRange>,
+}
+
+impl Fallback {
+    /// Marks this range of the body as a fallback for this namespace.
+    pub fn new<N: Into<String>>(for_: N, start: usize, end: usize) -> Fallback {
+        Fallback {
+            for_: for_.into(),
+            bodies: vec![BodyRange {
+                start: Some(start),
+                end: Some(end),
+            }],
+            subjects: Vec::new(),
+        }
+    }
+
+    /// Marks the whole body as a fallback for this namespace.
+    pub fn whole_body<N: Into<String>>(for_: N) -> Fallback {
+        Fallback {
+            for_: for_.into(),
+            bodies: Vec::new(),
+            subjects: Vec::new(),
+        }
+    }
+
+    /// Whether this code point of the body is part of this fallback.
+    pub fn contains(&self, index: usize) -> bool {
+        if self.bodies.is_empty() && self.subjects.is_empty() {
+            return true;
+        }
+        self.bodies.iter().any(|range| {
+            range.start.unwrap_or(0) <= index && index < range.end.unwrap_or(usize::MAX)
+        })
+    }
+}
+
+impl MessagePayload for Fallback {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(BodyRange, 32);
+        assert_size!(SubjectRange, 32);
+        assert_size!(Fallback, 72);
+    }
+
+    #[test]
+    fn test_simple() {
+        let elem: Element = "<fallback xmlns='urn:xmpp:fallback:0' for='urn:xmpp:reply:0'><body start='0' end='33'/></fallback>"
+            .parse()
+            .unwrap();
+        let fallback = Fallback::try_from(elem).unwrap();
+        assert_eq!(fallback, Fallback::new("urn:xmpp:reply:0", 0, 33));
+        assert!(fallback.contains(0));
+        assert!(fallback.contains(32));
+        assert!(!fallback.contains(33));
+    }
+
+    #[test]
+    fn test_whole_body() {
+        let elem: Element =
+            "<fallback xmlns='urn:xmpp:fallback:0' for='urn:xmpp:message-retract:1'/>"
+                .parse()
+                .unwrap();
+        let fallback = Fallback::try_from(elem).unwrap();
+        assert_eq!(fallback, Fallback::whole_body("urn:xmpp:message-retract:1"));
+        assert!(fallback.contains(1000));
+
+        let elem: Element =
+            "<fallback xmlns='urn:xmpp:fallback:0' for='urn:xmpp:sce:1'><body/></fallback>"
+                .parse()
+                .unwrap();
+        let fallback = Fallback::try_from(elem).unwrap();
+        assert!(fallback.contains(1000));
+    }
+
+    #[test]
+    fn test_subject_only() {
+        let elem: Element = "<fallback xmlns='urn:xmpp:fallback:0' for='urn:example:foo'><subject end='5'/></fallback>"
+            .parse()
+            .unwrap();
+        let fallback = Fallback::try_from(elem).unwrap();
+        assert_eq!(fallback.subjects[0].end, Some(5));
+        assert!(!fallback.contains(0));
+    }
+
+    #[test]
+    fn test_invalid_range() {
+        let elem: Element = "<fallback xmlns='urn:xmpp:fallback:0' for='urn:xmpp:reply:0'><body start='-1'/></fallback>"
+            .parse()
+            .unwrap();
+        let error = Fallback::try_from(elem).unwrap_err();
+        match error {
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(_) => (),
+                _ => panic!(),
+            },
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::date::DateTime;
+use crate::hashes::Hash;
+use crate::jingle_ft::{Desc, File};
+use crate::ns;
+use crate::thumbs::Thumbnail;
+use crate::util::error::Error;
+use crate::Element;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+type Lang = String;
+
+/// The metadata of a file, outside of any Jingle session, for instance when
+/// [sharing](../sfs/index.html) it.
+///
+/// It can be converted from and into a Jingle File Transfer
+/// [file](../jingle_ft/struct.File.html), with which it shares its parser
+/// and serialiser, the dimensions, length and thumbnails being specific to
+/// this element.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileMetadata {
+    /// The date of last modification of this file.
+    pub date: Option<DateTime>,
+
+    /// The MIME type of this file.
+    pub media_type: Option<String>,
+
+    /// The name of this file.
+    pub name: Option<String>,
+
+    /// The description of this file, possibly localised.
+    pub descs: BTreeMap<Lang, Desc>,
+
+    /// The size of this file, in bytes.
+    pub size: Option<u64>,
+
+    /// A list of hashes matching this file.
+    pub hashes: Vec<Hash>,
+
+    /// The width of this image or video, in pixels.
+    pub width: Option<u32>,
+
+    /// The height of this image or video, in pixels.
+    pub height: Option<u32>,
+
+    /// The length of this audio or video, in milliseconds.
+    pub length: Option<u32>,
+
+    /// Thumbnails of this image or video.
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+impl FileMetadata {
+    /// Creates an empty file metadata.
+    pub fn new() -> FileMetadata {
+        FileMetadata::default()
+    }
+}
+
+impl TryFrom<Element> for FileMetadata {
+    type Error = Error;
+
+    fn try_from(elem: Element) -> Result<FileMetadata, Error> {
+        check_self!(elem, "file", FILE_METADATA);
+        check_no_attributes!(elem, "file");
+
+        let mut file = File::new();
+        let mut width = None;
+        let mut height = None;
+        let mut length = None;
+        let mut thumbnails = Vec::new();
+        for child in elem.children() {
+            if file.parse_shared_child(child, ns::FILE_METADATA)? {
+                continue;
+            } else if child.is("width", ns::FILE_METADATA) {
+                parse_once(&mut width, child, "File must not have more than one width.")?;
+            } else if child.is("height", ns::FILE_METADATA) {
+                parse_once(
+                    &mut height,
+                    child,
+                    "File must not have more than one height.",
+                )?;
+            } else if child.is("length", ns::FILE_METADATA) {
+                parse_once(
+                    &mut length,
+                    child,
+                    "File must not have more than one length.",
+                )?;
+            } else if child.is("thumbnail", ns::THUMBS) {
+                thumbnails.push(Thumbnail::try_from(child.clone())?);
+            } else {
+                return Err(Error::ParseError("Unknown element in file metadata."));
+            }
+        }
+
+        Ok(FileMetadata {
+            width,
+            height,
+            length,
+            thumbnails,
+            ..FileMetadata::from(file)
+        })
+    }
+}
+
+/// Parses the text of a child which must be present at most once.
+fn parse_once(value: &mut Option<u32>, child: &Element, error: &'static str) -> Result<(), Error> {
+    if value.is_some() {
+        return Err(Error::ParseError(error));
+    }
+    *value = Some(child.text().parse()?);
+    Ok(())
+}
+
+/// Serialises an optional child as text.
+fn text_child(name: &str, value: Option<u32>) -> Option<Element> {
+    value.map(|value| {
+        Element::builder(name, ns::FILE_METADATA)
+            .append(value.to_string())
+            .build()
+    })
+}
+
+impl From<FileMetadata> for Element {
+    fn from(mut metadata: FileMetadata) -> Element {
+        let extra = text_child("width", metadata.width)
+            .into_iter()
+            .chain(text_child("height", metadata.height))
+            .chain(text_child("length", metadata.length))
+            .chain(
+                std::mem::take(&mut metadata.thumbnails)
+                    .into_iter()
+                    .map(Element::from),
+            )
+            .collect::<Vec<_>>();
+        File::from(metadata).into_element(ns::FILE_METADATA, extra)
+    }
+}
+
+impl From<File> for FileMetadata {
+    fn from(file: File) -> FileMetadata {
+        FileMetadata {
+            date: file.date,
+            media_type: file.media_type,
+            name: file.name,
+            descs: file.descs,
+            size: file.size,
+            hashes: file.hashes,
+            ..FileMetadata::default()
+        }
+    }
+}
+
+impl From<FileMetadata> for File {
+    fn from(file: FileMetadata) -> File {
+        File {
+            date: file.date,
+            media_type: file.media_type,
+            name: file.name,
+            descs: file.descs,
+            size: file.size,
+            range: None,
+            hashes: file.hashes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Algo;
+    use std::str::FromStr;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(FileMetadata, 176);
+    }
+
+    #[test]
+    fn test_simple() {
+        let elem: Element = "<file xmlns='urn:xmpp:file:metadata:0'>
+            <media-type>image/jpeg</media-type>
+            <name>summit.jpg</name>
+            <size>3032449</size>
+            <width>4096</width>
+            <height>2160</height>
+            <hash xmlns='urn:xmpp:hashes:2' algo='sha3-256'>2XarmwTlNxDAMkvymloX3S5+VbylNrJt/l5QyPa+YoU=</hash>
+            <hash xmlns='urn:xmpp:hashes:2' algo='id-blake2b256'>2AfMGH8O7UNPTvUVAM9aK13mpCY=</hash>
+            <desc>Photo from the summit.</desc>
+            <thumbnail xmlns='urn:xmpp:thumbs:1' uri='cid:sha1+ffd7c8d28e9c5e82afea41f97108c6b4@bob.xmpp.org' media-type='image/png' width='128' height='96'/>
+        </file>"
+            .parse()
+            .unwrap();
+        let file = FileMetadata::try_from(elem).unwrap();
+        assert_eq!(file.media_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(file.name.as_deref(), Some("summit.jpg"));
+        assert_eq!(file.size, Some(3032449));
+        assert_eq!(file.width, Some(4096));
+        assert_eq!(file.height, Some(2160));
+        assert_eq!(file.length, None);
+        assert_eq!(file.hashes.len(), 2);
+        assert_eq!(file.hashes[0].algo, Algo::Sha3_256);
+        assert_eq!(file.descs[""].0, "Photo from the summit.");
+        assert_eq!(file.thumbnails[0].width, Some(128));
+    }
+
+    #[test]
+    fn test_serialise() {
+        let file = FileMetadata {
+            name: Some(String::from("text.txt")),
+            date: Some(DateTime::from_str("2015-07-26T21:46:00+01:00").unwrap()),
+            size: Some(6144),
+            length: Some(1200),
+            ..FileMetadata::new()
+        };
+        let elem: Element = file.clone().into();
+        assert_eq!(
+            String::from(&elem),
+            "<file xmlns=\"urn:xmpp:file:metadata:0\"><date>2015-07-26T21:46:00+01:00</date><name>text.txt</name><size>6144</size><length>1200</length></file>"
+        );
+        assert_eq!(FileMetadata::try_from(elem).unwrap(), file);
+    }
+
+    #[test]
+    fn test_jingle_ft() {
+        let file = File::new()
+            .with_name(String::from("text.txt"))
+            .with_size(6144);
+        let metadata = FileMetadata::from(file);
+        assert_eq!(metadata.name.as_deref(), Some("text.txt"));
+        assert_eq!(metadata.size, Some(6144));
+        let file = File::from(metadata);
+        assert_eq!(file.name.as_deref(), Some("text.txt"));
+        assert!(file.range.is_none());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let elem: Element =
+            "<file xmlns='urn:xmpp:file:metadata:0'><size>1</size><size>2</size></file>"
+                .parse()
+                .unwrap();
+        let error = FileMetadata::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "File must not have more than one size.");
+
+        let elem: Element = "<file xmlns='urn:xmpp:file:metadata:0'><range/></file>"
+            .parse()
+            .unwrap();
+        let error = FileMetadata::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown element in file metadata.");
+    }
+}
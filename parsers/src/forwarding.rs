@@ -6,22 +6,24 @@
 
 use crate::delay::Delay;
 use crate::message::Message;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Contains a forwarded stanza, either standalone or part of another
-    /// extension (such as carbons).
-    Forwarded, "forwarded", FORWARD,
-    children: [
-        /// When the stanza originally got sent.
-        delay: Option<Delay> = ("delay", DELAY) => Delay,
+/// Contains a forwarded stanza, either standalone or part of another
+/// extension (such as carbons).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = FORWARD, name = "forwarded")]
+pub struct Forwarded {
+    /// When the stanza originally got sent.
+    #[xml(child = "delay", namespace = DELAY)]
+    pub delay: Option<Delay>,
 
-        // XXX: really?  Option?
-        /// The stanza being forwarded.
-        stanza: Option<Message> = ("message", DEFAULT_NS) => Message
-
-        // TODO: also handle the two other stanza possibilities.
-    ]
-);
+    // XXX: really?  Option?
+    /// The stanza being forwarded.
+    #[xml(child = "message", namespace = DEFAULT_NS)]
+    pub stanza: Option<Message>,
+    // TODO: also handle the two other stanza possibilities.
+}
 
 #[cfg(test)]
 mod tests {
@@ -6,14 +6,16 @@
 
 use crate::util::error::Error;
 use crate::util::helpers::Base64;
-use minidom::IntoAttributeValue;
+use minidom::{FromAttributeValue, IntoAttributeValue};
 use std::num::ParseIntError;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
 /// List of the algorithms we support, or Unknown.
 #[allow(non_camel_case_types)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Algo {
     /// The Secure Hash Algorithm 1, with known vulnerabilities, do not use it.
     ///
@@ -88,25 +90,34 @@ impl From<Algo> for String {
     }
 }
 
+impl FromAttributeValue for Algo {
+    type Error = Error;
+
+    fn from_attribute_value(value: &str) -> Result<Algo, Error> {
+        Algo::from_str(value)
+    }
+}
+
 impl IntoAttributeValue for Algo {
     fn into_attribute_value(self) -> Option<String> {
         Some(String::from(self))
     }
 }
 
-generate_element!(
-    /// This element represents a hash of some data, defined by the hash
-    /// algorithm used and the computed value.
-    Hash, "hash", HASHES,
-    attributes: [
-        /// The algorithm used to create this hash.
-        algo: Required<Algo> = "algo"
-    ],
-    text: (
-        /// The hash value, as a vector of bytes.
-        hash: Base64<Vec<u8>>
-    )
-);
+/// This element represents a hash of some data, defined by the hash
+/// algorithm used and the computed value.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HASHES, name = "hash")]
+pub struct Hash {
+    /// The algorithm used to create this hash.
+    #[xml(attribute = "algo")]
+    pub algo: Algo,
+
+    /// The hash value, as a vector of bytes.
+    #[xml(text, codec = Base64)]
+    pub hash: Vec<u8>,
+}
 
 impl Hash {
     /// Creates a [Hash] element with the given algo and data.
@@ -169,6 +180,7 @@ impl Hash {
 
 /// Helper for parsing and serialising a SHA-1 attribute.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sha1HexAttribute(Hash);
 
 impl FromStr for Sha1HexAttribute {
@@ -180,6 +192,14 @@ impl FromStr for Sha1HexAttribute {
     }
 }
 
+impl FromAttributeValue for Sha1HexAttribute {
+    type Error = ParseIntError;
+
+    fn from_attribute_value(value: &str) -> Result<Sha1HexAttribute, ParseIntError> {
+        Sha1HexAttribute::from_str(value)
+    }
+}
+
 impl IntoAttributeValue for Sha1HexAttribute {
     fn into_attribute_value(self) -> Option<String> {
         Some(self.to_hex())
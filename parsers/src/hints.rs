@@ -0,0 +1,94 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Asks the servers on the path not to store this message in a permanent archive, such as
+/// [MAM](../mam/index.html), while still possibly storing it for offline delivery.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HINTS, name = "no-permanent-store")]
+pub struct NoPermanentStore;
+
+impl MessagePayload for NoPermanentStore {}
+
+/// Asks the servers on the path not to store this message at all, not even for offline
+/// delivery.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HINTS, name = "no-store")]
+pub struct NoStore;
+
+impl MessagePayload for NoStore {}
+
+/// Asks the server not to copy this message to other resources, for instance with
+/// [carbons](../carbons/index.html).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HINTS, name = "no-copy")]
+pub struct NoCopy;
+
+impl MessagePayload for NoCopy {}
+
+/// Asks the servers on the path to store this message, even if it has no body.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HINTS, name = "store")]
+pub struct Store;
+
+impl MessagePayload for Store {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(NoPermanentStore, 0);
+        assert_size!(NoStore, 0);
+        assert_size!(NoCopy, 0);
+        assert_size!(Store, 0);
+    }
+
+    #[test]
+    fn test_simple() {
+        let elem: Element = "<no-permanent-store xmlns='urn:xmpp:hints'/>"
+            .parse()
+            .unwrap();
+        NoPermanentStore::try_from(elem).unwrap();
+
+        let elem: Element = "<no-store xmlns='urn:xmpp:hints'/>".parse().unwrap();
+        NoStore::try_from(elem).unwrap();
+
+        let elem: Element = "<no-copy xmlns='urn:xmpp:hints'/>".parse().unwrap();
+        NoCopy::try_from(elem).unwrap();
+
+        let elem: Element = "<store xmlns='urn:xmpp:hints'/>".parse().unwrap();
+        Store::try_from(elem).unwrap();
+    }
+
+    #[test]
+    fn test_serialise() {
+        let elem: Element = Store.into();
+        assert_eq!(String::from(&elem), "<store xmlns=\"urn:xmpp:hints\"/>");
+    }
+
+    #[test]
+    fn test_invalid_child() {
+        let elem: Element = "<no-copy xmlns='urn:xmpp:hints'><coucou/></no-copy>"
+            .parse()
+            .unwrap();
+        let error = NoCopy::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown child in no-copy element.");
+    }
+}
@@ -5,31 +5,36 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
 use crate::iq::{IqGetPayload, IqResultPayload};
 use crate::ns;
 use crate::util::error::Error;
 use crate::Element;
 
-generate_element!(
-    /// Requesting a slot
-    SlotRequest, "request", HTTP_UPLOAD,
-    attributes: [
-        /// The filename to be uploaded.
-        filename: Required<String> = "filename",
-
-        /// Size of the file to be uploaded.
-        size: Required<u64> = "size",
-
-        /// Content-Type of the file.
-        content_type: Option<String> = "content-type",
-    ]
-);
+/// Requesting a slot
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HTTP_UPLOAD, name = "request")]
+pub struct SlotRequest {
+    /// The filename to be uploaded.
+    #[xml(attribute = "filename")]
+    pub filename: String,
+
+    /// Size of the file to be uploaded.
+    #[xml(attribute = "size")]
+    pub size: u64,
+
+    /// Content-Type of the file.
+    #[xml(attribute = "content-type")]
+    pub content_type: Option<String>,
+}
 
 impl IqGetPayload for SlotRequest {}
 
 /// Slot header
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Header {
     /// Authorization header
     Authorization(String),
@@ -47,7 +52,7 @@ impl TryFrom<Element> for Header {
         check_self!(elem, "header", HTTP_UPLOAD);
         check_no_children!(elem, "header");
         check_no_unknown_attributes!(elem, "header", ["name"]);
-        let name: String = get_attr!(elem, "name", Required);
+        let name: String = elem.required_attr("name")?;
         let text = String::from(elem.text());
 
         Ok(match name.to_lowercase().as_str() {
@@ -78,38 +83,43 @@ impl From<Header> for Element {
     }
 }
 
-generate_element!(
-    /// Put URL
-    Put, "put", HTTP_UPLOAD,
-    attributes: [
-        /// URL
-        url: Required<String> = "url",
-    ],
-    children: [
-        /// Header list
-        headers: Vec<Header> = ("header", HTTP_UPLOAD) => Header
-    ]
-);
-
-generate_element!(
+/// Put URL
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HTTP_UPLOAD, name = "put")]
+pub struct Put {
+    /// URL
+    #[xml(attribute = "url")]
+    pub url: String,
+
+    /// Header list
+    #[xml(child = "header")]
+    pub headers: Vec<Header>,
+}
+
+/// Get URL
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HTTP_UPLOAD, name = "get")]
+pub struct Get {
+    /// URL
+    #[xml(attribute = "url")]
+    pub url: String,
+}
+
+/// Requesting a slot
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = HTTP_UPLOAD, name = "slot")]
+pub struct SlotResult {
+    /// Put URL and headers
+    #[xml(child = "put")]
+    pub put: Put,
+
     /// Get URL
-    Get, "get", HTTP_UPLOAD,
-    attributes: [
-        /// URL
-        url: Required<String> = "url",
-    ]
-);
-
-generate_element!(
-    /// Requesting a slot
-    SlotResult, "slot", HTTP_UPLOAD,
-    children: [
-        /// Put URL and headers
-        put: Required<Put> = ("put", HTTP_UPLOAD) => Put,
-        /// Get URL
-        get: Required<Get> = ("get", HTTP_UPLOAD) => Get
-    ]
-);
+    #[xml(child = "get")]
+    pub get: Get,
+}
 
 impl IqResultPayload for SlotResult {}
 
@@ -6,66 +6,79 @@
 
 use crate::iq::IqSetPayload;
 use crate::util::helpers::Base64;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_id!(
-    /// An identifier matching a stream.
-    StreamId
-);
+/// An identifier matching a stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct StreamId(pub String);
 
-generate_attribute!(
 /// Which stanza type to use to exchange data.
-Stanza, "stanza", {
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "stanza")]
+pub enum Stanza {
     /// `<iq/>` gives a feedback on whether the chunk has been received or not,
     /// which is useful in the case the recipient might not receive them in a
     /// timely manner, or to do your own throttling based on the results.
-    Iq => "iq",
+    #[xml(value = "iq", default)]
+    Iq,
 
     /// `<message/>` can be faster, since it doesn’t require any feedback, but in
     /// practice it will be throttled by the servers on the way.
-    Message => "message",
-}, Default = Iq);
+    #[xml(value = "message")]
+    Message,
+}
 
-generate_element!(
 /// Starts an In-Band Bytestream session with the given parameters.
-Open, "open", IBB,
-attributes: [
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = IBB, name = "open")]
+pub struct Open {
     /// Maximum size in bytes for each chunk.
-    block_size: Required<u16> = "block-size",
+    #[xml(attribute = "block-size")]
+    pub block_size: u16,
 
     /// The identifier to be used to create a stream.
-    sid: Required<StreamId> = "sid",
+    #[xml(attribute = "sid")]
+    pub sid: StreamId,
 
     /// Which stanza type to use to exchange data.
-    stanza: Default<Stanza> = "stanza",
-]);
+    #[xml(attribute = "stanza", default)]
+    pub stanza: Stanza,
+}
 
 impl IqSetPayload for Open {}
 
-generate_element!(
 /// Exchange a chunk of data in an open stream.
-Data, "data", IBB,
-    attributes: [
-        /// Sequence number of this chunk, must wraparound after 65535.
-        seq: Required<u16> = "seq",
-
-        /// The identifier of the stream on which data is being exchanged.
-        sid: Required<StreamId> = "sid"
-    ],
-    text: (
-        /// Vector of bytes to be exchanged.
-        data: Base64<Vec<u8>>
-    )
-);
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = IBB, name = "data")]
+pub struct Data {
+    /// Sequence number of this chunk, must wraparound after 65535.
+    #[xml(attribute = "seq")]
+    pub seq: u16,
+
+    /// The identifier of the stream on which data is being exchanged.
+    #[xml(attribute = "sid")]
+    pub sid: StreamId,
+
+    /// Vector of bytes to be exchanged.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
 
 impl IqSetPayload for Data {}
 
-generate_element!(
 /// Close an open stream.
-Close, "close", IBB,
-attributes: [
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = IBB, name = "close")]
+pub struct Close {
     /// The identifier of the stream to be closed.
-    sid: Required<StreamId> = "sid",
-]);
+    #[xml(attribute = "sid")]
+    pub sid: StreamId,
+}
 
 impl IqSetPayload for Close {}
 
@@ -131,18 +144,27 @@ mod tests {
             .parse()
             .unwrap();
         let error = Open::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'block-size' missing.");
+        assert_eq!(attribute, "block-size");
 
         let elem: Element = "<open xmlns='http://jabber.org/protocol/ibb' block-size='-5'/>"
             .parse()
             .unwrap();
         let error = Open::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "invalid digit found in string");
@@ -151,19 +173,32 @@ mod tests {
             .parse()
             .unwrap();
         let error = Open::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(error) => error,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'sid' missing.");
+        assert_eq!(attribute, "sid");
     }
 
     #[test]
     fn test_invalid_stanza() {
         let elem: Element = "<open xmlns='http://jabber.org/protocol/ibb' block-size='128' sid='coucou' stanza='fdsq'/>".parse().unwrap();
         let error = Open::try_from(elem).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid attribute 'stanza' in open element: parse error: Unknown value for 'stanza' attribute."
+        );
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'stanza' attribute.");
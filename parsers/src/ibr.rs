@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use crate::bob::Data;
 use crate::data_forms::DataForm;
 use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
 use crate::ns;
@@ -13,7 +14,8 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 /// Query for registering against a service.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Query {
     /// Deprecated fixed list of possible fields to fill before the user can
     /// register.
@@ -27,10 +29,51 @@ pub struct Query {
 
     /// A data form the user must fill before being allowed to register.
     pub form: Option<DataForm>,
+
+    /// Bits of Binary data referenced from the form, for instance the image
+    /// of a CAPTCHA (XEP-0158) pointed to by a media element.
+    pub bob: Vec<Data>,
     // Not yet implemented.
     //pub oob: Option<Oob>,
 }
 
+impl Query {
+    /// Create an empty query, used to request the registration fields from
+    /// a service.
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /// Set a legacy field of this query.
+    pub fn with_field<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Query {
+        self.fields.insert(name.into(), value.into());
+        self
+    }
+
+    /// Set the data form of this query.
+    pub fn with_form(mut self, form: DataForm) -> Query {
+        self.form = Some(form);
+        self
+    }
+
+    /// Create a query changing the password of an already registered
+    /// account.
+    pub fn change_password<U: Into<String>, P: Into<String>>(username: U, password: P) -> Query {
+        Query::new()
+            .with_field("username", username)
+            .with_field("password", password)
+    }
+
+    /// Create a query cancelling an existing registration, which removes
+    /// the account from the service.
+    pub fn cancel() -> Query {
+        Query {
+            remove: true,
+            ..Query::new()
+        }
+    }
+}
+
 impl IqGetPayload for Query {}
 impl IqSetPayload for Query {}
 impl IqResultPayload for Query {}
@@ -40,12 +83,7 @@ impl TryFrom<Element> for Query {
 
     fn try_from(elem: Element) -> Result<Query, Error> {
         check_self!(elem, "query", REGISTER, "IBR query");
-        let mut query = Query {
-            registered: false,
-            fields: HashMap::new(),
-            remove: false,
-            form: None,
-        };
+        let mut query = Query::new();
         for child in elem.children() {
             let namespace = child.ns();
             if namespace == ns::REGISTER {
@@ -81,6 +119,8 @@ impl TryFrom<Element> for Query {
                 }
             } else if child.is("x", ns::DATA_FORMS) {
                 query.form = Some(DataForm::try_from(child.clone())?);
+            } else if child.is("data", ns::BOB) {
+                query.bob.push(Data::try_from(child.clone())?);
             } else {
                 return Err(Error::ParseError("Unknown child in ibr element."));
             }
@@ -109,6 +149,7 @@ impl From<Query> for Element {
                 None
             })
             .append_all(query.form.map(Element::from))
+            .append_all(query.bob.into_iter().map(Element::from))
             .build()
     }
 }
@@ -120,13 +161,13 @@ mod tests {
     #[cfg(target_pointer_width = "32")]
     #[test]
     fn test_size() {
-        assert_size!(Query, 88);
+        assert_size!(Query, 100);
     }
 
     #[cfg(target_pointer_width = "64")]
     #[test]
     fn test_size() {
-        assert_size!(Query, 160);
+        assert_size!(Query, 184);
     }
 
     #[test]
@@ -192,4 +233,40 @@ mod tests {
         let elem2 = query.into();
         assert_eq!(elem1, elem2);
     }
+
+    #[test]
+    fn test_captcha() {
+        let elem: Element = "<query xmlns='jabber:iq:register'><x xmlns='jabber:x:data' type='form'><field type='hidden' var='FORM_TYPE'><value>urn:xmpp:captcha</value></field><field label='Enter the text you see' var='ocr'><media xmlns='urn:xmpp:media-element' height='80' width='290'><uri type='image/png'>cid:sha1+f24030b8d91d233bac14777be5ab531ca3b9f102@bob.xmpp.org</uri></media></field></x><data xmlns='urn:xmpp:bob' cid='sha1+f24030b8d91d233bac14777be5ab531ca3b9f102@bob.xmpp.org' type='image/png'>iVBORw0KGgo=</data></query>"
+        .parse()
+        .unwrap();
+        let elem1 = elem.clone();
+        let query = Query::try_from(elem).unwrap();
+        let form = query.form.as_ref().unwrap();
+        assert_eq!(form.form_type.as_deref(), Some("urn:xmpp:captcha"));
+        assert_eq!(form.fields[0].media.len(), 1);
+        assert_eq!(query.bob.len(), 1);
+        assert_eq!(query.bob[0].type_.as_deref(), Some("image/png"));
+        let elem2 = query.into();
+        assert_eq!(elem1, elem2);
+    }
+
+    #[test]
+    fn test_change_password() {
+        let query = Query::change_password("bill", "newpass");
+        let elem: Element = query.into();
+        let query = Query::try_from(elem).unwrap();
+        assert_eq!(query.fields.len(), 2);
+        assert_eq!(query.fields["username"], "bill");
+        assert_eq!(query.fields["password"], "newpass");
+        assert!(!query.remove);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let elem: Element = Query::cancel().into();
+        let elem1: Element = "<query xmlns='jabber:iq:register'><remove/></query>"
+            .parse()
+            .unwrap();
+        assert_eq!(elem, elem1);
+    }
 }
@@ -6,15 +6,17 @@
 
 use crate::date::DateTime;
 use crate::presence::PresencePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Represents the last time the user interacted with their system.
-    Idle, "idle", IDLE,
-    attributes: [
-        /// The time at which the user stopped interacting.
-        since: Required<DateTime> = "since",
-    ]
-);
+/// Represents the last time the user interacted with their system.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = IDLE, name = "idle")]
+pub struct Idle {
+    /// The time at which the user stopped interacting.
+    #[xml(attribute = "since")]
+    pub since: DateTime,
+}
 
 impl PresencePayload for Idle {}
 
@@ -56,11 +58,15 @@ mod tests {
     fn test_invalid_id() {
         let elem: Element = "<idle xmlns='urn:xmpp:idle:1'/>".parse().unwrap();
         let error = Idle::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'since' missing.");
+        assert_eq!(attribute, "since");
     }
 
     #[test]
@@ -71,7 +77,12 @@ mod tests {
             .unwrap();
         let error = Idle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ChronoParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ChronoParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "input is out of range");
@@ -82,7 +93,12 @@ mod tests {
             .unwrap();
         let error = Idle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ChronoParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ChronoParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "input is out of range");
@@ -93,7 +109,12 @@ mod tests {
             .unwrap();
         let error = Idle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ChronoParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ChronoParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "input contains invalid characters");
@@ -104,7 +125,12 @@ mod tests {
             .unwrap();
         let error = Idle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ChronoParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ChronoParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "input contains invalid characters");
@@ -115,7 +141,12 @@ mod tests {
             .unwrap();
         let error = Idle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ChronoParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ChronoParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "input contains invalid characters");
@@ -126,7 +157,12 @@ mod tests {
             .unwrap();
         let error = Idle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ChronoParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ChronoParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "premature end of input");
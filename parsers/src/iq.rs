@@ -10,7 +10,7 @@ use crate::stanza_error::StanzaError;
 use crate::util::error::Error;
 use crate::Element;
 use jid::Jid;
-use minidom::IntoAttributeValue;
+use minidom::{AsXml, IntoAttributeValue, XmlWriter};
 use std::convert::TryFrom;
 
 /// Should be implemented on every known payload of an `<iq type='get'/>`.
@@ -24,6 +24,7 @@ pub trait IqResultPayload: TryFrom<Element> + Into<Element> {}
 
 /// Represents one of the four possible iq types.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IqType {
     /// This is a request for accessing some data.
     Get(Element),
@@ -38,22 +39,26 @@ pub enum IqType {
     Error(StanzaError),
 }
 
+impl IqType {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            IqType::Get(_) => "get",
+            IqType::Set(_) => "set",
+            IqType::Result(_) => "result",
+            IqType::Error(_) => "error",
+        }
+    }
+}
+
 impl<'a> IntoAttributeValue for &'a IqType {
     fn into_attribute_value(self) -> Option<String> {
-        Some(
-            match *self {
-                IqType::Get(_) => "get",
-                IqType::Set(_) => "set",
-                IqType::Result(_) => "result",
-                IqType::Error(_) => "error",
-            }
-            .to_owned(),
-        )
+        Some(self.as_str().to_owned())
     }
 }
 
 /// The main structure representing the `<iq/>` stanza.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Iq {
     /// The JID emitting this stanza.
     pub from: Option<Jid>,
@@ -144,10 +149,10 @@ impl TryFrom<Element> for Iq {
 
     fn try_from(root: Element) -> Result<Iq, Error> {
         check_self!(root, "iq", DEFAULT_NS);
-        let from = get_attr!(root, "from", Option);
-        let to = get_attr!(root, "to", Option);
-        let id = get_attr!(root, "id", Required);
-        let type_: String = get_attr!(root, "type", Required);
+        let from = root.attr_as("from")?;
+        let to = root.attr_as("to")?;
+        let id = root.required_attr("id")?;
+        let type_: String = root.required_attr("type")?;
 
         let mut payload = None;
         let mut error_payload = None;
@@ -224,11 +229,35 @@ impl From<Iq> for Element {
     }
 }
 
+impl AsXml for Iq {
+    fn write_xml(&self, writer: &mut XmlWriter) -> minidom::Result<()> {
+        writer.start("iq", ns::DEFAULT_NS);
+        if let Some(ref from) = self.from {
+            writer.attr("from", from);
+        }
+        writer.attr("id", &self.id);
+        if let Some(ref to) = self.to {
+            writer.attr("to", to);
+        }
+        writer.attr("type", self.payload.as_str());
+        match self.payload {
+            IqType::Get(ref elem) | IqType::Set(ref elem) | IqType::Result(Some(ref elem)) => {
+                writer.write(elem)?
+            }
+            IqType::Error(ref error) => writer.write(error)?,
+            IqType::Result(None) => (),
+        }
+        writer.end();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::disco::DiscoInfoQuery;
     use crate::stanza_error::{DefinedCondition, ErrorType};
+    use jid::BareJid;
 
     #[cfg(target_pointer_width = "32")]
     #[test]
@@ -251,11 +280,15 @@ mod tests {
         #[cfg(feature = "component")]
         let elem: Element = "<iq xmlns='jabber:component:accept'/>".parse().unwrap();
         let error = Iq::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'id' missing.");
+        assert_eq!(attribute, "id");
 
         #[cfg(not(feature = "component"))]
         let elem: Element = "<iq xmlns='jabber:client' id='coucou'/>".parse().unwrap();
@@ -264,11 +297,15 @@ mod tests {
             .parse()
             .unwrap();
         let error = Iq::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'type' missing.");
+        assert_eq!(attribute, "type");
     }
 
     #[test]
@@ -458,4 +495,31 @@ mod tests {
         };
         assert!(disco_info.node.is_none());
     }
+
+    #[test]
+    fn test_as_xml() {
+        let iq = Iq::from_get("disco", DiscoInfoQuery { node: None })
+            .with_to(Jid::Bare(BareJid::from_domain("example.org")));
+        let bytes = iq.to_bytes().unwrap();
+        let elem = Element::from(iq);
+        assert_eq!(&bytes[..], String::from(&elem).as_bytes());
+
+        let mut error = StanzaError::new(
+            ErrorType::Cancel,
+            DefinedCondition::ItemNotFound,
+            "en",
+            "Not <found>",
+        );
+        error.other = Some(Element::builder("other", "urn:example").build());
+        let iq = Iq::from_error("disco", error)
+            .with_from(Jid::Bare(BareJid::from_domain("example.org")));
+        let bytes = iq.to_bytes().unwrap();
+        let elem = Element::from(iq);
+        assert_eq!(&bytes[..], String::from(&elem).as_bytes());
+
+        let iq = Iq::empty_result(Jid::Bare(BareJid::from_domain("example.org")), "res");
+        let bytes = iq.to_bytes().unwrap();
+        let elem = Element::from(iq);
+        assert_eq!(&bytes[..], String::from(&elem).as_bytes());
+    }
 }
@@ -7,15 +7,17 @@
 use crate::iq::{IqGetPayload, IqResultPayload};
 use crate::util::helpers::{JidCodec, Text};
 use jid::Jid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Request from a client to stringprep/PRECIS a string into a JID.
-    JidPrepQuery, "jid", JID_PREP,
-    text: (
-        /// The potential JID.
-        data: Text<String>
-    )
-);
+/// Request from a client to stringprep/PRECIS a string into a JID.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JID_PREP, name = "jid")]
+pub struct JidPrepQuery {
+    /// The potential JID.
+    #[xml(text, codec = Text)]
+    pub data: String,
+}
 
 impl IqGetPayload for JidPrepQuery {}
 
@@ -26,14 +28,15 @@ impl JidPrepQuery {
     }
 }
 
-generate_element!(
-    /// Response from the server with the stringprep’d/PRECIS’d JID.
-    JidPrepResponse, "jid", JID_PREP,
-    text: (
-        /// The JID.
-        jid: JidCodec<Jid>
-    )
-);
+/// Response from the server with the stringprep’d/PRECIS’d JID.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JID_PREP, name = "jid")]
+pub struct JidPrepResponse {
+    /// The JID.
+    #[xml(text, codec = JidCodec)]
+    pub jid: Jid,
+}
 
 impl IqResultPayload for JidPrepResponse {}
 
@@ -18,160 +18,200 @@ use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The action attribute.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "action")]
+pub enum Action {
+    /// Accept a content-add action received from another party.
+    #[xml(value = "content-accept")]
+    ContentAccept,
+
+    /// Add one or more new content definitions to the session.
+    #[xml(value = "content-add")]
+    ContentAdd,
+
+    /// Change the directionality of media sending.
+    #[xml(value = "content-modify")]
+    ContentModify,
+
+    /// Reject a content-add action received from another party.
+    #[xml(value = "content-reject")]
+    ContentReject,
+
+    /// Remove one or more content definitions from the session.
+    #[xml(value = "content-remove")]
+    ContentRemove,
+
+    /// Exchange information about parameters for an application type.
+    #[xml(value = "description-info")]
+    DescriptionInfo,
+
+    /// Exchange information about security preconditions.
+    #[xml(value = "security-info")]
+    SecurityInfo,
+
+    /// Definitively accept a session negotiation.
+    #[xml(value = "session-accept")]
+    SessionAccept,
+
+    /// Send session-level information, such as a ping or a ringing message.
+    #[xml(value = "session-info")]
+    SessionInfo,
+
+    /// Request negotiation of a new Jingle session.
+    #[xml(value = "session-initiate")]
+    SessionInitiate,
+
+    /// End an existing session.
+    #[xml(value = "session-terminate")]
+    SessionTerminate,
+
+    /// Accept a transport-replace action received from another party.
+    #[xml(value = "transport-accept")]
+    TransportAccept,
+
+    /// Exchange transport candidates.
+    #[xml(value = "transport-info")]
+    TransportInfo,
+
+    /// Reject a transport-replace action received from another party.
+    #[xml(value = "transport-reject")]
+    TransportReject,
+
+    /// Redefine a transport method or replace it with a different method.
+    #[xml(value = "transport-replace")]
+    TransportReplace,
+}
 
-generate_attribute!(
-    /// The action attribute.
-    Action, "action", {
-        /// Accept a content-add action received from another party.
-        ContentAccept => "content-accept",
-
-        /// Add one or more new content definitions to the session.
-        ContentAdd => "content-add",
-
-        /// Change the directionality of media sending.
-        ContentModify => "content-modify",
-
-        /// Reject a content-add action received from another party.
-        ContentReject => "content-reject",
-
-        /// Remove one or more content definitions from the session.
-        ContentRemove => "content-remove",
-
-        /// Exchange information about parameters for an application type.
-        DescriptionInfo => "description-info",
-
-        /// Exchange information about security preconditions.
-        SecurityInfo => "security-info",
-
-        /// Definitively accept a session negotiation.
-        SessionAccept => "session-accept",
-
-        /// Send session-level information, such as a ping or a ringing message.
-        SessionInfo => "session-info",
-
-        /// Request negotiation of a new Jingle session.
-        SessionInitiate => "session-initiate",
-
-        /// End an existing session.
-        SessionTerminate => "session-terminate",
-
-        /// Accept a transport-replace action received from another party.
-        TransportAccept => "transport-accept",
-
-        /// Exchange transport candidates.
-        TransportInfo => "transport-info",
-
-        /// Reject a transport-replace action received from another party.
-        TransportReject => "transport-reject",
-
-        /// Redefine a transport method or replace it with a different method.
-        TransportReplace => "transport-replace",
-    }
-);
-
-generate_attribute!(
-    /// Which party originally generated the content type.
-    Creator, "creator", {
-        /// This content was created by the initiator of this session.
-        Initiator => "initiator",
-
-        /// This content was created by the responder of this session.
-        Responder => "responder",
-    }
-);
-
-generate_attribute!(
-    /// Which parties in the session will be generating content.
-    Senders, "senders", {
-        /// Both parties can send for this content.
-        Both => "both",
-
-        /// Only the initiator can send for this content.
-        Initiator => "initiator",
-
-        /// No one can send for this content.
-        None => "none",
-
-        /// Only the responder can send for this content.
-        Responder => "responder",
-    }, Default = Both
-);
-
-generate_attribute!(
-    /// How the content definition is to be interpreted by the recipient. The
-    /// meaning of this attribute matches the "Content-Disposition" header as
-    /// defined in RFC 2183 and applied to SIP by RFC 3261.
-    ///
-    /// Possible values are defined here:
-    /// https://www.iana.org/assignments/cont-disp/cont-disp.xhtml
-    Disposition, "disposition", {
-        /// Displayed automatically.
-        Inline => "inline",
-
-        /// User controlled display.
-        Attachment => "attachment",
-
-        /// Process as form response.
-        FormData => "form-data",
-
-        /// Tunneled content to be processed silently.
-        Signal => "signal",
-
-        /// The body is a custom ring tone to alert the user.
-        Alert => "alert",
-
-        /// The body is displayed as an icon to the user.
-        Icon => "icon",
-
-        /// The body should be displayed to the user.
-        Render => "render",
-
-        /// The body contains a list of URIs that indicates the recipients of
-        /// the request.
-        RecipientListHistory => "recipient-list-history",
-
-        /// The body describes a communications session, for example, an
-        /// RFC2327 SDP body.
-        Session => "session",
-
-        /// Authenticated Identity Body.
-        Aib => "aib",
-
-        /// The body describes an early communications session, for example,
-        /// and [RFC2327] SDP body.
-        EarlySession => "early-session",
-
-        /// The body includes a list of URIs to which URI-list services are to
-        /// be applied.
-        RecipientList => "recipient-list",
-
-        /// The payload of the message carrying this Content-Disposition header
-        /// field value is an Instant Message Disposition Notification as
-        /// requested in the corresponding Instant Message.
-        Notification => "notification",
-
-        /// The body needs to be handled according to a reference to the body
-        /// that is located in the same SIP message as the body.
-        ByReference => "by-reference",
+/// Which party originally generated the content type.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "creator")]
+pub enum Creator {
+    /// This content was created by the initiator of this session.
+    #[xml(value = "initiator")]
+    Initiator,
+
+    /// This content was created by the responder of this session.
+    #[xml(value = "responder")]
+    Responder,
+}
 
-        /// The body contains information associated with an Info Package.
-        InfoPackage => "info-package",
+/// Which parties in the session will be generating content.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "senders")]
+pub enum Senders {
+    /// Both parties can send for this content.
+    #[xml(value = "both", default)]
+    Both,
+
+    /// Only the initiator can send for this content.
+    #[xml(value = "initiator")]
+    Initiator,
+
+    /// No one can send for this content.
+    #[xml(value = "none")]
+    None,
+
+    /// Only the responder can send for this content.
+    #[xml(value = "responder")]
+    Responder,
+}
 
-        /// The body describes either metadata about the RS or the reason for
-        /// the metadata snapshot request as determined by the MIME value
-        /// indicated in the Content-Type.
-        RecordingSession => "recording-session",
-    }, Default = Session
-);
+/// How the content definition is to be interpreted by the recipient. The
+/// meaning of this attribute matches the "Content-Disposition" header as
+/// defined in RFC 2183 and applied to SIP by RFC 3261.
+///
+/// Possible values are defined here:
+/// https://www.iana.org/assignments/cont-disp/cont-disp.xhtml
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "disposition")]
+pub enum Disposition {
+    /// Displayed automatically.
+    #[xml(value = "inline")]
+    Inline,
+
+    /// User controlled display.
+    #[xml(value = "attachment")]
+    Attachment,
+
+    /// Process as form response.
+    #[xml(value = "form-data")]
+    FormData,
+
+    /// Tunneled content to be processed silently.
+    #[xml(value = "signal")]
+    Signal,
+
+    /// The body is a custom ring tone to alert the user.
+    #[xml(value = "alert")]
+    Alert,
+
+    /// The body is displayed as an icon to the user.
+    #[xml(value = "icon")]
+    Icon,
+
+    /// The body should be displayed to the user.
+    #[xml(value = "render")]
+    Render,
+
+    /// The body contains a list of URIs that indicates the recipients of
+    /// the request.
+    #[xml(value = "recipient-list-history")]
+    RecipientListHistory,
+
+    /// The body describes a communications session, for example, an
+    /// RFC2327 SDP body.
+    #[xml(value = "session", default)]
+    Session,
+
+    /// Authenticated Identity Body.
+    #[xml(value = "aib")]
+    Aib,
+
+    /// The body describes an early communications session, for example,
+    /// and [RFC2327] SDP body.
+    #[xml(value = "early-session")]
+    EarlySession,
+
+    /// The body includes a list of URIs to which URI-list services are to
+    /// be applied.
+    #[xml(value = "recipient-list")]
+    RecipientList,
+
+    /// The payload of the message carrying this Content-Disposition header
+    /// field value is an Instant Message Disposition Notification as
+    /// requested in the corresponding Instant Message.
+    #[xml(value = "notification")]
+    Notification,
+
+    /// The body needs to be handled according to a reference to the body
+    /// that is located in the same SIP message as the body.
+    #[xml(value = "by-reference")]
+    ByReference,
+
+    /// The body contains information associated with an Info Package.
+    #[xml(value = "info-package")]
+    InfoPackage,
+
+    /// The body describes either metadata about the RS or the reason for
+    /// the metadata snapshot request as determined by the MIME value
+    /// indicated in the Content-Type.
+    #[xml(value = "recording-session")]
+    RecordingSession,
+}
 
-generate_id!(
-    /// An unique identifier in a session, referencing a
-    /// [struct.Content.html](Content element).
-    ContentId
-);
+/// An unique identifier in a session, referencing a
+/// [struct.Content.html](Content element).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct ContentId(pub String);
 
 /// Enum wrapping all of the various supported descriptions of a Content.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Description {
     /// Jingle RTP Sessions (XEP-0167) description.
     Rtp(RtpDescription),
@@ -209,6 +249,7 @@ impl From<Description> for Element {
 
 /// Enum wrapping all of the various supported transports of a Content.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Transport {
     /// Jingle ICE-UDP Bytestreams (XEP-0176) transport.
     IceUdp(IceUdpTransport),
@@ -268,34 +309,40 @@ impl From<Transport> for Element {
     }
 }
 
-generate_element!(
-    /// Describes a session’s content, there can be multiple content in one
-    /// session.
-    Content, "content", JINGLE,
-    attributes: [
-        /// Who created this content.
-        creator: Required<Creator> = "creator",
-
-        /// How the content definition is to be interpreted by the recipient.
-        disposition: Default<Disposition> = "disposition",
-
-        /// A per-session unique identifier for this content.
-        name: Required<ContentId> = "name",
-
-        /// Who can send data for this content.
-        senders: Default<Senders> = "senders",
-    ],
-    children: [
-        /// What to send.
-        description: Option<Description> = ("description", *) => Description,
-
-        /// How to send it.
-        transport: Option<Transport> = ("transport", *) => Transport,
-
-        /// With which security.
-        security: Option<Element> = ("security", JINGLE) => Element
-    ]
-);
+/// Describes a session’s content, there can be multiple content in one
+/// session.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE, name = "content")]
+pub struct Content {
+    /// Who created this content.
+    #[xml(attribute = "creator")]
+    pub creator: Creator,
+
+    /// How the content definition is to be interpreted by the recipient.
+    #[xml(attribute = "disposition", default)]
+    pub disposition: Disposition,
+
+    /// A per-session unique identifier for this content.
+    #[xml(attribute = "name")]
+    pub name: ContentId,
+
+    /// Who can send data for this content.
+    #[xml(attribute = "senders", default)]
+    pub senders: Senders,
+
+    /// What to send.
+    #[xml(child = "description", any_namespace)]
+    pub description: Option<Description>,
+
+    /// How to send it.
+    #[xml(child = "transport", any_namespace)]
+    pub transport: Option<Transport>,
+
+    /// With which security.
+    #[xml(child = "security")]
+    pub security: Option<Element>,
+}
 
 impl Content {
     /// Create a new content.
@@ -344,6 +391,7 @@ impl Content {
 
 /// Lists the possible reasons to be included in a Jingle iq.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Reason {
     /// The party prefers to use an existing session with the peer rather than
     /// initiate a new session; the Jingle session ID of the alternative
@@ -466,6 +514,7 @@ type Lang = String;
 
 /// Informs the recipient of something.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReasonElement {
     /// The list of possible reasons to be included in a Jingle iq.
     pub reason: Reason,
@@ -498,7 +547,7 @@ impl TryFrom<Element> for ReasonElement {
             if child.is("text", ns::JINGLE) {
                 check_no_children!(child, "text");
                 check_no_unknown_attributes!(child, "text", ["xml:lang"]);
-                let lang = get_attr!(elem, "xml:lang", Default);
+                let lang = elem.attr_as("xml:lang")?.unwrap_or_default();
                 if texts.insert(lang, child.text()).is_some() {
                     return Err(Error::ParseError(
                         "Text element present twice for the same xml:lang.",
@@ -535,13 +584,15 @@ impl From<ReasonElement> for Element {
     }
 }
 
-generate_id!(
-    /// Unique identifier for a session between two JIDs.
-    SessionId
-);
+/// Unique identifier for a session between two JIDs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct SessionId(pub String);
 
 /// The main Jingle container, to be included in an iq stanza.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Jingle {
     /// The action to execute on both ends.
     pub action: Action,
@@ -624,10 +675,10 @@ impl TryFrom<Element> for Jingle {
         check_no_unknown_attributes!(root, "Jingle", ["action", "initiator", "responder", "sid"]);
 
         let mut jingle = Jingle {
-            action: get_attr!(root, "action", Required),
-            initiator: get_attr!(root, "initiator", Option),
-            responder: get_attr!(root, "responder", Option),
-            sid: get_attr!(root, "sid", Required),
+            action: root.required_attr("action")?,
+            initiator: root.attr_as("initiator")?,
+            responder: root.attr_as("responder")?,
+            sid: root.required_attr("sid")?,
             contents: vec![],
             reason: None,
             group: None,
@@ -726,28 +777,41 @@ mod tests {
     fn test_invalid_jingle() {
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1'/>".parse().unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'action' missing.");
+        assert_eq!(attribute, "action");
 
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1' action='session-info'/>"
             .parse()
             .unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'sid' missing.");
+        assert_eq!(attribute, "sid");
 
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1' action='coucou' sid='coucou'/>"
             .parse()
             .unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'action' attribute.");
@@ -775,24 +839,37 @@ mod tests {
     fn test_invalid_content() {
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1' action='session-initiate' sid='coucou'><content/></jingle>".parse().unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'creator' missing.");
+        assert_eq!(attribute, "creator");
 
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1' action='session-initiate' sid='coucou'><content creator='initiator'/></jingle>".parse().unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'name' missing.");
+        assert_eq!(attribute, "name");
 
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1' action='session-initiate' sid='coucou'><content creator='coucou' name='coucou'/></jingle>".parse().unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'creator' attribute.");
@@ -800,7 +877,12 @@ mod tests {
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1' action='session-initiate' sid='coucou'><content creator='initiator' name='coucou' senders='coucou'/></jingle>".parse().unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'senders' attribute.");
@@ -808,7 +890,12 @@ mod tests {
         let elem: Element = "<jingle xmlns='urn:xmpp:jingle:1' action='session-initiate' sid='coucou'><content creator='initiator' name='coucou' senders=''/></jingle>".parse().unwrap();
         let error = Jingle::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'senders' attribute.");
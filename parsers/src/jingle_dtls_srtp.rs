@@ -7,45 +7,50 @@
 use crate::hashes::{Algo, Hash};
 use crate::util::error::Error;
 use crate::util::helpers::ColonSeparatedHex;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_attribute!(
-    /// Indicates which of the end points should initiate the TCP connection establishment.
-    Setup, "setup", {
-        /// The endpoint will initiate an outgoing connection.
-        Active => "active",
-
-        /// The endpoint will accept an incoming connection.
-        Passive => "passive",
+/// Indicates which of the end points should initiate the TCP connection establishment.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "setup")]
+pub enum Setup {
+    /// The endpoint will initiate an outgoing connection.
+    #[xml(value = "active")]
+    Active,
 
-        /// The endpoint is willing to accept an incoming connection or to initiate an outgoing
-        /// connection.
-        Actpass => "actpass",
+    /// The endpoint will accept an incoming connection.
+    #[xml(value = "passive")]
+    Passive,
 
-        /*
-        /// The endpoint does not want the connection to be established for the time being.
-        ///
-        /// Note that this value isn’t used, as per the XEP.
-        Holdconn => "holdconn",
-        */
-    }
-);
+    /// The endpoint is willing to accept an incoming connection or to initiate an outgoing
+    /// connection.
+    #[xml(value = "actpass")]
+    Actpass,
+    /*
+    /// The endpoint does not want the connection to be established for the time being.
+    ///
+    /// Note that this value isn’t used, as per the XEP.
+    Holdconn => "holdconn",
+    */
+}
 
 // TODO: use a hashes::Hash instead of two different fields here.
-generate_element!(
-    /// Fingerprint of the key used for a DTLS handshake.
-    Fingerprint, "fingerprint", JINGLE_DTLS,
-    attributes: [
-        /// The hash algorithm used for this fingerprint.
-        hash: Required<Algo> = "hash",
+/// Fingerprint of the key used for a DTLS handshake.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_DTLS, name = "fingerprint")]
+pub struct Fingerprint {
+    /// The hash algorithm used for this fingerprint.
+    #[xml(attribute = "hash")]
+    pub hash: Algo,
 
-        /// Indicates which of the end points should initiate the TCP connection establishment.
-        setup: Required<Setup> = "setup"
-    ],
-    text: (
-        /// Hash value of this fingerprint.
-        value: ColonSeparatedHex<Vec<u8>>
-    )
-);
+    /// Indicates which of the end points should initiate the TCP connection establishment.
+    #[xml(attribute = "setup")]
+    pub setup: Setup,
+
+    /// Hash value of this fingerprint.
+    #[xml(text, codec = ColonSeparatedHex)]
+    pub value: Vec<u8>,
+}
 
 impl Fingerprint {
     /// Create a new Fingerprint from a Setup and a Hash.
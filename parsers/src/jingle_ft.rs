@@ -13,24 +13,26 @@ use minidom::{Element, Node};
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::str::FromStr;
-
-generate_element!(
-    /// Represents a range in a file.
-    #[derive(Default)]
-    Range, "range", JINGLE_FT,
-    attributes: [
-        /// The offset in bytes from the beginning of the file.
-        offset: Default<u64> = "offset",
-
-        /// The length in bytes of the range, or None to be the entire
-        /// remaining of the file.
-        length: Option<u64> = "length"
-    ],
-    children: [
-        /// List of hashes for this range.
-        hashes: Vec<Hash> = ("hash", HASHES) => Hash
-    ]
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Represents a range in a file.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_FT, name = "range")]
+pub struct Range {
+    /// The offset in bytes from the beginning of the file.
+    #[xml(attribute = "offset", default)]
+    pub offset: u64,
+
+    /// The length in bytes of the range, or None to be the entire
+    /// remaining of the file.
+    #[xml(attribute = "length")]
+    pub length: Option<u64>,
+
+    /// List of hashes for this range.
+    #[xml(child = "hash", namespace = HASHES)]
+    pub hashes: Vec<Hash>,
+}
 
 impl Range {
     /// Creates a new range.
@@ -41,13 +43,15 @@ impl Range {
 
 type Lang = String;
 
-generate_id!(
-    /// Wrapper for a file description.
-    Desc
-);
+/// Wrapper for a file description.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct Desc(pub String);
 
 /// Represents a file to be transferred.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct File {
     /// The date of last modification of this file.
     pub date: Option<DateTime>,
@@ -127,6 +131,88 @@ impl File {
     }
 }
 
+impl File {
+    /// Parses a child which this element shares with the File Metadata (XEP-0446) one, in
+    /// the namespace of their parent, returning whether it was one of them.
+    pub(crate) fn parse_shared_child(
+        &mut self,
+        child: &Element,
+        namespace: &str,
+    ) -> Result<bool, Error> {
+        if child.is("date", namespace) {
+            if self.date.is_some() {
+                return Err(Error::ParseError("File must not have more than one date."));
+            }
+            self.date = Some(child.text().parse()?);
+        } else if child.is("media-type", namespace) {
+            if self.media_type.is_some() {
+                return Err(Error::ParseError(
+                    "File must not have more than one media-type.",
+                ));
+            }
+            self.media_type = Some(child.text());
+        } else if child.is("name", namespace) {
+            if self.name.is_some() {
+                return Err(Error::ParseError("File must not have more than one name."));
+            }
+            self.name = Some(child.text());
+        } else if child.is("desc", namespace) {
+            let lang = child.attr_as("xml:lang")?.unwrap_or_default();
+            let desc = Desc(child.text());
+            if self.descs.insert(lang, desc).is_some() {
+                return Err(Error::ParseError(
+                    "Desc element present twice for the same xml:lang.",
+                ));
+            }
+        } else if child.is("size", namespace) {
+            if self.size.is_some() {
+                return Err(Error::ParseError("File must not have more than one size."));
+            }
+            self.size = Some(child.text().parse()?);
+        } else if child.is("hash", ns::HASHES) {
+            self.hashes.push(Hash::try_from(child.clone())?);
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Serialises the children shared with the File Metadata (XEP-0446) element into a file
+    /// element of this namespace, with `extra` children before the hashes.  The range is left
+    /// to the caller.
+    pub(crate) fn into_element<I: IntoIterator<Item = Element>>(
+        self,
+        namespace: &str,
+        extra: I,
+    ) -> Element {
+        Element::builder("file", namespace)
+            .append_all(
+                self.date
+                    .map(|date| Element::builder("date", namespace).append(date)),
+            )
+            .append_all(
+                self.media_type
+                    .map(|media_type| Element::builder("media-type", namespace).append(media_type)),
+            )
+            .append_all(
+                self.name
+                    .map(|name| Element::builder("name", namespace).append(name)),
+            )
+            .append_all(self.descs.into_iter().map(|(lang, desc)| {
+                Element::builder("desc", namespace)
+                    .attr("xml:lang", lang)
+                    .append(desc.0)
+            }))
+            .append_all(
+                self.size
+                    .map(|size| Element::builder("size", namespace).append(format!("{}", size))),
+            )
+            .append_all(extra)
+            .append_all(self.hashes)
+            .build()
+    }
+}
+
 impl TryFrom<Element> for File {
     type Error = Error;
 
@@ -134,54 +220,15 @@ impl TryFrom<Element> for File {
         check_self!(elem, "file", JINGLE_FT);
         check_no_attributes!(elem, "file");
 
-        let mut file = File {
-            date: None,
-            media_type: None,
-            name: None,
-            descs: BTreeMap::new(),
-            size: None,
-            range: None,
-            hashes: vec![],
-        };
-
+        let mut file = File::new();
         for child in elem.children() {
-            if child.is("date", ns::JINGLE_FT) {
-                if file.date.is_some() {
-                    return Err(Error::ParseError("File must not have more than one date."));
-                }
-                file.date = Some(child.text().parse()?);
-            } else if child.is("media-type", ns::JINGLE_FT) {
-                if file.media_type.is_some() {
-                    return Err(Error::ParseError(
-                        "File must not have more than one media-type.",
-                    ));
-                }
-                file.media_type = Some(child.text());
-            } else if child.is("name", ns::JINGLE_FT) {
-                if file.name.is_some() {
-                    return Err(Error::ParseError("File must not have more than one name."));
-                }
-                file.name = Some(child.text());
-            } else if child.is("desc", ns::JINGLE_FT) {
-                let lang = get_attr!(child, "xml:lang", Default);
-                let desc = Desc(child.text());
-                if file.descs.insert(lang, desc).is_some() {
-                    return Err(Error::ParseError(
-                        "Desc element present twice for the same xml:lang.",
-                    ));
-                }
-            } else if child.is("size", ns::JINGLE_FT) {
-                if file.size.is_some() {
-                    return Err(Error::ParseError("File must not have more than one size."));
-                }
-                file.size = Some(child.text().parse()?);
+            if file.parse_shared_child(child, ns::JINGLE_FT)? {
+                continue;
             } else if child.is("range", ns::JINGLE_FT) {
                 if file.range.is_some() {
                     return Err(Error::ParseError("File must not have more than one range."));
                 }
                 file.range = Some(Range::try_from(child.clone())?);
-            } else if child.is("hash", ns::HASHES) {
-                file.hashes.push(Hash::try_from(child.clone())?);
             } else {
                 return Err(Error::ParseError("Unknown element in JingleFT file."));
             }
@@ -192,39 +239,15 @@ impl TryFrom<Element> for File {
 }
 
 impl From<File> for Element {
-    fn from(file: File) -> Element {
-        Element::builder("file", ns::JINGLE_FT)
-            .append_all(
-                file.date
-                    .map(|date| Element::builder("date", ns::JINGLE_FT).append(date)),
-            )
-            .append_all(
-                file.media_type.map(|media_type| {
-                    Element::builder("media-type", ns::JINGLE_FT).append(media_type)
-                }),
-            )
-            .append_all(
-                file.name
-                    .map(|name| Element::builder("name", ns::JINGLE_FT).append(name)),
-            )
-            .append_all(file.descs.into_iter().map(|(lang, desc)| {
-                Element::builder("desc", ns::JINGLE_FT)
-                    .attr("xml:lang", lang)
-                    .append(desc.0)
-            }))
-            .append_all(
-                file.size.map(|size| {
-                    Element::builder("size", ns::JINGLE_FT).append(format!("{}", size))
-                }),
-            )
-            .append_all(file.range)
-            .append_all(file.hashes)
-            .build()
+    fn from(mut file: File) -> Element {
+        let range = file.range.take().map(Element::from);
+        file.into_element(ns::JINGLE_FT, range)
     }
 }
 
 /// A wrapper element for a file.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Description {
     /// The actual file descriptor.
     pub file: File,
@@ -266,6 +289,7 @@ impl From<Description> for Element {
 
 /// A checksum for checking that the file has been transferred correctly.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Checksum {
     /// The identifier of the file transfer content.
     pub name: ContentId,
@@ -298,8 +322,8 @@ impl TryFrom<Element> for Checksum {
             ));
         }
         Ok(Checksum {
-            name: get_attr!(elem, "name", Required),
-            creator: get_attr!(elem, "creator", Required),
+            name: elem.required_attr("name")?,
+            creator: elem.required_attr("creator")?,
             file: file.unwrap(),
         })
     }
@@ -315,17 +339,19 @@ impl From<Checksum> for Element {
     }
 }
 
-generate_element!(
-    /// A notice that the file transfer has been completed.
-    Received, "received", JINGLE_FT,
-    attributes: [
-        /// The content identifier of this Jingle session.
-        name: Required<ContentId> = "name",
+/// A notice that the file transfer has been completed.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_FT, name = "received")]
+pub struct Received {
+    /// The content identifier of this Jingle session.
+    #[xml(attribute = "name")]
+    pub name: ContentId,
 
-        /// The creator of this file transfer.
-        creator: Required<Creator> = "creator",
-    ]
-);
+    /// The creator of this file transfer.
+    #[xml(attribute = "creator")]
+    pub creator: Creator,
+}
 
 #[cfg(test)]
 mod tests {
@@ -482,16 +508,25 @@ mod tests {
                 .parse()
                 .unwrap();
         let error = Received::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'name' missing.");
+        assert_eq!(attribute, "name");
 
         let elem: Element = "<received xmlns='urn:xmpp:jingle:apps:file-transfer:5' name='coucou' creator='coucou'/>".parse().unwrap();
         let error = Received::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'creator' attribute.");
@@ -548,16 +583,25 @@ mod tests {
 
         let elem: Element = "<checksum xmlns='urn:xmpp:jingle:apps:file-transfer:5' creator='initiator'><file><hash xmlns='urn:xmpp:hashes:2' algo='sha-1'>w0mcJylzCn+AfvuGdqkty2+KP48=</hash></file></checksum>".parse().unwrap();
         let error = Checksum::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'name' missing.");
+        assert_eq!(attribute, "name");
 
         let elem: Element = "<checksum xmlns='urn:xmpp:jingle:apps:file-transfer:5' name='coucou' creator='coucou'><file><hash xmlns='urn:xmpp:hashes:2' algo='sha-1'>w0mcJylzCn+AfvuGdqkty2+KP48=</hash></file></checksum>".parse().unwrap();
         let error = Checksum::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'creator' attribute.");
@@ -5,26 +5,30 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::jingle::ContentId;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_attribute!(
-    /// The semantics of the grouping.
-    Semantics, "semantics", {
-        /// Lip synchronsation.
-        Ls => "LS",
+/// The semantics of the grouping.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "semantics")]
+pub enum Semantics {
+    /// Lip synchronsation.
+    #[xml(value = "LS")]
+    Ls,
 
-        /// Bundle.
-        Bundle => "BUNDLE",
-    }
-);
+    /// Bundle.
+    #[xml(value = "BUNDLE")]
+    Bundle,
+}
 
-generate_element!(
-    /// Describes a content that should be grouped with other ones.
-    Content, "content", JINGLE_GROUPING,
-    attributes: [
-        /// The name of the matching [`Content`](crate::jingle::Content).
-        name: Required<ContentId> = "name",
-    ]
-);
+/// Describes a content that should be grouped with other ones.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_GROUPING, name = "content")]
+pub struct Content {
+    /// The name of the matching [`Content`](crate::jingle::Content).
+    #[xml(attribute = "name")]
+    pub name: ContentId,
+}
 
 impl Content {
     /// Creates a new <content/> element.
@@ -35,18 +39,19 @@ impl Content {
     }
 }
 
-generate_element!(
-    /// A semantic group of contents.
-    Group, "group", JINGLE_GROUPING,
-    attributes: [
-        /// Semantics of the grouping.
-        semantics: Required<Semantics> = "semantics",
-    ],
-    children: [
-        /// List of contents that should be grouped with each other.
-        contents: Vec<Content> = ("content", JINGLE_GROUPING) => Content
-    ]
-);
+/// A semantic group of contents.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_GROUPING, name = "group")]
+pub struct Group {
+    /// Semantics of the grouping.
+    #[xml(attribute = "semantics")]
+    pub semantics: Semantics,
+
+    /// List of contents that should be grouped with each other.
+    #[xml(child = "content")]
+    pub contents: Vec<Content>,
+}
 
 #[cfg(test)]
 mod tests {
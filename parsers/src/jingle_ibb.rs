@@ -5,21 +5,26 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::ibb::{Stanza, StreamId};
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
 /// Describes an [In-Band Bytestream](https://xmpp.org/extensions/xep-0047.html)
 /// Jingle transport, see also the [IBB module](../ibb.rs).
-Transport, "transport", JINGLE_IBB,
-attributes: [
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_IBB, name = "transport")]
+pub struct Transport {
     /// Maximum size in bytes for each chunk.
-    block_size: Required<u16> = "block-size",
+    #[xml(attribute = "block-size")]
+    pub block_size: u16,
 
     /// The identifier to be used to create a stream.
-    sid: Required<StreamId> = "sid",
+    #[xml(attribute = "sid")]
+    pub sid: StreamId,
 
     /// Which stanza type to use to exchange data.
-    stanza: Default<Stanza> = "stanza",
-]);
+    #[xml(attribute = "stanza", default)]
+    pub stanza: Stanza,
+}
 
 #[cfg(test)]
 mod tests {
@@ -58,11 +63,15 @@ mod tests {
             .parse()
             .unwrap();
         let error = Transport::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'block-size' missing.");
+        assert_eq!(attribute, "block-size");
 
         let elem: Element =
             "<transport xmlns='urn:xmpp:jingle:transports:ibb:1' block-size='65536'/>"
@@ -70,7 +79,12 @@ mod tests {
                 .unwrap();
         let error = Transport::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(
@@ -83,7 +97,12 @@ mod tests {
             .unwrap();
         let error = Transport::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message.to_string(), "invalid digit found in string");
@@ -93,11 +112,15 @@ mod tests {
                 .parse()
                 .unwrap();
         let error = Transport::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'sid' missing.");
+        assert_eq!(attribute, "sid");
     }
 
     #[test]
@@ -105,7 +128,12 @@ mod tests {
         let elem: Element = "<transport xmlns='urn:xmpp:jingle:transports:ibb:1' block-size='128' sid='coucou' stanza='fdsq'/>".parse().unwrap();
         let error = Transport::try_from(elem).unwrap_err();
         let message = match error {
-            Error::ParseError(string) => string,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseError(string) => string,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(message, "Unknown value for 'stanza' attribute.");
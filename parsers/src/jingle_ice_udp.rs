@@ -6,26 +6,29 @@
 
 use crate::jingle_dtls_srtp::Fingerprint;
 use std::net::IpAddr;
-
-generate_element!(
-    /// Wrapper element for an ICE-UDP transport.
-    #[derive(Default)]
-    Transport, "transport", JINGLE_ICE_UDP,
-    attributes: [
-        /// A Password as defined in ICE-CORE.
-        pwd: Option<String> = "pwd",
-
-        /// A User Fragment as defined in ICE-CORE.
-        ufrag: Option<String> = "ufrag",
-    ],
-    children: [
-        /// List of candidates for this ICE-UDP session.
-        candidates: Vec<Candidate> = ("candidate", JINGLE_ICE_UDP) => Candidate,
-
-        /// Fingerprint of the key used for the DTLS handshake.
-        fingerprint: Option<Fingerprint> = ("fingerprint", JINGLE_DTLS) => Fingerprint
-    ]
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Wrapper element for an ICE-UDP transport.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_ICE_UDP, name = "transport")]
+pub struct Transport {
+    /// A Password as defined in ICE-CORE.
+    #[xml(attribute = "pwd")]
+    pub pwd: Option<String>,
+
+    /// A User Fragment as defined in ICE-CORE.
+    #[xml(attribute = "ufrag")]
+    pub ufrag: Option<String>,
+
+    /// List of candidates for this ICE-UDP session.
+    #[xml(child = "candidate")]
+    pub candidates: Vec<Candidate>,
+
+    /// Fingerprint of the key used for the DTLS handshake.
+    #[xml(child = "fingerprint", namespace = JINGLE_DTLS)]
+    pub fingerprint: Option<Fingerprint>,
+}
 
 impl Transport {
     /// Create a new ICE-UDP transport.
@@ -46,67 +49,83 @@ impl Transport {
     }
 }
 
-generate_attribute!(
-    /// A Candidate Type as defined in ICE-CORE.
-    Type, "type", {
-        /// Host candidate.
-        Host => "host",
-
-        /// Peer reflexive candidate.
-        Prflx => "prflx",
-
-        /// Relayed candidate.
-        Relay => "relay",
-
-        /// Server reflexive candidate.
-        Srflx => "srflx",
-    }
-);
-
-generate_element!(
-    /// A candidate for an ICE-UDP session.
-    Candidate, "candidate", JINGLE_ICE_UDP,
-    attributes: [
-        /// A Component ID as defined in ICE-CORE.
-        component: Required<u8> = "component",
-
-        /// A Foundation as defined in ICE-CORE.
-        foundation: Required<String> = "foundation",
-
-        /// An index, starting at 0, that enables the parties to keep track of updates to the
-        /// candidate throughout the life of the session.
-        generation: Required<u8> = "generation",
-
-        /// A unique identifier for the candidate.
-        id: Required<String> = "id",
-
-        /// The Internet Protocol (IP) address for the candidate transport mechanism; this can be
-        /// either an IPv4 address or an IPv6 address.
-        ip: Required<IpAddr> = "ip",
-
-        /// The port at the candidate IP address.
-        port: Required<u16> = "port",
-
-        /// A Priority as defined in ICE-CORE.
-        priority: Required<u32> = "priority",
-
-        /// The protocol to be used. The only value defined by this specification is "udp".
-        protocol: Required<String> = "protocol",
-
-        /// A related address as defined in ICE-CORE.
-        rel_addr: Option<IpAddr> = "rel-addr",
-
-        /// A related port as defined in ICE-CORE.
-        rel_port: Option<u16> = "rel-port",
+/// A Candidate Type as defined in ICE-CORE.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "type")]
+pub enum Type {
+    /// Host candidate.
+    #[xml(value = "host")]
+    Host,
+
+    /// Peer reflexive candidate.
+    #[xml(value = "prflx")]
+    Prflx,
+
+    /// Relayed candidate.
+    #[xml(value = "relay")]
+    Relay,
+
+    /// Server reflexive candidate.
+    #[xml(value = "srflx")]
+    Srflx,
+}
 
-        /// An index, starting at 0, referencing which network this candidate is on for a given
-        /// peer.
-        network: Option<u8> = "network",
+/// A candidate for an ICE-UDP session.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_ICE_UDP, name = "candidate")]
+pub struct Candidate {
+    /// A Component ID as defined in ICE-CORE.
+    #[xml(attribute = "component")]
+    pub component: u8,
+
+    /// A Foundation as defined in ICE-CORE.
+    #[xml(attribute = "foundation")]
+    pub foundation: String,
+
+    /// An index, starting at 0, that enables the parties to keep track of updates to the
+    /// candidate throughout the life of the session.
+    #[xml(attribute = "generation")]
+    pub generation: u8,
+
+    /// A unique identifier for the candidate.
+    #[xml(attribute = "id")]
+    pub id: String,
+
+    /// The Internet Protocol (IP) address for the candidate transport mechanism; this can be
+    /// either an IPv4 address or an IPv6 address.
+    #[xml(attribute = "ip")]
+    pub ip: IpAddr,
+
+    /// The port at the candidate IP address.
+    #[xml(attribute = "port")]
+    pub port: u16,
+
+    /// A Priority as defined in ICE-CORE.
+    #[xml(attribute = "priority")]
+    pub priority: u32,
+
+    /// The protocol to be used. The only value defined by this specification is "udp".
+    #[xml(attribute = "protocol")]
+    pub protocol: String,
+
+    /// A related address as defined in ICE-CORE.
+    #[xml(attribute = "rel-addr")]
+    pub rel_addr: Option<IpAddr>,
+
+    /// A related port as defined in ICE-CORE.
+    #[xml(attribute = "rel-port")]
+    pub rel_port: Option<u16>,
+
+    /// An index, starting at 0, referencing which network this candidate is on for a given
+    /// peer.
+    #[xml(attribute = "network")]
+    pub network: Option<u8>,
 
-        /// A Candidate Type as defined in ICE-CORE.
-        type_: Required<Type> = "type",
-    ]
-);
+    /// A Candidate Type as defined in ICE-CORE.
+    #[xml(attribute = "type")]
+    pub type_: Type,
+}
 
 #[cfg(test)]
 mod tests {
@@ -13,6 +13,7 @@ use std::convert::TryFrom;
 /// Defines a protocol for broadcasting Jingle requests to all of the clients
 /// of a user.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JingleMI {
     /// Indicates we want to start a Jingle session.
     Propose {
@@ -39,7 +40,7 @@ pub enum JingleMI {
 
 fn get_sid(elem: Element) -> Result<SessionId, Error> {
     check_no_unknown_attributes!(elem, "Jingle message", ["id"]);
-    Ok(SessionId(get_attr!(elem, "id", Required)))
+    Ok(SessionId(elem.required_attr("id")?))
 }
 
 fn check_empty_and_get_sid(elem: Element) -> Result<SessionId, Error> {
@@ -6,16 +6,17 @@
 
 use crate::jingle_ice_udp::Type;
 use std::net::IpAddr;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Wrapper element for an raw UDP transport.
-    #[derive(Default)]
-    Transport, "transport", JINGLE_RAW_UDP,
-    children: [
-        /// List of candidates for this raw UDP session.
-        candidates: Vec<Candidate> = ("candidate", JINGLE_RAW_UDP) => Candidate
-    ]
-);
+/// Wrapper element for an raw UDP transport.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RAW_UDP, name = "transport")]
+pub struct Transport {
+    /// List of candidates for this raw UDP session.
+    #[xml(child = "candidate")]
+    pub candidates: Vec<Candidate>,
+}
 
 impl Transport {
     /// Create a new ICE-UDP transport.
@@ -30,31 +31,37 @@ impl Transport {
     }
 }
 
-generate_element!(
-    /// A candidate for an ICE-UDP session.
-    Candidate, "candidate", JINGLE_RAW_UDP,
-    attributes: [
-        /// A Component ID as defined in ICE-CORE.
-        component: Required<u8> = "component",
+/// A candidate for an ICE-UDP session.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RAW_UDP, name = "candidate")]
+pub struct Candidate {
+    /// A Component ID as defined in ICE-CORE.
+    #[xml(attribute = "component")]
+    pub component: u8,
 
-        /// An index, starting at 0, that enables the parties to keep track of updates to the
-        /// candidate throughout the life of the session.
-        generation: Required<u8> = "generation",
+    /// An index, starting at 0, that enables the parties to keep track of updates to the
+    /// candidate throughout the life of the session.
+    #[xml(attribute = "generation")]
+    pub generation: u8,
 
-        /// A unique identifier for the candidate.
-        id: Required<String> = "id",
+    /// A unique identifier for the candidate.
+    #[xml(attribute = "id")]
+    pub id: String,
 
-        /// The Internet Protocol (IP) address for the candidate transport mechanism; this can be
-        /// either an IPv4 address or an IPv6 address.
-        ip: Required<IpAddr> = "ip",
+    /// The Internet Protocol (IP) address for the candidate transport mechanism; this can be
+    /// either an IPv4 address or an IPv6 address.
+    #[xml(attribute = "ip")]
+    pub ip: IpAddr,
 
-        /// The port at the candidate IP address.
-        port: Required<u16> = "port",
+    /// The port at the candidate IP address.
+    #[xml(attribute = "port")]
+    pub port: u16,
 
-        /// A Candidate Type as defined in ICE-CORE.
-        type_: Option<Type> = "type",
-    ]
-);
+    /// A Candidate Type as defined in ICE-CORE.
+    #[xml(attribute = "type")]
+    pub type_: Option<Type>,
+}
 
 #[cfg(test)]
 mod tests {
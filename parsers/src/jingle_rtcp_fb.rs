@@ -4,17 +4,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-generate_element!(
-    /// Wrapper element for a rtcp-fb.
-    RtcpFb, "rtcp-fb", JINGLE_RTCP_FB,
-    attributes: [
-        /// Type of this rtcp-fb.
-        type_: Required<String> = "type",
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-        /// Subtype of this rtcp-fb, if relevant.
-        subtype: Option<String> = "subtype",
-    ]
-);
+/// Wrapper element for a rtcp-fb.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RTCP_FB, name = "rtcp-fb")]
+pub struct RtcpFb {
+    /// Type of this rtcp-fb.
+    #[xml(attribute = "type")]
+    pub type_: String,
+
+    /// Subtype of this rtcp-fb, if relevant.
+    #[xml(attribute = "subtype")]
+    pub subtype: Option<String>,
+}
 
 #[cfg(test)]
 mod tests {
@@ -7,47 +7,52 @@
 use crate::jingle_rtcp_fb::RtcpFb;
 use crate::jingle_rtp_hdrext::RtpHdrext;
 use crate::jingle_ssma::{Group, Source};
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Specifies the ability to multiplex RTP Data and Control Packets on a single port as
+/// described in RFC 5761.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RTP, name = "rtcp-mux")]
+pub struct RtcpMux;
+
+/// Wrapper element describing an RTP session.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RTP, name = "description")]
+pub struct Description {
+    /// Namespace of the encryption scheme used.
+    #[xml(attribute = "media")]
+    pub media: String,
+
+    /// User-friendly name for the encryption scheme, should be `None` for OTR,
+    /// legacy OpenPGP and OX.
+    // XXX: is this a String or an u32?!  Refer to RFC 3550.
+    #[xml(attribute = "ssrc")]
+    pub ssrc: Option<String>,
+
+    /// List of encodings that can be used for this RTP stream.
+    #[xml(child = "payload-type")]
+    pub payload_types: Vec<PayloadType>,
 
-generate_empty_element!(
     /// Specifies the ability to multiplex RTP Data and Control Packets on a single port as
     /// described in RFC 5761.
-    RtcpMux,
-    "rtcp-mux",
-    JINGLE_RTP
-);
-
-generate_element!(
-    /// Wrapper element describing an RTP session.
-    Description, "description", JINGLE_RTP,
-    attributes: [
-        /// Namespace of the encryption scheme used.
-        media: Required<String> = "media",
-
-        /// User-friendly name for the encryption scheme, should be `None` for OTR,
-        /// legacy OpenPGP and OX.
-        // XXX: is this a String or an u32?!  Refer to RFC 3550.
-        ssrc: Option<String> = "ssrc",
-    ],
-    children: [
-        /// List of encodings that can be used for this RTP stream.
-        payload_types: Vec<PayloadType> = ("payload-type", JINGLE_RTP) => PayloadType,
-
-        /// Specifies the ability to multiplex RTP Data and Control Packets on a single port as
-        /// described in RFC 5761.
-        rtcp_mux: Option<RtcpMux> = ("rtcp-mux", JINGLE_RTP) => RtcpMux,
-
-        /// List of ssrc-group.
-        ssrc_groups: Vec<Group> = ("ssrc-group", JINGLE_SSMA) => Group,
-
-        /// List of ssrc.
-        ssrcs: Vec<Source> = ("source", JINGLE_SSMA) => Source,
-
-        /// List of header extensions.
-        hdrexts: Vec<RtpHdrext> = ("rtp-hdrext", JINGLE_RTP_HDREXT) => RtpHdrext
-
-        // TODO: Add support for <encryption/> and <bandwidth/>.
-    ]
-);
+    #[xml(child = "rtcp-mux")]
+    pub rtcp_mux: Option<RtcpMux>,
+
+    /// List of ssrc-group.
+    #[xml(child = "ssrc-group", namespace = JINGLE_SSMA)]
+    pub ssrc_groups: Vec<Group>,
+
+    /// List of ssrc.
+    #[xml(child = "source", namespace = JINGLE_SSMA)]
+    pub ssrcs: Vec<Source>,
+
+    /// List of header extensions.
+    #[xml(child = "rtp-hdrext", namespace = JINGLE_RTP_HDREXT)]
+    pub hdrexts: Vec<RtpHdrext>,
+    // TODO: Add support for <encryption/> and <bandwidth/>.
+}
 
 impl Description {
     /// Create a new RTP description.
@@ -64,46 +69,51 @@ impl Description {
     }
 }
 
-generate_attribute!(
+/// The number of channels.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute, default = 1)]
+pub struct Channels(pub u8);
+
+/// An encoding that can be used for an RTP stream.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RTP, name = "payload-type")]
+pub struct PayloadType {
     /// The number of channels.
-    Channels,
-    "channels",
-    u8,
-    Default = 1
-);
-
-generate_element!(
-    /// An encoding that can be used for an RTP stream.
-    PayloadType, "payload-type", JINGLE_RTP,
-    attributes: [
-        /// The number of channels.
-        channels: Default<Channels> = "channels",
-
-        /// The sampling frequency in Hertz.
-        clockrate: Option<u32> = "clockrate",
-
-        /// The payload identifier.
-        id: Required<u8> = "id",
-
-        /// Maximum packet time as specified in RFC 4566.
-        maxptime: Option<u32> = "maxptime",
-
-        /// The appropriate subtype of the MIME type.
-        name: Option<String> = "name",
-
-        /// Packet time as specified in RFC 4566.
-        ptime: Option<u32> = "ptime",
-    ],
-    children: [
-        /// List of parameters specifying this payload-type.
-        ///
-        /// Their order MUST be ignored.
-        parameters: Vec<Parameter> = ("parameter", JINGLE_RTP) => Parameter,
-
-        /// List of rtcp-fb parameters from XEP-0293.
-        rtcp_fbs: Vec<RtcpFb> = ("rtcp-fb", JINGLE_RTCP_FB) => RtcpFb
-    ]
-);
+    #[xml(attribute = "channels", default)]
+    pub channels: Channels,
+
+    /// The sampling frequency in Hertz.
+    #[xml(attribute = "clockrate")]
+    pub clockrate: Option<u32>,
+
+    /// The payload identifier.
+    #[xml(attribute = "id")]
+    pub id: u8,
+
+    /// Maximum packet time as specified in RFC 4566.
+    #[xml(attribute = "maxptime")]
+    pub maxptime: Option<u32>,
+
+    /// The appropriate subtype of the MIME type.
+    #[xml(attribute = "name")]
+    pub name: Option<String>,
+
+    /// Packet time as specified in RFC 4566.
+    #[xml(attribute = "ptime")]
+    pub ptime: Option<u32>,
+
+    /// List of parameters specifying this payload-type.
+    ///
+    /// Their order MUST be ignored.
+    #[xml(child = "parameter")]
+    pub parameters: Vec<Parameter>,
+
+    /// List of rtcp-fb parameters from XEP-0293.
+    #[xml(child = "rtcp-fb", namespace = JINGLE_RTCP_FB)]
+    pub rtcp_fbs: Vec<RtcpFb>,
+}
 
 impl PayloadType {
     /// Create a new RTP payload-type.
@@ -136,18 +146,20 @@ impl PayloadType {
     }
 }
 
-generate_element!(
-    /// Parameter related to a payload.
-    Parameter, "parameter", JINGLE_RTP,
-    attributes: [
-        /// The name of the parameter, from the list at
-        /// https://www.iana.org/assignments/sdp-parameters/sdp-parameters.xhtml
-        name: Required<String> = "name",
-
-        /// The value of this parameter.
-        value: Required<String> = "value",
-    ]
-);
+/// Parameter related to a payload.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RTP, name = "parameter")]
+pub struct Parameter {
+    /// The name of the parameter, from the list at
+    /// https://www.iana.org/assignments/sdp-parameters/sdp-parameters.xhtml
+    #[xml(attribute = "name")]
+    pub name: String,
+
+    /// The value of this parameter.
+    #[xml(attribute = "value")]
+    pub value: String,
+}
 
 #[cfg(test)]
 mod tests {
@@ -4,36 +4,44 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-generate_attribute!(
-    /// Which party is allowed to send the negotiated RTP Header Extensions.
-    Senders, "senders", {
-        /// Both parties can send them.
-        Both => "both",
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Which party is allowed to send the negotiated RTP Header Extensions.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "senders")]
+pub enum Senders {
+    /// Both parties can send them.
+    #[xml(value = "both", default)]
+    Both,
 
-        /// Only the initiator can send them.
-        Initiator => "initiator",
+    /// Only the initiator can send them.
+    #[xml(value = "initiator")]
+    Initiator,
 
-        /// Only the responder can send them.
-        Responder => "responder",
-    }, Default = Both
-);
+    /// Only the responder can send them.
+    #[xml(value = "responder")]
+    Responder,
+}
 
-generate_element!(
-    /// Header extensions to be used in a RTP description.
-    RtpHdrext, "rtp-hdrext", JINGLE_RTP_HDREXT,
-    attributes: [
-        /// The ID of the extensions.  The allowed values are only in the 1-256, 4096-4351 ranges,
-        /// this isn’t enforced by xmpp-parsers yet!
-        // TODO: make it so.
-        id: Required<u16> = "id",
+/// Header extensions to be used in a RTP description.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_RTP_HDREXT, name = "rtp-hdrext")]
+pub struct RtpHdrext {
+    /// The ID of the extensions.  The allowed values are only in the 1-256, 4096-4351 ranges,
+    /// this isn’t enforced by xmpp-parsers yet!
+    // TODO: make it so.
+    #[xml(attribute = "id")]
+    pub id: u16,
 
-        /// The URI that defines the extension.
-        uri: Required<String> = "uri",
+    /// The URI that defines the extension.
+    #[xml(attribute = "uri")]
+    pub uri: String,
 
-        /// Which party is allowed to send the negotiated RTP Header Extensions.
-        senders: Default<Senders> = "senders",
-    ]
-);
+    /// Which party is allowed to send the negotiated RTP Header Extensions.
+    #[xml(attribute = "senders", default)]
+    pub senders: Senders,
+}
 
 impl RtpHdrext {
     /// Create a new RTP header extension element.
@@ -10,70 +10,85 @@ use crate::Element;
 use jid::Jid;
 use std::convert::TryFrom;
 use std::net::IpAddr;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The type of the connection being proposed by this candidate.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "type")]
+pub enum Type {
+    /// Direct connection using NAT assisting technologies like NAT-PMP or
+    /// UPnP-IGD.
+    #[xml(value = "assisted")]
+    Assisted,
+
+    /// Direct connection using the given interface.
+    #[xml(value = "direct", default)]
+    Direct,
+
+    /// SOCKS5 relay.
+    #[xml(value = "proxy")]
+    Proxy,
+
+    /// Tunnel protocol such as Teredo.
+    #[xml(value = "tunnel")]
+    Tunnel,
+}
+
+/// Which mode to use for the connection.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "mode")]
+pub enum Mode {
+    /// Use TCP, which is the default.
+    #[xml(value = "tcp", default)]
+    Tcp,
+
+    /// Use UDP.
+    #[xml(value = "udp")]
+    Udp,
+}
+
+/// An identifier for a candidate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct CandidateId(pub String);
+
+/// An identifier for a stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct StreamId(pub String);
+
+/// A candidate for a connection.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_S5B, name = "candidate")]
+pub struct Candidate {
+    /// The identifier for this candidate.
+    #[xml(attribute = "cid")]
+    pub cid: CandidateId,
+
+    /// The host to connect to.
+    #[xml(attribute = "host")]
+    pub host: IpAddr,
+
+    /// The JID to request at the given end.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
+    /// The port to connect to.
+    #[xml(attribute = "port")]
+    pub port: Option<u16>,
+
+    /// The priority of this candidate, computed using this formula:
+    /// priority = (2^16)*(type preference) + (local preference)
+    #[xml(attribute = "priority")]
+    pub priority: u32,
 
-generate_attribute!(
     /// The type of the connection being proposed by this candidate.
-    Type, "type", {
-        /// Direct connection using NAT assisting technologies like NAT-PMP or
-        /// UPnP-IGD.
-        Assisted => "assisted",
-
-        /// Direct connection using the given interface.
-        Direct => "direct",
-
-        /// SOCKS5 relay.
-        Proxy => "proxy",
-
-        /// Tunnel protocol such as Teredo.
-        Tunnel => "tunnel",
-    }, Default = Direct
-);
-
-generate_attribute!(
-    /// Which mode to use for the connection.
-    Mode, "mode", {
-        /// Use TCP, which is the default.
-        Tcp => "tcp",
-
-        /// Use UDP.
-        Udp => "udp",
-    }, Default = Tcp
-);
-
-generate_id!(
-    /// An identifier for a candidate.
-    CandidateId
-);
-
-generate_id!(
-    /// An identifier for a stream.
-    StreamId
-);
-
-generate_element!(
-    /// A candidate for a connection.
-    Candidate, "candidate", JINGLE_S5B,
-    attributes: [
-        /// The identifier for this candidate.
-        cid: Required<CandidateId> = "cid",
-
-        /// The host to connect to.
-        host: Required<IpAddr> = "host",
-
-        /// The JID to request at the given end.
-        jid: Required<Jid> = "jid",
-
-        /// The port to connect to.
-        port: Option<u16> = "port",
-
-        /// The priority of this candidate, computed using this formula:
-        /// priority = (2^16)*(type preference) + (local preference)
-        priority: Required<u32> = "priority",
-
-        /// The type of the connection being proposed by this candidate.
-        type_: Default<Type> = "type",
-    ]
-);
+    #[xml(attribute = "type", default)]
+    pub type_: Type,
+}
 
 impl Candidate {
     /// Creates a new candidate with the given parameters.
@@ -103,6 +118,7 @@ impl Candidate {
 
 /// The payload of a transport.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransportPayload {
     /// The responder informs the initiator that the bytestream pointed by this
     /// candidate has been activated.
@@ -127,6 +143,7 @@ pub enum TransportPayload {
 
 /// Describes a Jingle transport using a direct or proxied connection.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transport {
     /// The stream identifier for this transport.
     pub sid: StreamId,
@@ -177,9 +194,9 @@ impl TryFrom<Element> for Transport {
     fn try_from(elem: Element) -> Result<Transport, Error> {
         check_self!(elem, "transport", JINGLE_S5B);
         check_no_unknown_attributes!(elem, "transport", ["sid", "dstaddr", "mode"]);
-        let sid = get_attr!(elem, "sid", Required);
-        let dstaddr = get_attr!(elem, "dstaddr", Option);
-        let mode = get_attr!(elem, "mode", Default);
+        let sid = elem.required_attr("sid")?;
+        let dstaddr = elem.attr_as("dstaddr")?;
+        let mode = elem.attr_as("mode")?.unwrap_or_default();
 
         let mut payload = None;
         for child in elem.children() {
@@ -200,7 +217,7 @@ impl TryFrom<Element> for Transport {
                         "Non-activated child already present in JingleS5B transport element.",
                     ));
                 }
-                let cid = get_attr!(child, "cid", Required);
+                let cid = child.required_attr("cid")?;
                 TransportPayload::Activated(cid)
             } else if child.is("candidate-error", ns::JINGLE_S5B) {
                 if payload.is_some() {
@@ -215,7 +232,7 @@ impl TryFrom<Element> for Transport {
                         "Non-candidate-used child already present in JingleS5B transport element.",
                     ));
                 }
-                let cid = get_attr!(child, "cid", Required);
+                let cid = child.required_attr("cid")?;
                 TransportPayload::CandidateUsed(cid)
             } else if child.is("proxy-error", ns::JINGLE_S5B) {
                 if payload.is_some() {
@@ -4,18 +4,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-generate_element!(
-    /// Source element for the ssrc SDP attribute.
-    Source, "source", JINGLE_SSMA,
-    attributes: [
-        /// Maps to the ssrc-id parameter.
-        id: Required<u32> = "ssrc",
-    ],
-    children: [
-        /// List of attributes for this source.
-        parameters: Vec<Parameter> = ("parameter", JINGLE_SSMA) => Parameter
-    ]
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Source element for the ssrc SDP attribute.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_SSMA, name = "source")]
+pub struct Source {
+    /// Maps to the ssrc-id parameter.
+    #[xml(attribute = "ssrc")]
+    pub id: u32,
+
+    /// List of attributes for this source.
+    #[xml(child = "parameter")]
+    pub parameters: Vec<Parameter>,
+}
 
 impl Source {
     /// Create a new SSMA Source element.
@@ -27,53 +30,62 @@ impl Source {
     }
 }
 
-generate_element!(
-    /// Parameter associated with a ssrc.
-    Parameter, "parameter", JINGLE_SSMA,
-    attributes: [
-        /// The name of the parameter.
-        name: Required<String> = "name",
-
-        /// The optional value of the parameter.
-        value: Option<String> = "value",
-    ]
-);
-
-generate_attribute!(
-    /// From RFC5888, the list of allowed semantics.
-    Semantics, "semantics", {
-        /// Lip Synchronization, defined in RFC5888.
-        Ls => "LS",
-
-        /// Flow Identification, defined in RFC5888.
-        Fid => "FID",
-
-        /// Single Reservation Flow, defined in RFC3524.
-        Srf => "SRF",
-
-        /// Alternative Network Address Types, defined in RFC4091.
-        Anat => "ANAT",
+/// Parameter associated with a ssrc.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_SSMA, name = "parameter")]
+pub struct Parameter {
+    /// The name of the parameter.
+    #[xml(attribute = "name")]
+    pub name: String,
+
+    /// The optional value of the parameter.
+    #[xml(attribute = "value")]
+    pub value: Option<String>,
+}
 
-        /// Forward Error Correction, defined in RFC4756.
-        Fec => "FEC",
+/// From RFC5888, the list of allowed semantics.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "semantics")]
+pub enum Semantics {
+    /// Lip Synchronization, defined in RFC5888.
+    #[xml(value = "LS")]
+    Ls,
+
+    /// Flow Identification, defined in RFC5888.
+    #[xml(value = "FID")]
+    Fid,
+
+    /// Single Reservation Flow, defined in RFC3524.
+    #[xml(value = "SRF")]
+    Srf,
+
+    /// Alternative Network Address Types, defined in RFC4091.
+    #[xml(value = "ANAT")]
+    Anat,
+
+    /// Forward Error Correction, defined in RFC4756.
+    #[xml(value = "FEC")]
+    Fec,
+
+    /// Decoding Dependency, defined in RFC5583.
+    #[xml(value = "DDP")]
+    Ddp,
+}
 
-        /// Decoding Dependency, defined in RFC5583.
-        Ddp => "DDP",
-    }
-);
-
-generate_element!(
-    /// Element grouping multiple ssrc.
-    Group, "ssrc-group", JINGLE_SSMA,
-    attributes: [
-        /// The semantics of this group.
-        semantics: Required<Semantics> = "semantics",
-    ],
-    children: [
-        /// The various ssrc concerned by this group.
-        sources: Vec<Source> = ("source", JINGLE_SSMA) => Source
-    ]
-);
+/// Element grouping multiple ssrc.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLE_SSMA, name = "ssrc-group")]
+pub struct Group {
+    /// The semantics of this group.
+    #[xml(attribute = "semantics")]
+    pub semantics: Semantics,
+
+    /// The various ssrc concerned by this group.
+    #[xml(child = "source")]
+    pub sources: Vec<Source>,
+}
 
 #[cfg(test)]
 mod tests {
@@ -24,7 +24,7 @@
 #![warn(missing_docs)]
 
 pub use crate::util::error::Error;
-pub use jid::{BareJid, FullJid, Jid, JidParseError};
+pub use jid::{BareJid, DomainJid, FullJid, Jid, JidParseError};
 pub use minidom::Element;
 
 /// XML namespace definitions used through XMPP.
@@ -69,6 +69,12 @@ pub mod ibb;
 /// XEP-0048: Bookmarks
 pub mod bookmarks;
 
+/// XEP-0050: Ad-Hoc Commands
+pub mod commands;
+
+/// XEP-0054: vcard-temp
+pub mod vcard;
+
 /// XEP-0059: Result Set Management
 pub mod rsm;
 
@@ -93,6 +99,9 @@ pub mod chatstates;
 /// XEP-0092: Software Version
 pub mod version;
 
+/// XEP-0103: URL Address Information
+pub mod url_data;
+
 /// XEP-0107: User Mood
 pub mod mood;
 
@@ -105,6 +114,9 @@ pub mod caps;
 /// XEP-0118: User Tune
 pub mod tune;
 
+/// XEP-0153: vCard-Based Avatars
+pub mod vcard_update;
+
 /// XEP-0157: Contact Addresses for XMPP Services
 pub mod server_info;
 
@@ -165,9 +177,15 @@ pub mod jingle_s5b;
 /// XEP-0261: Jingle In-Band Bytestreams Transport Method
 pub mod jingle_ibb;
 
+/// XEP-0264: Jingle Content Thumbnails
+pub mod thumbs;
+
 /// XEP-0280: Message Carbons
 pub mod carbons;
 
+/// XEP-0292: vCard4 Over XMPP
+pub mod vcard4;
+
 /// XEP-0293: Jingle RTP Feedback Negotiation
 pub mod jingle_rtcp_fb;
 
@@ -195,6 +213,12 @@ pub mod jingle_dtls_srtp;
 /// XEP-0328: JID Prep
 pub mod jid_prep;
 
+/// XEP-0333: Chat Markers
+pub mod chat_markers;
+
+/// XEP-0334: Message Processing Hints
+pub mod hints;
+
 /// XEP-0338: Jingle Grouping Framework
 pub mod jingle_grouping;
 
@@ -222,14 +246,41 @@ pub mod openpgp;
 /// XEP-0380: Explicit Message Encryption
 pub mod eme;
 
+/// XEP-0384: OMEMO Encryption
+pub mod omemo;
+
 /// XEP-0390: Entity Capabilities 2.0
 pub mod ecaps2;
 
 /// XEP-0402: PEP Native Bookmarks
 pub mod bookmarks2;
 
+/// XEP-0420: Stanza Content Encryption
+pub mod sce;
+
 /// XEP-0421: Anonymous unique occupant identifiers for MUCs
 pub mod occupant_id;
 
+/// XEP-0424: Message Retraction
+pub mod message_retract;
+
+/// XEP-0425: Moderated Message Retraction
+pub mod message_moderate;
+
+/// XEP-0428: Fallback Indication
+pub mod fallback;
+
 /// XEP-0441: Message Archive Management Preferences
 pub mod mam_prefs;
+
+/// XEP-0444: Message Reactions
+pub mod reactions;
+
+/// XEP-0446: File metadata element
+pub mod file_metadata;
+
+/// XEP-0447: Stateless file sharing
+pub mod sfs;
+
+/// XEP-0461: Message Replies
+pub mod reply;
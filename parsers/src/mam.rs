@@ -10,80 +10,94 @@ use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
 use crate::message::MessagePayload;
 use crate::pubsub::NodeName;
 use crate::rsm::{SetQuery, SetResult};
-
-generate_id!(
-    /// An identifier matching a result message to the query requesting it.
-    QueryId
-);
-
-generate_element!(
-    /// Starts a query to the archive.
-    Query, "query", MAM,
-    attributes: [
-        /// An optional identifier for matching forwarded messages to this
-        /// query.
-        queryid: Option<QueryId> = "queryid",
-
-        /// Must be set to Some when querying a PubSub node’s archive.
-        node: Option<NodeName> = "node"
-    ],
-    children: [
-        /// Used for filtering the results.
-        form: Option<DataForm> = ("x", DATA_FORMS) => DataForm,
-
-        /// Used for paging through results.
-        set: Option<SetQuery> = ("set", RSM) => SetQuery
-    ]
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// An identifier matching a result message to the query requesting it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct QueryId(pub String);
+
+/// Starts a query to the archive.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MAM, name = "query")]
+pub struct Query {
+    /// An optional identifier for matching forwarded messages to this
+    /// query.
+    #[xml(attribute = "queryid")]
+    pub queryid: Option<QueryId>,
+
+    /// Must be set to Some when querying a PubSub node’s archive.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+
+    /// Used for filtering the results.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+
+    /// Used for paging through results.
+    #[xml(child = "set", namespace = RSM)]
+    pub set: Option<SetQuery>,
+}
 
 impl IqGetPayload for Query {}
 impl IqSetPayload for Query {}
 impl IqResultPayload for Query {}
 
-generate_element!(
-    /// The wrapper around forwarded stanzas.
-    Result_, "result", MAM,
-    attributes: [
-        /// The stanza-id under which the archive stored this stanza.
-        id: Required<String> = "id",
-
-        /// The same queryid as the one requested in the
-        /// [query](struct.Query.html).
-        queryid: Option<QueryId> = "queryid",
-    ],
-    children: [
-        /// The actual stanza being forwarded.
-        forwarded: Required<Forwarded> = ("forwarded", FORWARD) => Forwarded
-    ]
-);
+/// The wrapper around forwarded stanzas.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MAM, name = "result")]
+pub struct Result_ {
+    /// The stanza-id under which the archive stored this stanza.
+    #[xml(attribute = "id")]
+    pub id: String,
+
+    /// The same queryid as the one requested in the
+    /// [query](struct.Query.html).
+    #[xml(attribute = "queryid")]
+    pub queryid: Option<QueryId>,
+
+    /// The actual stanza being forwarded.
+    #[xml(child = "forwarded", namespace = FORWARD)]
+    pub forwarded: Forwarded,
+}
 
 impl MessagePayload for Result_ {}
 
-generate_attribute!(
+/// True when the end of a MAM query has been reached.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "complete")]
+pub enum Complete {
+    /// True value, represented by either 'true' or '1'.
+    #[xml(value = "true", alias = "1")]
+    True,
+
+    /// False value, represented by either 'false' or '0'.
+    #[xml(value = "false", alias = "0", default)]
+    False,
+}
+
+/// Notes the end of a page in a query.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MAM, name = "fin")]
+pub struct Fin {
     /// True when the end of a MAM query has been reached.
-    Complete,
-    "complete",
-    bool
-);
-
-generate_element!(
-    /// Notes the end of a page in a query.
-    Fin, "fin", MAM,
-    attributes: [
-        /// True when the end of a MAM query has been reached.
-        complete: Default<Complete> = "complete",
-    ],
-    children: [
-        /// Describes the current page, it should contain at least [first]
-        /// (with an [index]) and [last], and generally [count].
-        ///
-        /// [first]: ../rsm/struct.SetResult.html#structfield.first
-        /// [index]: ../rsm/struct.SetResult.html#structfield.first_index
-        /// [last]: ../rsm/struct.SetResult.html#structfield.last
-        /// [count]: ../rsm/struct.SetResult.html#structfield.count
-        set: Required<SetResult> = ("set", RSM) => SetResult
-    ]
-);
+    #[xml(attribute = "complete", default)]
+    pub complete: Complete,
+
+    /// Describes the current page, it should contain at least [first]
+    /// (with an [index]) and [last], and generally [count].
+    ///
+    /// [first]: ../rsm/struct.SetResult.html#structfield.first
+    /// [index]: ../rsm/struct.SetResult.html#structfield.first_index
+    /// [last]: ../rsm/struct.SetResult.html#structfield.last
+    /// [count]: ../rsm/struct.SetResult.html#structfield.count
+    #[xml(child = "set", namespace = RSM)]
+    pub set: SetResult,
+}
 
 impl IqResultPayload for Fin {}
 
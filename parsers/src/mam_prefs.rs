@@ -10,24 +10,29 @@ use crate::util::error::Error;
 use jid::Jid;
 use minidom::{Element, Node};
 use std::convert::TryFrom;
-
-generate_attribute!(
-    /// Notes the default archiving preference for the user.
-    DefaultPrefs, "default", {
-        /// The default is to always log messages in the archive.
-        Always => "always",
-
-        /// The default is to never log messages in the archive.
-        Never => "never",
-
-        /// The default is to log messages in the archive only for contacts
-        /// present in the user’s [roster](../roster/index.html).
-        Roster => "roster",
-    }
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Notes the default archiving preference for the user.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "default")]
+pub enum DefaultPrefs {
+    /// The default is to always log messages in the archive.
+    #[xml(value = "always")]
+    Always,
+
+    /// The default is to never log messages in the archive.
+    #[xml(value = "never")]
+    Never,
+
+    /// The default is to log messages in the archive only for contacts
+    /// present in the user’s [roster](../roster/index.html).
+    #[xml(value = "roster")]
+    Roster,
+}
 
 /// Controls the archiving preferences of the user.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Prefs {
     /// The default preference for JIDs in neither
     /// [always](#structfield.always) or [never](#structfield.never) lists.
@@ -71,7 +76,7 @@ impl TryFrom<Element> for Prefs {
                 return Err(Error::ParseError("Unknown child in prefs element."));
             }
         }
-        let default_ = get_attr!(elem, "default", Required);
+        let default_ = elem.required_attr("default")?;
         Ok(Prefs {
             default_,
             always,
@@ -5,42 +5,46 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::util::helpers::TrimmedPlainText;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Represents an URI used in a media element.
-    URI, "uri", MEDIA_ELEMENT,
-    attributes: [
-        /// The MIME type of the URI referenced.
-        ///
-        /// See the [IANA MIME Media Types Registry][1] for a list of
-        /// registered types, but unregistered or yet-to-be-registered are
-        /// accepted too.
-        ///
-        /// [1]: https://www.iana.org/assignments/media-types/media-types.xhtml
-        type_: Required<String> = "type"
-    ],
-    text: (
-        /// The actual URI contained.
-        uri: TrimmedPlainText<String>
-    )
-);
-
-generate_element!(
-    /// References a media element, to be used in [data
-    /// forms](../data_forms/index.html).
-    MediaElement, "media", MEDIA_ELEMENT,
-    attributes: [
-        /// The recommended display width in pixels.
-        width: Option<usize> = "width",
-
-        /// The recommended display height in pixels.
-        height: Option<usize> = "height"
-    ],
-    children: [
-        /// A list of URIs referencing this media.
-        uris: Vec<URI> = ("uri", MEDIA_ELEMENT) => URI
-    ]
-);
+/// Represents an URI used in a media element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MEDIA_ELEMENT, name = "uri")]
+pub struct URI {
+    /// The MIME type of the URI referenced.
+    ///
+    /// See the [IANA MIME Media Types Registry][1] for a list of
+    /// registered types, but unregistered or yet-to-be-registered are
+    /// accepted too.
+    ///
+    /// [1]: https://www.iana.org/assignments/media-types/media-types.xhtml
+    #[xml(attribute = "type")]
+    pub type_: String,
+
+    /// The actual URI contained.
+    #[xml(text, codec = TrimmedPlainText)]
+    pub uri: String,
+}
+
+/// References a media element, to be used in [data
+/// forms](../data_forms/index.html).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MEDIA_ELEMENT, name = "media")]
+pub struct MediaElement {
+    /// The recommended display width in pixels.
+    #[xml(attribute = "width")]
+    pub width: Option<usize>,
+
+    /// The recommended display height in pixels.
+    #[xml(attribute = "height")]
+    pub height: Option<usize>,
+
+    /// A list of URIs referencing this media.
+    #[xml(child = "uri")]
+    pub uris: Vec<URI>,
+}
 
 #[cfg(test)]
 mod tests {
@@ -99,7 +103,12 @@ mod tests {
             .unwrap();
         let error = MediaElement::try_from(elem).unwrap_err();
         let error = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(error.to_string(), "cannot parse integer from empty string");
@@ -109,7 +118,12 @@ mod tests {
             .unwrap();
         let error = MediaElement::try_from(elem).unwrap_err();
         let error = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(error.to_string(), "invalid digit found in string");
@@ -119,7 +133,12 @@ mod tests {
             .unwrap();
         let error = MediaElement::try_from(elem).unwrap_err();
         let error = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(error.to_string(), "cannot parse integer from empty string");
@@ -129,7 +148,12 @@ mod tests {
             .unwrap();
         let error = MediaElement::try_from(elem).unwrap_err();
         let error = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(error.to_string(), "invalid digit found in string");
@@ -155,11 +179,15 @@ mod tests {
                 .parse()
                 .unwrap();
         let error = MediaElement::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'type' missing.");
+        assert_eq!(attribute, "type");
 
         let elem: Element = "<media xmlns='urn:xmpp:media-element'><uri type='text/html'/></media>"
             .parse()
@@ -4,64 +4,72 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use crate::fallback::Fallback;
 use crate::ns;
+use crate::reply::Reply;
+use crate::stanza_id::{OriginId, StanzaId};
 use crate::util::error::Error;
 use crate::Element;
-use jid::Jid;
+use jid::{BareJid, Jid};
+use minidom::{AsXml, XmlWriter};
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
 /// Should be implemented on every known payload of a `<message/>`.
 pub trait MessagePayload: TryFrom<Element> + Into<Element> {}
 
-generate_attribute!(
-    /// The type of a message.
-    MessageType, "type", {
-        /// Standard instant messaging message.
-        Chat => "chat",
-
-        /// Notifies that an error happened.
-        Error => "error",
-
-        /// Standard group instant messaging message.
-        Groupchat => "groupchat",
-
-        /// Used by servers to notify users when things happen.
-        Headline => "headline",
-
-        /// This is an email-like message, it usually contains a
-        /// [subject](struct.Subject.html).
-        Normal => "normal",
-    }, Default = Normal
-);
+/// The type of a message.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "type")]
+pub enum MessageType {
+    /// Standard instant messaging message.
+    #[xml(value = "chat")]
+    Chat,
+
+    /// Notifies that an error happened.
+    #[xml(value = "error")]
+    Error,
+
+    /// Standard group instant messaging message.
+    #[xml(value = "groupchat")]
+    Groupchat,
+
+    /// Used by servers to notify users when things happen.
+    #[xml(value = "headline")]
+    Headline,
+
+    /// This is an email-like message, it usually contains a
+    /// [subject](struct.Subject.html).
+    #[xml(value = "normal", default)]
+    Normal,
+}
 
 type Lang = String;
 
-generate_elem_id!(
-    /// Represents one `<body/>` element, that is the free form text content of
-    /// a message.
-    Body,
-    "body",
-    DEFAULT_NS
-);
-
-generate_elem_id!(
-    /// Defines the subject of a room, or of an email-like normal message.
-    Subject,
-    "subject",
-    DEFAULT_NS
-);
-
-generate_elem_id!(
-    /// A thread identifier, so that other people can specify to which message
-    /// they are replying.
-    Thread,
-    "thread",
-    DEFAULT_NS
-);
+/// Represents one `<body/>` element, that is the free form text content of
+/// a message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DEFAULT_NS, name = "body")]
+pub struct Body(pub String);
+
+/// Defines the subject of a room, or of an email-like normal message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DEFAULT_NS, name = "subject")]
+pub struct Subject(pub String);
+
+/// A thread identifier, so that other people can specify to which message
+/// they are replying.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = DEFAULT_NS, name = "thread")]
+pub struct Thread(pub String);
 
 /// The main structure representing the `<message/>` stanza.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Message {
     /// The JID emitting this stanza.
     pub from: Option<Jid>,
@@ -147,6 +155,85 @@ impl Message {
     pub fn get_best_subject(&self, preferred_langs: Vec<&str>) -> Option<(Lang, &Subject)> {
         Message::get_best::<Subject>(&self.subjects, preferred_langs)
     }
+
+    /// Returns this body of the message without the parts marked as a
+    /// [fallback](../fallback/index.html) for any of these namespaces, for
+    /// instance the quote of a [reply](../reply/index.html).
+    ///
+    /// The ranges of the fallbacks are counted in Unicode code points, not in
+    /// bytes.
+    pub fn strip_fallbacks(&self, body: &Body, namespaces: &[&str]) -> Body {
+        let fallbacks: Vec<Fallback> = self
+            .payloads
+            .iter()
+            .filter(|payload| payload.is("fallback", ns::FALLBACK))
+            .filter_map(|payload| Fallback::try_from(payload.clone()).ok())
+            .filter(|fallback| namespaces.contains(&fallback.for_.as_str()))
+            .collect();
+        Body(
+            body.0
+                .chars()
+                .enumerate()
+                .filter(|(index, _)| !fallbacks.iter().any(|fallback| fallback.contains(*index)))
+                .map(|(_, c)| c)
+                .collect(),
+        )
+    }
+
+    /// Returns the id other messages use to reference this one: the stanza-id
+    /// stamped by the room in a groupchat, and otherwise its origin-id, or its
+    /// id when it has none.
+    pub fn reference_id(&self) -> Option<String> {
+        let payloads = self.payloads.iter().cloned();
+        if self.type_ == MessageType::Groupchat {
+            let room = Jid::Bare(BareJid::from(self.from.clone()?));
+            payloads
+                .filter(|payload| payload.is("stanza-id", ns::SID))
+                .filter_map(|payload| StanzaId::try_from(payload).ok())
+                .find(|stanza_id| stanza_id.by == room)
+                .map(|stanza_id| stanza_id.id)
+        } else {
+            payloads
+                .filter(|payload| payload.is("origin-id", ns::SID))
+                .filter_map(|payload| OriginId::try_from(payload).ok())
+                .map(|origin_id| origin_id.id)
+                .next()
+                .or_else(|| self.id.clone())
+        }
+    }
+
+    /// Creates a reply to this received message, whose body is the quote of
+    /// its best body followed by this text, the quote being marked as a
+    /// fallback for clients not supporting replies.
+    ///
+    /// Returns `None` when this message can’t be referenced, see
+    /// [Reply](../reply/struct.Reply.html).
+    pub fn reply<S: Into<String>>(&self, text: S) -> Option<Message> {
+        let from = self.from.clone()?;
+        let id = self.reference_id()?;
+        let to = match self.type_ {
+            MessageType::Groupchat => Jid::Bare(BareJid::from(from.clone())),
+            _ => from.clone(),
+        };
+        let mut reply = Message::new(to);
+        reply.type_ = self.type_.clone();
+        let quote: String = match self.get_best_body(vec![]) {
+            Some((_, body)) => self
+                .strip_fallbacks(body, &[ns::REPLY])
+                .0
+                .lines()
+                .map(|line| format!("> {}\n", line))
+                .collect(),
+            None => String::new(),
+        };
+        reply.payloads.push(Reply { to: Some(from), id }.into());
+        if !quote.is_empty() {
+            let end = quote.chars().count();
+            reply.payloads.push(Fallback::new(ns::REPLY, 0, end).into());
+        }
+        reply.bodies.insert(Lang::new(), Body(quote + &text.into()));
+        Some(reply)
+    }
 }
 
 impl TryFrom<Element> for Message {
@@ -154,10 +241,10 @@ impl TryFrom<Element> for Message {
 
     fn try_from(root: Element) -> Result<Message, Error> {
         check_self!(root, "message", DEFAULT_NS);
-        let from = get_attr!(root, "from", Option);
-        let to = get_attr!(root, "to", Option);
-        let id = get_attr!(root, "id", Option);
-        let type_ = get_attr!(root, "type", Default);
+        let from = root.attr_as("from")?;
+        let to = root.attr_as("to")?;
+        let id = root.attr_as("id")?;
+        let type_ = root.attr_as("type")?.unwrap_or_default();
         let mut bodies = BTreeMap::new();
         let mut subjects = BTreeMap::new();
         let mut thread = None;
@@ -165,7 +252,7 @@ impl TryFrom<Element> for Message {
         for elem in root.children() {
             if elem.is("body", ns::DEFAULT_NS) {
                 check_no_children!(elem, "body");
-                let lang = get_attr!(elem, "xml:lang", Default);
+                let lang = elem.attr_as("xml:lang")?.unwrap_or_default();
                 let body = Body(elem.text());
                 if bodies.insert(lang, body).is_some() {
                     return Err(Error::ParseError(
@@ -174,7 +261,7 @@ impl TryFrom<Element> for Message {
                 }
             } else if elem.is("subject", ns::DEFAULT_NS) {
                 check_no_children!(elem, "subject");
-                let lang = get_attr!(elem, "xml:lang", Default);
+                let lang = elem.attr_as("xml:lang")?.unwrap_or_default();
                 let subject = Subject(elem.text());
                 if subjects.insert(lang, subject).is_some() {
                     return Err(Error::ParseError(
@@ -233,15 +320,61 @@ impl From<Message> for Element {
                 );
                 body
             }))
+            .append_all(message.thread)
             .append_all(message.payloads.into_iter())
             .build()
     }
 }
 
+impl AsXml for Message {
+    fn write_xml(&self, writer: &mut XmlWriter) -> minidom::Result<()> {
+        writer.start("message", ns::DEFAULT_NS);
+        if let Some(ref from) = self.from {
+            writer.attr("from", from);
+        }
+        if let Some(ref id) = self.id {
+            writer.attr("id", id);
+        }
+        if let Some(ref to) = self.to {
+            writer.attr("to", to);
+        }
+        if let Some(type_) = self.type_.as_attribute_value() {
+            writer.attr("type", type_);
+        }
+        write_localised(writer, "subject", &self.subjects, |subject| &subject.0);
+        write_localised(writer, "body", &self.bodies, |body| &body.0);
+        if let Some(ref thread) = self.thread {
+            writer.write(thread)?;
+        }
+        for payload in &self.payloads {
+            writer.write(payload)?;
+        }
+        writer.end();
+        Ok(())
+    }
+}
+
+/// Writes one element per language, with its xml:lang attribute unless it is the default one.
+fn write_localised<T>(
+    writer: &mut XmlWriter,
+    name: &'static str,
+    map: &BTreeMap<Lang, T>,
+    text: impl Fn(&T) -> &str,
+) {
+    for (lang, value) in map {
+        writer.start(name, ns::DEFAULT_NS);
+        if !lang.is_empty() {
+            writer.attr("xml:lang", lang);
+        }
+        writer.text(text(value));
+        writer.end();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use jid::BareJid;
+    use jid::FullJid;
     use std::str::FromStr;
 
     #[cfg(target_pointer_width = "32")]
@@ -404,4 +537,126 @@ mod tests {
         let elem2 = message.into();
         assert_eq!(elem1, elem2);
     }
+
+    #[test]
+    fn test_thread() {
+        #[cfg(not(feature = "component"))]
+        let elem: Element = "<message xmlns='jabber:client' type='chat'><body>Hello world!</body><thread>coucou</thread></message>".parse().unwrap();
+        #[cfg(feature = "component")]
+        let elem: Element = "<message xmlns='jabber:component:accept' type='chat'><body>Hello world!</body><thread>coucou</thread></message>".parse().unwrap();
+        let elem1 = elem.clone();
+        let message = Message::try_from(elem).unwrap();
+        assert_eq!(message.thread, Some(Thread(String::from("coucou"))));
+        let elem2 = message.into();
+        assert_eq!(elem1, elem2);
+    }
+
+    #[test]
+    fn test_as_xml() {
+        let mut message = Message::new(Jid::Bare(BareJid::new("coucou", "example.org")));
+        message.id = Some(String::from("<&>"));
+        message
+            .bodies
+            .insert(String::from(""), Body::from_str("Hello world!").unwrap());
+        message.bodies.insert(
+            String::from("fr"),
+            Body::from_str("Salut « le » monde !").unwrap(),
+        );
+        message
+            .subjects
+            .insert(String::from(""), Subject::from_str("'\"'").unwrap());
+        message.thread = Some(Thread(String::from("coucou")));
+        message.payloads.push(
+            Element::builder("attention", ns::ATTENTION)
+                .append(Element::builder("child", ns::ATTENTION))
+                .build(),
+        );
+        let bytes = message.to_bytes().unwrap();
+        let elem = Element::from(message);
+        assert_eq!(&bytes[..], String::from(&elem).as_bytes());
+
+        let message = Message::new(None);
+        let bytes = message.to_bytes().unwrap();
+        let elem = Element::from(message);
+        assert_eq!(&bytes[..], String::from(&elem).as_bytes());
+    }
+
+    #[test]
+    fn test_strip_fallbacks() {
+        let elem: Element =
+            "<message xmlns='jabber:client' to='anna@example.com' id='message-id2' type='chat'>
+            <body>> Anna wrote:
+> We should bake a cake 🎂
+Great idea!</body>
+            <reply to='anna@example.com/tablet' id='message-id1' xmlns='urn:xmpp:reply:0'/>
+            <fallback xmlns='urn:xmpp:fallback:0' for='urn:xmpp:reply:0'>
+                <body start='0' end='40'/>
+            </fallback>
+        </message>"
+                .parse()
+                .unwrap();
+        let message = Message::try_from(elem).unwrap();
+        let body = &message.bodies[""];
+        assert_eq!(message.strip_fallbacks(body, &[ns::REPLY]).0, "Great idea!");
+        assert_eq!(message.strip_fallbacks(body, &[ns::SCE]), *body);
+    }
+
+    #[test]
+    fn test_reply() {
+        let elem: Element = "<message xmlns='jabber:client' from='coven@chat.shakespeare.lit/firstwitch' to='hag66@shakespeare.lit/pda' id='origin' type='groupchat'>
+            <body>Thrice the brinded cat hath mew’d.
+Thrice and once the hedge-pig whined.</body>
+            <stanza-id xmlns='urn:xmpp:sid:0' id='forged' by='hag66@shakespeare.lit'/>
+            <stanza-id xmlns='urn:xmpp:sid:0' id='room-id' by='coven@chat.shakespeare.lit'/>
+        </message>"
+            .parse()
+            .unwrap();
+        let message = Message::try_from(elem).unwrap();
+        let reply = message.reply("Harpier cries.").unwrap();
+        assert_eq!(
+            reply.to,
+            Some(Jid::Bare(BareJid::new("coven", "chat.shakespeare.lit")))
+        );
+        assert_eq!(reply.type_, MessageType::Groupchat);
+        let body = &reply.bodies[""];
+        assert_eq!(
+            body.0,
+            "> Thrice the brinded cat hath mew’d.\n> Thrice and once the hedge-pig whined.\nHarpier cries."
+        );
+        assert_eq!(
+            reply.strip_fallbacks(body, &[ns::REPLY]).0,
+            "Harpier cries."
+        );
+        let payload = Reply::try_from(reply.payloads[0].clone()).unwrap();
+        assert_eq!(payload.id, "room-id");
+        assert_eq!(
+            payload.to,
+            Some(Jid::Full(FullJid::new(
+                "coven",
+                "chat.shakespeare.lit",
+                "firstwitch"
+            )))
+        );
+
+        // A reply to a reply doesn’t quote the first quote.
+        let elem: Element = Element::from(reply);
+        let mut message = Message::try_from(elem).unwrap();
+        message.from = Some(Jid::Full(FullJid::new("juliet", "capulet.lit", "balcony")));
+        message.type_ = MessageType::Chat;
+        message.id = Some(String::from("message-id"));
+        let reply = message.reply("Cool").unwrap();
+        assert_eq!(reply.bodies[""].0, "> Harpier cries.\nCool");
+        let payload = Reply::try_from(reply.payloads[0].clone()).unwrap();
+        assert_eq!(payload.id, "message-id");
+    }
+
+    #[test]
+    fn test_reply_unreferenced() {
+        let mut message = Message::new(None);
+        message.from = Some(Jid::Bare(BareJid::new("juliet", "capulet.lit")));
+        assert!(message.reply("Hi").is_none());
+        message.type_ = MessageType::Groupchat;
+        message.id = Some(String::from("id"));
+        assert!(message.reply("Hi").is_none());
+    }
 }
@@ -5,16 +5,18 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Defines that the message containing this payload should replace a
-    /// previous message, identified by the id.
-    Replace, "replace", MESSAGE_CORRECT,
-    attributes: [
-        /// The 'id' attribute of the message getting corrected.
-        id: Required<String> = "id",
-    ]
-);
+/// Defines that the message containing this payload should replace a
+/// previous message, identified by the id.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MESSAGE_CORRECT, name = "replace")]
+pub struct Replace {
+    /// The 'id' attribute of the message getting corrected.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
 
 impl MessagePayload for Replace {}
 
@@ -78,11 +80,15 @@ mod tests {
             .parse()
             .unwrap();
         let error = Replace::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'id' missing.");
+        assert_eq!(attribute, "id");
     }
 
     #[test]
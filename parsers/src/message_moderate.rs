@@ -0,0 +1,113 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::iq::IqSetPayload;
+use crate::occupant_id::OccupantId;
+use jid::Jid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Asks a room to retract a message from another occupant, sent by a moderator.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MESSAGE_MODERATE, name = "moderate")]
+pub struct Moderate {
+    /// The stanza-id the room stamped on the message to retract.
+    #[xml(attribute = "id")]
+    pub id: String,
+
+    /// Whether to retract this message, the only action defined so far.
+    #[xml(flag = "retract", namespace = MESSAGE_RETRACT)]
+    pub retract: bool,
+
+    /// Why this message is getting retracted, which will be shown to the occupants.
+    #[xml(child = "reason")]
+    pub reason: Option<String>,
+}
+
+impl Moderate {
+    /// Creates a request to retract the message of this stanza-id.
+    pub fn retract<I: Into<String>>(id: I, reason: Option<String>) -> Moderate {
+        Moderate {
+            id: id.into(),
+            retract: true,
+            reason,
+        }
+    }
+}
+
+impl IqSetPayload for Moderate {}
+
+/// Marks a [retraction](../message_retract/struct.Retract.html) or a
+/// [tombstone](../message_retract/struct.Retracted.html) as done by a moderator.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MESSAGE_MODERATE, name = "moderated")]
+pub struct Moderated {
+    /// The occupant JID of the moderator.
+    #[xml(attribute = "by")]
+    pub by: Option<Jid>,
+
+    /// The occupant id of the moderator, when the room supports them.
+    #[xml(child = "occupant-id", namespace = OID)]
+    pub occupant_id: Option<OccupantId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iq::Iq;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Moderate, 56);
+        assert_size!(Moderated, 64);
+    }
+
+    #[test]
+    fn test_moderate() {
+        let elem: Element = "<moderate xmlns='urn:xmpp:message-moderate:1' id='stanza-id-1'>
+            <retract xmlns='urn:xmpp:message-retract:1'/>
+            <reason>This message contains inappropriate content for this forum</reason>
+        </moderate>"
+            .parse()
+            .unwrap();
+        let moderate = Moderate::try_from(elem).unwrap();
+        assert_eq!(
+            moderate,
+            Moderate::retract(
+                "stanza-id-1",
+                Some(String::from(
+                    "This message contains inappropriate content for this forum"
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn test_serialise() {
+        let iq = Iq::from_set("retract-request-1", Moderate::retract("stanza-id-1", None));
+        let elem: Element = iq.into();
+        let expected: Element = "<iq xmlns='jabber:client' type='set' id='retract-request-1'><moderate xmlns='urn:xmpp:message-moderate:1' id='stanza-id-1'><retract xmlns='urn:xmpp:message-retract:1'/></moderate></iq>"
+            .parse()
+            .unwrap();
+        assert_eq!(elem, expected);
+    }
+
+    #[test]
+    fn test_invalid_child() {
+        let elem: Element = "<moderated xmlns='urn:xmpp:message-moderate:1'><coucou/></moderated>"
+            .parse()
+            .unwrap();
+        let error = Moderated::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown child in moderated element.");
+    }
+}
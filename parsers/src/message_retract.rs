@@ -0,0 +1,157 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::date::DateTime;
+use crate::message::MessagePayload;
+use crate::message_moderate::Moderated;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Retracts a previous message, identified by its
+/// [reference id](../message/struct.Message.html#method.reference_id).
+///
+/// A retraction sent by a room on behalf of a moderator is marked as moderated.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MESSAGE_RETRACT, name = "retract")]
+pub struct Retract {
+    /// The id of the message getting retracted.
+    #[xml(attribute = "id")]
+    pub id: String,
+
+    /// The moderator who retracted this message, if it wasn’t its author.
+    #[xml(child = "moderated", namespace = MESSAGE_MODERATE)]
+    pub moderated: Option<Moderated>,
+
+    /// Why the moderator retracted this message.
+    #[xml(child = "reason")]
+    pub reason: Option<String>,
+}
+
+impl Retract {
+    /// Creates the retraction of the message of this id, by its author.
+    pub fn new<I: Into<String>>(id: I) -> Retract {
+        Retract {
+            id: id.into(),
+            moderated: None,
+            reason: None,
+        }
+    }
+}
+
+impl MessagePayload for Retract {}
+
+/// The tombstone replacing a retracted message, for instance in an archive.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MESSAGE_RETRACT, name = "retracted")]
+pub struct Retracted {
+    /// When this message got retracted.
+    #[xml(attribute = "stamp")]
+    pub stamp: DateTime,
+
+    /// The moderator who retracted this message, if it wasn’t its author.
+    #[xml(child = "moderated", namespace = MESSAGE_MODERATE)]
+    pub moderated: Option<Moderated>,
+
+    /// Why the moderator retracted this message.
+    #[xml(child = "reason")]
+    pub reason: Option<String>,
+}
+
+impl MessagePayload for Retracted {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mam::Result_;
+    use crate::util::error::Error;
+    use crate::Element;
+    use jid::Jid;
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Retract, 112);
+        assert_size!(Retracted, 104);
+    }
+
+    #[test]
+    fn test_retract() {
+        let elem: Element = "<retract xmlns='urn:xmpp:message-retract:1' id='origin-id-1'/>"
+            .parse()
+            .unwrap();
+        let retract = Retract::try_from(elem).unwrap();
+        assert_eq!(retract, Retract::new("origin-id-1"));
+    }
+
+    #[test]
+    fn test_moderated() {
+        let elem: Element = "<retract xmlns='urn:xmpp:message-retract:1' id='stanza-id-1'>
+            <moderated xmlns='urn:xmpp:message-moderate:1' by='room@muc.example.com/macbeth'>
+                <occupant-id xmlns='urn:xmpp:occupant-id:0' id='dd72603deec90a38ba552f7c68cbcc61bca202cd'/>
+            </moderated>
+            <reason>This message contains inappropriate content for this forum</reason>
+        </retract>"
+            .parse()
+            .unwrap();
+        let retract = Retract::try_from(elem).unwrap();
+        assert_eq!(retract.id, "stanza-id-1");
+        let moderated = retract.moderated.unwrap();
+        assert_eq!(
+            moderated.by.unwrap(),
+            Jid::from_str("room@muc.example.com/macbeth").unwrap()
+        );
+        assert_eq!(
+            moderated.occupant_id.unwrap().id,
+            "dd72603deec90a38ba552f7c68cbcc61bca202cd"
+        );
+        assert_eq!(
+            retract.reason.unwrap(),
+            "This message contains inappropriate content for this forum"
+        );
+    }
+
+    #[test]
+    fn test_mam_tombstone() {
+        let elem: Element = "<result xmlns='urn:xmpp:mam:2' queryid='f27' id='5d398-28273-f7382'>
+            <forwarded xmlns='urn:xmpp:forward:0'>
+                <delay xmlns='urn:xmpp:delay' stamp='2019-09-20T23:08:25Z'/>
+                <message xmlns='jabber:client' type='chat' from='juliet@capulet.net/balcony' to='romeo@montague.lit/orchard' id='origin-id-1'>
+                    <retracted xmlns='urn:xmpp:message-retract:1' stamp='2019-09-20T23:09:32Z'/>
+                    <origin-id xmlns='urn:xmpp:sid:0' id='origin-id-1'/>
+                </message>
+            </forwarded>
+        </result>"
+            .parse()
+            .unwrap();
+        let result = Result_::try_from(elem).unwrap();
+        let message = result.forwarded.stanza.unwrap();
+        assert!(message.bodies.is_empty());
+        let retracted = Retracted::try_from(message.payloads[0].clone()).unwrap();
+        assert_eq!(
+            retracted.stamp,
+            DateTime::from_str("2019-09-20T23:09:32Z").unwrap()
+        );
+        assert_eq!(retracted.moderated, None);
+    }
+
+    #[test]
+    fn test_missing_id() {
+        let elem: Element = "<retract xmlns='urn:xmpp:message-retract:1'/>"
+            .parse()
+            .unwrap();
+        let error = Retract::try_from(elem).unwrap_err();
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
+            _ => panic!(),
+        };
+        assert_eq!(attribute, "id");
+    }
+}
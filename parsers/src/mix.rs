@@ -11,11 +11,13 @@ use crate::iq::{IqResultPayload, IqSetPayload};
 use crate::message::MessagePayload;
 use crate::pubsub::{NodeName, PubSubPayload};
 use jid::BareJid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_id!(
-    /// The identifier a participant receives when joining a channel.
-    ParticipantId
-);
+/// The identifier a participant receives when joining a channel.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct ParticipantId(pub String);
 
 impl ParticipantId {
     /// Create a new ParticipantId.
@@ -24,24 +26,27 @@ impl ParticipantId {
     }
 }
 
-generate_id!(
-    /// A MIX channel identifier.
-    ChannelId
-);
-
-generate_element!(
-    /// Represents a participant in a MIX channel, usually returned on the
-    /// urn:xmpp:mix:nodes:participants PubSub node.
-    Participant, "participant", MIX_CORE,
-    children: [
-        /// The nick of this participant.
-        nick: Required<String> = ("nick", MIX_CORE) => String,
-
-        /// The bare JID of this participant.
-        // TODO: should be a BareJid!
-        jid: Required<String> = ("jid", MIX_CORE) => String
-    ]
-);
+/// A MIX channel identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct ChannelId(pub String);
+
+/// Represents a participant in a MIX channel, usually returned on the
+/// urn:xmpp:mix:nodes:participants PubSub node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "participant")]
+pub struct Participant {
+    /// The nick of this participant.
+    #[xml(child = "nick")]
+    pub nick: String,
+
+    /// The bare JID of this participant.
+    // TODO: should be a BareJid!
+    #[xml(child = "jid")]
+    pub jid: String,
+}
 
 impl PubSubPayload for Participant {}
 
@@ -55,14 +60,15 @@ impl Participant {
     }
 }
 
-generate_element!(
-    /// A node to subscribe to.
-    Subscribe, "subscribe", MIX_CORE,
-    attributes: [
-        /// The PubSub node to subscribe to.
-        node: Required<NodeName> = "node",
-    ]
-);
+/// A node to subscribe to.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "subscribe")]
+pub struct Subscribe {
+    /// The PubSub node to subscribe to.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+}
 
 impl Subscribe {
     /// Create a new Subscribe element.
@@ -73,21 +79,23 @@ impl Subscribe {
     }
 }
 
-generate_element!(
-    /// A request from a user’s server to join a MIX channel.
-    Join, "join", MIX_CORE,
-    attributes: [
-        /// The participant identifier returned by the MIX service on successful join.
-        id: Option<ParticipantId> = "id",
-    ],
-    children: [
-        /// The nick requested by the user or set by the service.
-        nick: Required<String> = ("nick", MIX_CORE) => String,
-
-        /// Which MIX nodes to subscribe to.
-        subscribes: Vec<Subscribe> = ("subscribe", MIX_CORE) => Subscribe
-    ]
-);
+/// A request from a user’s server to join a MIX channel.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "join")]
+pub struct Join {
+    /// The participant identifier returned by the MIX service on successful join.
+    #[xml(attribute = "id")]
+    pub id: Option<ParticipantId>,
+
+    /// The nick requested by the user or set by the service.
+    #[xml(child = "nick")]
+    pub nick: String,
+
+    /// Which MIX nodes to subscribe to.
+    #[xml(child = "subscribe")]
+    pub subscribes: Vec<Subscribe>,
+}
 
 impl IqSetPayload for Join {}
 impl IqResultPayload for Join {}
@@ -110,21 +118,22 @@ impl Join {
     }
 }
 
-generate_element!(
-    /// Update a given subscription.
-    UpdateSubscription, "update-subscription", MIX_CORE,
-    attributes: [
-        /// The JID of the user to be affected.
-        // TODO: why is it not a participant id instead?
-        jid: Option<BareJid> = "jid",
-    ],
-    children: [
-        /// The list of additional nodes to subscribe to.
-        // TODO: what happens when we are already subscribed?  Also, how do we unsubscribe from
-        // just one?
-        subscribes: Vec<Subscribe> = ("subscribe", MIX_CORE) => Subscribe
-    ]
-);
+/// Update a given subscription.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "update-subscription")]
+pub struct UpdateSubscription {
+    /// The JID of the user to be affected.
+    // TODO: why is it not a participant id instead?
+    #[xml(attribute = "jid")]
+    pub jid: Option<BareJid>,
+
+    /// The list of additional nodes to subscribe to.
+    // TODO: what happens when we are already subscribed?  Also, how do we unsubscribe from
+    // just one?
+    #[xml(child = "subscribe")]
+    pub subscribes: Vec<Subscribe>,
+}
 
 impl IqSetPayload for UpdateSubscription {}
 impl IqResultPayload for UpdateSubscription {}
@@ -146,25 +155,25 @@ impl UpdateSubscription {
     }
 }
 
-generate_empty_element!(
-    /// Request to leave a given MIX channel.  It will automatically unsubscribe the user from all
-    /// nodes on this channel.
-    Leave,
-    "leave",
-    MIX_CORE
-);
+/// Request to leave a given MIX channel.  It will automatically unsubscribe the user from all
+/// nodes on this channel.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "leave")]
+pub struct Leave;
 
 impl IqSetPayload for Leave {}
 impl IqResultPayload for Leave {}
 
-generate_element!(
-    /// A request to change the user’s nick.
-    SetNick, "setnick", MIX_CORE,
-    children: [
-        /// The new requested nick.
-        nick: Required<String> = ("nick", MIX_CORE) => String
-    ]
-);
+/// A request to change the user’s nick.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "setnick")]
+pub struct SetNick {
+    /// The new requested nick.
+    #[xml(child = "nick")]
+    pub nick: String,
+}
 
 impl IqSetPayload for SetNick {}
 impl IqResultPayload for SetNick {}
@@ -176,19 +185,21 @@ impl SetNick {
     }
 }
 
-generate_element!(
-    /// Message payload describing who actually sent the message, since unlike in MUC, all messages
-    /// are sent from the channel’s JID.
-    Mix, "mix", MIX_CORE,
-    children: [
-        /// The nick of the user who said something.
-        nick: Required<String> = ("nick", MIX_CORE) => String,
-
-        /// The JID of the user who said something.
-        // TODO: should be a BareJid!
-        jid: Required<String> = ("jid", MIX_CORE) => String
-    ]
-);
+/// Message payload describing who actually sent the message, since unlike in MUC, all messages
+/// are sent from the channel’s JID.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "mix")]
+pub struct Mix {
+    /// The nick of the user who said something.
+    #[xml(child = "nick")]
+    pub nick: String,
+
+    /// The JID of the user who said something.
+    // TODO: should be a BareJid!
+    #[xml(child = "jid")]
+    pub jid: String,
+}
 
 impl MessagePayload for Mix {}
 
@@ -202,15 +213,15 @@ impl Mix {
     }
 }
 
-generate_element!(
-    /// Create a new MIX channel.
-    #[derive(Default)]
-    Create, "create", MIX_CORE,
-    attributes: [
-        /// The requested channel identifier.
-        channel: Option<ChannelId> = "channel",
-    ]
-);
+/// Create a new MIX channel.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "create")]
+pub struct Create {
+    /// The requested channel identifier.
+    #[xml(attribute = "channel")]
+    pub channel: Option<ChannelId>,
+}
 
 impl IqSetPayload for Create {}
 impl IqResultPayload for Create {}
@@ -229,14 +240,15 @@ impl Create {
     }
 }
 
-generate_element!(
-    /// Destroy a given MIX channel.
-    Destroy, "destroy", MIX_CORE,
-    attributes: [
-        /// The channel identifier to be destroyed.
-        channel: Required<ChannelId> = "channel",
-    ]
-);
+/// Destroy a given MIX channel.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MIX_CORE, name = "destroy")]
+pub struct Destroy {
+    /// The channel identifier to be destroyed.
+    #[xml(attribute = "channel")]
+    pub channel: ChannelId,
+}
 
 // TODO: section 7.3.4, example 33, doesn’t mirror the <destroy/> in the iq result unlike every
 // other section so far.
@@ -1,272 +1,356 @@
+use xmpp_parsers_macros::{FromXml, IntoXml};
 // Copyright (c) 2017 Emmanuel Gil Peyrot <linkmauve@linkmauve.fr>
 //
 // This Source Code Form is subject to the terms of the Mozilla Public
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-generate_element_enum!(
-    /// Enum representing all of the possible values of the XEP-0107 moods.
-    MoodEnum, "mood", MOOD, {
-        /// Impressed with fear or apprehension; in fear; apprehensive.
-        Afraid => "afraid",
+/// Enum representing all of the possible values of the XEP-0107 moods.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(namespace = MOOD, name = "mood")]
+pub enum MoodEnum {
+    /// Impressed with fear or apprehension; in fear; apprehensive.
+    #[xml(name = "afraid")]
+    Afraid,
 
-        /// Astonished; confounded with fear, surprise or wonder.
-        Amazed => "amazed",
+    /// Astonished; confounded with fear, surprise or wonder.
+    #[xml(name = "amazed")]
+    Amazed,
 
-        /// Inclined to love; having a propensity to love, or to sexual enjoyment; loving, fond, affectionate, passionate, lustful, sexual, etc.
-        Amorous => "amorous",
+    /// Inclined to love; having a propensity to love, or to sexual enjoyment; loving, fond, affectionate, passionate, lustful, sexual, etc.
+    #[xml(name = "amorous")]
+    Amorous,
 
-        /// Displaying or feeling anger, i.e., a strong feeling of displeasure, hostility or antagonism towards someone or something, usually combined with an urge to harm.
-        Angry => "angry",
+    /// Displaying or feeling anger, i.e., a strong feeling of displeasure, hostility or antagonism towards someone or something, usually combined with an urge to harm.
+    #[xml(name = "angry")]
+    Angry,
 
-        /// To be disturbed or irritated, especially by continued or repeated acts.
-        Annoyed => "annoyed",
+    /// To be disturbed or irritated, especially by continued or repeated acts.
+    #[xml(name = "annoyed")]
+    Annoyed,
 
-        /// Full of anxiety or disquietude; greatly concerned or solicitous, esp. respecting something future or unknown; being in painful suspense.
-        Anxious => "anxious",
+    /// Full of anxiety or disquietude; greatly concerned or solicitous, esp. respecting something future or unknown; being in painful suspense.
+    #[xml(name = "anxious")]
+    Anxious,
 
-        /// To be stimulated in one's feelings, especially to be sexually stimulated.
-        Aroused => "aroused",
+    /// To be stimulated in one's feelings, especially to be sexually stimulated.
+    #[xml(name = "aroused")]
+    Aroused,
 
-        /// Feeling shame or guilt.
-        Ashamed => "ashamed",
+    /// Feeling shame or guilt.
+    #[xml(name = "ashamed")]
+    Ashamed,
 
-        /// Suffering from boredom; uninterested, without attention.
-        Bored => "bored",
+    /// Suffering from boredom; uninterested, without attention.
+    #[xml(name = "bored")]
+    Bored,
 
-        /// Strong in the face of fear; courageous.
-        Brave => "brave",
+    /// Strong in the face of fear; courageous.
+    #[xml(name = "brave")]
+    Brave,
 
-        /// Peaceful, quiet.
-        Calm => "calm",
+    /// Peaceful, quiet.
+    #[xml(name = "calm")]
+    Calm,
 
-        /// Taking care or caution; tentative.
-        Cautious => "cautious",
+    /// Taking care or caution; tentative.
+    #[xml(name = "cautious")]
+    Cautious,
 
-        /// Feeling the sensation of coldness, especially to the point of discomfort.
-        Cold => "cold",
+    /// Feeling the sensation of coldness, especially to the point of discomfort.
+    #[xml(name = "cold")]
+    Cold,
 
-        /// Feeling very sure of or positive about something, especially about one's own capabilities.
-        Confident => "confident",
+    /// Feeling very sure of or positive about something, especially about one's own capabilities.
+    #[xml(name = "confident")]
+    Confident,
 
-        /// Chaotic, jumbled or muddled.
-        Confused => "confused",
+    /// Chaotic, jumbled or muddled.
+    #[xml(name = "confused")]
+    Confused,
 
-        /// Feeling introspective or thoughtful.
-        Contemplative => "contemplative",
+    /// Feeling introspective or thoughtful.
+    #[xml(name = "contemplative")]
+    Contemplative,
 
-        /// Pleased at the satisfaction of a want or desire; satisfied.
-        Contented => "contented",
+    /// Pleased at the satisfaction of a want or desire; satisfied.
+    #[xml(name = "contented")]
+    Contented,
 
-        /// Grouchy, irritable; easily upset.
-        Cranky => "cranky",
+    /// Grouchy, irritable; easily upset.
+    #[xml(name = "cranky")]
+    Cranky,
 
-        /// Feeling out of control; feeling overly excited or enthusiastic.
-        Crazy => "crazy",
+    /// Feeling out of control; feeling overly excited or enthusiastic.
+    #[xml(name = "crazy")]
+    Crazy,
 
-        /// Feeling original, expressive, or imaginative.
-        Creative => "creative",
+    /// Feeling original, expressive, or imaginative.
+    #[xml(name = "creative")]
+    Creative,
 
-        /// Inquisitive; tending to ask questions, investigate, or explore.
-        Curious => "curious",
+    /// Inquisitive; tending to ask questions, investigate, or explore.
+    #[xml(name = "curious")]
+    Curious,
 
-        /// Feeling sad and dispirited.
-        Dejected => "dejected",
+    /// Feeling sad and dispirited.
+    #[xml(name = "dejected")]
+    Dejected,
 
-        /// Severely despondent and unhappy.
-        Depressed => "depressed",
+    /// Severely despondent and unhappy.
+    #[xml(name = "depressed")]
+    Depressed,
 
-        /// Defeated of expectation or hope; let down.
-        Disappointed => "disappointed",
+    /// Defeated of expectation or hope; let down.
+    #[xml(name = "disappointed")]
+    Disappointed,
 
-        /// Filled with disgust; irritated and out of patience.
-        Disgusted => "disgusted",
+    /// Filled with disgust; irritated and out of patience.
+    #[xml(name = "disgusted")]
+    Disgusted,
 
-        /// Feeling a sudden or complete loss of courage in the face of trouble or danger.
-        Dismayed => "dismayed",
+    /// Feeling a sudden or complete loss of courage in the face of trouble or danger.
+    #[xml(name = "dismayed")]
+    Dismayed,
 
-        /// Having one's attention diverted; preoccupied.
-        Distracted => "distracted",
+    /// Having one's attention diverted; preoccupied.
+    #[xml(name = "distracted")]
+    Distracted,
 
-        /// Having a feeling of shameful discomfort.
-        Embarrassed => "embarrassed",
+    /// Having a feeling of shameful discomfort.
+    #[xml(name = "embarrassed")]
+    Embarrassed,
 
-        /// Feeling pain by the excellence or good fortune of another.
-        Envious => "envious",
+    /// Feeling pain by the excellence or good fortune of another.
+    #[xml(name = "envious")]
+    Envious,
 
-        /// Having great enthusiasm.
-        Excited => "excited",
+    /// Having great enthusiasm.
+    #[xml(name = "excited")]
+    Excited,
 
-        /// In the mood for flirting.
-        Flirtatious => "flirtatious",
+    /// In the mood for flirting.
+    #[xml(name = "flirtatious")]
+    Flirtatious,
 
-        /// Suffering from frustration; dissatisfied, agitated, or discontented because one is unable to perform an action or fulfill a desire.
-        Frustrated => "frustrated",
+    /// Suffering from frustration; dissatisfied, agitated, or discontented because one is unable to perform an action or fulfill a desire.
+    #[xml(name = "frustrated")]
+    Frustrated,
 
-        /// Feeling appreciation or thanks.
-        Grateful => "grateful",
+    /// Feeling appreciation or thanks.
+    #[xml(name = "grateful")]
+    Grateful,
 
-        /// Feeling very sad about something, especially something lost; mournful; sorrowful.
-        Grieving => "grieving",
+    /// Feeling very sad about something, especially something lost; mournful; sorrowful.
+    #[xml(name = "grieving")]
+    Grieving,
 
-        /// Unhappy and irritable.
-        Grumpy => "grumpy",
+    /// Unhappy and irritable.
+    #[xml(name = "grumpy")]
+    Grumpy,
 
-        /// Feeling responsible for wrongdoing; feeling blameworthy.
-        Guilty => "guilty",
+    /// Feeling responsible for wrongdoing; feeling blameworthy.
+    #[xml(name = "guilty")]
+    Guilty,
 
-        /// Experiencing the effect of favourable fortune; having the feeling arising from the consciousness of well-being or of enjoyment; enjoying good of any kind, as peace, tranquillity, comfort; contented; joyous.
-        Happy => "happy",
+    /// Experiencing the effect of favourable fortune; having the feeling arising from the consciousness of well-being or of enjoyment; enjoying good of any kind, as peace, tranquillity, comfort; contented; joyous.
+    #[xml(name = "happy")]
+    Happy,
 
-        /// Having a positive feeling, belief, or expectation that something wished for can or will happen.
-        Hopeful => "hopeful",
+    /// Having a positive feeling, belief, or expectation that something wished for can or will happen.
+    #[xml(name = "hopeful")]
+    Hopeful,
 
-        /// Feeling the sensation of heat, especially to the point of discomfort.
-        Hot => "hot",
+    /// Feeling the sensation of heat, especially to the point of discomfort.
+    #[xml(name = "hot")]
+    Hot,
 
-        /// Having or showing a modest or low estimate of one's own importance; feeling lowered in dignity or importance.
-        Humbled => "humbled",
+    /// Having or showing a modest or low estimate of one's own importance; feeling lowered in dignity or importance.
+    #[xml(name = "humbled")]
+    Humbled,
 
-        /// Feeling deprived of dignity or self-respect.
-        Humiliated => "humiliated",
+    /// Feeling deprived of dignity or self-respect.
+    #[xml(name = "humiliated")]
+    Humiliated,
 
-        /// Having a physical need for food.
-        Hungry => "hungry",
+    /// Having a physical need for food.
+    #[xml(name = "hungry")]
+    Hungry,
 
-        /// Wounded, injured, or pained, whether physically or emotionally.
-        Hurt => "hurt",
+    /// Wounded, injured, or pained, whether physically or emotionally.
+    #[xml(name = "hurt")]
+    Hurt,
 
-        /// Favourably affected by something or someone.
-        Impressed => "impressed",
+    /// Favourably affected by something or someone.
+    #[xml(name = "impressed")]
+    Impressed,
 
-        /// Feeling amazement at something or someone; or feeling a combination of fear and reverence.
-        InAwe => "in_awe",
+    /// Feeling amazement at something or someone; or feeling a combination of fear and reverence.
+    #[xml(name = "in_awe")]
+    InAwe,
 
-        /// Feeling strong affection, care, liking, or attraction..
-        InLove => "in_love",
+    /// Feeling strong affection, care, liking, or attraction..
+    #[xml(name = "in_love")]
+    InLove,
 
-        /// Showing anger or indignation, especially at something unjust or wrong.
-        Indignant => "indignant",
+    /// Showing anger or indignation, especially at something unjust or wrong.
+    #[xml(name = "indignant")]
+    Indignant,
 
-        /// Showing great attention to something or someone; having or showing interest.
-        Interested => "interested",
+    /// Showing great attention to something or someone; having or showing interest.
+    #[xml(name = "interested")]
+    Interested,
 
-        /// Under the influence of alcohol; drunk.
-        Intoxicated => "intoxicated",
+    /// Under the influence of alcohol; drunk.
+    #[xml(name = "intoxicated")]
+    Intoxicated,
 
-        /// Feeling as if one cannot be defeated, overcome or denied.
-        Invincible => "invincible",
+    /// Feeling as if one cannot be defeated, overcome or denied.
+    #[xml(name = "invincible")]
+    Invincible,
 
-        /// Fearful of being replaced in position or affection.
-        Jealous => "jealous",
+    /// Fearful of being replaced in position or affection.
+    #[xml(name = "jealous")]
+    Jealous,
 
-        /// Feeling isolated, empty, or abandoned.
-        Lonely => "lonely",
+    /// Feeling isolated, empty, or abandoned.
+    #[xml(name = "lonely")]
+    Lonely,
 
-        /// Unable to find one's way, either physically or emotionally.
-        Lost => "lost",
+    /// Unable to find one's way, either physically or emotionally.
+    #[xml(name = "lost")]
+    Lost,
 
-        /// Feeling as if one will be favored by luck.
-        Lucky => "lucky",
+    /// Feeling as if one will be favored by luck.
+    #[xml(name = "lucky")]
+    Lucky,
 
-        /// Causing or intending to cause intentional harm; bearing ill will towards another; cruel; malicious.
-        Mean => "mean",
+    /// Causing or intending to cause intentional harm; bearing ill will towards another; cruel; malicious.
+    #[xml(name = "mean")]
+    Mean,
 
-        /// Given to sudden or frequent changes of mind or feeling; temperamental.
-        Moody => "moody",
+    /// Given to sudden or frequent changes of mind or feeling; temperamental.
+    #[xml(name = "moody")]
+    Moody,
 
-        /// Easily agitated or alarmed; apprehensive or anxious.
-        Nervous => "nervous",
+    /// Easily agitated or alarmed; apprehensive or anxious.
+    #[xml(name = "nervous")]
+    Nervous,
 
-        /// Not having a strong mood or emotional state.
-        Neutral => "neutral",
+    /// Not having a strong mood or emotional state.
+    #[xml(name = "neutral")]
+    Neutral,
 
-        /// Feeling emotionally hurt, displeased, or insulted.
-        Offended => "offended",
+    /// Feeling emotionally hurt, displeased, or insulted.
+    #[xml(name = "offended")]
+    Offended,
 
-        /// Feeling resentful anger caused by an extremely violent or vicious attack, or by an offensive, immoral, or indecent act.
-        Outraged => "outraged",
+    /// Feeling resentful anger caused by an extremely violent or vicious attack, or by an offensive, immoral, or indecent act.
+    #[xml(name = "outraged")]
+    Outraged,
 
-        /// Interested in play; fun, recreational, unserious, lighthearted; joking, silly.
-        Playful => "playful",
+    /// Interested in play; fun, recreational, unserious, lighthearted; joking, silly.
+    #[xml(name = "playful")]
+    Playful,
 
-        /// Feeling a sense of one's own worth or accomplishment.
-        Proud => "proud",
+    /// Feeling a sense of one's own worth or accomplishment.
+    #[xml(name = "proud")]
+    Proud,
 
-        /// Having an easy-going mood; not stressed; calm.
-        Relaxed => "relaxed",
+    /// Having an easy-going mood; not stressed; calm.
+    #[xml(name = "relaxed")]
+    Relaxed,
 
-        /// Feeling uplifted because of the removal of stress or discomfort.
-        Relieved => "relieved",
+    /// Feeling uplifted because of the removal of stress or discomfort.
+    #[xml(name = "relieved")]
+    Relieved,
 
-        /// Feeling regret or sadness for doing something wrong.
-        Remorseful => "remorseful",
+    /// Feeling regret or sadness for doing something wrong.
+    #[xml(name = "remorseful")]
+    Remorseful,
 
-        /// Without rest; unable to be still or quiet; uneasy; continually moving.
-        Restless => "restless",
+    /// Without rest; unable to be still or quiet; uneasy; continually moving.
+    #[xml(name = "restless")]
+    Restless,
 
-        /// Feeling sorrow; sorrowful, mournful.
-        Sad => "sad",
+    /// Feeling sorrow; sorrowful, mournful.
+    #[xml(name = "sad")]
+    Sad,
 
-        /// Mocking and ironical.
-        Sarcastic => "sarcastic",
+    /// Mocking and ironical.
+    #[xml(name = "sarcastic")]
+    Sarcastic,
 
-        /// Pleased at the fulfillment of a need or desire.
-        Satisfied => "satisfied",
+    /// Pleased at the fulfillment of a need or desire.
+    #[xml(name = "satisfied")]
+    Satisfied,
 
-        /// Without humor or expression of happiness; grave in manner or disposition; earnest; thoughtful; solemn.
-        Serious => "serious",
+    /// Without humor or expression of happiness; grave in manner or disposition; earnest; thoughtful; solemn.
+    #[xml(name = "serious")]
+    Serious,
 
-        /// Surprised, startled, confused, or taken aback.
-        Shocked => "shocked",
+    /// Surprised, startled, confused, or taken aback.
+    #[xml(name = "shocked")]
+    Shocked,
 
-        /// Feeling easily frightened or scared; timid; reserved or coy.
-        Shy => "shy",
+    /// Feeling easily frightened or scared; timid; reserved or coy.
+    #[xml(name = "shy")]
+    Shy,
 
-        /// Feeling in poor health; ill.
-        Sick => "sick",
+    /// Feeling in poor health; ill.
+    #[xml(name = "sick")]
+    Sick,
 
-        /// Feeling the need for sleep.
-        Sleepy => "sleepy",
+    /// Feeling the need for sleep.
+    #[xml(name = "sleepy")]
+    Sleepy,
 
-        /// Acting without planning; natural; impulsive.
-        Spontaneous => "spontaneous",
+    /// Acting without planning; natural; impulsive.
+    #[xml(name = "spontaneous")]
+    Spontaneous,
 
-        /// Suffering emotional pressure.
-        Stressed => "stressed",
+    /// Suffering emotional pressure.
+    #[xml(name = "stressed")]
+    Stressed,
 
-        /// Capable of producing great physical force; or, emotionally forceful, able, determined, unyielding.
-        Strong => "strong",
+    /// Capable of producing great physical force; or, emotionally forceful, able, determined, unyielding.
+    #[xml(name = "strong")]
+    Strong,
 
-        /// Experiencing a feeling caused by something unexpected.
-        Surprised => "surprised",
+    /// Experiencing a feeling caused by something unexpected.
+    #[xml(name = "surprised")]
+    Surprised,
 
-        /// Showing appreciation or gratitude.
-        Thankful => "thankful",
+    /// Showing appreciation or gratitude.
+    #[xml(name = "thankful")]
+    Thankful,
 
-        /// Feeling the need to drink.
-        Thirsty => "thirsty",
+    /// Feeling the need to drink.
+    #[xml(name = "thirsty")]
+    Thirsty,
 
-        /// In need of rest or sleep.
-        Tired => "tired",
+    /// In need of rest or sleep.
+    #[xml(name = "tired")]
+    Tired,
 
-        /// [Feeling any emotion not defined here.]
-        Undefined => "undefined",
+    /// [Feeling any emotion not defined here.]
+    #[xml(name = "undefined")]
+    Undefined,
 
-        /// Lacking in force or ability, either physical or emotional.
-        Weak => "weak",
+    /// Lacking in force or ability, either physical or emotional.
+    #[xml(name = "weak")]
+    Weak,
 
-        /// Thinking about unpleasant things that have happened or that might happen; feeling afraid and unhappy.
-        Worried => "worried",
-    }
-);
-
-generate_elem_id!(
-    /// Free-form text description of the mood.
-    Text,
-    "text",
-    MOOD
-);
+    /// Thinking about unpleasant things that have happened or that might happen; feeling afraid and unhappy.
+    #[xml(name = "worried")]
+    Worried,
+}
+
+/// Free-form text description of the mood.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MOOD, name = "text")]
+pub struct Text(pub String);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,320 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
+use crate::muc::user::{Actor, Affiliation, Role};
+use crate::ns;
+use crate::util::error::Error;
+use crate::Element;
+use jid::{BareJid, Jid};
+use std::convert::TryFrom;
+
+/// An item of a muc#admin query, unlike those of muc#user it only carries the affiliation or
+/// the role being changed or listed.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Item {
+    /// The affiliation to give to this user, or to list.
+    pub affiliation: Option<Affiliation>,
+
+    /// The JID of this user, bare when changing their affiliation.
+    pub jid: Option<Jid>,
+
+    /// The nickname of this occupant.
+    pub nick: Option<String>,
+
+    /// The role to give to this occupant, or to list.
+    pub role: Option<Role>,
+
+    /// Who did this change, in the items returned by the room.
+    pub actor: Option<Actor>,
+
+    /// Why this change got done.
+    pub reason: Option<String>,
+}
+
+impl TryFrom<Element> for Item {
+    type Error = Error;
+
+    fn try_from(elem: Element) -> Result<Item, Error> {
+        check_self!(elem, "item", MUC_ADMIN);
+        check_no_unknown_attributes!(elem, "item", ["affiliation", "jid", "nick", "role"]);
+        let mut item = Item {
+            affiliation: elem.attr_as("affiliation")?,
+            jid: elem.attr_as("jid")?,
+            nick: elem.attr_as("nick")?,
+            role: elem.attr_as("role")?,
+            actor: None,
+            reason: None,
+        };
+        for child in elem.children() {
+            if child.is("actor", ns::MUC_ADMIN) {
+                if item.actor.is_some() {
+                    return Err(Error::ParseError(
+                        "Element item must not have more than one actor child.",
+                    ));
+                }
+                item.actor = Some(Actor::parse(child.clone(), ns::MUC_ADMIN)?);
+            } else if child.is("reason", ns::MUC_ADMIN) {
+                if item.reason.is_some() {
+                    return Err(Error::ParseError(
+                        "Element item must not have more than one reason child.",
+                    ));
+                }
+                item.reason = Some(child.text());
+            } else {
+                return Err(Error::ParseError("Unknown child in item element."));
+            }
+        }
+        Ok(item)
+    }
+}
+
+impl From<Item> for Element {
+    fn from(item: Item) -> Element {
+        Element::builder("item", ns::MUC_ADMIN)
+            .attr("affiliation", item.affiliation)
+            .attr("jid", item.jid)
+            .attr("nick", item.nick)
+            .attr("role", item.role)
+            .append_all(item.actor.map(|actor| actor.into_element(ns::MUC_ADMIN)))
+            .append_all(
+                item.reason
+                    .map(|reason| Element::builder("reason", ns::MUC_ADMIN).append(reason)),
+            )
+            .build()
+    }
+}
+
+/// The muc#admin query, used by moderators to change the role of occupants, and by admins and
+/// owners to change the affiliation of users, or to retrieve the list of users having a given
+/// role or affiliation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdminQuery {
+    /// The items to change, to request, or returned by the room, each with an affiliation or a
+    /// role.
+    pub items: Vec<Item>,
+}
+
+impl IqGetPayload for AdminQuery {}
+impl IqSetPayload for AdminQuery {}
+impl IqResultPayload for AdminQuery {}
+
+impl AdminQuery {
+    /// Creates a query changing the role of the occupant using this nickname, for instance
+    /// `Role::None` to kick them, or `Role::Visitor` to revoke their voice.
+    pub fn set_role<N: Into<String>>(nick: N, role: Role, reason: Option<String>) -> AdminQuery {
+        AdminQuery {
+            items: vec![Item {
+                nick: Some(nick.into()),
+                role: Some(role),
+                reason,
+                ..Item::default()
+            }],
+        }
+    }
+
+    /// Creates a query changing the affiliation of this user, for instance
+    /// `Affiliation::Outcast` to ban them, or `Affiliation::Member` to add them to the member
+    /// list.
+    pub fn set_affiliation(
+        jid: BareJid,
+        affiliation: Affiliation,
+        reason: Option<String>,
+    ) -> AdminQuery {
+        AdminQuery {
+            items: vec![Item {
+                jid: Some(jid.into()),
+                affiliation: Some(affiliation),
+                reason,
+                ..Item::default()
+            }],
+        }
+    }
+
+    /// Creates a query retrieving every user having this affiliation, for instance the ban list
+    /// with `Affiliation::Outcast`.
+    pub fn list_affiliation(affiliation: Affiliation) -> AdminQuery {
+        AdminQuery {
+            items: vec![Item {
+                affiliation: Some(affiliation),
+                ..Item::default()
+            }],
+        }
+    }
+
+    /// Creates a query retrieving every occupant having this role, for instance the moderators.
+    pub fn list_role(role: Role) -> AdminQuery {
+        AdminQuery {
+            items: vec![Item {
+                role: Some(role),
+                ..Item::default()
+            }],
+        }
+    }
+}
+
+impl TryFrom<Element> for AdminQuery {
+    type Error = Error;
+
+    fn try_from(elem: Element) -> Result<AdminQuery, Error> {
+        check_self!(elem, "query", MUC_ADMIN);
+        check_no_attributes!(elem, "query");
+        let mut items = Vec::new();
+        for child in elem.children() {
+            if child.is("item", ns::MUC_ADMIN) {
+                let item = Item::try_from(child.clone())?;
+                if item.affiliation.is_none() && item.role.is_none() {
+                    return Err(Error::ParseError(
+                        "Either 'affiliation' or 'role' attribute is required.",
+                    ));
+                }
+                items.push(item);
+            } else {
+                return Err(Error::ParseError("Unknown child in query element."));
+            }
+        }
+        Ok(AdminQuery { items })
+    }
+}
+
+impl From<AdminQuery> for Element {
+    fn from(query: AdminQuery) -> Element {
+        Element::builder("query", ns::MUC_ADMIN)
+            .append_all(query.items)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kick() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#admin'><item nick='pistol' role='none'><reason>Avaunt, you cullion!</reason></item></query>"
+            .parse()
+            .unwrap();
+        let query = AdminQuery::try_from(elem.clone()).unwrap();
+        assert_eq!(query.items.len(), 1);
+        assert_eq!(query.items[0].nick, Some(String::from("pistol")));
+        assert_eq!(query.items[0].role, Some(Role::None));
+        assert_eq!(query.items[0].affiliation, None);
+        assert_eq!(
+            query.items[0].reason,
+            Some(String::from("Avaunt, you cullion!"))
+        );
+
+        let query2 = AdminQuery::set_role(
+            "pistol",
+            Role::None,
+            Some(String::from("Avaunt, you cullion!")),
+        );
+        assert_eq!(query, query2);
+        assert_eq!(Element::from(query2), elem);
+    }
+
+    #[test]
+    fn test_ban() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#admin'><item affiliation='outcast' jid='earlofcambridge@shakespeare.lit'/></query>"
+            .parse()
+            .unwrap();
+        let jid = BareJid::new("earlofcambridge", "shakespeare.lit");
+        let query = AdminQuery::set_affiliation(jid.clone(), Affiliation::Outcast, None);
+        assert_eq!(AdminQuery::try_from(elem.clone()).unwrap(), query);
+        assert_eq!(Element::from(query), elem);
+    }
+
+    #[test]
+    fn test_ban_list() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#admin'><item affiliation='outcast'/></query>"
+            .parse()
+            .unwrap();
+        let query = AdminQuery::list_affiliation(Affiliation::Outcast);
+        assert_eq!(AdminQuery::try_from(elem.clone()).unwrap(), query);
+        assert_eq!(Element::from(query), elem);
+
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#admin'>
+            <item affiliation='outcast' jid='earlofcambridge@shakespeare.lit'>
+                <actor nick='kinghenryv'/>
+                <reason>Treason</reason>
+            </item>
+        </query>"
+            .parse()
+            .unwrap();
+        let query = AdminQuery::try_from(elem).unwrap();
+        assert_eq!(
+            query.items[0].jid,
+            Some(Jid::Bare(BareJid::new(
+                "earlofcambridge",
+                "shakespeare.lit"
+            )))
+        );
+        assert_eq!(
+            query.items[0].actor,
+            Some(Actor::Nick(String::from("kinghenryv")))
+        );
+    }
+
+    #[test]
+    fn test_actor() {
+        let elem: Element = "<item xmlns='http://jabber.org/protocol/muc#admin' affiliation='outcast' jid='earlofcambridge@shakespeare.lit'><actor nick='kinghenryv'/></item>"
+            .parse()
+            .unwrap();
+        let item = Item::try_from(elem.clone()).unwrap();
+        assert_eq!(item.actor, Some(Actor::Nick(String::from("kinghenryv"))));
+        assert_eq!(Element::from(item), elem);
+
+        let elem: Element = "<item xmlns='http://jabber.org/protocol/muc#admin' role='none'><actor xmlns='http://jabber.org/protocol/muc#user' nick='kinghenryv'/></item>"
+            .parse()
+            .unwrap();
+        let error = Item::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown child in item element.");
+    }
+
+    #[test]
+    fn test_moderator_list() {
+        let query = AdminQuery::list_role(Role::Moderator);
+        let elem: Element = query.into();
+        assert_eq!(
+            String::from(&elem),
+            "<query xmlns=\"http://jabber.org/protocol/muc#admin\"><item role=\"moderator\"/></query>"
+        );
+    }
+
+    #[test]
+    fn test_invalid_child() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#admin'><item xmlns='http://jabber.org/protocol/muc#user' role='none'/></query>"
+            .parse()
+            .unwrap();
+        let error = AdminQuery::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown child in query element.");
+    }
+
+    #[test]
+    fn test_item_without_affiliation_nor_role() {
+        let elem: Element =
+            "<query xmlns='http://jabber.org/protocol/muc#admin'><item nick='pistol'/></query>"
+                .parse()
+                .unwrap();
+        let error = AdminQuery::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(
+            message,
+            "Either 'affiliation' or 'role' attribute is required."
+        );
+    }
+}
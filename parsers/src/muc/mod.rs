@@ -10,5 +10,13 @@ pub mod muc;
 /// The http://jabber.org/protocol/muc#user protocol.
 pub mod user;
 
+/// The http://jabber.org/protocol/muc#admin protocol.
+pub mod admin;
+
+/// The http://jabber.org/protocol/muc#owner protocol.
+pub mod owner;
+
+pub use self::admin::AdminQuery;
 pub use self::muc::Muc;
+pub use self::owner::OwnerQuery;
 pub use self::user::MucUser;
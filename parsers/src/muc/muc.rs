@@ -7,25 +7,29 @@
 
 use crate::date::DateTime;
 use crate::presence::PresencePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Represents the query for messages before our join.
-    #[derive(Default)]
-    History, "history", MUC,
-    attributes: [
-        /// How many characters of history to send, in XML characters.
-        maxchars: Option<u32> = "maxchars",
+/// Represents the query for messages before our join.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC, name = "history")]
+pub struct History {
+    /// How many characters of history to send, in XML characters.
+    #[xml(attribute = "maxchars")]
+    pub maxchars: Option<u32>,
 
-        /// How many messages to send.
-        maxstanzas: Option<u32> = "maxstanzas",
+    /// How many messages to send.
+    #[xml(attribute = "maxstanzas")]
+    pub maxstanzas: Option<u32>,
 
-        /// Only send messages received in these last seconds.
-        seconds: Option<u32> = "seconds",
+    /// Only send messages received in these last seconds.
+    #[xml(attribute = "seconds")]
+    pub seconds: Option<u32>,
 
-        /// Only send messages after this date.
-        since: Option<DateTime> = "since",
-    ]
-);
+    /// Only send messages after this date.
+    #[xml(attribute = "since")]
+    pub since: Option<DateTime>,
+}
 
 impl History {
     /// Create a new empty history element.
@@ -58,17 +62,19 @@ impl History {
     }
 }
 
-generate_element!(
-    /// Represents a room join request.
-    #[derive(Default)]
-    Muc, "x", MUC, children: [
-        /// Password to use when the room is protected by a password.
-        password: Option<String> = ("password", MUC) => String,
-
-        /// Controls how much and how old we want to receive history on join.
-        history: Option<History> = ("history", MUC) => History
-    ]
-);
+/// Represents a room join request.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC, name = "x")]
+pub struct Muc {
+    /// Password to use when the room is protected by a password.
+    #[xml(child = "password")]
+    pub password: Option<String>,
+
+    /// Controls how much and how old we want to receive history on join.
+    #[xml(child = "history")]
+    pub history: Option<History>,
+}
 
 impl PresencePayload for Muc {}
 
@@ -0,0 +1,163 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::data_forms::{DataForm, DataFormType};
+use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
+use crate::ns;
+use jid::BareJid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Destroys a room, optionally pointing its occupants to another one.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC_OWNER, name = "destroy")]
+pub struct Destroy {
+    /// The JID of an alternate room to join instead.
+    #[xml(attribute = "jid")]
+    pub jid: Option<BareJid>,
+
+    /// The password of the alternate room.
+    #[xml(child = "password")]
+    pub password: Option<String>,
+
+    /// Why the room got destroyed.
+    #[xml(child = "reason")]
+    pub reason: Option<String>,
+}
+
+/// The muc#owner query, used to retrieve and submit the configuration form of a room, or to
+/// destroy it.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC_OWNER, name = "query")]
+pub struct OwnerQuery {
+    /// The configuration form, of FORM_TYPE `muc#roomconfig`.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+
+    /// Destroys the room.
+    #[xml(child = "destroy")]
+    pub destroy: Option<Destroy>,
+}
+
+impl IqGetPayload for OwnerQuery {}
+impl IqSetPayload for OwnerQuery {}
+impl IqResultPayload for OwnerQuery {}
+
+impl OwnerQuery {
+    /// Creates an empty query, to request the configuration form of a room.
+    pub fn new() -> OwnerQuery {
+        OwnerQuery::default()
+    }
+
+    /// Creates a query submitting a configuration form, whose type should be `Submit`, or
+    /// `Cancel` to abort the configuration of a newly created room.
+    pub fn configure(form: DataForm) -> OwnerQuery {
+        OwnerQuery {
+            form: Some(form),
+            destroy: None,
+        }
+    }
+
+    /// Creates a query accepting the default configuration of a newly created room.
+    pub fn instant_room() -> OwnerQuery {
+        OwnerQuery::configure(DataForm::new(
+            DataFormType::Submit,
+            ns::MUC_ROOMCONFIG,
+            vec![],
+        ))
+    }
+
+    /// Creates a query destroying the room.
+    pub fn destroy(destroy: Destroy) -> OwnerQuery {
+        OwnerQuery {
+            form: None,
+            destroy: Some(destroy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_forms::FieldType;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_config_request() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#owner'/>"
+            .parse()
+            .unwrap();
+        let query = OwnerQuery::try_from(elem.clone()).unwrap();
+        assert_eq!(query, OwnerQuery::new());
+        assert_eq!(Element::from(query), elem);
+    }
+
+    #[test]
+    fn test_config_form() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#owner'>
+            <x xmlns='jabber:x:data' type='form'>
+                <title>Configuration for coven Room</title>
+                <field type='hidden' var='FORM_TYPE'>
+                    <value>http://jabber.org/protocol/muc#roomconfig</value>
+                </field>
+                <field label='Natural-Language Room Name' type='text-single' var='muc#roomconfig_roomname'/>
+                <field label='Make Room Persistent?' type='boolean' var='muc#roomconfig_persistentroom'>
+                    <value>0</value>
+                </field>
+            </x>
+        </query>"
+            .parse()
+            .unwrap();
+        let query = OwnerQuery::try_from(elem).unwrap();
+        let form = query.form.unwrap();
+        assert_eq!(form.type_, DataFormType::Form);
+        assert_eq!(form.form_type, Some(String::from(ns::MUC_ROOMCONFIG)));
+        assert_eq!(form.fields.len(), 2);
+        assert_eq!(form.fields[1].var, "muc#roomconfig_persistentroom");
+        assert_eq!(form.fields[1].type_, FieldType::Boolean);
+        assert!(query.destroy.is_none());
+    }
+
+    #[test]
+    fn test_instant_room() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#owner'><x xmlns='jabber:x:data' type='submit'><field var='FORM_TYPE' type='hidden'><value>http://jabber.org/protocol/muc#roomconfig</value></field></x></query>"
+            .parse()
+            .unwrap();
+        assert_eq!(Element::from(OwnerQuery::instant_room()), elem);
+        assert_eq!(
+            OwnerQuery::try_from(elem).unwrap(),
+            OwnerQuery::instant_room()
+        );
+    }
+
+    #[test]
+    fn test_destroy() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#owner'><destroy jid='coven@chat.shakespeare.lit'><password>cauldronburn</password><reason>Macbeth doth come.</reason></destroy></query>"
+            .parse()
+            .unwrap();
+        let query = OwnerQuery::destroy(Destroy {
+            jid: Some(BareJid::new("coven", "chat.shakespeare.lit")),
+            password: Some(String::from("cauldronburn")),
+            reason: Some(String::from("Macbeth doth come.")),
+        });
+        assert_eq!(OwnerQuery::try_from(elem.clone()).unwrap(), query);
+        assert_eq!(Element::from(query), elem);
+    }
+
+    #[test]
+    fn test_invalid_child() {
+        let elem: Element = "<query xmlns='http://jabber.org/protocol/muc#owner'><coucou/></query>"
+            .parse()
+            .unwrap();
+        let error = OwnerQuery::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown child in query element.");
+    }
+}
@@ -10,68 +10,88 @@ use crate::util::error::Error;
 use crate::Element;
 use jid::FullJid;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_attribute_enum!(
 /// Lists all of the possible status codes used in MUC presences.
-Status, "status", MUC_USER, "code", {
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(namespace = MUC_USER, name = "status", attribute = "code")]
+pub enum Status {
     /// Inform user that any occupant is allowed to see the user's full JID
-    NonAnonymousRoom => 100,
+    #[xml(value = 100)]
+    NonAnonymousRoom,
 
     /// Inform user that his or her affiliation changed while not in the room
-    AffiliationChange => 101,
+    #[xml(value = 101)]
+    AffiliationChange,
 
     /// Inform occupants that room now shows unavailable members
-    ConfigShowsUnavailableMembers => 102,
+    #[xml(value = 102)]
+    ConfigShowsUnavailableMembers,
 
     /// Inform occupants that room now does not show unavailable members
-    ConfigHidesUnavailableMembers => 103,
+    #[xml(value = 103)]
+    ConfigHidesUnavailableMembers,
 
     /// Inform occupants that a non-privacy-related room configuration change has occurred
-    ConfigNonPrivacyRelated => 104,
+    #[xml(value = 104)]
+    ConfigNonPrivacyRelated,
 
     /// Inform user that presence refers to itself
-    SelfPresence => 110,
+    #[xml(value = 110)]
+    SelfPresence,
 
     /// Inform occupants that room logging is now enabled
-    ConfigRoomLoggingEnabled => 170,
+    #[xml(value = 170)]
+    ConfigRoomLoggingEnabled,
 
     /// Inform occupants that room logging is now disabled
-    ConfigRoomLoggingDisabled => 171,
+    #[xml(value = 171)]
+    ConfigRoomLoggingDisabled,
 
     /// Inform occupants that the room is now non-anonymous
-    ConfigRoomNonAnonymous => 172,
+    #[xml(value = 172)]
+    ConfigRoomNonAnonymous,
 
     /// Inform occupants that the room is now semi-anonymous
-    ConfigRoomSemiAnonymous => 173,
+    #[xml(value = 173)]
+    ConfigRoomSemiAnonymous,
 
     /// Inform user that a new room has been created
-    RoomHasBeenCreated => 201,
+    #[xml(value = 201)]
+    RoomHasBeenCreated,
 
     /// Inform user that service has assigned or modified occupant's roomnick
-    AssignedNick => 210,
+    #[xml(value = 210)]
+    AssignedNick,
 
     /// Inform user that he or she has been banned from the room
-    Banned => 301,
+    #[xml(value = 301)]
+    Banned,
 
     /// Inform all occupants of new room nickname
-    NewNick => 303,
+    #[xml(value = 303)]
+    NewNick,
 
     /// Inform user that he or she has been kicked from the room
-    Kicked => 307,
+    #[xml(value = 307)]
+    Kicked,
 
     /// Inform user that he or she is being removed from the room
     /// because of an affiliation change
-    RemovalFromRoom => 321,
+    #[xml(value = 321)]
+    RemovalFromRoom,
 
     /// Inform user that he or she is being removed from the room
     /// because the room has been changed to members-only and the
     /// user is not a member
-    ConfigMembersOnly => 322,
+    #[xml(value = 322)]
+    ConfigMembersOnly,
 
     /// Inform user that he or she is being removed from the room
     /// because the MUC service is being shut down
-    ServiceShutdown => 332,
-});
+    #[xml(value = 332)]
+    ServiceShutdown,
+}
 
 /// Optional <actor/> element used in <item/> elements inside presence stanzas of type
 /// "unavailable" that are sent to users who are kick or banned, as well as within IQs for tracking
@@ -80,6 +100,7 @@ Status, "status", MUC_USER, "code", {
 /// Possesses a 'jid' and a 'nick' attribute, so that an action can be attributed either to a real
 /// JID or to a roomnick. -- CHANGELOG  1.25 (2012-02-08)
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Actor {
     /// The full JID associated with this user.
     Jid(FullJid),
@@ -88,15 +109,17 @@ pub enum Actor {
     Nick(String),
 }
 
-impl TryFrom<Element> for Actor {
-    type Error = Error;
-
-    fn try_from(elem: Element) -> Result<Actor, Error> {
-        check_self!(elem, "actor", MUC_USER);
+impl Actor {
+    /// Parses an actor in this namespace, muc#user in presences and messages, or muc#admin
+    /// in the items of an admin query.
+    pub(crate) fn parse(elem: Element, namespace: &str) -> Result<Actor, Error> {
+        if !elem.is("actor", namespace) {
+            return Err(Error::ParseError("This is not a actor element."));
+        }
         check_no_unknown_attributes!(elem, "actor", ["jid", "nick"]);
         check_no_children!(elem, "actor");
-        let jid: Option<FullJid> = get_attr!(elem, "jid", Option);
-        let nick = get_attr!(elem, "nick", Option);
+        let jid: Option<FullJid> = elem.attr_as("jid")?;
+        let nick = elem.attr_as("nick")?;
 
         match (jid, nick) {
             (Some(_), Some(_)) | (None, None) => Err(Error::ParseError(
@@ -106,13 +129,12 @@ impl TryFrom<Element> for Actor {
             (_, Some(nick)) => Ok(Actor::Nick(nick)),
         }
     }
-}
 
-impl From<Actor> for Element {
-    fn from(actor: Actor) -> Element {
-        let elem = Element::builder("actor", ns::MUC_USER);
+    /// Serialises this actor into this namespace.
+    pub(crate) fn into_element(self, namespace: &str) -> Element {
+        let elem = Element::builder("actor", namespace);
 
-        (match actor {
+        (match self {
             Actor::Jid(jid) => elem.attr("jid", jid),
             Actor::Nick(nick) => elem.attr("nick", nick),
         })
@@ -120,92 +142,125 @@ impl From<Actor> for Element {
     }
 }
 
-generate_element!(
-    /// Used to continue a one-to-one discussion in a room, with more than one
-    /// participant.
-    Continue, "continue", MUC_USER,
-    attributes: [
-        /// The thread to continue in this room.
-        thread: Option<String> = "thread",
-    ]
-);
-
-generate_elem_id!(
-    /// A reason for inviting, declining, etc. a request.
-    Reason,
-    "reason",
-    MUC_USER
-);
-
-generate_attribute!(
-    /// The affiliation of an entity with a room, which isn’t tied to its
-    /// presence in it.
-    Affiliation, "affiliation", {
-        /// The user who created the room, or who got appointed by its creator
-        /// to be their equal.
-        Owner => "owner",
-
-        /// A user who has been empowered by an owner to do administrative
-        /// operations.
-        Admin => "admin",
-
-        /// A user who is whitelisted to speak in moderated rooms, or to join a
-        /// member-only room.
-        Member => "member",
-
-        /// A user who has been banned from this room.
-        Outcast => "outcast",
-
-        /// A normal participant.
-        None => "none",
-    }, Default = None
-);
-
-generate_attribute!(
-    /// The current role of an entity in a room, it can be changed by an owner
-    /// or an administrator but will be lost once they leave the room.
-    Role, "role", {
-        /// This user can kick other participants, as well as grant and revoke
-        /// them voice.
-        Moderator => "moderator",
-
-        /// A user who can speak in this room.
-        Participant => "participant",
-
-        /// A user who cannot speak in this room, and must request voice before
-        /// doing so.
-        Visitor => "visitor",
-
-        /// A user who is absent from the room.
-        None => "none",
-    }, Default = None
-);
-
-generate_element!(
-    /// An item representing a user in a room.
-    Item, "item", MUC_USER, attributes: [
-        /// The affiliation of this user with the room.
-        affiliation: Required<Affiliation> = "affiliation",
-
-        /// The real JID of this user, if you are allowed to see it.
-        jid: Option<FullJid> = "jid",
-
-        /// The current nickname of this user.
-        nick: Option<String> = "nick",
-
-        /// The current role of this user.
-        role: Required<Role> = "role",
-    ], children: [
-        /// The actor affected by this item.
-        actor: Option<Actor> = ("actor", MUC_USER) => Actor,
-
-        /// Whether this continues a one-to-one discussion.
-        continue_: Option<Continue> = ("continue", MUC_USER) => Continue,
-
-        /// A reason for this item.
-        reason: Option<Reason> = ("reason", MUC_USER) => Reason
-    ]
-);
+impl TryFrom<Element> for Actor {
+    type Error = Error;
+
+    fn try_from(elem: Element) -> Result<Actor, Error> {
+        Actor::parse(elem, ns::MUC_USER)
+    }
+}
+
+impl From<Actor> for Element {
+    fn from(actor: Actor) -> Element {
+        actor.into_element(ns::MUC_USER)
+    }
+}
+
+/// Used to continue a one-to-one discussion in a room, with more than one
+/// participant.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC_USER, name = "continue")]
+pub struct Continue {
+    /// The thread to continue in this room.
+    #[xml(attribute = "thread")]
+    pub thread: Option<String>,
+}
+
+/// A reason for inviting, declining, etc. a request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC_USER, name = "reason")]
+pub struct Reason(pub String);
+
+/// The affiliation of an entity with a room, which isn’t tied to its
+/// presence in it.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[xml(attribute = "affiliation")]
+pub enum Affiliation {
+    /// The user who created the room, or who got appointed by its creator
+    /// to be their equal.
+    #[xml(value = "owner")]
+    Owner,
+
+    /// A user who has been empowered by an owner to do administrative
+    /// operations.
+    #[xml(value = "admin")]
+    Admin,
+
+    /// A user who is whitelisted to speak in moderated rooms, or to join a
+    /// member-only room.
+    #[xml(value = "member")]
+    Member,
+
+    /// A user who has been banned from this room.
+    #[xml(value = "outcast")]
+    Outcast,
+
+    /// A normal participant.
+    #[default]
+    #[xml(value = "none")]
+    None,
+}
+
+/// The current role of an entity in a room, it can be changed by an owner
+/// or an administrator but will be lost once they leave the room.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[xml(attribute = "role")]
+pub enum Role {
+    /// This user can kick other participants, as well as grant and revoke
+    /// them voice.
+    #[xml(value = "moderator")]
+    Moderator,
+
+    /// A user who can speak in this room.
+    #[xml(value = "participant")]
+    Participant,
+
+    /// A user who cannot speak in this room, and must request voice before
+    /// doing so.
+    #[xml(value = "visitor")]
+    Visitor,
+
+    /// A user who is absent from the room.
+    #[default]
+    #[xml(value = "none")]
+    None,
+}
+
+/// An item representing a user in a room.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC_USER, name = "item")]
+pub struct Item {
+    /// The affiliation of this user with the room.
+    #[xml(attribute = "affiliation")]
+    pub affiliation: Affiliation,
+
+    /// The real JID of this user, if you are allowed to see it.
+    #[xml(attribute = "jid")]
+    pub jid: Option<FullJid>,
+
+    /// The current nickname of this user.
+    #[xml(attribute = "nick")]
+    pub nick: Option<String>,
+
+    /// The current role of this user.
+    #[xml(attribute = "role")]
+    pub role: Role,
+
+    /// The actor affected by this item.
+    #[xml(child = "actor")]
+    pub actor: Option<Actor>,
+
+    /// Whether this continues a one-to-one discussion.
+    #[xml(child = "continue")]
+    pub continue_: Option<Continue>,
+
+    /// A reason for this item.
+    #[xml(child = "reason")]
+    pub reason: Option<Reason>,
+}
 
 impl Item {
     /// Creates a new item with the given affiliation and role.
@@ -222,16 +277,19 @@ impl Item {
     }
 }
 
-generate_element!(
-    /// The main muc#user element.
-    MucUser, "x", MUC_USER, children: [
-        /// List of statuses applying to this item.
-        status: Vec<Status> = ("status", MUC_USER) => Status,
-
-        /// List of items.
-        items: Vec<Item> = ("item", MUC_USER) => Item
-    ]
-);
+/// The main muc#user element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = MUC_USER, name = "x")]
+pub struct MucUser {
+    /// List of statuses applying to this item.
+    #[xml(child = "status")]
+    pub status: Vec<Status>,
+
+    /// List of items.
+    #[xml(child = "item")]
+    pub items: Vec<Item>,
+}
 
 #[cfg(test)]
 mod tests {
@@ -333,11 +391,15 @@ mod tests {
         .parse()
         .unwrap();
         let error = Status::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'code' missing.");
+        assert_eq!(attribute, "code");
     }
 
     #[cfg(not(feature = "disable-validation"))]
@@ -393,7 +455,12 @@ mod tests {
         .unwrap();
         let error = Status::try_from(elem).unwrap_err();
         let error = match error {
-            Error::ParseIntError(error) => error,
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(error) => error,
+                _ => panic!(),
+            },
             _ => panic!(),
         };
         assert_eq!(error.to_string(), "invalid digit found in string");
@@ -587,11 +654,15 @@ mod tests {
         .parse()
         .unwrap();
         let error = Item::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'role' missing.".to_owned());
+        assert_eq!(attribute, "role");
     }
 
     #[test]
@@ -619,14 +690,23 @@ mod tests {
         .parse()
         .unwrap();
         let error = Item::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(
-            message,
-            "Required attribute 'affiliation' missing.".to_owned()
-        );
+        assert_eq!(attribute, "affiliation");
+    }
+
+    #[test]
+    fn test_item_none_serialised() {
+        let item = Item::new(Affiliation::None, Role::None);
+        let elem: Element = item.into();
+        assert_eq!(elem.attr("affiliation"), Some("none"));
+        assert_eq!(elem.attr("role"), Some("none"));
     }
 
     #[test]
@@ -1,15 +1,15 @@
+use xmpp_parsers_macros::{FromXml, IntoXml};
 // Copyright (c) 2018 Emmanuel Gil Peyrot <linkmauve@linkmauve.fr>
 //
 // This Source Code Form is subject to the terms of the Mozilla Public
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-generate_elem_id!(
-    /// Represents a global, memorable, friendly or informal name chosen by a user.
-    Nick,
-    "nick",
-    NICK
-);
+/// Represents a global, memorable, friendly or informal name chosen by a user.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = NICK, name = "nick")]
+pub struct Nick(pub String);
 
 #[cfg(test)]
 mod tests {
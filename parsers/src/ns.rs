@@ -36,6 +36,12 @@ pub const DISCO_ITEMS: &str = "http://jabber.org/protocol/disco#items";
 pub const MUC: &str = "http://jabber.org/protocol/muc";
 /// XEP-0045: Multi-User Chat
 pub const MUC_USER: &str = "http://jabber.org/protocol/muc#user";
+/// XEP-0045: Multi-User Chat
+pub const MUC_ADMIN: &str = "http://jabber.org/protocol/muc#admin";
+/// XEP-0045: Multi-User Chat
+pub const MUC_OWNER: &str = "http://jabber.org/protocol/muc#owner";
+/// XEP-0045: Multi-User Chat, the FORM_TYPE of room configuration forms
+pub const MUC_ROOMCONFIG: &str = "http://jabber.org/protocol/muc#roomconfig";
 
 /// XEP-0047: In-Band Bytestreams
 pub const IBB: &str = "http://jabber.org/protocol/ibb";
@@ -43,6 +49,12 @@ pub const IBB: &str = "http://jabber.org/protocol/ibb";
 /// XEP-0048: Bookmarks
 pub const BOOKMARKS: &str = "storage:bookmarks";
 
+/// XEP-0050: Ad-Hoc Commands
+pub const COMMANDS: &str = "http://jabber.org/protocol/commands";
+
+/// XEP-0054: vcard-temp
+pub const VCARD: &str = "vcard-temp";
+
 /// XEP-0059: Result Set Management
 pub const RSM: &str = "http://jabber.org/protocol/rsm";
 
@@ -56,6 +68,8 @@ pub const PUBSUB_EVENT: &str = "http://jabber.org/protocol/pubsub#event";
 pub const PUBSUB_OWNER: &str = "http://jabber.org/protocol/pubsub#owner";
 /// XEP-0060: Publish-Subscribe node configuration
 pub const PUBSUB_CONFIGURE: &str = "http://jabber.org/protocol/pubsub#node_config";
+/// XEP-0060: Publish-Subscribe publish options
+pub const PUBSUB_PUBLISH_OPTIONS: &str = "http://jabber.org/protocol/pubsub#publish-options";
 
 /// XEP-0071: XHTML-IM
 pub const XHTML_IM: &str = "http://jabber.org/protocol/xhtml-im";
@@ -64,6 +78,8 @@ pub const XHTML: &str = "http://www.w3.org/1999/xhtml";
 
 /// XEP-0077: In-Band Registration
 pub const REGISTER: &str = "jabber:iq:register";
+/// XEP-0077: In-Band Registration
+pub const REGISTER_FEATURE: &str = "http://jabber.org/features/iq-register";
 
 /// XEP-0084: User Avatar
 pub const AVATAR_DATA: &str = "urn:xmpp:avatar:data";
@@ -76,6 +92,9 @@ pub const CHATSTATES: &str = "http://jabber.org/protocol/chatstates";
 /// XEP-0092: Software Version
 pub const VERSION: &str = "jabber:iq:version";
 
+/// XEP-0103: URL Address Information
+pub const URL_DATA: &str = "http://jabber.org/protocol/url-data";
+
 /// XEP-0107: User Mood
 pub const MOOD: &str = "http://jabber.org/protocol/mood";
 
@@ -91,6 +110,9 @@ pub const CAPS: &str = "http://jabber.org/protocol/caps";
 /// XEP-0118: User Tune
 pub const TUNE: &str = "http://jabber.org/protocol/tune";
 
+/// XEP-0153: vCard-Based Avatars
+pub const VCARD_UPDATE: &str = "vcard-temp:x:update";
+
 /// XEP-0157: Contact Addresses for XMPP Services
 pub const SERVER_INFO: &str = "http://jabber.org/network/serverinfo";
 
@@ -159,12 +181,20 @@ pub const JINGLE_S5B: &str = "urn:xmpp:jingle:transports:s5b:1";
 /// XEP-0261: Jingle In-Band Bytestreams Transport Method
 pub const JINGLE_IBB: &str = "urn:xmpp:jingle:transports:ibb:1";
 
+/// XEP-0264: Jingle Content Thumbnails
+pub const THUMBS: &str = "urn:xmpp:thumbs:1";
+
 /// XEP-0277: Microblogging over XMPP
 pub const MICROBLOG: &str = "urn:xmpp:microblog:0";
 
 /// XEP-0280: Message Carbons
 pub const CARBONS: &str = "urn:xmpp:carbons:2";
 
+/// XEP-0292: vCard4 Over XMPP
+pub const VCARD4: &str = "urn:ietf:params:xml:ns:vcard-4.0";
+/// XEP-0292: vCard4 Over XMPP, the PEP node of the vCard
+pub const VCARD4_NODE: &str = "urn:xmpp:vcard4";
+
 /// XEP-0293: Jingle RTP Feedback Negotiation
 pub const JINGLE_RTCP_FB: &str = "urn:xmpp:jingle:apps:rtp:rtcp-fb:0";
 
@@ -204,6 +234,12 @@ pub const JINGLE_DTLS: &str = "urn:xmpp:jingle:apps:dtls:0";
 /// XEP-0328: JID Prep
 pub const JID_PREP: &str = "urn:xmpp:jidprep:0";
 
+/// XEP-0333: Chat Markers
+pub const CHAT_MARKERS: &str = "urn:xmpp:chat-markers:0";
+
+/// XEP-0334: Message Processing Hints
+pub const HINTS: &str = "urn:xmpp:hints";
+
 /// XEP-0338: Jingle Grouping Framework
 pub const JINGLE_GROUPING: &str = "urn:xmpp:jingle:apps:grouping:0";
 
@@ -216,6 +252,9 @@ pub const CSI: &str = "urn:xmpp:csi:0";
 /// XEP-0353: Jingle Message Initiation
 pub const JINGLE_MESSAGE: &str = "urn:xmpp:jingle-message:0";
 
+/// XEP-0358: Publishing Available Jingle Sessions
+pub const JINGLEPUB: &str = "urn:xmpp:jinglepub:1";
+
 /// XEP-0359: Unique and Stable Stanza IDs
 pub const SID: &str = "urn:xmpp:sid:0";
 
@@ -247,6 +286,13 @@ pub const OX_PUBKEYS: &str = "urn:xmpp:openpgp:0:public-keys";
 /// XEP-0380: Explicit Message Encryption
 pub const EME: &str = "urn:xmpp:eme:0";
 
+/// XEP-0384: OMEMO Encryption
+pub const OMEMO: &str = "urn:xmpp:omemo:2";
+/// XEP-0384: OMEMO Encryption, the PEP node of the device list
+pub const OMEMO_DEVICES: &str = "urn:xmpp:omemo:2:devices";
+/// XEP-0384: OMEMO Encryption, the PEP node of the bundles
+pub const OMEMO_BUNDLES: &str = "urn:xmpp:omemo:2:bundles";
+
 /// XEP-0390: Entity Capabilities 2.0
 pub const ECAPS2: &str = "urn:xmpp:caps";
 /// XEP-0390: Entity Capabilities 2.0
@@ -259,9 +305,33 @@ pub const BOOKMARKS2_COMPAT: &str = "urn:xmpp:bookmarks:1#compat";
 /// XEP-0402: PEP Native Bookmarks
 pub const BOOKMARKS2_COMPAT_PEP: &str = "urn:xmpp:bookmarks:1#compat-pep";
 
+/// XEP-0420: Stanza Content Encryption
+pub const SCE: &str = "urn:xmpp:sce:1";
+
 /// XEP-0421: Anonymous unique occupant identifiers for MUCs
 pub const OID: &str = "urn:xmpp:occupant-id:0";
 
+/// XEP-0424: Message Retraction
+pub const MESSAGE_RETRACT: &str = "urn:xmpp:message-retract:1";
+
+/// XEP-0425: Moderated Message Retraction
+pub const MESSAGE_MODERATE: &str = "urn:xmpp:message-moderate:1";
+
+/// XEP-0428: Fallback Indication
+pub const FALLBACK: &str = "urn:xmpp:fallback:0";
+
+/// XEP-0444: Message Reactions
+pub const REACTIONS: &str = "urn:xmpp:reactions:0";
+
+/// XEP-0446: File metadata element
+pub const FILE_METADATA: &str = "urn:xmpp:file:metadata:0";
+
+/// XEP-0447: Stateless file sharing
+pub const SFS: &str = "urn:xmpp:sfs:0";
+
+/// XEP-0461: Message Replies
+pub const REPLY: &str = "urn:xmpp:reply:0";
+
 /// Alias for the main namespace of the stream, that is "jabber:client" when
 /// the component feature isn’t enabled.
 #[cfg(not(feature = "component"))]
@@ -6,19 +6,20 @@
 
 use crate::message::MessagePayload;
 use crate::presence::PresencePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Unique identifier given to a MUC participant.
-    ///
-    /// It allows clients to identify a MUC participant across reconnects and
-    /// renames. It thus prevents impersonification of anonymous users.
-    OccupantId, "occupant-id", OID,
-
-    attributes: [
-        /// The id associated to the sending user by the MUC service.
-        id: Required<String> = "id",
-    ]
-);
+/// Unique identifier given to a MUC participant.
+///
+/// It allows clients to identify a MUC participant across reconnects and
+/// renames. It thus prevents impersonification of anonymous users.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OID, name = "occupant-id")]
+pub struct OccupantId {
+    /// The id associated to the sending user by the MUC service.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
 
 impl MessagePayload for OccupantId {}
 impl PresencePayload for OccupantId {}
@@ -70,11 +71,15 @@ mod tests {
             .parse()
             .unwrap();
         let error = OccupantId::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'id' missing.");
+        assert_eq!(attribute, "id");
     }
 
     #[test]
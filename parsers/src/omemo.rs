@@ -0,0 +1,380 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::message::MessagePayload;
+use crate::pubsub::PubSubPayload;
+use crate::util::helpers::Base64;
+use jid::BareJid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Whether a key is part of a key exchange, which starts a new session with this device.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "kex")]
+pub enum IsKeyExchange {
+    /// True value, represented by either 'true' or '1'.
+    #[xml(value = "true", alias = "1")]
+    True,
+
+    /// False value, represented by either 'false' or '0'.
+    #[xml(value = "false", alias = "0", default)]
+    False,
+}
+
+/// The message key, encrypted for a single device of the recipient.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "key")]
+pub struct Key {
+    /// The id of the device this key is encrypted for.
+    #[xml(attribute = "rid")]
+    pub rid: u32,
+
+    /// Whether this key is wrapped in a key exchange message.
+    #[xml(attribute = "kex", default)]
+    pub kex: IsKeyExchange,
+
+    /// The encrypted key.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// The keys of all of the devices of a single recipient.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "keys")]
+pub struct Keys {
+    /// The recipient of these keys.
+    #[xml(attribute = "jid")]
+    pub jid: BareJid,
+
+    /// One key per device of this recipient.
+    #[xml(child = "key")]
+    pub keys: Vec<Key>,
+}
+
+/// The unencrypted header of an OMEMO message.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "header")]
+pub struct Header {
+    /// The id of the device which sent this message.
+    #[xml(attribute = "sid")]
+    pub sid: u32,
+
+    /// The keys for each recipient, including the other devices of the sender.
+    #[xml(child = "keys")]
+    pub keys: Vec<Keys>,
+}
+
+/// The encrypted content of an OMEMO message, an SCE envelope once decrypted.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "payload")]
+pub struct Payload {
+    /// The ciphertext.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// An OMEMO encrypted message.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "encrypted")]
+pub struct Encrypted {
+    /// The header, containing the message key for each recipient device.
+    #[xml(child = "header")]
+    pub header: Header,
+
+    /// The encrypted content, absent from the empty messages used to establish or to ratchet
+    /// forward a session.
+    #[xml(child = "payload")]
+    pub payload: Option<Payload>,
+}
+
+impl MessagePayload for Encrypted {}
+
+impl Encrypted {
+    /// Returns the key encrypted for this device of this recipient, if any.
+    pub fn key_for(&self, jid: &BareJid, rid: u32) -> Option<&Key> {
+        self.header
+            .keys
+            .iter()
+            .filter(|keys| keys.jid == *jid)
+            .flat_map(|keys| keys.keys.iter())
+            .find(|key| key.rid == rid)
+    }
+}
+
+/// A device supporting OMEMO.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "device")]
+pub struct Device {
+    /// The id of this device, unique among the devices of its owner.
+    #[xml(attribute = "id")]
+    pub id: u32,
+
+    /// A human-readable label for this device.
+    #[xml(attribute = "label")]
+    pub label: Option<String>,
+}
+
+/// The list of the devices of an account, published in the single `current` item of its
+/// `urn:xmpp:omemo:2:devices` node.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "devices")]
+pub struct DeviceList {
+    /// The devices of this account.
+    #[xml(child = "device")]
+    pub devices: Vec<Device>,
+}
+
+impl PubSubPayload for DeviceList {}
+
+impl DeviceList {
+    /// Whether the device with this id is in this list.
+    pub fn contains(&self, id: u32) -> bool {
+        self.devices.iter().any(|device| device.id == id)
+    }
+}
+
+/// The public part of the signed pre-key of a device.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "spk")]
+pub struct SignedPreKeyPublic {
+    /// The id of this signed pre-key.
+    #[xml(attribute = "id")]
+    pub id: u32,
+
+    /// The public key.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// The signature of the signed pre-key, made with the identity key.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "spks")]
+pub struct SignedPreKeySignature {
+    /// The signature.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// The public part of the identity key of a device.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "ik")]
+pub struct IdentityKey {
+    /// The public key.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// The public part of a one-time pre-key.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "pk")]
+pub struct PreKeyPublic {
+    /// The id of this pre-key.
+    #[xml(attribute = "id")]
+    pub id: u32,
+
+    /// The public key.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// The list of one-time pre-keys of a device.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "prekeys")]
+pub struct PreKeys {
+    /// The pre-keys, one of which gets used by each new session.
+    #[xml(child = "pk")]
+    pub keys: Vec<PreKeyPublic>,
+}
+
+/// The public keys a device needs to publish for others to start a session with it, in the
+/// item of its `urn:xmpp:omemo:2:bundles` node whose id is its device id.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OMEMO, name = "bundle")]
+pub struct Bundle {
+    /// The signed pre-key.
+    #[xml(child = "spk")]
+    pub spk: SignedPreKeyPublic,
+
+    /// The signature of the signed pre-key.
+    #[xml(child = "spks")]
+    pub spks: SignedPreKeySignature,
+
+    /// The identity key.
+    #[xml(child = "ik")]
+    pub ik: IdentityKey,
+
+    /// The one-time pre-keys.
+    #[xml(child = "prekeys")]
+    pub prekeys: PreKeys,
+}
+
+impl PubSubPayload for Bundle {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ns;
+    use crate::pubsub::{
+        event::Item as EventItem,
+        pubsub::{Item as PubSubItem, Publish},
+        Item, ItemId, NodeName,
+    };
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(IsKeyExchange, 1);
+        assert_size!(Key, 32);
+        assert_size!(Keys, 56);
+        assert_size!(Header, 32);
+        assert_size!(Encrypted, 56);
+        assert_size!(Device, 32);
+        assert_size!(DeviceList, 24);
+        assert_size!(Bundle, 104);
+    }
+
+    #[test]
+    fn test_encrypted() {
+        let elem: Element = "<encrypted xmlns='urn:xmpp:omemo:2'>
+            <header sid='27183'>
+                <keys jid='juliet@capulet.lit'>
+                    <key rid='31415'>AAAA</key>
+                </keys>
+                <keys jid='romeo@montague.lit'>
+                    <key kex='true' rid='1666'>AQID</key>
+                    <key rid='4223'>BAUG</key>
+                </keys>
+            </header>
+            <payload>BwgJ</payload>
+        </encrypted>"
+            .parse()
+            .unwrap();
+        let encrypted = Encrypted::try_from(elem).unwrap();
+        assert_eq!(encrypted.header.sid, 27183);
+        assert_eq!(encrypted.header.keys.len(), 2);
+        let romeo = BareJid::new("romeo", "montague.lit");
+        let key = encrypted.key_for(&romeo, 1666).unwrap();
+        assert_eq!(key.kex, IsKeyExchange::True);
+        assert_eq!(key.data, vec![1, 2, 3]);
+        let key = encrypted.key_for(&romeo, 4223).unwrap();
+        assert_eq!(key.kex, IsKeyExchange::False);
+        assert!(encrypted.key_for(&romeo, 31415).is_none());
+        assert!(encrypted.payload.is_some());
+    }
+
+    #[test]
+    fn test_empty_message() {
+        let encrypted = Encrypted {
+            header: Header {
+                sid: 27183,
+                keys: vec![Keys {
+                    jid: BareJid::new("juliet", "capulet.lit"),
+                    keys: vec![Key {
+                        rid: 31415,
+                        kex: IsKeyExchange::True,
+                        data: vec![0, 0, 0],
+                    }],
+                }],
+            },
+            payload: None,
+        };
+        let elem: Element = "<encrypted xmlns='urn:xmpp:omemo:2'><header sid='27183'><keys jid='juliet@capulet.lit'><key rid='31415' kex='true'>AAAA</key></keys></header></encrypted>"
+            .parse()
+            .unwrap();
+        assert_eq!(Element::from(encrypted.clone()), elem);
+        assert_eq!(Encrypted::try_from(elem).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn test_device_list() {
+        let elem: Element = "<item xmlns='http://jabber.org/protocol/pubsub#event' id='current'>
+            <devices xmlns='urn:xmpp:omemo:2'>
+                <device id='12345'/>
+                <device id='4223' label='Gajim on Ubuntu Linux'/>
+            </devices>
+        </item>"
+            .parse()
+            .unwrap();
+        let item = EventItem::try_from(elem).unwrap();
+        assert_eq!(item.id, Some(ItemId(String::from("current"))));
+        let list = DeviceList::try_from(item.payload.clone().unwrap()).unwrap();
+        assert_eq!(list.devices.len(), 2);
+        assert!(list.contains(4223));
+        assert!(!list.contains(1));
+        assert_eq!(
+            list.devices[1].label,
+            Some(String::from("Gajim on Ubuntu Linux"))
+        );
+    }
+
+    #[test]
+    fn test_publish_bundle() {
+        let bundle = Bundle {
+            spk: SignedPreKeyPublic {
+                id: 0,
+                data: vec![1],
+            },
+            spks: SignedPreKeySignature { data: vec![2] },
+            ik: IdentityKey { data: vec![3] },
+            prekeys: PreKeys {
+                keys: vec![
+                    PreKeyPublic {
+                        id: 0,
+                        data: vec![4],
+                    },
+                    PreKeyPublic {
+                        id: 1,
+                        data: vec![5],
+                    },
+                ],
+            },
+        };
+        let publish = Publish {
+            node: NodeName(String::from(ns::OMEMO_BUNDLES)),
+            items: vec![PubSubItem(Item::new(
+                Some(ItemId(String::from("31415"))),
+                None,
+                Some(bundle.clone()),
+            ))],
+        };
+        let elem: Element = publish.into();
+        let expected: Element = "<publish xmlns='http://jabber.org/protocol/pubsub' node='urn:xmpp:omemo:2:bundles'><item id='31415'><bundle xmlns='urn:xmpp:omemo:2'><spk id='0'>AQ==</spk><spks>Ag==</spks><ik>Aw==</ik><prekeys><pk id='0'>BA==</pk><pk id='1'>BQ==</pk></prekeys></bundle></item></publish>"
+            .parse()
+            .unwrap();
+        assert_eq!(elem, expected);
+
+        let publish = Publish::try_from(elem).unwrap();
+        let payload = publish.items[0].payload.clone().unwrap();
+        assert_eq!(Bundle::try_from(payload).unwrap(), bundle);
+    }
+
+    #[test]
+    fn test_missing_header() {
+        let elem: Element =
+            "<encrypted xmlns='urn:xmpp:omemo:2'><payload>AAAA</payload></encrypted>"
+                .parse()
+                .unwrap();
+        let error = Encrypted::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Missing child header in encrypted element.");
+    }
+}
@@ -7,51 +7,58 @@
 use crate::date::DateTime;
 use crate::pubsub::PubSubPayload;
 use crate::util::helpers::Base64;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
 // TODO: Merge this container with the PubKey struct
-generate_element!(
-    /// Data contained in the PubKey element
-    PubKeyData, "data", OX,
-    text: (
-        /// Base64 data
-        data: Base64<Vec<u8>>
-    )
-);
-
-generate_element!(
-    /// Pubkey element to be used in PubSub publish payloads.
-    PubKey, "pubkey", OX,
-    attributes: [
-        /// Last updated date
-        date: Option<DateTime> = "date"
-    ],
-    children: [
-        /// Public key as base64 data
-        data: Required<PubKeyData> = ("data", OX) => PubKeyData
-    ]
-);
+/// Data contained in the PubKey element
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OX, name = "data")]
+pub struct PubKeyData {
+    /// Base64 data
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// Pubkey element to be used in PubSub publish payloads.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OX, name = "pubkey")]
+pub struct PubKey {
+    /// Last updated date
+    #[xml(attribute = "date")]
+    pub date: Option<DateTime>,
+
+    /// Public key as base64 data
+    #[xml(child = "data")]
+    pub data: PubKeyData,
+}
 
 impl PubSubPayload for PubKey {}
 
-generate_element!(
-    /// Public key metadata
-    PubKeyMeta, "pubkey-metadata", OX,
-    attributes: [
-        /// OpenPGP v4 fingerprint
-        v4fingerprint: Required<String> = "v4-fingerprint",
-        /// Time the key was published or updated
-        date: Required<DateTime> = "date",
-    ]
-);
-
-generate_element!(
-    /// List of public key metadata
-    PubKeysMeta, "public-key-list", OX,
-    children: [
-        /// Public keys
-        pubkeys: Vec<PubKeyMeta> = ("pubkey-metadata", OX) => PubKeyMeta
-    ]
-);
+/// Public key metadata
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OX, name = "pubkey-metadata")]
+pub struct PubKeyMeta {
+    /// OpenPGP v4 fingerprint
+    #[xml(attribute = "v4-fingerprint")]
+    pub v4fingerprint: String,
+
+    /// Time the key was published or updated
+    #[xml(attribute = "date")]
+    pub date: DateTime,
+}
+
+/// List of public key metadata
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = OX, name = "public-key-list")]
+pub struct PubKeysMeta {
+    /// Public keys
+    #[xml(child = "pubkey-metadata")]
+    pub pubkeys: Vec<PubKeyMeta>,
+}
 
 impl PubSubPayload for PubKeysMeta {}
 
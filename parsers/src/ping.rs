@@ -6,14 +6,14 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::iq::IqGetPayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// Represents a ping to the recipient, which must be answered with an
-    /// empty `<iq/>` or with an error.
-    Ping,
-    "ping",
-    PING
-);
+/// Represents a ping to the recipient, which must be answered with an
+/// empty `<iq/>` or with an error.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PING, name = "ping")]
+pub struct Ping;
 
 impl IqGetPayload for Ping {}
 
@@ -8,7 +8,7 @@
 use crate::ns;
 use crate::util::error::Error;
 use jid::Jid;
-use minidom::{Element, IntoAttributeValue};
+use minidom::{AsXml, Element, FromAttributeValue, IntoAttributeValue, XmlWriter};
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -18,6 +18,7 @@ pub trait PresencePayload: TryFrom<Element> + Into<Element> {}
 
 /// Specifies the availability of an entity or resource.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Show {
     /// The entity or resource is temporarily away.
     Away,
@@ -48,15 +49,21 @@ impl FromStr for Show {
     }
 }
 
+impl Show {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Show::Away => "away",
+            Show::Chat => "chat",
+            Show::Dnd => "dnd",
+            Show::Xa => "xa",
+        }
+    }
+}
+
 impl From<Show> for Element {
     fn from(show: Show) -> Element {
         Element::builder("show", ns::DEFAULT_NS)
-            .append(match show {
-                Show::Away => "away",
-                Show::Chat => "chat",
-                Show::Dnd => "dnd",
-                Show::Xa => "xa",
-            })
+            .append(show.as_str())
             .build()
     }
 }
@@ -68,6 +75,7 @@ type Priority = i8;
 
 ///
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Type {
     /// This value is not an acceptable 'type' attribute, it is only used
     /// internally to signal the absence of 'type'.
@@ -127,27 +135,39 @@ impl FromStr for Type {
     }
 }
 
+impl Type {
+    fn as_attribute_value(&self) -> Option<&'static str> {
+        Some(match self {
+            Type::None => return None,
+
+            Type::Error => "error",
+            Type::Probe => "probe",
+            Type::Subscribe => "subscribe",
+            Type::Subscribed => "subscribed",
+            Type::Unavailable => "unavailable",
+            Type::Unsubscribe => "unsubscribe",
+            Type::Unsubscribed => "unsubscribed",
+        })
+    }
+}
+
+impl FromAttributeValue for Type {
+    type Error = Error;
+
+    fn from_attribute_value(value: &str) -> Result<Type, Error> {
+        Type::from_str(value)
+    }
+}
+
 impl IntoAttributeValue for Type {
     fn into_attribute_value(self) -> Option<String> {
-        Some(
-            match self {
-                Type::None => return None,
-
-                Type::Error => "error",
-                Type::Probe => "probe",
-                Type::Subscribe => "subscribe",
-                Type::Subscribed => "subscribed",
-                Type::Unavailable => "unavailable",
-                Type::Unsubscribe => "unsubscribe",
-                Type::Unsubscribed => "unsubscribed",
-            }
-            .to_owned(),
-        )
+        self.as_attribute_value().map(String::from)
     }
 }
 
 /// The main structure representing the `<presence/>` stanza.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Presence {
     /// The sender of this presence.
     pub from: Option<Jid>,
@@ -252,10 +272,10 @@ impl TryFrom<Element> for Presence {
         let mut show = None;
         let mut priority = None;
         let mut presence = Presence {
-            from: get_attr!(root, "from", Option),
-            to: get_attr!(root, "to", Option),
-            id: get_attr!(root, "id", Option),
-            type_: get_attr!(root, "type", Default),
+            from: root.attr_as("from")?,
+            to: root.attr_as("to")?,
+            id: root.attr_as("id")?,
+            type_: root.attr_as("type")?.unwrap_or_default(),
             show: None,
             statuses: BTreeMap::new(),
             priority: 0i8,
@@ -274,7 +294,7 @@ impl TryFrom<Element> for Presence {
             } else if elem.is("status", ns::DEFAULT_NS) {
                 check_no_unknown_attributes!(elem, "status", ["xml:lang"]);
                 check_no_children!(elem, "status");
-                let lang = get_attr!(elem, "xml:lang", Default);
+                let lang = elem.attr_as("xml:lang")?.unwrap_or_default();
                 if presence.statuses.insert(lang, elem.text()).is_some() {
                     return Err(Error::ParseError(
                         "Status element present twice for the same xml:lang.",
@@ -333,6 +353,47 @@ impl From<Presence> for Element {
     }
 }
 
+impl AsXml for Presence {
+    fn write_xml(&self, writer: &mut XmlWriter) -> minidom::Result<()> {
+        writer.start("presence", ns::DEFAULT_NS);
+        if let Some(ref from) = self.from {
+            writer.attr("from", from);
+        }
+        if let Some(ref id) = self.id {
+            writer.attr("id", id);
+        }
+        if let Some(ref to) = self.to {
+            writer.attr("to", to);
+        }
+        if let Some(type_) = self.type_.as_attribute_value() {
+            writer.attr("type", type_);
+        }
+        if let Some(ref show) = self.show {
+            writer.start("show", ns::DEFAULT_NS);
+            writer.text(show.as_str());
+            writer.end();
+        }
+        for (lang, status) in &self.statuses {
+            writer.start("status", ns::DEFAULT_NS);
+            if !lang.is_empty() {
+                writer.attr("xml:lang", lang);
+            }
+            writer.text(status);
+            writer.end();
+        }
+        if self.priority != 0 {
+            writer.start("priority", ns::DEFAULT_NS);
+            writer.text(&self.priority);
+            writer.end();
+        }
+        for payload in &self.payloads {
+            writer.write(payload)?;
+        }
+        writer.end();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -630,11 +691,12 @@ mod tests {
         let elem: Element = presence.into();
         assert_eq!(elem.attr("to"), None);
 
-        let presence = Presence::new(Type::None).with_to(Jid::Bare(BareJid::domain("localhost")));
+        let presence =
+            Presence::new(Type::None).with_to(Jid::Bare(BareJid::from_domain("localhost")));
         let elem: Element = presence.into();
         assert_eq!(elem.attr("to"), Some("localhost"));
 
-        let presence = Presence::new(Type::None).with_to(BareJid::domain("localhost"));
+        let presence = Presence::new(Type::None).with_to(BareJid::from_domain("localhost"));
         let elem: Element = presence.into();
         assert_eq!(elem.attr("to"), Some("localhost"));
 
@@ -651,4 +713,25 @@ mod tests {
         let elem: Element = presence.into();
         assert_eq!(elem.attr("to"), Some("test@localhost/coucou"));
     }
+
+    #[test]
+    fn test_as_xml() {
+        let mut presence = Presence::new(Type::Unavailable)
+            .with_from(FullJid::new("test", "localhost", "coucou"))
+            .with_show(Show::Dnd)
+            .with_priority(-1);
+        presence.set_status("", "Gone <3");
+        presence.set_status("fr", "Parti");
+        presence
+            .payloads
+            .push(Element::builder("test", "invalid").build());
+        let bytes = presence.to_bytes().unwrap();
+        let elem = Element::from(presence);
+        assert_eq!(&bytes[..], String::from(&elem).as_bytes());
+
+        let presence = Presence::new(Type::None);
+        let bytes = presence.to_bytes().unwrap();
+        let elem = Element::from(presence);
+        assert_eq!(&bytes[..], String::from(&elem).as_bytes());
+    }
 }
@@ -16,12 +16,14 @@ use std::convert::TryFrom;
 
 /// Event wrapper for a PubSub `<item/>`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Item(pub PubSubItem);
 
 impl_pubsub_item!(Item, PUBSUB_EVENT);
 
 /// Represents an event happening to a PubSub node.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PubSubEvent {
     /*
     Collection {
@@ -116,7 +118,7 @@ fn parse_items(elem: Element, node: NodeName) -> Result<PubSubEvent, Error> {
             }
             check_no_children!(child, "retract");
             check_no_unknown_attributes!(child, "retract", ["id"]);
-            let id = get_attr!(child, "id", Required);
+            let id = child.required_attr("id")?;
             retracts.push(id);
         } else {
             return Err(Error::ParseError("Invalid child in items element."));
@@ -141,7 +143,7 @@ impl TryFrom<Element> for PubSubEvent {
 
         let mut payload = None;
         for child in elem.children() {
-            let node = get_attr!(child, "node", Required);
+            let node = child.required_attr("node")?;
             if child.is("configuration", ns::PUBSUB_EVENT) {
                 let mut payloads = child.children().cloned().collect::<Vec<_>>();
                 let item = payloads.pop();
@@ -164,7 +166,7 @@ impl TryFrom<Element> for PubSubEvent {
                                 "More than one redirect in delete element.",
                             ));
                         }
-                        let uri = get_attr!(item, "uri", Required);
+                        let uri = item.required_attr("uri")?;
                         redirect = Some(uri);
                     } else {
                         return Err(Error::ParseError("Unknown child in delete element."));
@@ -180,10 +182,10 @@ impl TryFrom<Element> for PubSubEvent {
                 check_no_children!(child, "subscription");
                 payload = Some(PubSubEvent::Subscription {
                     node,
-                    expiry: get_attr!(child, "expiry", Option),
-                    jid: get_attr!(child, "jid", Option),
-                    subid: get_attr!(child, "subid", Option),
-                    subscription: get_attr!(child, "subscription", Option),
+                    expiry: child.attr_as("expiry")?,
+                    jid: child.attr_as("jid")?,
+                    subid: child.attr_as("subid")?,
+                    subscription: child.attr_as("subscription")?,
                 });
             } else {
                 return Err(Error::ParseError("Unknown child in event element."));
@@ -18,65 +18,80 @@ pub use self::owner::PubSubOwner;
 pub use self::pubsub::PubSub;
 
 use crate::{Element, Jid};
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The name of a PubSub node, used to identify it on a JID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct NodeName(pub String);
+
+/// The identifier of an item, which is unique per node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct ItemId(pub String);
+
+/// The identifier of a subscription to a PubSub node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct SubscriptionId(pub String);
+
+/// The state of a subscription to a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "subscription")]
+pub enum Subscription {
+    /// The user is not subscribed to this node.
+    #[xml(value = "none", default)]
+    None,
+
+    /// The user’s subscription to this node is still pending.
+    #[xml(value = "pending")]
+    Pending,
+
+    /// The user is subscribed to this node.
+    #[xml(value = "subscribed")]
+    Subscribed,
+
+    /// The user’s subscription to this node will only be valid once
+    /// configured.
+    #[xml(value = "unconfigured")]
+    Unconfigured,
+}
 
-generate_id!(
-    /// The name of a PubSub node, used to identify it on a JID.
-    NodeName
-);
-
-generate_id!(
-    /// The identifier of an item, which is unique per node.
-    ItemId
-);
-
-generate_id!(
-    /// The identifier of a subscription to a PubSub node.
-    SubscriptionId
-);
-
-generate_attribute!(
-    /// The state of a subscription to a node.
-    Subscription, "subscription", {
-        /// The user is not subscribed to this node.
-        None => "none",
-
-        /// The user’s subscription to this node is still pending.
-        Pending => "pending",
-
-        /// The user is subscribed to this node.
-        Subscribed => "subscribed",
-
-        /// The user’s subscription to this node will only be valid once
-        /// configured.
-        Unconfigured => "unconfigured",
-    }, Default = None
-);
-
-generate_attribute!(
-    /// A list of possible affiliations to a node.
-    AffiliationAttribute, "affiliation", {
-        /// You are a member of this node, you can subscribe and retrieve items.
-        Member => "member",
-
-        /// You don’t have a specific affiliation with this node, you can only subscribe to it.
-        None => "none",
-
-        /// You are banned from this node.
-        Outcast => "outcast",
-
-        /// You are an owner of this node, and can do anything with it.
-        Owner => "owner",
-
-        /// You are a publisher on this node, you can publish and retract items to it.
-        Publisher => "publisher",
-
-        /// You can publish and retract items on this node, but not subscribe or retrieve items.
-        PublishOnly => "publish-only",
-    }
-);
+/// A list of possible affiliations to a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "affiliation")]
+pub enum AffiliationAttribute {
+    /// You are a member of this node, you can subscribe and retrieve items.
+    #[xml(value = "member")]
+    Member,
+
+    /// You don’t have a specific affiliation with this node, you can only subscribe to it.
+    #[xml(value = "none")]
+    None,
+
+    /// You are banned from this node.
+    #[xml(value = "outcast")]
+    Outcast,
+
+    /// You are an owner of this node, and can do anything with it.
+    #[xml(value = "owner")]
+    Owner,
+
+    /// You are a publisher on this node, you can publish and retract items to it.
+    #[xml(value = "publisher")]
+    Publisher,
+
+    /// You can publish and retract items on this node, but not subscribe or retrieve items.
+    #[xml(value = "publish-only")]
+    PublishOnly,
+}
 
 /// An item from a PubSub node.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Item {
     /// The identifier for this item, unique per node.
     pub id: Option<ItemId>,
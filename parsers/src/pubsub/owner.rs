@@ -13,117 +13,131 @@ use crate::util::error::Error;
 use crate::Element;
 use jid::Jid;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// A list of affiliations you have on a service, or on a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "affiliations")]
+pub struct Affiliations {
+    /// The node name this request pertains to.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+
+    /// The actual list of affiliation elements.
+    #[xml(child = "affiliation")]
+    pub affiliations: Vec<Affiliation>,
+}
+
+/// An affiliation element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "affiliation")]
+pub struct Affiliation {
+    /// The node this affiliation pertains to.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
+    /// The affiliation you currently have on this node.
+    #[xml(attribute = "affiliation")]
+    pub affiliation: AffiliationAttribute,
+}
+
+/// Request to configure a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "configure")]
+pub struct Configure {
+    /// The node to be configured.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+
+    /// The form to configure it.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+}
+
+/// Request to change default configuration.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "default")]
+pub struct Default {
+    /// The form to configure it.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+}
+
+/// Request to delete a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "delete")]
+pub struct Delete {
+    /// The node to be configured.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+
+    /// Redirection to replace the deleted node.
+    #[xml(child = "redirect")]
+    pub redirect: Option<Redirect>,
+}
+
+/// A redirect element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "redirect")]
+pub struct Redirect {
+    /// The node this node will be redirected to.
+    #[xml(attribute = "uri")]
+    pub uri: String,
+}
+
+/// Request to delete a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "purge")]
+pub struct Purge {
+    /// The node to be configured.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+}
+
+/// A request for current subscriptions.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "subscriptions")]
+pub struct Subscriptions {
+    /// The node to query.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+
+    /// The list of subscription elements returned.
+    #[xml(child = "subscription")]
+    pub subscriptions: Vec<SubscriptionElem>,
+}
 
-generate_element!(
-    /// A list of affiliations you have on a service, or on a node.
-    Affiliations, "affiliations", PUBSUB_OWNER,
-    attributes: [
-        /// The node name this request pertains to.
-        node: Required<NodeName> = "node",
-    ],
-    children: [
-        /// The actual list of affiliation elements.
-        affiliations: Vec<Affiliation> = ("affiliation", PUBSUB_OWNER) => Affiliation
-    ]
-);
-
-generate_element!(
-    /// An affiliation element.
-    Affiliation, "affiliation", PUBSUB_OWNER,
-    attributes: [
-        /// The node this affiliation pertains to.
-        jid: Required<Jid> = "jid",
-
-        /// The affiliation you currently have on this node.
-        affiliation: Required<AffiliationAttribute> = "affiliation",
-    ]
-);
-
-generate_element!(
-    /// Request to configure a node.
-    Configure, "configure", PUBSUB_OWNER,
-    attributes: [
-        /// The node to be configured.
-        node: Option<NodeName> = "node",
-    ],
-    children: [
-        /// The form to configure it.
-        form: Option<DataForm> = ("x", DATA_FORMS) => DataForm
-    ]
-);
-
-generate_element!(
-    /// Request to change default configuration.
-    Default, "default", PUBSUB_OWNER,
-    children: [
-        /// The form to configure it.
-        form: Option<DataForm> = ("x", DATA_FORMS) => DataForm
-    ]
-);
-
-generate_element!(
-    /// Request to delete a node.
-    Delete, "delete", PUBSUB_OWNER,
-    attributes: [
-        /// The node to be configured.
-        node: Required<NodeName> = "node",
-    ],
-    children: [
-        /// Redirection to replace the deleted node.
-        redirect: Option<Redirect> = ("redirect", PUBSUB_OWNER) => Redirect
-    ]
-);
-
-generate_element!(
-    /// A redirect element.
-    Redirect, "redirect", PUBSUB_OWNER,
-    attributes: [
-        /// The node this node will be redirected to.
-        uri: Required<String> = "uri",
-    ]
-);
-
-generate_element!(
-    /// Request to delete a node.
-    Purge, "purge", PUBSUB_OWNER,
-    attributes: [
-        /// The node to be configured.
-        node: Required<NodeName> = "node",
-    ]
-);
-
-generate_element!(
-    /// A request for current subscriptions.
-    Subscriptions, "subscriptions", PUBSUB_OWNER,
-    attributes: [
-        /// The node to query.
-        node: Required<NodeName> = "node",
-    ],
-    children: [
-        /// The list of subscription elements returned.
-        subscriptions: Vec<SubscriptionElem> = ("subscription", PUBSUB_OWNER) => SubscriptionElem
-    ]
-);
-
-generate_element!(
-    /// A subscription element, describing the state of a subscription.
-    SubscriptionElem, "subscription", PUBSUB_OWNER,
-    attributes: [
-        /// The JID affected by this subscription.
-        jid: Required<Jid> = "jid",
-
-        /// The state of the subscription.
-        subscription: Required<Subscription> = "subscription",
-
-        /// Subscription unique id.
-        subid: Option<String> = "subid",
-    ]
-);
+/// A subscription element, describing the state of a subscription.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB_OWNER, name = "subscription")]
+pub struct SubscriptionElem {
+    /// The JID affected by this subscription.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
+    /// The state of the subscription.
+    #[xml(attribute = "subscription")]
+    pub subscription: Subscription,
+
+    /// Subscription unique id.
+    #[xml(attribute = "subid")]
+    pub subid: Option<String>,
+}
 
 /// Main payload used to communicate with a PubSubOwner service.
 ///
 /// `<pubsub xmlns="http://jabber.org/protocol/pubsub#owner"/>`
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PubSubOwner {
     /// Manage the affiliations of a node.
     Affiliations(Affiliations),
@@ -14,83 +14,92 @@ use crate::util::error::Error;
 use crate::Element;
 use jid::Jid;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
 // TODO: a better solution would be to split this into a query and a result elements, like for
 // XEP-0030.
-generate_element!(
-    /// A list of affiliations you have on a service, or on a node.
-    Affiliations, "affiliations", PUBSUB,
-    attributes: [
-        /// The optional node name this request pertains to.
-        node: Option<NodeName> = "node",
-    ],
-    children: [
-        /// The actual list of affiliation elements.
-        affiliations: Vec<Affiliation> = ("affiliation", PUBSUB) => Affiliation
-    ]
-);
-
-generate_element!(
-    /// An affiliation element.
-    Affiliation, "affiliation", PUBSUB,
-    attributes: [
-        /// The node this affiliation pertains to.
-        node: Required<NodeName> = "node",
-
-        /// The affiliation you currently have on this node.
-        affiliation: Required<AffiliationAttribute> = "affiliation",
-    ]
-);
-
-generate_element!(
-    /// Request to configure a new node.
-    Configure, "configure", PUBSUB,
-    children: [
-        /// The form to configure it.
-        form: Option<DataForm> = ("x", DATA_FORMS) => DataForm
-    ]
-);
-
-generate_element!(
-    /// Request to create a new node.
-    Create, "create", PUBSUB,
-    attributes: [
-        /// The node name to create, if `None` the service will generate one.
-        node: Option<NodeName> = "node",
-    ]
-);
-
-generate_element!(
-    /// Request for a default node configuration.
-    Default, "default", PUBSUB,
-    attributes: [
-        /// The node targeted by this request, otherwise the entire service.
-        node: Option<NodeName> = "node",
+/// A list of affiliations you have on a service, or on a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "affiliations")]
+pub struct Affiliations {
+    /// The optional node name this request pertains to.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+
+    /// The actual list of affiliation elements.
+    #[xml(child = "affiliation")]
+    pub affiliations: Vec<Affiliation>,
+}
+
+/// An affiliation element.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "affiliation")]
+pub struct Affiliation {
+    /// The node this affiliation pertains to.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+
+    /// The affiliation you currently have on this node.
+    #[xml(attribute = "affiliation")]
+    pub affiliation: AffiliationAttribute,
+}
 
-        // TODO: do we really want to support collection nodes?
-        // type: Option<String> = "type",
-    ]
-);
+/// Request to configure a new node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "configure")]
+pub struct Configure {
+    /// The form to configure it.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+}
 
-generate_element!(
-    /// A request for a list of items.
-    Items, "items", PUBSUB,
-    attributes: [
-        // TODO: should be an xs:positiveInteger, that is, an unbounded int ≥ 1.
-        /// Maximum number of items returned.
-        max_items: Option<u32> = "max_items",
-
-        /// The node queried by this request.
-        node: Required<NodeName> = "node",
-
-        /// The subscription identifier related to this request.
-        subid: Option<SubscriptionId> = "subid",
-    ],
-    children: [
-        /// The actual list of items returned.
-        items: Vec<Item> = ("item", PUBSUB) => Item
-    ]
-);
+/// Request to create a new node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "create")]
+pub struct Create {
+    /// The node name to create, if `None` the service will generate one.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+}
+
+/// Request for a default node configuration.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "default")]
+pub struct Default {
+    /// The node targeted by this request, otherwise the entire service.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+    // TODO: do we really want to support collection nodes?
+    // type: Option<String> = "type",
+}
+
+/// A request for a list of items.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "items")]
+pub struct Items {
+    // TODO: should be an xs:positiveInteger, that is, an unbounded int ≥ 1.
+    /// Maximum number of items returned.
+    #[xml(attribute = "max_items")]
+    pub max_items: Option<u32>,
+
+    /// The node queried by this request.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+
+    /// The subscription identifier related to this request.
+    #[xml(attribute = "subid")]
+    pub subid: Option<SubscriptionId>,
+
+    /// The actual list of items returned.
+    #[xml(child = "item")]
+    pub items: Vec<Item>,
+}
 
 impl Items {
     /// Create a new items request.
@@ -106,76 +115,91 @@ impl Items {
 
 /// Response wrapper for a PubSub `<item/>`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Item(pub PubSubItem);
 
 impl_pubsub_item!(Item, PUBSUB);
 
-generate_element!(
-    /// The options associated to a subscription request.
-    Options, "options", PUBSUB,
-    attributes: [
-        /// The JID affected by this request.
-        jid: Required<Jid> = "jid",
-
-        /// The node affected by this request.
-        node: Option<NodeName> = "node",
-
-        /// The subscription identifier affected by this request.
-        subid: Option<SubscriptionId> = "subid",
-    ],
-    children: [
-        /// The form describing the subscription.
-        form: Option<DataForm> = ("x", DATA_FORMS) => DataForm
-    ]
-);
-
-generate_element!(
-    /// Request to publish items to a node.
-    Publish, "publish", PUBSUB,
-    attributes: [
-        /// The target node for this operation.
-        node: Required<NodeName> = "node",
-    ],
-    children: [
-        /// The items you want to publish.
-        items: Vec<Item> = ("item", PUBSUB) => Item
-    ]
-);
-
-generate_element!(
-    /// The options associated to a publish request.
-    PublishOptions, "publish-options", PUBSUB,
-    children: [
-        /// The form describing these options.
-        form: Option<DataForm> = ("x", DATA_FORMS) => DataForm
-    ]
-);
-
-generate_attribute!(
+/// The options associated to a subscription request.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "options")]
+pub struct Options {
+    /// The JID affected by this request.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
+    /// The node affected by this request.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+
+    /// The subscription identifier affected by this request.
+    #[xml(attribute = "subid")]
+    pub subid: Option<SubscriptionId>,
+
+    /// The form describing the subscription.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+}
+
+/// Request to publish items to a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "publish")]
+pub struct Publish {
+    /// The target node for this operation.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+
+    /// The items you want to publish.
+    #[xml(child = "item")]
+    pub items: Vec<Item>,
+}
+
+/// The options associated to a publish request.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "publish-options")]
+pub struct PublishOptions {
+    /// The form describing these options.
+    #[xml(child = "x", namespace = DATA_FORMS)]
+    pub form: Option<DataForm>,
+}
+
+/// Whether a retract request should notify subscribers or not.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "notify")]
+pub enum Notify {
+    /// True value, represented by either 'true' or '1'.
+    #[xml(value = "true", alias = "1")]
+    True,
+
+    /// False value, represented by either 'false' or '0'.
+    #[xml(value = "false", alias = "0", default)]
+    False,
+}
+
+/// A request to retract some items from a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "retract")]
+pub struct Retract {
+    /// The node affected by this request.
+    #[xml(attribute = "node")]
+    pub node: NodeName,
+
     /// Whether a retract request should notify subscribers or not.
-    Notify,
-    "notify",
-    bool
-);
+    #[xml(attribute = "notify", default)]
+    pub notify: Notify,
 
-generate_element!(
-    /// A request to retract some items from a node.
-    Retract, "retract", PUBSUB,
-    attributes: [
-        /// The node affected by this request.
-        node: Required<NodeName> = "node",
-
-        /// Whether a retract request should notify subscribers or not.
-        notify: Default<Notify> = "notify",
-    ],
-    children: [
-        /// The items affected by this request.
-        items: Vec<Item> = ("item", PUBSUB) => Item
-    ]
-);
+    /// The items affected by this request.
+    #[xml(child = "item")]
+    pub items: Vec<Item>,
+}
 
 /// Indicate that the subscription can be configured.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubscribeOptions {
     /// If `true`, the configuration is actually required.
     required: bool,
@@ -218,72 +242,83 @@ impl From<SubscribeOptions> for Element {
     }
 }
 
-generate_element!(
-    /// A request to subscribe a JID to a node.
-    Subscribe, "subscribe", PUBSUB,
-    attributes: [
-        /// The JID being subscribed.
-        jid: Required<Jid> = "jid",
-
-        /// The node to subscribe to.
-        node: Option<NodeName> = "node",
-    ]
-);
+/// A request to subscribe a JID to a node.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "subscribe")]
+pub struct Subscribe {
+    /// The JID being subscribed.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
+    /// The node to subscribe to.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+}
 
-generate_element!(
-    /// A request for current subscriptions.
-    Subscriptions, "subscriptions", PUBSUB,
-    attributes: [
-        /// The node to query.
-        node: Option<NodeName> = "node",
-    ],
-    children: [
-        /// The list of subscription elements returned.
-        subscription: Vec<SubscriptionElem> = ("subscription", PUBSUB) => SubscriptionElem
-    ]
-);
-
-generate_element!(
-    /// A subscription element, describing the state of a subscription.
-    SubscriptionElem, "subscription", PUBSUB,
-    attributes: [
-        /// The JID affected by this subscription.
-        jid: Required<Jid> = "jid",
-
-        /// The node affected by this subscription.
-        node: Option<NodeName> = "node",
-
-        /// The subscription identifier for this subscription.
-        subid: Option<SubscriptionId> = "subid",
-
-        /// The state of the subscription.
-        subscription: Option<Subscription> = "subscription",
-    ],
-    children: [
-        /// The options related to this subscription.
-        subscribe_options: Option<SubscribeOptions> = ("subscribe-options", PUBSUB) => SubscribeOptions
-    ]
-);
-
-generate_element!(
-    /// An unsubscribe request.
-    Unsubscribe, "unsubscribe", PUBSUB,
-    attributes: [
-        /// The JID affected by this request.
-        jid: Required<Jid> = "jid",
+/// A request for current subscriptions.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "subscriptions")]
+pub struct Subscriptions {
+    /// The node to query.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+
+    /// The list of subscription elements returned.
+    #[xml(child = "subscription")]
+    pub subscription: Vec<SubscriptionElem>,
+}
 
-        /// The node affected by this request.
-        node: Option<NodeName> = "node",
+/// A subscription element, describing the state of a subscription.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "subscription")]
+pub struct SubscriptionElem {
+    /// The JID affected by this subscription.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
+    /// The node affected by this subscription.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+
+    /// The subscription identifier for this subscription.
+    #[xml(attribute = "subid")]
+    pub subid: Option<SubscriptionId>,
+
+    /// The state of the subscription.
+    #[xml(attribute = "subscription")]
+    pub subscription: Option<Subscription>,
+
+    /// The options related to this subscription.
+    #[xml(child = "subscribe-options")]
+    pub subscribe_options: Option<SubscribeOptions>,
+}
 
-        /// The subscription identifier for this subscription.
-        subid: Option<SubscriptionId> = "subid",
-    ]
-);
+/// An unsubscribe request.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = PUBSUB, name = "unsubscribe")]
+pub struct Unsubscribe {
+    /// The JID affected by this request.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+
+    /// The node affected by this request.
+    #[xml(attribute = "node")]
+    pub node: Option<NodeName>,
+
+    /// The subscription identifier for this subscription.
+    #[xml(attribute = "subid")]
+    pub subid: Option<SubscriptionId>,
+}
 
 /// Main payload used to communicate with a PubSub service.
 ///
 /// `<pubsub xmlns="http://jabber.org/protocol/pubsub"/>`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PubSub {
     /// Request to create a new node, with optional suggested name and suggested configuration.
     Create {
@@ -0,0 +1,108 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// A single reaction, usually an emoji.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = REACTIONS, name = "reaction")]
+pub struct Reaction {
+    /// The emoji of this reaction.
+    #[xml(text)]
+    pub emoji: String,
+}
+
+impl Reaction {
+    /// Creates a reaction with this emoji.
+    pub fn new<E: Into<String>>(emoji: E) -> Reaction {
+        Reaction {
+            emoji: emoji.into(),
+        }
+    }
+}
+
+/// The full set of reactions of the sender to a message, replacing any previous one.
+///
+/// The message is referenced by its
+/// [reference id](../message/struct.Message.html#method.reference_id).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = REACTIONS, name = "reactions")]
+pub struct Reactions {
+    /// The id of the message these reactions apply to.
+    #[xml(attribute = "id")]
+    pub id: String,
+
+    /// The reactions, an empty list removing all of them.
+    #[xml(child = "reaction")]
+    pub reactions: Vec<Reaction>,
+}
+
+impl Reactions {
+    /// Creates the reactions to the message of this id.
+    pub fn new<I: Into<String>>(id: I, reactions: Vec<Reaction>) -> Reactions {
+        Reactions {
+            id: id.into(),
+            reactions,
+        }
+    }
+}
+
+impl MessagePayload for Reactions {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Reaction, 24);
+        assert_size!(Reactions, 48);
+    }
+
+    #[test]
+    fn test_reactions() {
+        let elem: Element = "<reactions xmlns='urn:xmpp:reactions:0' id='744f6e18-a57a-11e9-a656-4889e7820c76'><reaction>👋</reaction><reaction>🐢</reaction></reactions>"
+            .parse()
+            .unwrap();
+        let reactions = Reactions::try_from(elem).unwrap();
+        assert_eq!(reactions.id, "744f6e18-a57a-11e9-a656-4889e7820c76");
+        assert_eq!(
+            reactions.reactions,
+            vec![Reaction::new("👋"), Reaction::new("🐢")]
+        );
+    }
+
+    #[test]
+    fn test_removal() {
+        let reactions = Reactions::new("foo", vec![]);
+        let elem: Element = reactions.clone().into();
+        assert_eq!(
+            String::from(&elem),
+            "<reactions xmlns=\"urn:xmpp:reactions:0\" id=\"foo\"/>"
+        );
+        assert_eq!(Reactions::try_from(elem).unwrap(), reactions);
+    }
+
+    #[test]
+    fn test_missing_id() {
+        let elem: Element = "<reactions xmlns='urn:xmpp:reactions:0'/>".parse().unwrap();
+        let error = Reactions::try_from(elem).unwrap_err();
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
+            _ => panic!(),
+        };
+        assert_eq!(attribute, "id");
+    }
+}
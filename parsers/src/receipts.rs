@@ -5,26 +5,27 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::message::MessagePayload;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// Requests that this message is acked by the final recipient once
-    /// received.
-    Request,
-    "request",
-    RECEIPTS
-);
+/// Requests that this message is acked by the final recipient once
+/// received.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = RECEIPTS, name = "request")]
+pub struct Request;
 
 impl MessagePayload for Request {}
 
-generate_element!(
-    /// Notes that a previous message has correctly been received, it is
-    /// referenced by its 'id' attribute.
-    Received, "received", RECEIPTS,
-    attributes: [
-        /// The 'id' attribute of the received message.
-        id: Required<String> = "id",
-    ]
-);
+/// Notes that a previous message has correctly been received, it is
+/// referenced by its 'id' attribute.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = RECEIPTS, name = "received")]
+pub struct Received {
+    /// The 'id' attribute of the received message.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
 
 impl MessagePayload for Received {}
 
@@ -65,11 +66,15 @@ mod tests {
     fn test_missing_id() {
         let elem: Element = "<received xmlns='urn:xmpp:receipts'/>".parse().unwrap();
         let error = Received::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'id' missing.");
+        assert_eq!(attribute, "id");
     }
 
     #[test]
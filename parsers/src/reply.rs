@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::message::MessagePayload;
+use jid::Jid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Marks this message as a reply to a previous one, referenced by its
+/// [reference id](../message/struct.Message.html#method.reference_id).
+///
+/// See [Message::reply()](../message/struct.Message.html#method.reply) to create one.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = REPLY, name = "reply")]
+pub struct Reply {
+    /// The full JID of the author of the message replied to.
+    #[xml(attribute = "to")]
+    pub to: Option<Jid>,
+
+    /// The id of the message replied to.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
+
+impl MessagePayload for Reply {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Reply, 64);
+    }
+
+    #[test]
+    fn test_simple() {
+        let elem: Element =
+            "<reply xmlns='urn:xmpp:reply:0' to='anna@example.com/laptop' id='message-id1'/>"
+                .parse()
+                .unwrap();
+        let reply = Reply::try_from(elem).unwrap();
+        assert_eq!(
+            reply.to,
+            Some(Jid::from_str("anna@example.com/laptop").unwrap())
+        );
+        assert_eq!(reply.id, "message-id1");
+    }
+
+    #[test]
+    fn test_missing_id() {
+        let elem: Element = "<reply xmlns='urn:xmpp:reply:0'/>".parse().unwrap();
+        let error = Reply::try_from(elem).unwrap_err();
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
+            _ => panic!(),
+        };
+        assert_eq!(attribute, "id");
+    }
+}
@@ -6,83 +6,97 @@
 
 use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
 use jid::BareJid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Represents a group a contact is part of.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = ROSTER, name = "group")]
+pub struct Group(pub String);
+
+/// The state of your mutual subscription with a contact.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "subscription")]
+pub enum Subscription {
+    /// The user doesn’t have any subscription to this contact’s presence,
+    /// and neither does this contact.
+    #[xml(value = "none", default)]
+    None,
+
+    /// Only this contact has a subscription with you, not the opposite.
+    #[xml(value = "from")]
+    From,
+
+    /// Only you have a subscription with this contact, not the opposite.
+    #[xml(value = "to")]
+    To,
+
+    /// Both you and your contact are subscribed to each other’s presence.
+    #[xml(value = "both")]
+    Both,
+
+    /// In a roster set, this asks the server to remove this contact item
+    /// from your roster.
+    #[xml(value = "remove")]
+    Remove,
+}
+
+/// The sub-state of subscription with a contact.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "ask")]
+pub enum Ask {
+    /// Pending sub-state of the 'none' subscription state.
+    #[xml(value = "subscribe")]
+    Subscribe,
+
+    /// Value when absent.
+    #[xml(default)]
+    None,
+}
+
+/// Contact from the user’s contact list.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = ROSTER, name = "item")]
+pub struct Item {
+    /// JID of this contact.
+    #[xml(attribute = "jid")]
+    pub jid: BareJid,
+
+    /// Name of this contact.
+    #[xml(attribute = "name", empty_as_none)]
+    pub name: Option<String>,
+
+    /// Subscription status of this contact.
+    #[xml(attribute = "subscription", default)]
+    pub subscription: Subscription,
+
+    /// Indicates “Pending Out” sub-states for this contact.
+    #[xml(attribute = "ask", default)]
+    pub ask: Ask,
+
+    /// Groups this contact is part of.
+    #[xml(child = "group")]
+    pub groups: Vec<Group>,
+}
 
-generate_elem_id!(
-    /// Represents a group a contact is part of.
-    Group,
-    "group",
-    ROSTER
-);
-
-generate_attribute!(
-    /// The state of your mutual subscription with a contact.
-    Subscription, "subscription", {
-        /// The user doesn’t have any subscription to this contact’s presence,
-        /// and neither does this contact.
-        None => "none",
-
-        /// Only this contact has a subscription with you, not the opposite.
-        From => "from",
-
-        /// Only you have a subscription with this contact, not the opposite.
-        To => "to",
-
-        /// Both you and your contact are subscribed to each other’s presence.
-        Both => "both",
-
-        /// In a roster set, this asks the server to remove this contact item
-        /// from your roster.
-        Remove => "remove",
-    }, Default = None
-);
-
-generate_attribute!(
-    /// The sub-state of subscription with a contact.
-    Ask, "ask", (
-        /// Pending sub-state of the 'none' subscription state.
-        Subscribe => "subscribe"
-    )
-);
-
-generate_element!(
-    /// Contact from the user’s contact list.
-    Item, "item", ROSTER,
-    attributes: [
-        /// JID of this contact.
-        jid: Required<BareJid> = "jid",
-
-        /// Name of this contact.
-        name: OptionEmpty<String> = "name",
-
-        /// Subscription status of this contact.
-        subscription: Default<Subscription> = "subscription",
-
-        /// Indicates “Pending Out” sub-states for this contact.
-        ask: Default<Ask> = "ask",
-    ],
-
-    children: [
-        /// Groups this contact is part of.
-        groups: Vec<Group> = ("group", ROSTER) => Group
-    ]
-);
-
-generate_element!(
-    /// The contact list of the user.
-    Roster, "query", ROSTER,
-    attributes: [
-        /// Version of the contact list.
-        ///
-        /// This is an opaque string that should only be sent back to the server on
-        /// a new connection, if this client is storing the contact list between
-        /// connections.
-        ver: Option<String> = "ver"
-    ],
-    children: [
-        /// List of the contacts of the user.
-        items: Vec<Item> = ("item", ROSTER) => Item
-    ]
-);
+/// The contact list of the user.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = ROSTER, name = "query")]
+pub struct Roster {
+    /// Version of the contact list.
+    ///
+    /// This is an opaque string that should only be sent back to the server on
+    /// a new connection, if this client is storing the contact list between
+    /// connections.
+    #[xml(attribute = "ver")]
+    pub ver: Option<String>,
+
+    /// List of the contacts of the user.
+    #[xml(child = "item")]
+    pub items: Vec<Item>,
+}
 
 impl IqGetPayload for Roster {}
 impl IqSetPayload for Roster {}
@@ -102,7 +116,7 @@ mod tests {
         assert_size!(Group, 12);
         assert_size!(Subscription, 1);
         assert_size!(Ask, 1);
-        assert_size!(Item, 52);
+        assert_size!(Item, 44);
         assert_size!(Roster, 24);
     }
 
@@ -112,7 +126,7 @@ mod tests {
         assert_size!(Group, 24);
         assert_size!(Subscription, 1);
         assert_size!(Ask, 1);
-        assert_size!(Item, 104);
+        assert_size!(Item, 88);
         assert_size!(Roster, 48);
     }
 
@@ -283,11 +297,15 @@ mod tests {
             .parse()
             .unwrap();
         let error = Roster::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'jid' missing.");
+        assert_eq!(attribute, "jid");
 
         /*
         let elem: Element = "<query xmlns='jabber:iq:roster'><item jid=''/></query>".parse().unwrap();
@@ -12,6 +12,7 @@ use std::convert::TryFrom;
 /// Requests paging through a potentially big set of items (represented by an
 /// UID).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetQuery {
     /// Limit the number of items, or use the recipient’s defaults if None.
     pub max: Option<usize>,
@@ -93,6 +94,7 @@ impl From<SetQuery> for Element {
 
 /// Describes the paging result of a [query](struct.SetQuery.html).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetResult {
     /// The UID of the first item of the page.
     pub first: Option<String>,
@@ -124,7 +126,7 @@ impl TryFrom<Element> for SetResult {
                 if set.first.is_some() {
                     return Err(Error::ParseError("Set can’t have more than one first."));
                 }
-                set.first_index = get_attr!(child, "index", Option);
+                set.first_index = child.attr_as("index")?;
                 set.first = Some(child.text());
             } else if child.is("last", ns::RSM) {
                 if set.last.is_some() {
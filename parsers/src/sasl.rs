@@ -10,138 +10,160 @@ use crate::util::helpers::Base64;
 use crate::Element;
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The list of available SASL mechanisms.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "mechanism")]
+pub enum Mechanism {
+    /// Uses no hashing mechanism and transmit the password in clear to the
+    /// server, using a single step.
+    #[xml(value = "PLAIN")]
+    Plain,
+
+    /// Challenge-based mechanism using HMAC and SHA-1, allows both the
+    /// client and the server to avoid having to store the password in
+    /// clear.
+    ///
+    /// See https://tools.ietf.org/html/rfc5802
+    #[xml(value = "SCRAM-SHA-1")]
+    ScramSha1,
+
+    /// Same as [ScramSha1](#structfield.ScramSha1), with the addition of
+    /// channel binding.
+    #[xml(value = "SCRAM-SHA-1-PLUS")]
+    ScramSha1Plus,
+
+    /// Same as [ScramSha1](#structfield.ScramSha1), but using SHA-256
+    /// instead of SHA-1 as the hash function.
+    #[xml(value = "SCRAM-SHA-256")]
+    ScramSha256,
+
+    /// Same as [ScramSha256](#structfield.ScramSha256), with the addition
+    /// of channel binding.
+    #[xml(value = "SCRAM-SHA-256-PLUS")]
+    ScramSha256Plus,
+
+    /// Creates a temporary JID on login, which will be destroyed on
+    /// disconnect.
+    #[xml(value = "ANONYMOUS")]
+    Anonymous,
+}
 
-generate_attribute!(
-    /// The list of available SASL mechanisms.
-    Mechanism, "mechanism", {
-        /// Uses no hashing mechanism and transmit the password in clear to the
-        /// server, using a single step.
-        Plain => "PLAIN",
-
-        /// Challenge-based mechanism using HMAC and SHA-1, allows both the
-        /// client and the server to avoid having to store the password in
-        /// clear.
-        ///
-        /// See https://tools.ietf.org/html/rfc5802
-        ScramSha1 => "SCRAM-SHA-1",
-
-        /// Same as [ScramSha1](#structfield.ScramSha1), with the addition of
-        /// channel binding.
-        ScramSha1Plus => "SCRAM-SHA-1-PLUS",
-
-        /// Same as [ScramSha1](#structfield.ScramSha1), but using SHA-256
-        /// instead of SHA-1 as the hash function.
-        ScramSha256 => "SCRAM-SHA-256",
-
-        /// Same as [ScramSha256](#structfield.ScramSha256), with the addition
-        /// of channel binding.
-        ScramSha256Plus => "SCRAM-SHA-256-PLUS",
-
-        /// Creates a temporary JID on login, which will be destroyed on
-        /// disconnect.
-        Anonymous => "ANONYMOUS",
-    }
-);
-
-generate_element!(
-    /// The first step of the SASL process, selecting the mechanism and sending
-    /// the first part of the handshake.
-    Auth, "auth", SASL,
-    attributes: [
-        /// The mechanism used.
-        mechanism: Required<Mechanism> = "mechanism"
-    ],
-    text: (
-        /// The content of the handshake.
-        data: Base64<Vec<u8>>
-    )
-);
-
-generate_element!(
-    /// In case the mechanism selected at the [auth](struct.Auth.html) step
-    /// requires a second step, the server sends this element with additional
-    /// data.
-    Challenge, "challenge", SASL,
-    text: (
-        /// The challenge data.
-        data: Base64<Vec<u8>>
-    )
-);
-
-generate_element!(
-    /// In case the mechanism selected at the [auth](struct.Auth.html) step
-    /// requires a second step, this contains the client’s response to the
-    /// server’s [challenge](struct.Challenge.html).
-    Response, "response", SASL,
-    text: (
-        /// The response data.
-        data: Base64<Vec<u8>>
-    )
-);
-
-generate_empty_element!(
-    /// Sent by the client at any point after [auth](struct.Auth.html) if it
-    /// wants to cancel the current authentication process.
-    Abort,
-    "abort",
-    SASL
-);
-
-generate_element!(
-    /// Sent by the server on SASL success.
-    Success, "success", SASL,
-    text: (
-        /// Possible data sent on success.
-        data: Base64<Vec<u8>>
-    )
-);
-
-generate_element_enum!(
-    /// List of possible failure conditions for SASL.
-    DefinedCondition, "defined-condition", SASL, {
-        /// The client aborted the authentication with
-        /// [abort](struct.Abort.html).
-        Aborted => "aborted",
-
-        /// The account the client is trying to authenticate against has been
-        /// disabled.
-        AccountDisabled => "account-disabled",
-
-        /// The credentials for this account have expired.
-        CredentialsExpired => "credentials-expired",
-
-        /// You must enable StartTLS or use direct TLS before using this
-        /// authentication mechanism.
-        EncryptionRequired => "encryption-required",
-
-        /// The base64 data sent by the client is invalid.
-        IncorrectEncoding => "incorrect-encoding",
-
-        /// The authzid provided by the client is invalid.
-        InvalidAuthzid => "invalid-authzid",
-
-        /// The client tried to use an invalid mechanism, or none.
-        InvalidMechanism => "invalid-mechanism",
-
-        /// The client sent a bad request.
-        MalformedRequest => "malformed-request",
-
-        /// The mechanism selected is weaker than what the server allows.
-        MechanismTooWeak => "mechanism-too-weak",
-
-        /// The credentials provided are invalid.
-        NotAuthorized => "not-authorized",
-
-        /// The server encountered an issue which may be fixed later, the
-        /// client should retry at some point.
-        TemporaryAuthFailure => "temporary-auth-failure",
-    }
-);
+/// The first step of the SASL process, selecting the mechanism and sending
+/// the first part of the handshake.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL, name = "auth")]
+pub struct Auth {
+    /// The mechanism used.
+    #[xml(attribute = "mechanism")]
+    pub mechanism: Mechanism,
+
+    /// The content of the handshake.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// In case the mechanism selected at the [auth](struct.Auth.html) step
+/// requires a second step, the server sends this element with additional
+/// data.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL, name = "challenge")]
+pub struct Challenge {
+    /// The challenge data.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// In case the mechanism selected at the [auth](struct.Auth.html) step
+/// requires a second step, this contains the client’s response to the
+/// server’s [challenge](struct.Challenge.html).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL, name = "response")]
+pub struct Response {
+    /// The response data.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// Sent by the client at any point after [auth](struct.Auth.html) if it
+/// wants to cancel the current authentication process.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL, name = "abort")]
+pub struct Abort;
+
+/// Sent by the server on SASL success.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SASL, name = "success")]
+pub struct Success {
+    /// Possible data sent on success.
+    #[xml(text, codec = Base64)]
+    pub data: Vec<u8>,
+}
+
+/// List of possible failure conditions for SASL.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(namespace = SASL, name = "defined-condition")]
+pub enum DefinedCondition {
+    /// The client aborted the authentication with
+    /// [abort](struct.Abort.html).
+    #[xml(name = "aborted")]
+    Aborted,
+
+    /// The account the client is trying to authenticate against has been
+    /// disabled.
+    #[xml(name = "account-disabled")]
+    AccountDisabled,
+
+    /// The credentials for this account have expired.
+    #[xml(name = "credentials-expired")]
+    CredentialsExpired,
+
+    /// You must enable StartTLS or use direct TLS before using this
+    /// authentication mechanism.
+    #[xml(name = "encryption-required")]
+    EncryptionRequired,
+
+    /// The base64 data sent by the client is invalid.
+    #[xml(name = "incorrect-encoding")]
+    IncorrectEncoding,
+
+    /// The authzid provided by the client is invalid.
+    #[xml(name = "invalid-authzid")]
+    InvalidAuthzid,
+
+    /// The client tried to use an invalid mechanism, or none.
+    #[xml(name = "invalid-mechanism")]
+    InvalidMechanism,
+
+    /// The client sent a bad request.
+    #[xml(name = "malformed-request")]
+    MalformedRequest,
+
+    /// The mechanism selected is weaker than what the server allows.
+    #[xml(name = "mechanism-too-weak")]
+    MechanismTooWeak,
+
+    /// The credentials provided are invalid.
+    #[xml(name = "not-authorized")]
+    NotAuthorized,
+
+    /// The server encountered an issue which may be fixed later, the
+    /// client should retry at some point.
+    #[xml(name = "temporary-auth-failure")]
+    TemporaryAuthFailure,
+}
 
 type Lang = String;
 
 /// Sent by the server on SASL failure.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Failure {
     /// One of the allowed defined-conditions for SASL.
     pub defined_condition: DefinedCondition,
@@ -164,7 +186,7 @@ impl TryFrom<Element> for Failure {
             if child.is("text", ns::SASL) {
                 check_no_unknown_attributes!(child, "text", ["xml:lang"]);
                 check_no_children!(child, "text");
-                let lang = get_attr!(child, "xml:lang", Default);
+                let lang = child.attr_as("xml:lang")?.unwrap_or_default();
                 if texts.insert(lang, child.text()).is_some() {
                     return Err(Error::ParseError(
                         "Text element present twice for the same xml:lang in failure element.",
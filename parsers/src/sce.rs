@@ -0,0 +1,280 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::date::DateTime;
+use crate::Element;
+use jid::Jid;
+use std::error::Error as StdError;
+use std::fmt;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The payloads to encrypt, which would otherwise have been direct children of the stanza,
+/// such as its `<body xmlns='jabber:client'/>`.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SCE, name = "content")]
+pub struct Content {
+    /// The payloads, in any namespace.
+    #[xml(other_children)]
+    pub payloads: Vec<Element>,
+}
+
+/// The time at which the envelope got created, against replay attacks.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SCE, name = "time")]
+pub struct TimeAffix {
+    /// The time itself.
+    #[xml(attribute = "stamp")]
+    pub stamp: DateTime,
+}
+
+/// The intended recipient of the envelope, against the forwarding of it to someone else.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SCE, name = "to")]
+pub struct ToAffix {
+    /// The JID of the recipient, bare for a one-to-one chat, or the bare JID of the room.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+}
+
+/// The sender of the envelope, against the replay of it by someone else.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SCE, name = "from")]
+pub struct FromAffix {
+    /// The JID of the sender.
+    #[xml(attribute = "jid")]
+    pub jid: Jid,
+}
+
+/// The plaintext of an encrypted stanza, with the affixes protecting its content.
+///
+/// Which affixes are required depends on the encryption protocol using this envelope; the
+/// ones present can be checked against the stanza with [validate()](#method.validate).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SCE, name = "envelope")]
+pub struct Envelope {
+    /// The encrypted payloads.
+    #[xml(child = "content")]
+    pub content: Content,
+
+    /// Random padding, so that the length of the ciphertext doesn’t leak the length of the
+    /// content.
+    #[xml(child = "rpad")]
+    pub rpad: Option<String>,
+
+    /// The time at which the envelope got created.
+    #[xml(child = "time")]
+    pub time: Option<TimeAffix>,
+
+    /// The intended recipient.
+    #[xml(child = "to")]
+    pub to: Option<ToAffix>,
+
+    /// The sender.
+    #[xml(child = "from")]
+    pub from: Option<FromAffix>,
+
+    /// The custom affixes defined by other protocols.
+    #[xml(other_children)]
+    pub affixes: Vec<Element>,
+}
+
+/// Whether `affix` designates `jid`, either exactly or as its bare JID.
+fn matches(affix: &Jid, jid: &Jid) -> bool {
+    match affix {
+        Jid::Bare(bare) => bare.node() == jid.node() && bare.domain() == jid.domain(),
+        Jid::Full(_) => affix == jid,
+    }
+}
+
+impl Envelope {
+    /// Creates an envelope around these payloads, without any affix.
+    pub fn new(payloads: Vec<Element>) -> Envelope {
+        Envelope {
+            content: Content { payloads },
+            rpad: None,
+            time: None,
+            to: None,
+            from: None,
+            affixes: Vec::new(),
+        }
+    }
+
+    /// Adds this padding, which should be of random length.
+    pub fn with_rpad<S: Into<String>>(mut self, rpad: S) -> Envelope {
+        self.rpad = Some(rpad.into());
+        self
+    }
+
+    /// Adds the time of creation.
+    pub fn with_time(mut self, stamp: DateTime) -> Envelope {
+        self.time = Some(TimeAffix { stamp });
+        self
+    }
+
+    /// Adds the intended recipient.
+    pub fn with_to(mut self, jid: Jid) -> Envelope {
+        self.to = Some(ToAffix { jid });
+        self
+    }
+
+    /// Adds the sender.
+    pub fn with_from(mut self, jid: Jid) -> Envelope {
+        self.from = Some(FromAffix { jid });
+        self
+    }
+
+    /// Checks the `<from/>` and `<to/>` affixes, when present, against the sender and the
+    /// recipient of the stanza this envelope got decrypted from.
+    ///
+    /// A bare JID in an affix matches any resource of this JID.
+    pub fn validate(&self, from: &Jid, to: &Jid) -> Result<(), AffixError> {
+        if let Some(affix) = &self.from {
+            if !matches(&affix.jid, from) {
+                return Err(AffixError::From);
+            }
+        }
+        if let Some(affix) = &self.to {
+            if !matches(&affix.jid, to) {
+                return Err(AffixError::To);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The affix of an [Envelope](struct.Envelope.html) which doesn’t match the stanza it got decrypted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AffixError {
+    /// The `<from/>` affix doesn’t match the sender of the stanza.
+    From,
+
+    /// The `<to/>` affix doesn’t match the recipient of the stanza.
+    To,
+}
+
+impl StdError for AffixError {}
+
+impl fmt::Display for AffixError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AffixError::From => {
+                write!(fmt, "the from affix doesn’t match the sender of the stanza")
+            }
+            AffixError::To => write!(
+                fmt,
+                "the to affix doesn’t match the recipient of the stanza"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Body;
+    use crate::util::error::Error;
+    use jid::{BareJid, FullJid};
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Content, 24);
+        assert_size!(TimeAffix, 16);
+        assert_size!(ToAffix, 40);
+        assert_size!(FromAffix, 40);
+        assert_size!(Envelope, 168);
+        assert_size!(AffixError, 1);
+    }
+
+    #[test]
+    fn test_envelope() {
+        let elem: Element = "<envelope xmlns='urn:xmpp:sce:1'>
+            <content>
+                <body xmlns='jabber:client'>Have you seen that new movie?</body>
+                <x xmlns='jabber:x:oob'><url>https://en.wikipedia.org/wiki/Fight_Club#Plot</url></x>
+            </content>
+            <rpad>7He45fsdq</rpad>
+            <time stamp='2004-01-25T05:05:00-05:00'/>
+            <to jid='missioncontrol@houston.nasa.gov'/>
+            <from jid='opportunity@mars.planet/rover'/>
+        </envelope>"
+            .parse()
+            .unwrap();
+        let envelope = Envelope::try_from(elem).unwrap();
+        assert_eq!(envelope.content.payloads.len(), 2);
+        assert_eq!(
+            Body::try_from(envelope.content.payloads[0].clone()).unwrap(),
+            Body(String::from("Have you seen that new movie?"))
+        );
+        assert_eq!(envelope.rpad, Some(String::from("7He45fsdq")));
+        assert_eq!(
+            envelope.time.as_ref().unwrap().stamp,
+            DateTime::from_str("2004-01-25T05:05:00-05:00").unwrap()
+        );
+        assert!(envelope.affixes.is_empty());
+
+        let sender = Jid::Full(FullJid::new("opportunity", "mars.planet", "rover"));
+        let recipient = Jid::Full(FullJid::new("missioncontrol", "houston.nasa.gov", "desk"));
+        envelope.validate(&sender, &recipient).unwrap();
+    }
+
+    #[test]
+    fn test_build() {
+        let envelope = Envelope::new(vec![Body(String::from("Hi")).into()])
+            .with_rpad("xyz")
+            .with_from(Jid::Bare(BareJid::new("juliet", "capulet.lit")));
+        let elem: Element = envelope.clone().into();
+        let expected: Element = "<envelope xmlns='urn:xmpp:sce:1'><content><body xmlns='jabber:client'>Hi</body></content><rpad>xyz</rpad><from jid='juliet@capulet.lit'/></envelope>"
+            .parse()
+            .unwrap();
+        assert_eq!(elem, expected);
+        assert_eq!(Envelope::try_from(elem).unwrap(), envelope);
+    }
+
+    #[test]
+    fn test_custom_affix() {
+        let elem: Element = "<envelope xmlns='urn:xmpp:sce:1'><content/><nonce xmlns='urn:example:nonce'>42</nonce></envelope>"
+            .parse()
+            .unwrap();
+        let envelope = Envelope::try_from(elem).unwrap();
+        assert!(envelope.content.payloads.is_empty());
+        assert_eq!(envelope.affixes.len(), 1);
+        assert!(envelope.affixes[0].is("nonce", "urn:example:nonce"));
+    }
+
+    #[test]
+    fn test_invalid_affixes() {
+        let juliet = Jid::Full(FullJid::new("juliet", "capulet.lit", "balcony"));
+        let romeo = Jid::Bare(BareJid::new("romeo", "montague.lit"));
+        let envelope = Envelope::new(vec![])
+            .with_from(Jid::Full(FullJid::new("juliet", "capulet.lit", "chamber")))
+            .with_to(romeo.clone());
+        let error = envelope.validate(&juliet, &romeo).unwrap_err();
+        assert_eq!(error, AffixError::From);
+
+        let envelope = Envelope::new(vec![]).with_to(romeo);
+        let error = envelope.validate(&juliet, &juliet).unwrap_err();
+        assert_eq!(error, AffixError::To);
+    }
+
+    #[test]
+    fn test_missing_content() {
+        let elem: Element = "<envelope xmlns='urn:xmpp:sce:1'><rpad/></envelope>"
+            .parse()
+            .unwrap();
+        let error = Envelope::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Missing child content in envelope element.");
+    }
+}
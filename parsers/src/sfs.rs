@@ -0,0 +1,210 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::file_metadata::FileMetadata;
+use crate::message::MessagePayload;
+use crate::url_data::UrlData;
+use crate::Element;
+use jid::Jid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// How the recipient should present a shared file.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "disposition")]
+pub enum Disposition {
+    /// Displayed within the conversation, such as an image.
+    #[xml(value = "inline")]
+    Inline,
+
+    /// Only offered for download.
+    #[xml(value = "attachment")]
+    Attachment,
+}
+
+/// A file available from an entity over Jingle File Transfer.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = JINGLEPUB, name = "jinglepub")]
+pub struct JinglePub {
+    /// The full JID of the entity to request the file from.
+    #[xml(attribute = "from")]
+    pub from: Jid,
+
+    /// The id under which this entity published the file.
+    #[xml(attribute = "id")]
+    pub id: String,
+
+    /// The Jingle description to use in the session, kept as is.
+    #[xml(child = "description", any_namespace)]
+    pub description: Option<Element>,
+}
+
+/// Where to retrieve a shared file from, either inside of a
+/// [file-sharing](struct.FileSharing.html) element, or added later to the message sharing it.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SFS, name = "sources")]
+pub struct Sources {
+    /// When sent on their own, the id of the file-sharing element these sources are for.
+    #[xml(attribute = "id")]
+    pub id: Option<String>,
+
+    /// URLs to download the file from, such as an [HTTP Upload](../http_upload/index.html)
+    /// GET URL.
+    #[xml(child = "url-data", namespace = URL_DATA)]
+    pub urls: Vec<UrlData>,
+
+    /// Entities to request the file from over Jingle.
+    #[xml(child = "jinglepub", namespace = JINGLEPUB)]
+    pub jingle: Vec<JinglePub>,
+
+    /// The sources of other kinds, kept as is.
+    #[xml(other_children)]
+    pub others: Vec<Element>,
+}
+
+impl MessagePayload for Sources {}
+
+/// Shares a file, described by its metadata, along with where to retrieve it from.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SFS, name = "file-sharing")]
+pub struct FileSharing {
+    /// How this file should be presented.
+    #[xml(attribute = "disposition")]
+    pub disposition: Option<Disposition>,
+
+    /// An id used to add sources to this file later.
+    #[xml(attribute = "id")]
+    pub id: Option<String>,
+
+    /// The metadata of this file.
+    #[xml(child = "file", namespace = FILE_METADATA)]
+    pub file: FileMetadata,
+
+    /// Where to retrieve this file from.
+    #[xml(child = "sources")]
+    pub sources: Sources,
+}
+
+impl FileSharing {
+    /// Shares this file, available at this URL, for instance its
+    /// [HTTP Upload](../http_upload/struct.Get.html) GET URL.
+    pub fn new<U: Into<UrlData>>(file: FileMetadata, url: U) -> FileSharing {
+        FileSharing {
+            disposition: None,
+            id: None,
+            file,
+            sources: Sources {
+                urls: vec![url.into()],
+                ..Sources::default()
+            },
+        }
+    }
+}
+
+impl MessagePayload for FileSharing {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_upload::Get;
+    use crate::util::error::Error;
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Disposition, 1);
+        assert_size!(JinglePub, 200);
+        assert_size!(Sources, 96);
+        assert_size!(FileSharing, 304);
+    }
+
+    #[test]
+    fn test_file_sharing() {
+        let elem: Element = "<file-sharing xmlns='urn:xmpp:sfs:0' disposition='inline'>
+            <file xmlns='urn:xmpp:file:metadata:0'>
+                <media-type>image/jpeg</media-type>
+                <name>summit.jpg</name>
+                <size>3032449</size>
+                <hash xmlns='urn:xmpp:hashes:2' algo='sha3-256'>2XarmwTlNxDAMkvymloX3S5+VbylNrJt/l5QyPa+YoU=</hash>
+                <thumbnail xmlns='urn:xmpp:thumbs:1' uri='cid:sha1+ffd7c8d28e9c5e82afea41f97108c6b4@bob.xmpp.org' media-type='image/png' width='128' height='96'/>
+            </file>
+            <sources>
+                <url-data xmlns='http://jabber.org/protocol/url-data' target='https://download.montague.lit/4a771ac1-f0b2-4a4a-9700-f2a26fa2bb67/summit.jpg'/>
+                <jinglepub xmlns='urn:xmpp:jinglepub:1' from='romeo@montague.lit/resource' id='9559976B-3FBF-4E7E-B457-2DAA225972BB'>
+                    <description xmlns='urn:xmpp:jingle:apps:file-transfer:5'/>
+                </jinglepub>
+            </sources>
+        </file-sharing>"
+            .parse()
+            .unwrap();
+        let sharing = FileSharing::try_from(elem).unwrap();
+        assert_eq!(sharing.disposition, Some(Disposition::Inline));
+        assert_eq!(sharing.file.name.as_deref(), Some("summit.jpg"));
+        assert_eq!(sharing.file.thumbnails.len(), 1);
+        assert_eq!(
+            sharing.sources.urls,
+            vec![UrlData::new(
+                "https://download.montague.lit/4a771ac1-f0b2-4a4a-9700-f2a26fa2bb67/summit.jpg"
+            )]
+        );
+        let jingle = &sharing.sources.jingle[0];
+        assert_eq!(
+            jingle.from,
+            Jid::from_str("romeo@montague.lit/resource").unwrap()
+        );
+        assert!(jingle
+            .description
+            .as_ref()
+            .unwrap()
+            .is("description", crate::ns::JINGLE_FT));
+    }
+
+    #[test]
+    fn test_sources_later() {
+        let elem: Element = "<sources xmlns='urn:xmpp:sfs:0' id='file-1'><url-data xmlns='http://jabber.org/protocol/url-data' target='https://example.org/file'/><magnet xmlns='urn:example:magnet' uri='magnet:?xt=foo'/></sources>"
+            .parse()
+            .unwrap();
+        let sources = Sources::try_from(elem).unwrap();
+        assert_eq!(sources.id.as_deref(), Some("file-1"));
+        assert_eq!(sources.urls.len(), 1);
+        assert!(sources.jingle.is_empty());
+        assert!(sources.others[0].is("magnet", "urn:example:magnet"));
+    }
+
+    #[test]
+    fn test_http_upload() {
+        let get = Get {
+            url: String::from("https://download.montague.lit/summit.jpg"),
+        };
+        let file = FileMetadata {
+            name: Some(String::from("summit.jpg")),
+            size: Some(3032449),
+            ..FileMetadata::new()
+        };
+        let sharing = FileSharing::new(file, get);
+        let elem: Element = sharing.clone().into();
+        let expected: Element = "<file-sharing xmlns='urn:xmpp:sfs:0'><file xmlns='urn:xmpp:file:metadata:0'><name>summit.jpg</name><size>3032449</size></file><sources><url-data xmlns='http://jabber.org/protocol/url-data' target='https://download.montague.lit/summit.jpg'/></sources></file-sharing>"
+            .parse()
+            .unwrap();
+        assert_eq!(elem, expected);
+        assert_eq!(FileSharing::try_from(elem).unwrap(), sharing);
+    }
+
+    #[test]
+    fn test_missing_sources() {
+        let elem: Element = "<file-sharing xmlns='urn:xmpp:sfs:0'><file xmlns='urn:xmpp:file:metadata:0'/></file-sharing>"
+            .parse()
+            .unwrap();
+        let error = FileSharing::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Missing child sources in file-sharing element.");
+    }
+}
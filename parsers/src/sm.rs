@@ -5,15 +5,17 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::stanza_error::DefinedCondition;
-
-generate_element!(
-    /// Acknowledgement of the currently received stanzas.
-    A, "a", SM,
-    attributes: [
-        /// The last handled stanza.
-        h: Required<u32> = "h",
-    ]
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// Acknowledgement of the currently received stanzas.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "a")]
+pub struct A {
+    /// The last handled stanza.
+    #[xml(attribute = "h")]
+    pub h: u32,
+}
 
 impl A {
     /// Generates a new `<a/>` element.
@@ -22,26 +24,33 @@ impl A {
     }
 }
 
-generate_attribute!(
-    /// Whether to allow resumption of a previous stream.
-    ResumeAttr,
-    "resume",
-    bool
-);
-
-generate_element!(
-    /// Client request for enabling stream management.
-    #[derive(Default)]
-    Enable, "enable", SM,
-    attributes: [
-        /// The preferred resumption time in seconds by the client.
-        // TODO: should be the infinite integer set ≥ 1.
-        max: Option<u32> = "max",
-
-        /// Whether the client wants to be allowed to resume the stream.
-        resume: Default<ResumeAttr> = "resume",
-    ]
-);
+/// Whether to allow resumption of a previous stream.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[xml(attribute = "resume")]
+pub enum ResumeAttr {
+    /// True value, represented by either 'true' or '1'.
+    #[xml(value = "true", alias = "1")]
+    True,
+
+    /// False value, represented by either 'false' or '0'.
+    #[xml(value = "false", alias = "0", default)]
+    False,
+}
+
+/// Client request for enabling stream management.
+#[derive(Default, Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "enable")]
+pub struct Enable {
+    /// The preferred resumption time in seconds by the client.
+    // TODO: should be the infinite integer set ≥ 1.
+    #[xml(attribute = "max")]
+    pub max: Option<u32>,
+
+    /// Whether the client wants to be allowed to resume the stream.
+    #[xml(attribute = "resume", default)]
+    pub resume: ResumeAttr,
+}
 
 impl Enable {
     /// Generates a new `<enable/>` element.
@@ -62,85 +71,93 @@ impl Enable {
     }
 }
 
-generate_id!(
+/// A random identifier used for stream resumption.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(attribute)]
+pub struct StreamId(pub String);
+
+/// Server response once stream management is enabled.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "enabled")]
+pub struct Enabled {
     /// A random identifier used for stream resumption.
-    StreamId
-);
-
-generate_element!(
-    /// Server response once stream management is enabled.
-    Enabled, "enabled", SM,
-    attributes: [
-        /// A random identifier used for stream resumption.
-        id: Option<StreamId> = "id",
-
-        /// The preferred IP, domain, IP:port or domain:port location for
-        /// resumption.
-        location: Option<String> = "location",
-
-        /// The preferred resumption time in seconds by the server.
-        // TODO: should be the infinite integer set ≥ 1.
-        max: Option<u32> = "max",
-
-        /// Whether stream resumption is allowed.
-        resume: Default<ResumeAttr> = "resume",
-    ]
-);
-
-generate_element!(
-    /// A stream management error happened.
-    Failed, "failed", SM,
-    attributes: [
-        /// The last handled stanza.
-        h: Option<u32> = "h",
-    ],
-    children: [
-        /// The error returned.
-        // XXX: implement the * handling.
-        error: Option<DefinedCondition> = ("*", XMPP_STANZAS) => DefinedCondition
-    ]
-);
-
-generate_empty_element!(
-    /// Requests the currently received stanzas by the other party.
-    R,
-    "r",
-    SM
-);
-
-generate_element!(
-    /// Requests a stream resumption.
-    Resume, "resume", SM,
-    attributes: [
-        /// The last handled stanza.
-        h: Required<u32> = "h",
-
-        /// The previous id given by the server on
-        /// [enabled](struct.Enabled.html).
-        previd: Required<StreamId> = "previd",
-    ]
-);
-
-generate_element!(
-    /// The response by the server for a successfully resumed stream.
-    Resumed, "resumed", SM,
-    attributes: [
-        /// The last handled stanza.
-        h: Required<u32> = "h",
-
-        /// The previous id given by the server on
-        /// [enabled](struct.Enabled.html).
-        previd: Required<StreamId> = "previd",
-    ]
-);
+    #[xml(attribute = "id")]
+    pub id: Option<StreamId>,
+
+    /// The preferred IP, domain, IP:port or domain:port location for
+    /// resumption.
+    #[xml(attribute = "location")]
+    pub location: Option<String>,
+
+    /// The preferred resumption time in seconds by the server.
+    // TODO: should be the infinite integer set ≥ 1.
+    #[xml(attribute = "max")]
+    pub max: Option<u32>,
+
+    /// Whether stream resumption is allowed.
+    #[xml(attribute = "resume", default)]
+    pub resume: ResumeAttr,
+}
+
+/// A stream management error happened.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "failed")]
+pub struct Failed {
+    /// The last handled stanza.
+    #[xml(attribute = "h")]
+    pub h: Option<u32>,
+
+    /// The error returned.
+    // XXX: implement the * handling.
+    #[xml(child = "*", namespace = XMPP_STANZAS)]
+    pub error: Option<DefinedCondition>,
+}
+
+/// Requests the currently received stanzas by the other party.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "r")]
+pub struct R;
+
+/// Requests a stream resumption.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "resume")]
+pub struct Resume {
+    /// The last handled stanza.
+    #[xml(attribute = "h")]
+    pub h: u32,
+
+    /// The previous id given by the server on
+    /// [enabled](struct.Enabled.html).
+    #[xml(attribute = "previd")]
+    pub previd: StreamId,
+}
+
+/// The response by the server for a successfully resumed stream.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "resumed")]
+pub struct Resumed {
+    /// The last handled stanza.
+    #[xml(attribute = "h")]
+    pub h: u32,
+
+    /// The previous id given by the server on
+    /// [enabled](struct.Enabled.html).
+    #[xml(attribute = "previd")]
+    pub previd: StreamId,
+}
 
 // TODO: add support for optional and required.
-generate_empty_element!(
-    /// Represents availability of Stream Management in `<stream:features/>`.
-    StreamManagement,
-    "sm",
-    SM
-);
+/// Represents availability of Stream Management in `<stream:features/>`.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SM, name = "sm")]
+pub struct StreamManagement;
 
 #[cfg(test)]
 mod tests {
@@ -6,31 +6,35 @@
 
 use crate::message::MessagePayload;
 use jid::Jid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// Gives the identifier a service has stamped on this stanza, often in
-    /// order to identify it inside of [an archive](../mam/index.html).
-    StanzaId, "stanza-id", SID,
-    attributes: [
-        /// The id associated to this stanza by another entity.
-        id: Required<String> = "id",
+/// Gives the identifier a service has stamped on this stanza, often in
+/// order to identify it inside of [an archive](../mam/index.html).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SID, name = "stanza-id")]
+pub struct StanzaId {
+    /// The id associated to this stanza by another entity.
+    #[xml(attribute = "id")]
+    pub id: String,
 
-        /// The entity who stamped this stanza-id.
-        by: Required<Jid> = "by",
-    ]
-);
+    /// The entity who stamped this stanza-id.
+    #[xml(attribute = "by")]
+    pub by: Jid,
+}
 
 impl MessagePayload for StanzaId {}
 
-generate_element!(
-    /// A hack for MUC before version 1.31 to track a message which may have
-    /// its 'id' attribute changed.
-    OriginId, "origin-id", SID,
-    attributes: [
-        /// The id this client set for this stanza.
-        id: Required<String> = "id",
-    ]
-);
+/// A hack for MUC before version 1.31 to track a message which may have
+/// its 'id' attribute changed.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = SID, name = "origin-id")]
+pub struct OriginId {
+    /// The id this client set for this stanza.
+    #[xml(attribute = "id")]
+    pub id: String,
+}
 
 impl MessagePayload for OriginId {}
 
@@ -89,11 +93,15 @@ mod tests {
     fn test_invalid_id() {
         let elem: Element = "<stanza-id xmlns='urn:xmpp:sid:0'/>".parse().unwrap();
         let error = StanzaId::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'id' missing.");
+        assert_eq!(attribute, "id");
     }
 
     #[test]
@@ -102,11 +110,15 @@ mod tests {
             .parse()
             .unwrap();
         let error = StanzaId::try_from(elem).unwrap_err();
-        let message = match error {
-            Error::ParseError(string) => string,
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
             _ => panic!(),
         };
-        assert_eq!(message, "Required attribute 'by' missing.");
+        assert_eq!(attribute, "by");
     }
 
     #[test]
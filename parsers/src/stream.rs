@@ -5,28 +5,34 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use jid::BareJid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// The stream opening for client-server communications.
-    Stream, "stream", STREAM,
-    attributes: [
-        /// The JID of the entity opening this stream.
-        from: Option<BareJid> = "from",
+/// The stream opening for client-server communications.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = STREAM, name = "stream")]
+pub struct Stream {
+    /// The JID of the entity opening this stream.
+    #[xml(attribute = "from")]
+    pub from: Option<BareJid>,
 
-        /// The JID of the entity receiving this stream opening.
-        to: Option<BareJid> = "to",
+    /// The JID of the entity receiving this stream opening.
+    #[xml(attribute = "to")]
+    pub to: Option<BareJid>,
 
-        /// The id of the stream, used for authentication challenges.
-        id: Option<String> = "id",
+    /// The id of the stream, used for authentication challenges.
+    #[xml(attribute = "id")]
+    pub id: Option<String>,
 
-        /// The XMPP version used during this stream.
-        version: Option<String> = "version",
+    /// The XMPP version used during this stream.
+    #[xml(attribute = "version")]
+    pub version: Option<String>,
 
-        /// The default human language for all subsequent stanzas, which will
-        /// be transmitted to other entities for better localisation.
-        xml_lang: Option<String> = "xml:lang",
-    ]
-);
+    /// The default human language for all subsequent stanzas, which will
+    /// be transmitted to other entities for better localisation.
+    #[xml(attribute = "xml:lang")]
+    pub xml_lang: Option<String>,
+}
 
 impl Stream {
     /// Creates a simple client→server `<stream:stream>` element.
@@ -79,20 +85,23 @@ mod tests {
     #[cfg(target_pointer_width = "32")]
     #[test]
     fn test_size() {
-        assert_size!(Stream, 84);
+        assert_size!(Stream, 68);
     }
 
     #[cfg(target_pointer_width = "64")]
     #[test]
     fn test_size() {
-        assert_size!(Stream, 168);
+        assert_size!(Stream, 136);
     }
 
     #[test]
     fn test_simple() {
         let elem: Element = "<stream:stream xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' xml:lang='en' version='1.0' id='abc' from='some-server.example'/>".parse().unwrap();
         let stream = Stream::try_from(elem).unwrap();
-        assert_eq!(stream.from, Some(BareJid::domain("some-server.example")));
+        assert_eq!(
+            stream.from,
+            Some(BareJid::from_domain("some-server.example"))
+        );
         assert_eq!(stream.to, None);
         assert_eq!(stream.id, Some(String::from("abc")));
         assert_eq!(stream.version, Some(String::from("1.0")));
@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// A thumbnail of an image or video, to display while the full file hasn’t been downloaded.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = THUMBS, name = "thumbnail")]
+pub struct Thumbnail {
+    /// Where to retrieve this thumbnail from, usually a cid: URI of
+    /// [Bits of Binary](../bob/index.html) data, or an HTTP URL.
+    #[xml(attribute = "uri")]
+    pub uri: String,
+
+    /// The MIME type of this thumbnail.
+    #[xml(attribute = "media-type")]
+    pub media_type: Option<String>,
+
+    /// The width of this thumbnail, in pixels.
+    #[xml(attribute = "width")]
+    pub width: Option<u32>,
+
+    /// The height of this thumbnail, in pixels.
+    #[xml(attribute = "height")]
+    pub height: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Thumbnail, 64);
+    }
+
+    #[test]
+    fn test_simple() {
+        let elem: Element = "<thumbnail xmlns='urn:xmpp:thumbs:1' uri='cid:sha1+ffd7c8d28e9c5e82afea41f97108c6b4@bob.xmpp.org' media-type='image/png' width='128' height='96'/>"
+            .parse()
+            .unwrap();
+        let thumbnail = Thumbnail::try_from(elem).unwrap();
+        assert_eq!(
+            thumbnail.uri,
+            "cid:sha1+ffd7c8d28e9c5e82afea41f97108c6b4@bob.xmpp.org"
+        );
+        assert_eq!(thumbnail.media_type.unwrap(), "image/png");
+        assert_eq!(thumbnail.width, Some(128));
+        assert_eq!(thumbnail.height, Some(96));
+    }
+
+    #[test]
+    fn test_invalid_width() {
+        let elem: Element =
+            "<thumbnail xmlns='urn:xmpp:thumbs:1' uri='cid:foo@bob.xmpp.org' width='-1'/>"
+                .parse()
+                .unwrap();
+        let error = Thumbnail::try_from(elem).unwrap_err();
+        match error {
+            Error::AttributeError {
+                error: Some(error), ..
+            } => match *error {
+                Error::ParseIntError(_) => (),
+                _ => panic!(),
+            },
+            _ => panic!(),
+        }
+    }
+}
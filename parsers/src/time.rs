@@ -12,18 +12,19 @@ use crate::Element;
 use chrono::FixedOffset;
 use std::convert::TryFrom;
 use std::str::FromStr;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// An entity time query.
-    TimeQuery,
-    "time",
-    TIME
-);
+/// An entity time query.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TIME, name = "time")]
+pub struct TimeQuery;
 
 impl IqGetPayload for TimeQuery {}
 
 /// An entity time result, containing an unique DateTime.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeResult(pub DateTime);
 
 impl IqResultPayload for TimeResult {}
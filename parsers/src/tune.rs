@@ -9,62 +9,55 @@ use crate::pubsub::PubSubPayload;
 use crate::util::error::Error;
 use crate::Element;
 use std::convert::TryFrom;
-
-generate_elem_id!(
-    /// The artist or performer of the song or piece.
-    Artist,
-    "artist",
-    TUNE
-);
-
-generate_elem_id!(
-    /// The duration of the song or piece in seconds.
-    Length,
-    "length",
-    TUNE,
-    u16
-);
-
-generate_elem_id!(
-    /// The user's rating of the song or piece, from 1 (lowest) to 10 (highest).
-    Rating,
-    "rating",
-    TUNE,
-    u8
-);
-
-generate_elem_id!(
-    /// The collection (e.g., album) or other source (e.g., a band website that hosts streams or
-    /// audio files).
-    Source,
-    "source",
-    TUNE
-);
-
-generate_elem_id!(
-    /// The title of the song or piece.
-    Title,
-    "title",
-    TUNE
-);
-
-generate_elem_id!(
-    /// A unique identifier for the tune; e.g., the track number within a collection or the
-    /// specific URI for the object (e.g., a stream or audio file).
-    Track,
-    "track",
-    TUNE
-);
-
-generate_elem_id!(
-    /// A URI or URL pointing to information about the song, collection, or artist.
-    Uri,
-    "uri",
-    TUNE
-);
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The artist or performer of the song or piece.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TUNE, name = "artist")]
+pub struct Artist(pub String);
+
+/// The duration of the song or piece in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TUNE, name = "length")]
+pub struct Length(pub u16);
+
+/// The user's rating of the song or piece, from 1 (lowest) to 10 (highest).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TUNE, name = "rating")]
+pub struct Rating(pub u8);
+
+/// The collection (e.g., album) or other source (e.g., a band website that hosts streams or
+/// audio files).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TUNE, name = "source")]
+pub struct Source(pub String);
+
+/// The title of the song or piece.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TUNE, name = "title")]
+pub struct Title(pub String);
+
+/// A unique identifier for the tune; e.g., the track number within a collection or the
+/// specific URI for the object (e.g., a stream or audio file).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TUNE, name = "track")]
+pub struct Track(pub String);
+
+/// A URI or URL pointing to information about the song, collection, or artist.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = TUNE, name = "uri")]
+pub struct Uri(pub String);
 
 /// Container for formatted text.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tune {
     /// The artist or performer of the song or piece.
     artist: Option<Artist>,
@@ -0,0 +1,99 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::http_upload::Get;
+use crate::Element;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// A URL from which some data can be retrieved, for instance a
+/// [file](../sfs/index.html) uploaded with [HTTP Upload](../http_upload/index.html).
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = URL_DATA, name = "url-data")]
+pub struct UrlData {
+    /// The URL itself.
+    #[xml(attribute = "target")]
+    pub target: String,
+
+    /// The session this URL is part of.
+    #[xml(attribute = "sid")]
+    pub sid: Option<String>,
+
+    /// Scheme-specific data, such as the HTTP headers to send, kept as is.
+    #[xml(other_children)]
+    pub scheme_data: Vec<Element>,
+}
+
+impl UrlData {
+    /// Creates a URL without any scheme-specific data.
+    pub fn new<T: Into<String>>(target: T) -> UrlData {
+        UrlData {
+            target: target.into(),
+            sid: None,
+            scheme_data: Vec::new(),
+        }
+    }
+}
+
+impl From<Get> for UrlData {
+    fn from(get: Get) -> UrlData {
+        UrlData::new(get.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(UrlData, 72);
+    }
+
+    #[test]
+    fn test_simple() {
+        let elem: Element = "<url-data xmlns='http://jabber.org/protocol/url-data' target='https://download.montague.lit/4a771ac1-f0b2-4a4a-9700-f2a26fa2bb67/summit.jpg'/>"
+            .parse()
+            .unwrap();
+        let url_data = UrlData::try_from(elem).unwrap();
+        assert_eq!(
+            url_data,
+            UrlData::new(
+                "https://download.montague.lit/4a771ac1-f0b2-4a4a-9700-f2a26fa2bb67/summit.jpg"
+            )
+        );
+    }
+
+    #[test]
+    fn test_scheme_data() {
+        let elem: Element = "<url-data xmlns='http://jabber.org/protocol/url-data' xmlns:http='http://jabber.org/protocol/url-data/scheme/http' target='http://www.jabber.org/members/index.php'><http:auth scheme='basic'/></url-data>"
+            .parse()
+            .unwrap();
+        let url_data = UrlData::try_from(elem).unwrap();
+        assert_eq!(url_data.scheme_data.len(), 1);
+        assert!(
+            url_data.scheme_data[0].is("auth", "http://jabber.org/protocol/url-data/scheme/http")
+        );
+    }
+
+    #[test]
+    fn test_missing_target() {
+        let elem: Element = "<url-data xmlns='http://jabber.org/protocol/url-data'/>"
+            .parse()
+            .unwrap();
+        let error = UrlData::try_from(elem).unwrap_err();
+        let attribute = match error {
+            Error::AttributeError {
+                attribute,
+                error: None,
+                ..
+            } => attribute,
+            _ => panic!(),
+        };
+        assert_eq!(attribute, "target");
+    }
+}
@@ -38,6 +38,19 @@ pub enum Error {
     /// Generated when text which should be a
     /// [DateTime](../date/struct.DateTime.html) fails to parse.
     ChronoParseError(chrono::ParseError),
+
+    /// Generated when a required attribute is missing, or when the value of an
+    /// attribute fails to parse.
+    AttributeError {
+        /// The name of the element.
+        element: String,
+
+        /// The name of the attribute.
+        attribute: String,
+
+        /// Why the value failed to parse, or `None` if the attribute is missing.
+        error: Option<Box<Error>>,
+    },
 }
 
 impl StdError for Error {
@@ -50,6 +63,7 @@ impl StdError for Error {
             Error::ParseAddrError(e) => Some(e),
             Error::JidParseError(e) => Some(e),
             Error::ChronoParseError(e) => Some(e),
+            Error::AttributeError { error, .. } => error.as_deref().map(|e| e as &dyn StdError),
         }
     }
 }
@@ -64,6 +78,50 @@ impl fmt::Display for Error {
             Error::ParseAddrError(e) => write!(fmt, "IP address parsing error: {}", e),
             Error::JidParseError(e) => write!(fmt, "JID parsing error: {}", e),
             Error::ChronoParseError(e) => write!(fmt, "time parsing error: {}", e),
+            Error::AttributeError {
+                element,
+                attribute,
+                error: None,
+            } => write!(
+                fmt,
+                "required attribute '{}' missing in {} element",
+                attribute, element
+            ),
+            Error::AttributeError {
+                element,
+                attribute,
+                error: Some(e),
+            } => write!(
+                fmt,
+                "invalid attribute '{}' in {} element: {}",
+                attribute, element, e
+            ),
+        }
+    }
+}
+
+/// Converts the errors of `Element::attr_as()` and `Element::required_attr()`, keeping the
+/// names of the element and of the attribute.
+impl<E> From<minidom::AttributeError<E>> for Error
+where
+    Error: From<E>,
+{
+    fn from(err: minidom::AttributeError<E>) -> Error {
+        match err {
+            minidom::AttributeError::Missing { element, attribute } => Error::AttributeError {
+                element,
+                attribute,
+                error: None,
+            },
+            minidom::AttributeError::Invalid {
+                element,
+                attribute,
+                error,
+            } => Error::AttributeError {
+                element,
+                attribute,
+                error: Some(Box::new(Error::from(error))),
+            },
         }
     }
 }
@@ -0,0 +1,368 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::iq::{IqGetPayload, IqResultPayload, IqSetPayload};
+use crate::util::helpers::WhitespaceAwareBase64;
+use crate::Element;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The structured name of the owner of a vCard.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD, name = "N")]
+pub struct Name {
+    /// The family name.
+    #[xml(child = "FAMILY")]
+    pub family: Option<String>,
+
+    /// The given name.
+    #[xml(child = "GIVEN")]
+    pub given: Option<String>,
+
+    /// The middle name.
+    #[xml(child = "MIDDLE")]
+    pub middle: Option<String>,
+
+    /// The honorific prefix, such as “Dr.”.
+    #[xml(child = "PREFIX")]
+    pub prefix: Option<String>,
+
+    /// The honorific suffix, such as “Jr.”.
+    #[xml(child = "SUFFIX")]
+    pub suffix: Option<String>,
+}
+
+/// The binary data of a photo.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD, name = "BINVAL")]
+pub struct Binval {
+    /// The bytes of the image.
+    #[xml(text, codec = WhitespaceAwareBase64)]
+    pub data: Vec<u8>,
+}
+
+/// A photo of the owner of a vCard, used as their avatar by XEP-0153.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD, name = "PHOTO")]
+pub struct Photo {
+    /// The IANA-registered content type of the image.
+    #[xml(child = "TYPE")]
+    pub type_: Option<String>,
+
+    /// The image itself.
+    #[xml(child = "BINVAL")]
+    pub binval: Option<Binval>,
+
+    /// The URI at which the image is hosted, instead of being included.
+    #[xml(child = "EXTVAL")]
+    pub extval: Option<String>,
+}
+
+impl Photo {
+    /// Creates a photo including the image data.
+    pub fn new<T: Into<String>>(type_: T, data: Vec<u8>) -> Photo {
+        Photo {
+            type_: Some(type_.into()),
+            binval: Some(Binval { data }),
+            extval: None,
+        }
+    }
+}
+
+/// An email address.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD, name = "EMAIL")]
+pub struct Email {
+    /// Whether this is a personal address.
+    #[xml(flag = "HOME")]
+    pub home: bool,
+
+    /// Whether this is a professional address.
+    #[xml(flag = "WORK")]
+    pub work: bool,
+
+    /// Whether this is an Internet address.
+    #[xml(flag = "INTERNET")]
+    pub internet: bool,
+
+    /// Whether this is the preferred address.
+    #[xml(flag = "PREF")]
+    pub pref: bool,
+
+    /// Whether this is an X.400 address.
+    #[xml(flag = "X400")]
+    pub x400: bool,
+
+    /// The address itself.
+    #[xml(child = "USERID")]
+    pub userid: String,
+}
+
+/// A telephone number.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD, name = "TEL")]
+pub struct Tel {
+    /// Whether this is a personal number.
+    #[xml(flag = "HOME")]
+    pub home: bool,
+
+    /// Whether this is a professional number.
+    #[xml(flag = "WORK")]
+    pub work: bool,
+
+    /// Whether this number supports voice calls.
+    #[xml(flag = "VOICE")]
+    pub voice: bool,
+
+    /// Whether this is a fax number.
+    #[xml(flag = "FAX")]
+    pub fax: bool,
+
+    /// Whether this is a pager number.
+    #[xml(flag = "PAGER")]
+    pub pager: bool,
+
+    /// Whether this number has voice messaging.
+    #[xml(flag = "MSG")]
+    pub msg: bool,
+
+    /// Whether this is a mobile phone number.
+    #[xml(flag = "CELL")]
+    pub cell: bool,
+
+    /// Whether this number supports video calls.
+    #[xml(flag = "VIDEO")]
+    pub video: bool,
+
+    /// Whether this is a bulletin board system number.
+    #[xml(flag = "BBS")]
+    pub bbs: bool,
+
+    /// Whether this is a modem number.
+    #[xml(flag = "MODEM")]
+    pub modem: bool,
+
+    /// Whether this is an ISDN number.
+    #[xml(flag = "ISDN")]
+    pub isdn: bool,
+
+    /// Whether this is a personal communication services number.
+    #[xml(flag = "PCS")]
+    pub pcs: bool,
+
+    /// Whether this is the preferred number.
+    #[xml(flag = "PREF")]
+    pub pref: bool,
+
+    /// The number itself.
+    #[xml(child = "NUMBER")]
+    pub number: String,
+}
+
+/// The organisation the owner of a vCard belongs to.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD, name = "ORG")]
+pub struct Org {
+    /// The name of the organisation.
+    #[xml(child = "ORGNAME")]
+    pub orgname: String,
+
+    /// The units of the organisation, from the largest to the smallest.
+    #[xml(child = "ORGUNIT")]
+    pub orgunits: Vec<String>,
+}
+
+/// A vCard, as stored by the server for each account, containing the profile of its owner.
+///
+/// The most common properties are typed, the other ones are kept as is.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD, name = "vCard")]
+pub struct VCard {
+    /// The full name of the owner.
+    #[xml(child = "FN")]
+    pub fn_: Option<String>,
+
+    /// The structured name of the owner.
+    #[xml(child = "N")]
+    pub n: Option<Name>,
+
+    /// The nickname of the owner.
+    #[xml(child = "NICKNAME")]
+    pub nickname: Option<String>,
+
+    /// The photo of the owner.
+    #[xml(child = "PHOTO")]
+    pub photo: Option<Photo>,
+
+    /// The birthday of the owner, usually as an ISO 8601 date.
+    #[xml(child = "BDAY")]
+    pub bday: Option<String>,
+
+    /// The telephone numbers of the owner.
+    #[xml(child = "TEL")]
+    pub tels: Vec<Tel>,
+
+    /// The email addresses of the owner.
+    #[xml(child = "EMAIL")]
+    pub emails: Vec<Email>,
+
+    /// The JID of the owner.
+    #[xml(child = "JABBERID")]
+    pub jabberid: Option<String>,
+
+    /// The job title of the owner.
+    #[xml(child = "TITLE")]
+    pub title: Option<String>,
+
+    /// The role of the owner in their organisation.
+    #[xml(child = "ROLE")]
+    pub role: Option<String>,
+
+    /// The organisation of the owner.
+    #[xml(child = "ORG")]
+    pub org: Option<Org>,
+
+    /// A note about the owner.
+    #[xml(child = "NOTE")]
+    pub note: Option<String>,
+
+    /// The website of the owner.
+    #[xml(child = "URL")]
+    pub url: Option<String>,
+
+    /// A description of the owner.
+    #[xml(child = "DESC")]
+    pub desc: Option<String>,
+
+    /// The other properties of this vCard, such as ADR or GEO.
+    #[xml(other_children)]
+    pub others: Vec<Element>,
+}
+
+impl IqGetPayload for VCard {}
+impl IqSetPayload for VCard {}
+impl IqResultPayload for VCard {}
+
+impl VCard {
+    /// Creates an empty vCard, to request the vCard of an account.
+    pub fn new() -> VCard {
+        VCard::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Name, 120);
+        assert_size!(Binval, 24);
+        assert_size!(Photo, 72);
+        assert_size!(Email, 32);
+        assert_size!(Tel, 40);
+        assert_size!(Org, 48);
+        assert_size!(VCard, 528);
+    }
+
+    #[test]
+    fn test_request() {
+        let elem: Element = "<vCard xmlns='vcard-temp'/>".parse().unwrap();
+        let vcard = VCard::try_from(elem.clone()).unwrap();
+        assert_eq!(vcard, VCard::new());
+        assert_eq!(Element::from(vcard), elem);
+    }
+
+    #[test]
+    fn test_vcard() {
+        let elem: Element = "<vCard xmlns='vcard-temp'>
+            <FN>Peter Saint-Andre</FN>
+            <N><FAMILY>Saint-Andre</FAMILY><GIVEN>Peter</GIVEN><MIDDLE/></N>
+            <NICKNAME>stpeter</NICKNAME>
+            <PHOTO><TYPE>image/png</TYPE><BINVAL>iVBORw0K
+                GgoAAAAN</BINVAL></PHOTO>
+            <TEL><WORK/><VOICE/><NUMBER>303-308-3282</NUMBER></TEL>
+            <EMAIL><INTERNET/><PREF/><USERID>stpeter@jabber.org</USERID></EMAIL>
+            <JABBERID>stpeter@jabber.org</JABBERID>
+            <ORG><ORGNAME>XMPP Standards Foundation</ORGNAME><ORGUNIT/></ORG>
+            <ADR><WORK/><LOCALITY>Denver</LOCALITY></ADR>
+            <URL>http://www.xmpp.org/xsf/people/stpeter.shtml</URL>
+        </vCard>"
+            .parse()
+            .unwrap();
+        let vcard = VCard::try_from(elem).unwrap();
+        assert_eq!(vcard.fn_, Some(String::from("Peter Saint-Andre")));
+        let n = vcard.n.unwrap();
+        assert_eq!(n.family, Some(String::from("Saint-Andre")));
+        assert_eq!(n.given, Some(String::from("Peter")));
+        assert_eq!(n.middle, Some(String::new()));
+        assert_eq!(n.prefix, None);
+        assert_eq!(vcard.nickname, Some(String::from("stpeter")));
+        let photo = vcard.photo.unwrap();
+        assert_eq!(photo.type_, Some(String::from("image/png")));
+        assert_eq!(
+            photo.binval.unwrap().data,
+            b"\x89PNG\r\n\x1a\n\0\0\0\r".to_vec()
+        );
+        assert_eq!(vcard.tels.len(), 1);
+        assert!(vcard.tels[0].work);
+        assert!(vcard.tels[0].voice);
+        assert!(!vcard.tels[0].home);
+        assert_eq!(vcard.tels[0].number, "303-308-3282");
+        assert_eq!(vcard.emails.len(), 1);
+        assert!(vcard.emails[0].internet && vcard.emails[0].pref);
+        assert_eq!(vcard.emails[0].userid, "stpeter@jabber.org");
+        assert_eq!(vcard.jabberid, Some(String::from("stpeter@jabber.org")));
+        assert_eq!(
+            vcard.org,
+            Some(Org {
+                orgname: String::from("XMPP Standards Foundation"),
+                orgunits: vec![String::new()],
+            })
+        );
+        assert_eq!(vcard.others.len(), 1);
+        assert!(vcard.others[0].is("ADR", crate::ns::VCARD));
+    }
+
+    #[test]
+    fn test_serialise() {
+        let vcard = VCard {
+            nickname: Some(String::from("juliet")),
+            photo: Some(Photo::new("image/png", b"\x89PNG".to_vec())),
+            others: vec![
+                "<GEO xmlns='vcard-temp'><LAT>45.4</LAT><LON>11.9</LON></GEO>"
+                    .parse()
+                    .unwrap(),
+            ],
+            ..VCard::new()
+        };
+        let elem: Element = "<vCard xmlns='vcard-temp'><NICKNAME>juliet</NICKNAME><PHOTO><TYPE>image/png</TYPE><BINVAL>iVBORw==</BINVAL></PHOTO><GEO><LAT>45.4</LAT><LON>11.9</LON></GEO></vCard>"
+            .parse()
+            .unwrap();
+        assert_eq!(Element::from(vcard.clone()), elem);
+        assert_eq!(VCard::try_from(elem).unwrap(), vcard);
+    }
+
+    #[test]
+    fn test_missing_userid() {
+        let elem: Element = "<vCard xmlns='vcard-temp'><EMAIL><HOME/></EMAIL></vCard>"
+            .parse()
+            .unwrap();
+        let error = VCard::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Missing child USERID in EMAIL element.");
+    }
+}
@@ -0,0 +1,500 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::pubsub::PubSubPayload;
+use crate::Element;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The formatted name of the owner of a vCard.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "fn")]
+pub struct FormattedName {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as text.
+    #[xml(child = "text")]
+    pub value: String,
+}
+
+impl FormattedName {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> FormattedName {
+        FormattedName {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// A nickname of the owner.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "nickname")]
+pub struct Nickname {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as text.
+    #[xml(child = "text")]
+    pub value: String,
+}
+
+impl Nickname {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Nickname {
+        Nickname {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// The birthday of the owner, as an ISO 8601 date without separators.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "bday")]
+pub struct Birthday {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as a date.
+    #[xml(child = "date")]
+    pub value: String,
+}
+
+impl Birthday {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Birthday {
+        Birthday {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// The URI of a photo of the owner, often a data: URI.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "photo")]
+pub struct Photo {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as a URI.
+    #[xml(child = "uri")]
+    pub value: String,
+}
+
+impl Photo {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Photo {
+        Photo {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// An email address.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "email")]
+pub struct Email {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as text.
+    #[xml(child = "text")]
+    pub value: String,
+}
+
+impl Email {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Email {
+        Email {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// A telephone number, usually as a tel: URI.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "tel")]
+pub struct Tel {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as a URI.
+    #[xml(child = "uri")]
+    pub value: String,
+}
+
+impl Tel {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Tel {
+        Tel {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// An instant messaging address, such as an xmpp: URI.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "impp")]
+pub struct Impp {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as a URI.
+    #[xml(child = "uri")]
+    pub value: String,
+}
+
+impl Impp {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Impp {
+        Impp {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// A website of the owner.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "url")]
+pub struct Url {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as a URI.
+    #[xml(child = "uri")]
+    pub value: String,
+}
+
+impl Url {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Url {
+        Url {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// The job title of the owner.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "title")]
+pub struct Title {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as text.
+    #[xml(child = "text")]
+    pub value: String,
+}
+
+impl Title {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Title {
+        Title {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// The role of the owner in their organisation.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "role")]
+pub struct Role {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as text.
+    #[xml(child = "text")]
+    pub value: String,
+}
+
+impl Role {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Role {
+        Role {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// The organisation of the owner.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "org")]
+pub struct Org {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as text.
+    #[xml(child = "text")]
+    pub value: String,
+}
+
+impl Org {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Org {
+        Org {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// A note about the owner.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "note")]
+pub struct Note {
+    /// The parameters of this property, such as its type or preference, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The value of this property, as text.
+    #[xml(child = "text")]
+    pub value: String,
+}
+
+impl Note {
+    /// Creates this property with the given value, and no parameter.
+    pub fn new<V: Into<String>>(value: V) -> Note {
+        Note {
+            parameters: None,
+            value: value.into(),
+        }
+    }
+}
+
+/// The structured name of the owner of a vCard, each component possibly having several
+/// values.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "n")]
+pub struct Name {
+    /// The parameters of this property, kept as is.
+    #[xml(child = "parameters")]
+    pub parameters: Option<Element>,
+
+    /// The family names.
+    #[xml(child = "surname")]
+    pub surnames: Vec<String>,
+
+    /// The given names.
+    #[xml(child = "given")]
+    pub given: Vec<String>,
+
+    /// The additional names.
+    #[xml(child = "additional")]
+    pub additional: Vec<String>,
+
+    /// The honorific prefixes, such as “Dr.”.
+    #[xml(child = "prefix")]
+    pub prefixes: Vec<String>,
+
+    /// The honorific suffixes, such as “Jr.”.
+    #[xml(child = "suffix")]
+    pub suffixes: Vec<String>,
+}
+
+/// A vCard4, as published by its owner in PEP, on the `urn:xmpp:vcard4` node.
+///
+/// The most common properties are typed, the other ones are kept as is.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD4, name = "vcard")]
+pub struct VCard4 {
+    /// The formatted names of the owner.
+    #[xml(child = "fn")]
+    pub formatted_names: Vec<FormattedName>,
+
+    /// The structured name of the owner.
+    #[xml(child = "n")]
+    pub name: Option<Name>,
+
+    /// The nicknames of the owner.
+    #[xml(child = "nickname")]
+    pub nicknames: Vec<Nickname>,
+
+    /// The birthday of the owner.
+    #[xml(child = "bday")]
+    pub birthday: Option<Birthday>,
+
+    /// The photos of the owner.
+    #[xml(child = "photo")]
+    pub photos: Vec<Photo>,
+
+    /// The email addresses of the owner.
+    #[xml(child = "email")]
+    pub emails: Vec<Email>,
+
+    /// The telephone numbers of the owner.
+    #[xml(child = "tel")]
+    pub tels: Vec<Tel>,
+
+    /// The instant messaging addresses of the owner.
+    #[xml(child = "impp")]
+    pub impps: Vec<Impp>,
+
+    /// The websites of the owner.
+    #[xml(child = "url")]
+    pub urls: Vec<Url>,
+
+    /// The job titles of the owner.
+    #[xml(child = "title")]
+    pub titles: Vec<Title>,
+
+    /// The roles of the owner.
+    #[xml(child = "role")]
+    pub roles: Vec<Role>,
+
+    /// The organisations of the owner.
+    #[xml(child = "org")]
+    pub orgs: Vec<Org>,
+
+    /// Notes about the owner.
+    #[xml(child = "note")]
+    pub notes: Vec<Note>,
+
+    /// The other properties of this vCard, such as adr or gender.
+    #[xml(other_children)]
+    pub others: Vec<Element>,
+}
+
+impl PubSubPayload for VCard4 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubsub::{pubsub::Item as PubSubItem, Item, ItemId};
+    use crate::util::error::Error;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(FormattedName, 160);
+        assert_size!(Name, 256);
+        assert_size!(VCard4, 704);
+    }
+
+    #[test]
+    fn test_vcard() {
+        let elem: Element = "<vcard xmlns='urn:ietf:params:xml:ns:vcard-4.0'>
+            <fn><text>Peter Saint-Andre</text></fn>
+            <n><surname>Saint-Andre</surname><given>Peter</given><additional/></n>
+            <nickname><text>stpeter</text></nickname>
+            <nickname><text>psa</text></nickname>
+            <bday><date>19660201</date></bday>
+            <gender><sex><text>M</text></sex></gender>
+            <email>
+                <parameters><type><text>work</text></type></parameters>
+                <text>psaintan@cisco.com</text>
+            </email>
+            <impp><uri>xmpp:stpeter@jabber.org</uri></impp>
+            <tel><uri>tel:+1-303-308-3282</uri></tel>
+        </vcard>"
+            .parse()
+            .unwrap();
+        let vcard = VCard4::try_from(elem).unwrap();
+        assert_eq!(
+            vcard.formatted_names,
+            vec![FormattedName::new("Peter Saint-Andre")]
+        );
+        let name = vcard.name.unwrap();
+        assert_eq!(name.surnames, vec![String::from("Saint-Andre")]);
+        assert_eq!(name.given, vec![String::from("Peter")]);
+        assert_eq!(name.additional, vec![String::new()]);
+        assert!(name.prefixes.is_empty());
+        assert_eq!(
+            vcard.nicknames,
+            vec![Nickname::new("stpeter"), Nickname::new("psa")]
+        );
+        assert_eq!(vcard.birthday, Some(Birthday::new("19660201")));
+        assert_eq!(vcard.emails.len(), 1);
+        assert_eq!(vcard.emails[0].value, "psaintan@cisco.com");
+        assert!(vcard.emails[0]
+            .parameters
+            .as_ref()
+            .unwrap()
+            .has_child("type", crate::ns::VCARD4));
+        assert_eq!(vcard.impps, vec![Impp::new("xmpp:stpeter@jabber.org")]);
+        assert_eq!(vcard.tels, vec![Tel::new("tel:+1-303-308-3282")]);
+        assert_eq!(vcard.others.len(), 1);
+        assert!(vcard.others[0].is("gender", crate::ns::VCARD4));
+    }
+
+    #[test]
+    fn test_serialise() {
+        let vcard = VCard4 {
+            nicknames: vec![Nickname::new("juliet")],
+            urls: vec![Url::new("https://capulet.lit/")],
+            ..VCard4::default()
+        };
+        let elem: Element = vcard.clone().into();
+        assert_eq!(
+            String::from(&elem),
+            "<vcard xmlns=\"urn:ietf:params:xml:ns:vcard-4.0\"><nickname><text>juliet</text></nickname><url><uri>https://capulet.lit/</uri></url></vcard>"
+        );
+        assert_eq!(VCard4::try_from(elem).unwrap(), vcard);
+    }
+
+    #[test]
+    fn test_pubsub_item() {
+        let vcard = VCard4 {
+            formatted_names: vec![FormattedName::new("Juliet Capulet")],
+            ..VCard4::default()
+        };
+        let item = PubSubItem(Item::new(
+            Some(ItemId(String::from("current"))),
+            None,
+            Some(vcard.clone()),
+        ));
+        let elem: Element = item.into();
+        let item = PubSubItem::try_from(elem).unwrap();
+        let payload = item.0.payload.unwrap();
+        assert!(payload.is("vcard", crate::ns::VCARD4));
+        assert_eq!(VCard4::try_from(payload).unwrap(), vcard);
+    }
+
+    #[test]
+    fn test_missing_value() {
+        let elem: Element = "<vcard xmlns='urn:ietf:params:xml:ns:vcard-4.0'><nickname/></vcard>"
+            .parse()
+            .unwrap();
+        let error = VCard4::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Missing child text in nickname element.");
+    }
+}
@@ -0,0 +1,117 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::presence::PresencePayload;
+use crate::util::helpers::PlainText;
+use xmpp_parsers_macros::{FromXml, IntoXml};
+
+/// The avatar currently in the vCard of the sender.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD_UPDATE, name = "photo")]
+pub struct Photo {
+    /// The hexadecimal SHA-1 hash of the image data, or `None` when there is no avatar.
+    #[xml(text, codec = PlainText)]
+    pub hash: Option<String>,
+}
+
+/// Advertises in presence the avatar of the vCard of the sender.
+#[derive(Debug, Clone, PartialEq, Default, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VCARD_UPDATE, name = "x")]
+pub struct VCardUpdate {
+    /// The avatar, or `None` when the sender isn’t ready to advertise it yet, for instance
+    /// before having retrieved their own vCard.
+    #[xml(child = "photo")]
+    pub photo: Option<Photo>,
+}
+
+impl PresencePayload for VCardUpdate {}
+
+impl VCardUpdate {
+    /// Advertises this avatar, given as the hexadecimal SHA-1 of its image data.
+    pub fn new<H: Into<String>>(hash: H) -> VCardUpdate {
+        VCardUpdate {
+            photo: Some(Photo {
+                hash: Some(hash.into()),
+            }),
+        }
+    }
+
+    /// Advertises having no avatar.
+    pub fn no_avatar() -> VCardUpdate {
+        VCardUpdate {
+            photo: Some(Photo { hash: None }),
+        }
+    }
+
+    /// Returns the hash of the avatar advertised, if any.
+    pub fn hash(&self) -> Option<&str> {
+        self.photo.as_ref()?.hash.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::Error;
+    use crate::Element;
+    use std::convert::TryFrom;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        assert_size!(Photo, 24);
+        assert_size!(VCardUpdate, 24);
+    }
+
+    #[test]
+    fn test_avatar() {
+        let elem: Element = "<x xmlns='vcard-temp:x:update'><photo>01b87fcd030b72895ff8e88db57ec525450f000d</photo></x>"
+            .parse()
+            .unwrap();
+        let update = VCardUpdate::try_from(elem.clone()).unwrap();
+        assert_eq!(
+            update.hash(),
+            Some("01b87fcd030b72895ff8e88db57ec525450f000d")
+        );
+        assert_eq!(
+            update,
+            VCardUpdate::new("01b87fcd030b72895ff8e88db57ec525450f000d")
+        );
+        assert_eq!(Element::from(update), elem);
+    }
+
+    #[test]
+    fn test_no_avatar() {
+        let elem: Element = "<x xmlns='vcard-temp:x:update'><photo/></x>"
+            .parse()
+            .unwrap();
+        let update = VCardUpdate::try_from(elem.clone()).unwrap();
+        assert_eq!(update, VCardUpdate::no_avatar());
+        assert_eq!(update.hash(), None);
+        assert_eq!(Element::from(update), elem);
+    }
+
+    #[test]
+    fn test_not_ready() {
+        let elem: Element = "<x xmlns='vcard-temp:x:update'/>".parse().unwrap();
+        let update = VCardUpdate::try_from(elem).unwrap();
+        assert_eq!(update.photo, None);
+        assert_eq!(update.hash(), None);
+    }
+
+    #[test]
+    fn test_invalid_child() {
+        let elem: Element = "<x xmlns='vcard-temp:x:update'><nickname/></x>"
+            .parse()
+            .unwrap();
+        let error = VCardUpdate::try_from(elem).unwrap_err();
+        let message = match error {
+            Error::ParseError(string) => string,
+            _ => panic!(),
+        };
+        assert_eq!(message, "Unknown child in x element.");
+    }
+}
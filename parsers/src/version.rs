@@ -5,36 +5,39 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::iq::{IqGetPayload, IqResultPayload};
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_empty_element!(
-    /// Represents a query for the software version a remote entity is using.
-    ///
-    /// It should only be used in an `<iq type='get'/>`, as it can only
-    /// represent the request, and not a result.
-    VersionQuery,
-    "query",
-    VERSION
-);
+/// Represents a query for the software version a remote entity is using.
+///
+/// It should only be used in an `<iq type='get'/>`, as it can only
+/// represent the request, and not a result.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VERSION, name = "query")]
+pub struct VersionQuery;
 
 impl IqGetPayload for VersionQuery {}
 
-generate_element!(
-    /// Represents the answer about the software version we are using.
-    ///
-    /// It should only be used in an `<iq type='result'/>`, as it can only
-    /// represent the result, and not a request.
-    VersionResult, "query", VERSION,
-    children: [
-        /// The name of this client.
-        name: Required<String> = ("name", VERSION) => String,
+/// Represents the answer about the software version we are using.
+///
+/// It should only be used in an `<iq type='result'/>`, as it can only
+/// represent the result, and not a request.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = VERSION, name = "query")]
+pub struct VersionResult {
+    /// The name of this client.
+    #[xml(child = "name")]
+    pub name: String,
 
-        /// The version of this client.
-        version: Required<String> = ("version", VERSION) => String,
+    /// The version of this client.
+    #[xml(child = "version")]
+    pub version: String,
 
-        /// The OS this client is running on.
-        os: Option<String> = ("os", VERSION) => String
-    ]
-);
+    /// The OS this client is running on.
+    #[xml(child = "os")]
+    pub os: Option<String>,
+}
 
 impl IqResultPayload for VersionResult {}
 
@@ -5,28 +5,34 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use jid::BareJid;
+use xmpp_parsers_macros::{FromXml, IntoXml};
 
-generate_element!(
-    /// The stream opening for WebSocket.
-    Open, "open", WEBSOCKET,
-    attributes: [
-        /// The JID of the entity opening this stream.
-        from: Option<BareJid> = "from",
+/// The stream opening for WebSocket.
+#[derive(Debug, Clone, PartialEq, FromXml, IntoXml)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(namespace = WEBSOCKET, name = "open")]
+pub struct Open {
+    /// The JID of the entity opening this stream.
+    #[xml(attribute = "from")]
+    pub from: Option<BareJid>,
 
-        /// The JID of the entity receiving this stream opening.
-        to: Option<BareJid> = "to",
+    /// The JID of the entity receiving this stream opening.
+    #[xml(attribute = "to")]
+    pub to: Option<BareJid>,
 
-        /// The id of the stream, used for authentication challenges.
-        id: Option<String> = "id",
+    /// The id of the stream, used for authentication challenges.
+    #[xml(attribute = "id")]
+    pub id: Option<String>,
 
-        /// The XMPP version used during this stream.
-        version: Option<String> = "version",
+    /// The XMPP version used during this stream.
+    #[xml(attribute = "version")]
+    pub version: Option<String>,
 
-        /// The default human language for all subsequent stanzas, which will
-        /// be transmitted to other entities for better localisation.
-        xml_lang: Option<String> = "xml:lang",
-    ]
-);
+    /// The default human language for all subsequent stanzas, which will
+    /// be transmitted to other entities for better localisation.
+    #[xml(attribute = "xml:lang")]
+    pub xml_lang: Option<String>,
+}
 
 impl Open {
     /// Creates a simple client→server `<open/>` element.
@@ -78,13 +84,13 @@ mod tests {
     #[cfg(target_pointer_width = "32")]
     #[test]
     fn test_size() {
-        assert_size!(Open, 84);
+        assert_size!(Open, 68);
     }
 
     #[cfg(target_pointer_width = "64")]
     #[test]
     fn test_size() {
-        assert_size!(Open, 168);
+        assert_size!(Open, 136);
     }
 
     #[test]
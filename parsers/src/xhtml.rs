@@ -16,6 +16,11 @@ type Lang = String;
 
 /// Container for formatted text.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Element", try_from = "Element")
+)]
 pub struct XhtmlIm {
     /// Map of language to body element.
     bodies: HashMap<Lang, Body>,
@@ -160,7 +165,7 @@ impl TryFrom<Element> for Body {
 
         Ok(Body {
             style: parse_css(elem.attr("style")),
-            xml_lang: elem.attr("xml:lang").map(|xml_lang| xml_lang.to_string()),
+            xml_lang: elem.attr_as("xml:lang")?,
             children,
         })
     }
@@ -307,9 +312,9 @@ impl TryFrom<Element> for Tag {
 
         Ok(match elem.name() {
             "a" => Tag::A {
-                href: elem.attr("href").map(|href| href.to_string()),
+                href: elem.attr_as("href")?,
                 style: parse_css(elem.attr("style")),
-                type_: elem.attr("type").map(|type_| type_.to_string()),
+                type_: elem.attr_as("type")?,
                 children,
             },
             "blockquote" => Tag::Blockquote {
@@ -323,8 +328,8 @@ impl TryFrom<Element> for Tag {
             },
             "em" => Tag::Em { children },
             "img" => Tag::Img {
-                src: elem.attr("src").map(|src| src.to_string()),
-                alt: elem.attr("alt").map(|alt| alt.to_string()),
+                src: elem.attr_as("src")?,
+                alt: elem.attr_as("alt")?,
             },
             "li" => Tag::Li {
                 style: parse_css(elem.attr("style")),
@@ -1,4 +1,5 @@
 use futures::{sink::SinkExt, task::Poll, Future, Sink, Stream};
+use minidom::AsXml;
 use sasl::common::{ChannelBinding, Credentials};
 use std::mem::replace;
 use std::pin::Pin;
@@ -15,6 +16,7 @@ use xmpp_parsers::{ns, Element, Jid, JidParseError};
 
 use super::auth::auth;
 use super::bind::bind;
+use super::register;
 use crate::event::Event;
 use crate::happy_eyeballs::{connect_to_host, connect_with_srv};
 use crate::starttls::starttls;
@@ -106,13 +108,14 @@ impl Client {
         jid: Jid,
         password: String,
     ) -> Result<XMPPStream, Error> {
-        let username = jid.clone().node().unwrap();
+        let username = jid.node().unwrap().to_owned();
         let password = password;
 
         // TCP connection
         let tcp_stream = match server {
             ServerConfig::UseSrv => {
-                connect_with_srv(&jid.clone().domain(), "_xmpp-client._tcp", 5222).await?
+                let domain = idna::domain_to_ascii(jid.domain()).map_err(|_| Error::Idna)?;
+                connect_with_srv(&domain, "_xmpp-client._tcp", 5222).await?
             }
             ServerConfig::Manual { host, port } => connect_to_host(host.as_str(), port).await?,
         };
@@ -161,6 +164,42 @@ impl Client {
         self.send(Packet::Stanza(stanza)).await
     }
 
+    /// Send a stanza serialised directly, without building an `Element`
+    pub async fn send_xml<T: AsXml + ?Sized>(&mut self, stanza: &T) -> Result<(), Error> {
+        self.send(Packet::from_xml(stanza)?).await
+    }
+
+    /// Change the password of the account this client is logged into
+    /// (XEP-0077)
+    ///
+    /// The new password is then used when reconnecting. Other stanzas
+    /// received while waiting for the answer are discarded.
+    pub async fn change_password<P: Into<String>>(&mut self, password: P) -> Result<(), Error> {
+        let password = password.into();
+        match self.state {
+            ClientState::Connected(ref mut stream) => {
+                register::change_password(stream, password.clone()).await?
+            }
+            _ => return Err(Error::InvalidState),
+        }
+        self.config.password = password;
+        Ok(())
+    }
+
+    /// Remove the account this client is logged into from the server
+    /// (XEP-0077)
+    ///
+    /// This disables reconnecting, as the server will usually close the
+    /// connection afterwards.
+    pub async fn cancel_registration(&mut self) -> Result<(), Error> {
+        match self.state {
+            ClientState::Connected(ref mut stream) => register::cancel_registration(stream).await?,
+            _ => return Err(Error::InvalidState),
+        }
+        self.reconnect = false;
+        Ok(())
+    }
+
     /// End connection by sending `</stream:stream>`
     ///
     /// You may expect the server to respond with the same. This
@@ -256,8 +295,9 @@ impl Stream for Client {
                         self.state = ClientState::Connected(stream);
                         Poll::Ready(Some(Event::Stanza(stanza)))
                     }
-                    Poll::Ready(Some(Ok(Packet::Text(_)))) => {
-                        // Ignore text between stanzas
+                    Poll::Ready(Some(Ok(Packet::Text(_))))
+                    | Poll::Ready(Some(Ok(Packet::Raw(_)))) => {
+                        // Ignore text between stanzas, raw packets are only ever sent
                         self.state = ClientState::Connected(stream);
                         Poll::Pending
                     }
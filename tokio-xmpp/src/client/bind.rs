@@ -17,7 +17,7 @@ pub async fn bind<S: AsyncRead + AsyncWrite + Unpin>(
 ) -> Result<XMPPStream<S>, Error> {
     if stream.stream_features.can_bind() {
         let resource = if let Jid::Full(jid) = stream.jid.clone() {
-            Some(jid.resource)
+            Some(String::from(jid.resource()))
         } else {
             None
         };
@@ -2,4 +2,5 @@ mod auth;
 mod bind;
 
 pub mod async_client;
+pub mod register;
 pub mod simple_client;
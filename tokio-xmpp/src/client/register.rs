@@ -0,0 +1,310 @@
+//! In-band registration (XEP-0077) of accounts
+
+use futures::{sink::SinkExt, stream::StreamExt};
+use std::convert::TryFrom;
+use std::marker::Unpin;
+use std::str::FromStr;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+#[cfg(feature = "tls-native")]
+use tokio_native_tls::TlsStream;
+#[cfg(feature = "tls-rust")]
+use tokio_rustls::client::TlsStream;
+use xmpp_parsers::ibr::Query;
+use xmpp_parsers::iq::{Iq, IqType};
+use xmpp_parsers::{ns, DomainJid, Element, Jid};
+
+use crate::happy_eyeballs::connect_with_srv;
+use crate::starttls::starttls;
+use crate::xmpp_codec::Packet;
+use crate::xmpp_stream;
+use crate::xmpp_stream::XMPPStream;
+use crate::{Error, ProtocolError, RegistrationError};
+
+const REGISTER_FORM_REQ_ID: &str = "register-form";
+const REGISTER_SUBMIT_REQ_ID: &str = "register-submit";
+const CHANGE_PASSWORD_REQ_ID: &str = "change-password";
+const CANCEL_REGISTRATION_REQ_ID: &str = "cancel-registration";
+
+type TlsXMPPStream = xmpp_stream::XMPPStream<TlsStream<TcpStream>>;
+
+/// An unauthenticated XMPP stream, used to register a new account
+///
+/// Once the registration succeeded, this stream should be ended, and
+/// a new `AsyncClient` or `SimpleClient` created with the
+/// credentials which got submitted.
+pub struct Registration {
+    stream: TlsXMPPStream,
+}
+
+impl Registration {
+    /// Connect to the server of `domain`, without authenticating
+    pub async fn connect(domain: &str) -> Result<Self, Error> {
+        let jid = Jid::from(DomainJid::from_str(domain)?);
+        let domain = idna::domain_to_ascii(domain).map_err(|_| Error::Idna)?;
+
+        // TCP connection
+        let tcp_stream = connect_with_srv(&domain, "_xmpp-client._tcp", 5222).await?;
+
+        // Unencryped XMPPStream
+        let xmpp_stream =
+            xmpp_stream::XMPPStream::start(tcp_stream, jid.clone(), ns::JABBER_CLIENT.to_owned())
+                .await?;
+
+        let xmpp_stream = if xmpp_stream.stream_features.can_starttls() {
+            // TlsStream
+            let tls_stream = starttls(xmpp_stream).await?;
+            // Encrypted XMPPStream
+            xmpp_stream::XMPPStream::start(tls_stream, jid, ns::JABBER_CLIENT.to_owned()).await?
+        } else {
+            return Err(Error::Protocol(ProtocolError::NoTls));
+        };
+
+        Ok(Registration {
+            stream: xmpp_stream,
+        })
+    }
+
+    /// Ensure the server advertises in-band registration in its
+    /// stream features, failing with `RegistrationError::NotSupported`
+    /// otherwise
+    ///
+    /// Some servers still answer registration queries without
+    /// advertising them, so this is only a hint.
+    pub fn ensure_registration_supported(&self) -> Result<(), Error> {
+        if self.stream.stream_features.can_register() {
+            Ok(())
+        } else {
+            Err(RegistrationError::NotSupported.into())
+        }
+    }
+
+    /// Retrieve the fields or data form the server requires to create
+    /// an account
+    ///
+    /// The returned data form may contain media elements (XEP-0221),
+    /// for instance a CAPTCHA (XEP-0158), whose data is then usually
+    /// included in `Query::bob`.
+    pub async fn fetch_form(&mut self) -> Result<Query, Error> {
+        fetch_form(&mut self.stream).await
+    }
+
+    /// Submit the filled fields or data form to create the account
+    pub async fn submit(&mut self, query: Query) -> Result<(), Error> {
+        submit(&mut self.stream, query).await
+    }
+
+    /// End connection by sending `</stream:stream>`
+    pub async fn end(mut self) -> Result<(), Error> {
+        self.stream.send(Packet::StreamEnd).await?;
+
+        // Wait for stream end from server
+        while let Some(Ok(_)) = self.stream.next().await {}
+
+        Ok(())
+    }
+}
+
+async fn fetch_form<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut XMPPStream<S>,
+) -> Result<Query, Error> {
+    let to = stream.jid.clone();
+    let iq = Iq::from_get(REGISTER_FORM_REQ_ID, Query::new()).with_to(to);
+    match send_iq(stream, iq).await? {
+        Some(payload) => {
+            Query::try_from(payload).map_err(|_| RegistrationError::InvalidResponse.into())
+        }
+        None => Err(RegistrationError::InvalidResponse.into()),
+    }
+}
+
+async fn submit<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut XMPPStream<S>,
+    query: Query,
+) -> Result<(), Error> {
+    let to = stream.jid.clone();
+    let iq = Iq::from_set(REGISTER_SUBMIT_REQ_ID, query).with_to(to);
+    send_iq(stream, iq).await?;
+    Ok(())
+}
+
+/// Change the password of the account an authenticated stream is
+/// logged into
+///
+/// Other stanzas received while waiting for the answer are discarded.
+pub async fn change_password<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut XMPPStream<S>,
+    password: String,
+) -> Result<(), Error> {
+    let username = stream.jid.node().ok_or(Error::InvalidState)?.to_owned();
+    let to = Jid::from(stream.jid.to_domain());
+    let query = Query::change_password(username, password);
+    let iq = Iq::from_set(CHANGE_PASSWORD_REQ_ID, query).with_to(to);
+    send_iq(stream, iq).await?;
+    Ok(())
+}
+
+/// Remove the account an authenticated stream is logged into
+///
+/// The server will usually close the stream right after answering.
+pub async fn cancel_registration<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut XMPPStream<S>,
+) -> Result<(), Error> {
+    let to = Jid::from(stream.jid.to_domain());
+    let iq = Iq::from_set(CANCEL_REGISTRATION_REQ_ID, Query::cancel()).with_to(to);
+    send_iq(stream, iq).await?;
+    Ok(())
+}
+
+/// Send an iq, then wait for its result
+async fn send_iq<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut XMPPStream<S>,
+    iq: Iq,
+) -> Result<Option<Element>, Error> {
+    let id = iq.id.clone();
+    stream.send_stanza(iq).await?;
+
+    loop {
+        match stream.next().await {
+            Some(Ok(Packet::Stanza(stanza))) => match Iq::try_from(stanza) {
+                Ok(iq) if iq.id == id => match iq.payload {
+                    IqType::Result(payload) => return Ok(payload),
+                    IqType::Error(error) => return Err(RegistrationError::Rejected(error).into()),
+                    _ => return Err(RegistrationError::InvalidResponse.into()),
+                },
+                _ => {}
+            },
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e),
+            None => return Err(Error::Disconnected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xmpp_codec::XMPPCodec;
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt, DuplexStream};
+    use tokio_util::codec::Framed;
+
+    const STREAM_START: &str = "<stream:stream xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' version='1.0'>";
+
+    fn connect() -> (XMPPStream<DuplexStream>, DuplexStream) {
+        let (client, server) = duplex(4096);
+        let stream = XMPPStream::new(
+            Jid::from_str("example.org").unwrap(),
+            Framed::new(client, XMPPCodec::new()),
+            ns::JABBER_CLIENT.to_owned(),
+            String::from("stream-id"),
+            "<stream:features xmlns:stream='http://etherx.jabber.org/streams'/>"
+                .parse()
+                .unwrap(),
+        );
+        (stream, server)
+    }
+
+    async fn receive(server: &mut DuplexStream) -> Element {
+        let mut buf = vec![0; 4096];
+        let len = server.read(&mut buf).await.unwrap();
+        std::str::from_utf8(&buf[..len]).unwrap().parse().unwrap()
+    }
+
+    async fn answer(server: &mut DuplexStream, stanzas: &str) {
+        let data = format!("{}{}", STREAM_START, stanzas);
+        server.write_all(data.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_form() {
+        let (mut stream, mut server) = connect();
+        let (query, ()) = tokio::join!(fetch_form(&mut stream), async {
+            let iq = Iq::try_from(receive(&mut server).await).unwrap();
+            assert_eq!(iq.id, REGISTER_FORM_REQ_ID);
+            assert_eq!(iq.to, Some(Jid::from_str("example.org").unwrap()));
+            match iq.payload {
+                IqType::Get(payload) => {
+                    assert!(payload.is("query", ns::REGISTER));
+                    assert_eq!(payload.children().count(), 0);
+                }
+                _ => panic!(),
+            }
+            answer(
+                &mut server,
+                "<message from='example.org'><body>Welcome!</body></message>
+                <iq type='result' id='register-form'>
+                    <query xmlns='jabber:iq:register'>
+                        <instructions>Choose a username and password.</instructions>
+                        <username/>
+                        <password/>
+                    </query>
+                </iq>",
+            )
+            .await;
+        });
+        let query = query.unwrap();
+        assert!(!query.registered);
+        assert_eq!(query.fields["username"], "");
+        assert_eq!(query.fields["password"], "");
+    }
+
+    #[tokio::test]
+    async fn test_submit() {
+        let (mut stream, mut server) = connect();
+        let query = Query::new()
+            .with_field("username", "bill")
+            .with_field("password", "Calliope");
+        let (result, ()) = tokio::join!(submit(&mut stream, query), async {
+            let iq = Iq::try_from(receive(&mut server).await).unwrap();
+            assert_eq!(iq.id, REGISTER_SUBMIT_REQ_ID);
+            match iq.payload {
+                IqType::Set(payload) => {
+                    let query = Query::try_from(payload).unwrap();
+                    assert_eq!(query.fields["username"], "bill");
+                    assert_eq!(query.fields["password"], "Calliope");
+                }
+                _ => panic!(),
+            }
+            answer(&mut server, "<iq type='result' id='register-submit'/>").await;
+        });
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejected() {
+        let (mut stream, mut server) = connect();
+        let query = Query::new().with_field("username", "bill");
+        let (result, ()) = tokio::join!(submit(&mut stream, query), async {
+            receive(&mut server).await;
+            answer(
+                &mut server,
+                "<iq type='error' id='register-submit'>
+                    <error type='cancel'>
+                        <conflict xmlns='urn:ietf:params:xml:ns:xmpp-stanzas'/>
+                    </error>
+                </iq>",
+            )
+            .await;
+        });
+        match result {
+            Err(Error::Registration(RegistrationError::Rejected(error))) => assert_eq!(
+                error.defined_condition,
+                xmpp_parsers::stanza_error::DefinedCondition::Conflict
+            ),
+            _ => panic!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_form_disconnected() {
+        let (mut stream, mut server) = connect();
+        let (result, ()) = tokio::join!(fetch_form(&mut stream), async {
+            receive(&mut server).await;
+            drop(server);
+        });
+        match result {
+            Err(Error::Disconnected) => (),
+            _ => panic!(),
+        }
+    }
+}
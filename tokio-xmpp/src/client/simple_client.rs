@@ -1,5 +1,6 @@
 use futures::{sink::SinkExt, Sink, Stream};
 use idna;
+use minidom::AsXml;
 use sasl::common::{ChannelBinding, Credentials};
 use std::pin::Pin;
 use std::str::FromStr;
@@ -14,6 +15,7 @@ use xmpp_parsers::{ns, Element, Jid};
 
 use super::auth::auth;
 use super::bind::bind;
+use super::register;
 use crate::happy_eyeballs::connect_with_srv;
 use crate::starttls::starttls;
 use crate::xmpp_codec::Packet;
@@ -50,9 +52,9 @@ impl Client {
     }
 
     async fn connect(jid: Jid, password: String) -> Result<XMPPStream, Error> {
-        let username = jid.clone().node().unwrap();
+        let username = jid.node().unwrap().to_owned();
         let password = password;
-        let domain = idna::domain_to_ascii(&jid.clone().domain()).map_err(|_| Error::Idna)?;
+        let domain = idna::domain_to_ascii(jid.domain()).map_err(|_| Error::Idna)?;
 
         // TCP connection
         let tcp_stream = connect_with_srv(&domain, "_xmpp-client._tcp", 5222).await?;
@@ -101,6 +103,25 @@ impl Client {
         self.send(Packet::Stanza(stanza.into())).await
     }
 
+    /// Send a stanza serialised directly, without building an `Element`
+    pub async fn send_xml<T: AsXml + ?Sized>(&mut self, stanza: &T) -> Result<(), Error> {
+        self.send(Packet::from_xml(stanza)?).await
+    }
+
+    /// Change the password of the account this client is logged into
+    /// (XEP-0077)
+    pub async fn change_password<P: Into<String>>(&mut self, password: P) -> Result<(), Error> {
+        register::change_password(&mut self.stream, password.into()).await
+    }
+
+    /// Remove the account this client is logged into from the server
+    /// (XEP-0077)
+    ///
+    /// The server will usually close the connection afterwards.
+    pub async fn cancel_registration(mut self) -> Result<(), Error> {
+        register::cancel_registration(&mut self.stream).await
+    }
+
     /// End connection by sending `</stream:stream>`
     ///
     /// You may expect the server to respond with the same. This
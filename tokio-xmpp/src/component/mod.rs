@@ -2,11 +2,12 @@
 //! XMPP server under a JID consisting of just a domain name. They are
 //! allowed to use any user and resource identifiers in their stanzas.
 use futures::{sink::SinkExt, task::Poll, Sink, Stream};
+use minidom::AsXml;
 use std::pin::Pin;
 use std::str::FromStr;
 use std::task::Context;
 use tokio::net::TcpStream;
-use xmpp_parsers::{ns, Element, Jid};
+use xmpp_parsers::{ns, DomainJid, Element};
 
 use super::happy_eyeballs::connect_to_host;
 use super::xmpp_codec::Packet;
@@ -21,7 +22,7 @@ mod auth;
 /// (stanzas). Connection handling however is up to the user.
 pub struct Component {
     /// The component's Jabber-Id
-    pub jid: Jid,
+    pub jid: DomainJid,
     stream: XMPPStream,
 }
 
@@ -30,14 +31,14 @@ type XMPPStream = xmpp_stream::XMPPStream<TcpStream>;
 impl Component {
     /// Start a new XMPP component
     pub async fn new(jid: &str, password: &str, server: &str, port: u16) -> Result<Self, Error> {
-        let jid = Jid::from_str(jid)?;
+        let jid = DomainJid::from_str(jid)?;
         let password = password.to_owned();
         let stream = Self::connect(jid.clone(), password, server, port).await?;
         Ok(Component { jid, stream })
     }
 
     async fn connect(
-        jid: Jid,
+        jid: DomainJid,
         password: String,
         server: &str,
         port: u16,
@@ -45,7 +46,7 @@ impl Component {
         let password = password;
         let tcp_stream = connect_to_host(server, port).await?;
         let mut xmpp_stream =
-            xmpp_stream::XMPPStream::start(tcp_stream, jid, ns::COMPONENT_ACCEPT.to_owned())
+            xmpp_stream::XMPPStream::start(tcp_stream, jid.into(), ns::COMPONENT_ACCEPT.to_owned())
                 .await?;
         auth::auth(&mut xmpp_stream, password).await?;
         Ok(xmpp_stream)
@@ -56,6 +57,11 @@ impl Component {
         self.send(stanza).await
     }
 
+    /// Send a stanza serialised directly, without building an `Element`
+    pub async fn send_xml<T: AsXml + ?Sized>(&mut self, stanza: &T) -> Result<(), Error> {
+        self.stream.send(Packet::from_xml(stanza)?).await
+    }
+
     /// End connection
     pub async fn send_end(&mut self) -> Result<(), Error> {
         self.close().await
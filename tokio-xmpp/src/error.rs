@@ -1,11 +1,9 @@
+use minidom::Error as MinidomError;
 #[cfg(feature = "tls-native")]
 use native_tls::Error as TlsError;
 use sasl::client::MechanismError as SaslMechanismError;
-use std::borrow::Cow;
-use std::error::Error as StdError;
 use std::fmt;
 use std::io::Error as IoError;
-use std::str::Utf8Error;
 #[cfg(feature = "tls-rust")]
 use tokio_rustls::rustls::client::InvalidDnsNameError;
 #[cfg(feature = "tls-rust")]
@@ -14,6 +12,7 @@ use trust_dns_proto::error::ProtoError;
 use trust_dns_resolver::error::ResolveError;
 
 use xmpp_parsers::sasl::DefinedCondition as SaslDefinedCondition;
+use xmpp_parsers::stanza_error::StanzaError;
 use xmpp_parsers::{Error as ParsersError, JidParseError};
 
 /// Top-level error type
@@ -32,6 +31,8 @@ pub enum Error {
     Protocol(ProtocolError),
     /// Authentication error
     Auth(AuthError),
+    /// In-band registration error
+    Registration(RegistrationError),
     /// TLS error
     Tls(TlsError),
     #[cfg(feature = "tls-rust")]
@@ -52,6 +53,7 @@ impl fmt::Display for Error {
             Error::JidParse(e) => write!(fmt, "jid parse error: {}", e),
             Error::Protocol(e) => write!(fmt, "protocol error: {}", e),
             Error::Auth(e) => write!(fmt, "authentication error: {}", e),
+            Error::Registration(e) => write!(fmt, "registration error: {}", e),
             Error::Tls(e) => write!(fmt, "TLS error: {}", e),
             #[cfg(feature = "tls-rust")]
             Error::DnsNameError(e) => write!(fmt, "DNS name error: {}", e),
@@ -91,6 +93,12 @@ impl From<AuthError> for Error {
     }
 }
 
+impl From<RegistrationError> for Error {
+    fn from(e: RegistrationError) -> Self {
+        Error::Registration(e)
+    }
+}
+
 impl From<TlsError> for Error {
     fn from(e: TlsError) -> Self {
         Error::Tls(e)
@@ -107,12 +115,8 @@ impl From<InvalidDnsNameError> for Error {
 /// Causes for stream parsing errors
 #[derive(Debug)]
 pub enum ParserError {
-    /// Encoding error
-    Utf8(Utf8Error),
     /// XML parse error
-    Parse(ParseError),
-    /// Illegal `</>`
-    ShortTag,
+    Xml(MinidomError),
     /// Required by `impl Decoder`
     Io(IoError),
 }
@@ -120,9 +124,7 @@ pub enum ParserError {
 impl fmt::Display for ParserError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParserError::Utf8(e) => write!(fmt, "UTF-8 error: {}", e),
-            ParserError::Parse(e) => write!(fmt, "parse error: {}", e),
-            ParserError::ShortTag => write!(fmt, "short tag"),
+            ParserError::Xml(e) => write!(fmt, "XML error: {}", e),
             ParserError::Io(e) => write!(fmt, "IO error: {}", e),
         }
     }
@@ -134,28 +136,15 @@ impl From<IoError> for ParserError {
     }
 }
 
-impl From<ParserError> for Error {
-    fn from(e: ParserError) -> Self {
-        ProtocolError::Parser(e).into()
-    }
-}
-
-/// XML parse error wrapper type
-#[derive(Debug)]
-pub struct ParseError(pub Cow<'static, str>);
-
-impl StdError for ParseError {
-    fn description(&self) -> &str {
-        self.0.as_ref()
-    }
-    fn cause(&self) -> Option<&dyn StdError> {
-        None
+impl From<MinidomError> for ParserError {
+    fn from(e: MinidomError) -> Self {
+        ParserError::Xml(e)
     }
 }
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+impl From<ParserError> for Error {
+    fn from(e: ParserError) -> Self {
+        ProtocolError::Parser(e).into()
     }
 }
 
@@ -235,6 +224,27 @@ impl fmt::Display for AuthError {
     }
 }
 
+/// In-band registration error
+#[derive(Debug)]
+pub enum RegistrationError {
+    /// The server doesn’t advertise in-band registration
+    NotSupported,
+    /// The server answered with an error
+    Rejected(StanzaError),
+    /// The server answered with something else than a registration query
+    InvalidResponse,
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistrationError::NotSupported => write!(fmt, "in-band registration not supported"),
+            RegistrationError::Rejected(e) => write!(fmt, "rejected by the server: {:?}", e),
+            RegistrationError::InvalidResponse => write!(fmt, "invalid registration response"),
+        }
+    }
+}
+
 /// Error establishing connection
 #[derive(Debug)]
 pub enum ConnecterError {
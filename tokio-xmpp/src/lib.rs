@@ -12,9 +12,14 @@ mod client;
 mod happy_eyeballs;
 pub mod stream_features;
 pub mod xmpp_stream;
-pub use client::{async_client::Client as AsyncClient, simple_client::Client as SimpleClient};
+pub use client::{
+    async_client::Client as AsyncClient, register::Registration,
+    simple_client::Client as SimpleClient,
+};
 mod component;
 pub use crate::component::Component;
 mod error;
-pub use crate::error::{AuthError, ConnecterError, Error, ParseError, ParserError, ProtocolError};
+pub use crate::error::{
+    AuthError, ConnecterError, Error, ParserError, ProtocolError, RegistrationError,
+};
 pub use starttls::starttls;
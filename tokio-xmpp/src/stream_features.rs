@@ -32,6 +32,11 @@ impl StreamFeatures {
             .map(|mech_el| mech_el.text()))
     }
 
+    /// Does server support in-band registration?
+    pub fn can_register(&self) -> bool {
+        self.0.get_child("register", ns::REGISTER_FEATURE).is_some()
+    }
+
     /// Does server support user resource binding?
     pub fn can_bind(&self) -> bool {
         self.0.get_child("bind", ns::BIND).is_some()
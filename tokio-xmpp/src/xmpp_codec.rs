@@ -1,23 +1,14 @@
 //! XML stream parser for XMPP
 
-use crate::{ParseError, ParserError};
-use bytes::{BufMut, BytesMut};
-use log::{debug, error};
+use crate::ParserError;
+use bytes::{BufMut, Bytes, BytesMut};
+use log::debug;
+use minidom::{AsXml, Parser, ParserLimits, ParserResult};
 use std;
-use std::borrow::Cow;
-use std::collections::vec_deque::VecDeque;
 use std::collections::HashMap;
-use std::default::Default;
 use std::fmt::Write;
 use std::io;
-use std::iter::FromIterator;
-use std::str::from_utf8;
-use std::sync::Arc;
-use std::sync::Mutex;
 use tokio_util::codec::{Decoder, Encoder};
-use xml5ever::buffer_queue::BufferQueue;
-use xml5ever::interface::Attribute;
-use xml5ever::tokenizer::{Tag, TagKind, Token, TokenSink, XmlTokenizer};
 use xmpp_parsers::Element;
 
 /// Anything that can be sent or received on an XMPP/XML stream
@@ -29,179 +20,38 @@ pub enum Packet {
     Stanza(Element),
     /// Plain text (think whitespace keep-alive)
     Text(String),
+    /// An already serialised stanza or nonza, written as is
+    Raw(Bytes),
     /// `</stream:stream>` closing tag
     StreamEnd,
 }
 
-type QueueItem = Result<Packet, ParserError>;
-
-/// Parser state
-struct ParserSink {
-    // Ready stanzas, shared with XMPPCodec
-    queue: Arc<Mutex<VecDeque<QueueItem>>>,
-    // Parsing stack
-    stack: Vec<Element>,
-    ns_stack: Vec<HashMap<Option<String>, String>>,
-}
-
-impl ParserSink {
-    pub fn new(queue: Arc<Mutex<VecDeque<QueueItem>>>) -> Self {
-        ParserSink {
-            queue,
-            stack: vec![],
-            ns_stack: vec![],
-        }
-    }
-
-    fn push_queue(&self, pkt: Packet) {
-        self.queue.lock().unwrap().push_back(Ok(pkt));
-    }
-
-    fn push_queue_error(&self, e: ParserError) {
-        self.queue.lock().unwrap().push_back(Err(e));
-    }
-
-    /// Lookup XML namespace declaration for given prefix (or no prefix)
-    fn lookup_ns(&self, prefix: &Option<String>) -> Option<&str> {
-        for nss in self.ns_stack.iter().rev() {
-            if let Some(ns) = nss.get(prefix) {
-                return Some(ns);
-            }
-        }
-
-        None
-    }
-
-    fn handle_start_tag(&mut self, tag: Tag) {
-        let mut nss = HashMap::new();
-        let is_prefix_xmlns = |attr: &Attribute| {
-            attr.name
-                .prefix
-                .as_ref()
-                .map(|prefix| prefix.eq_str_ignore_ascii_case("xmlns"))
-                .unwrap_or(false)
-        };
-        for attr in &tag.attrs {
-            match attr.name.local.as_ref() {
-                "xmlns" => {
-                    nss.insert(None, attr.value.as_ref().to_owned());
-                }
-                prefix if is_prefix_xmlns(attr) => {
-                    nss.insert(Some(prefix.to_owned()), attr.value.as_ref().to_owned());
-                }
-                _ => (),
-            }
-        }
-        self.ns_stack.push(nss);
-
-        let el = {
-            let el_ns = self
-                .lookup_ns(&tag.name.prefix.map(|prefix| prefix.as_ref().to_owned()))
-                .unwrap();
-            let mut el_builder = Element::builder(tag.name.local.as_ref(), el_ns);
-            for attr in &tag.attrs {
-                match attr.name.local.as_ref() {
-                    "xmlns" => (),
-                    _ if is_prefix_xmlns(attr) => (),
-                    _ => {
-                        let attr_name = if let Some(ref prefix) = attr.name.prefix {
-                            Cow::Owned(format!("{}:{}", prefix, attr.name.local))
-                        } else {
-                            Cow::Borrowed(attr.name.local.as_ref())
-                        };
-                        el_builder = el_builder.attr(attr_name, attr.value.as_ref());
-                    }
-                }
-            }
-            el_builder.build()
-        };
-
-        if self.stack.is_empty() {
-            let attrs = HashMap::from_iter(tag.attrs.iter().map(|attr| {
-                (
-                    attr.name.local.as_ref().to_owned(),
-                    attr.value.as_ref().to_owned(),
-                )
-            }));
-            self.push_queue(Packet::StreamStart(attrs));
-        }
-
-        self.stack.push(el);
-    }
-
-    fn handle_end_tag(&mut self) {
-        let el = self.stack.pop().unwrap();
-        self.ns_stack.pop();
-
-        match self.stack.len() {
-            // </stream:stream>
-            0 => self.push_queue(Packet::StreamEnd),
-            // </stanza>
-            1 => self.push_queue(Packet::Stanza(el)),
-            len => {
-                let parent = &mut self.stack[len - 1];
-                parent.append_child(el);
-            }
-        }
+impl Packet {
+    /// Serialises a stanza or nonza directly, without building an `Element` first
+    pub fn from_xml<T: AsXml + ?Sized>(item: &T) -> Result<Packet, ParserError> {
+        Ok(Packet::Raw(item.to_bytes()?.freeze()))
     }
 }
 
-impl TokenSink for ParserSink {
-    fn process_token(&mut self, token: Token) {
-        match token {
-            Token::TagToken(tag) => match tag.kind {
-                TagKind::StartTag => self.handle_start_tag(tag),
-                TagKind::EndTag => self.handle_end_tag(),
-                TagKind::EmptyTag => {
-                    self.handle_start_tag(tag);
-                    self.handle_end_tag();
-                }
-                TagKind::ShortTag => self.push_queue_error(ParserError::ShortTag),
-            },
-            Token::CharacterTokens(tendril) => match self.stack.len() {
-                0 | 1 => self.push_queue(Packet::Text(tendril.into())),
-                len => {
-                    let el = &mut self.stack[len - 1];
-                    el.append_text_node(tendril);
-                }
-            },
-            Token::EOFToken => self.push_queue(Packet::StreamEnd),
-            Token::ParseError(s) => {
-                self.push_queue_error(ParserError::Parse(ParseError(s)));
-            }
-            _ => (),
-        }
-    }
-
-    // fn end(&mut self) {
-    // }
-}
-
 /// Stateful encoder/decoder for a bytestream from/to XMPP `Packet`
 pub struct XMPPCodec {
     /// Outgoing
     ns: Option<String>,
     /// Incoming
-    parser: XmlTokenizer<ParserSink>,
-    /// For handling incoming truncated utf8
-    // TODO: optimize using  tendrils?
-    buf: Vec<u8>,
-    /// Shared with ParserSink
-    queue: Arc<Mutex<VecDeque<QueueItem>>>,
+    parser: Parser,
 }
 
 impl XMPPCodec {
     /// Constructor
     pub fn new() -> Self {
-        let queue = Arc::new(Mutex::new(VecDeque::new()));
-        let sink = ParserSink::new(queue.clone());
-        // TODO: configure parser?
-        let parser = XmlTokenizer::new(sink, Default::default());
+        Self::with_limits(ParserLimits::default())
+    }
+
+    /// Constructor, bounding what the peer can send
+    pub fn with_limits(limits: ParserLimits) -> Self {
         XMPPCodec {
             ns: None,
-            parser,
-            queue,
-            buf: vec![],
+            parser: Parser::with_limits(limits),
         }
     }
 }
@@ -212,59 +62,37 @@ impl Default for XMPPCodec {
     }
 }
 
+/// Attributes of the `<stream:stream>` start tag, including its namespace declarations.
+fn stream_attrs(root: &Element) -> HashMap<String, String> {
+    let declarations = root
+        .declared_prefixes()
+        .iter()
+        .map(|(prefix, ns)| match prefix {
+            None => (String::from("xmlns"), ns.clone()),
+            Some(prefix) => (format!("xmlns:{}", prefix), ns.clone()),
+        });
+    root.attrs()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .chain(declarations)
+        .collect()
+}
+
 impl Decoder for XMPPCodec {
     type Item = Packet;
     type Error = ParserError;
 
     fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let buf1: Box<dyn AsRef<[u8]>> = if !self.buf.is_empty() && !buf.is_empty() {
-            let mut prefix = std::mem::replace(&mut self.buf, vec![]);
-            prefix.extend_from_slice(&buf.split_to(buf.len()));
-            Box::new(prefix)
-        } else {
-            Box::new(buf.split_to(buf.len()))
-        };
-        let buf1 = buf1.as_ref().as_ref();
-        match from_utf8(buf1) {
-            Ok(s) => {
-                debug!("<< {:?}", s);
-                if !s.is_empty() {
-                    let mut buffer_queue = BufferQueue::new();
-                    let tendril = FromIterator::from_iter(s.chars());
-                    buffer_queue.push_back(tendril);
-                    self.parser.feed(&mut buffer_queue);
-                }
-            }
-            // Remedies for truncated utf8
-            Err(e) if e.valid_up_to() >= buf1.len() - 3 => {
-                // Prepare all the valid data
-                let mut b = BytesMut::with_capacity(e.valid_up_to());
-                b.put(&buf1[0..e.valid_up_to()]);
-
-                // Retry
-                let result = self.decode(&mut b);
-
-                // Keep the tail back in
-                self.buf.extend_from_slice(&buf1[e.valid_up_to()..]);
-
-                return result;
-            }
-            Err(e) => {
-                error!(
-                    "error {} at {}/{} in {:?}",
-                    e,
-                    e.valid_up_to(),
-                    buf1.len(),
-                    buf1
-                );
-                return Err(ParserError::Utf8(e));
-            }
+        if !buf.is_empty() {
+            debug!("<< {:?}", String::from_utf8_lossy(buf));
+            self.parser.feed(buf.split())?;
         }
 
-        match self.queue.lock().unwrap().pop_front() {
-            None => Ok(None),
-            Some(result) => result.map(|pkt| Some(pkt)),
-        }
+        Ok(self.parser.poll()?.map(|result| match result {
+            ParserResult::StreamStart(root) => Packet::StreamStart(stream_attrs(&root)),
+            ParserResult::Single(stanza) => Packet::Stanza(stanza),
+            ParserResult::Text(text) => Packet::Text(text),
+            ParserResult::StreamEnd => Packet::StreamEnd,
+        }))
     }
 
     fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
@@ -314,6 +142,11 @@ impl Encoder<Packet> for XMPPCodec {
                     Ok(())
                 })
                 .map_err(to_io_err),
+            Packet::Raw(bytes) => {
+                dst.put_slice(&bytes);
+                debug!(">> {:?}", dst);
+                Ok(())
+            }
             Packet::StreamEnd => write!(dst, "</stream:stream>\n").map_err(to_io_err),
         }
     }
@@ -369,6 +202,7 @@ impl<'a> std::io::Write for WriteBytes<'a> {
 mod tests {
     use super::*;
     use bytes::BytesMut;
+    use xmpp_parsers::ns;
 
     #[test]
     fn test_stream_start() {
@@ -376,10 +210,65 @@ mod tests {
         let mut b = BytesMut::with_capacity(1024);
         b.put_slice(b"<?xml version='1.0'?><stream:stream xmlns:stream='http://etherx.jabber.org/streams' version='1.0' xmlns='jabber:client'>");
         let r = c.decode(&mut b);
+        assert!(match r {
+            Ok(Some(Packet::StreamStart(ref attrs)))
+                if attrs.get("xmlns").map_or(false, |ns| ns == "jabber:client")
+                    && attrs
+                        .get("xmlns:stream")
+                        .map_or(false, |ns| ns == "http://etherx.jabber.org/streams")
+                    && attrs.get("version").map_or(false, |v| v == "1.0") =>
+                true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn test_multiple_stanzas() {
+        let mut c = XMPPCodec::new();
+        let mut b = BytesMut::with_capacity(1024);
+        b.put_slice(b"<stream:stream xmlns:stream='http://etherx.jabber.org/streams' xmlns='jabber:client'><stream:features/> <iq type='get'/>");
+        let r = c.decode(&mut b);
         assert!(match r {
             Ok(Some(Packet::StreamStart(_))) => true,
             _ => false,
         });
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Ok(Some(Packet::Stanza(ref el))) if el.is("features", ns::STREAM) => true,
+            _ => false,
+        });
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Ok(Some(Packet::Text(ref text))) if text == " " => true,
+            _ => false,
+        });
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Ok(Some(Packet::Stanza(ref el))) if el.is("iq", ns::JABBER_CLIENT) => true,
+            _ => false,
+        });
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Ok(None) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn test_invalid_xml() {
+        let mut c = XMPPCodec::new();
+        let mut b = BytesMut::with_capacity(1024);
+        b.put_slice(b"<stream:stream xmlns:stream='http://etherx.jabber.org/streams' xmlns='jabber:client'><message></iq>");
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Ok(Some(Packet::StreamStart(_))) => true,
+            _ => false,
+        });
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Err(ParserError::Xml(_)) => true,
+            _ => false,
+        });
     }
 
     #[test]
@@ -508,6 +397,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_raw_packet() {
+        use futures::{executor::block_on, sink::SinkExt};
+        use std::io::Cursor;
+        use tokio_util::codec::FramedWrite;
+        use xmpp_parsers::message::{Body, Message};
+        let mut framed = FramedWrite::new(Cursor::new(vec![]), XMPPCodec::new());
+        let mut message = Message::new(None);
+        message
+            .bodies
+            .insert(String::new(), Body(String::from("Hello & goodbye")));
+        let expected = String::from(&Element::from(message.clone()));
+        block_on(framed.send(Packet::from_xml(&message).unwrap())).expect("send");
+        assert_eq!(framed.get_ref().get_ref(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_too_large_stanza() {
+        let mut c = XMPPCodec::with_limits(ParserLimits {
+            max_stanza_size: 1024,
+            ..ParserLimits::default()
+        });
+        let mut b = BytesMut::with_capacity(4096);
+        b.put_slice(b"<?xml version='1.0'?><stream:stream xmlns:stream='http://etherx.jabber.org/streams' version='1.0' xmlns='jabber:client'>");
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Ok(Some(Packet::StreamStart(_))) => true,
+            _ => false,
+        });
+
+        b.clear();
+        b.put_slice(b"<message><body>");
+        b.put_slice(&[b'A'; 1024][..]);
+        let r = c.decode(&mut b);
+        assert!(match r {
+            Err(ParserError::Xml(minidom::Error::ParserError(
+                minidom::ParserError::StanzaTooLarge,
+            ))) => true,
+            _ => false,
+        });
+    }
+
     #[test]
     fn test_cut_out_stanza() {
         let mut c = XMPPCodec::new();
@@ -2,6 +2,7 @@
 
 use futures::sink::Send;
 use futures::{sink::SinkExt, task::Poll, Sink, Stream};
+use minidom::AsXml;
 use std::ops::DerefMut;
 use std::pin::Pin;
 use std::sync::Mutex;
@@ -76,6 +77,11 @@ impl<S: AsyncRead + AsyncWrite + Unpin> XMPPStream<S> {
     pub fn send_stanza<E: Into<Element>>(&mut self, e: E) -> Send<Self, Packet> {
         self.send(Packet::Stanza(e.into()))
     }
+
+    /// Serialises a stanza directly into the stream, without building an `Element`
+    pub async fn send_xml<T: AsXml + ?Sized>(&mut self, item: &T) -> Result<(), Error> {
+        self.send(Packet::from_xml(item)?).await
+    }
 }
 
 /// Proxy to self.stream
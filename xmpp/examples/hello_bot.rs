@@ -51,12 +51,12 @@ async fn main() -> Result<(), Option<()>> {
                 Event::ContactChanged(contact) => {
                     println!("Contact {} changed.", contact.jid);
                 }
-                Event::ChatMessage(jid, body) => {
+                Event::ChatMessage(jid, _id, body) => {
                     println!("Message from {}: {}", jid, body.0);
                 }
                 Event::JoinRoom(jid, conference) => {
                     println!("Joining room {} ({:?})…", jid, conference.name);
-                    client
+                    if let Err(err) = client
                         .join_room(
                             jid,
                             conference.nick,
@@ -64,7 +64,10 @@ async fn main() -> Result<(), Option<()>> {
                             "en",
                             "Yet another bot!",
                         )
-                        .await;
+                        .await
+                    {
+                        println!("Invalid nick: {}", err);
+                    }
                 }
                 Event::LeaveRoom(jid) => {
                     println!("Leaving room {}…", jid);
@@ -81,12 +84,21 @@ async fn main() -> Result<(), Option<()>> {
                 Event::RoomLeft(jid) => {
                     println!("Left room {}.", jid);
                 }
-                Event::RoomMessage(jid, nick, body) => {
+                Event::RoomMessage(jid, nick, _id, body) => {
                     println!("Message in room {} from {}: {}", jid, nick, body.0);
                 }
                 Event::AvatarRetrieved(jid, path) => {
                     println!("Received avatar for {} in {}.", jid, path);
                 }
+                Event::Reactions(jid, id, reactions) => {
+                    println!("Reactions from {} to {}: {:?}", jid, id, reactions);
+                }
+                Event::MessageRetracted(jid, id) => {
+                    println!("Message {} retracted by {}.", id, jid);
+                }
+                Event::MessageDisplayed(jid, id) => {
+                    println!("Message {} displayed to {}.", id, jid);
+                }
             }
         }
     }
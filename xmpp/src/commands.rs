@@ -0,0 +1,467 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use xmpp_parsers::{
+    commands::{Action, Actions, Command, Note, Status},
+    data_forms::DataForm,
+    disco::{DiscoItemsResult, Item as DiscoItem},
+    ns,
+    stanza_error::{DefinedCondition, ErrorType, StanzaError},
+    Jid,
+};
+
+/// The state of a command being executed by a requester, kept between its stages.
+#[derive(Debug)]
+pub struct CommandSession {
+    /// The entity executing this command.
+    pub from: Jid,
+
+    /// The node of the command this session belongs to.
+    pub node: String,
+
+    /// The identifier of this session.
+    pub id: String,
+
+    /// The forms submitted at each stage so far, the last one being the one just submitted.
+    ///
+    /// Going back to the previous stage removes the last one, so its length is also the
+    /// current stage.
+    pub forms: Vec<DataForm>,
+}
+
+/// What a command handler answers at each stage.
+#[derive(Debug)]
+pub struct CommandResponse {
+    /// Whether the command expects another stage, or is finished.
+    pub status: Status,
+
+    /// The actions allowed at the next stage, when executing.
+    pub actions: Option<Actions>,
+
+    /// Notes to display to the requester.
+    pub notes: Vec<Note>,
+
+    /// The form to fill at the next stage, or the result of the command.
+    pub form: Option<DataForm>,
+}
+
+impl CommandResponse {
+    /// Asks the requester to fill this form, and to do one of these actions.
+    pub fn form(form: DataForm, actions: Actions) -> CommandResponse {
+        CommandResponse {
+            status: Status::Executing,
+            actions: Some(actions),
+            notes: vec![],
+            form: Some(form),
+        }
+    }
+
+    /// Completes the command, with these notes for the requester.
+    pub fn completed(notes: Vec<Note>) -> CommandResponse {
+        CommandResponse {
+            status: Status::Completed,
+            actions: None,
+            notes,
+            form: None,
+        }
+    }
+
+    /// Completes the command, with a result form.
+    pub fn completed_with_form(form: DataForm) -> CommandResponse {
+        CommandResponse {
+            status: Status::Completed,
+            actions: None,
+            notes: vec![],
+            form: Some(form),
+        }
+    }
+}
+
+/// A command which can be executed by other entities.
+///
+/// It is called once per stage, with `session.forms` containing what the requester submitted so
+/// far, and `action` being `Execute` on the first stage.  Canceling is handled by the agent.
+pub trait CommandHandler {
+    /// Runs the current stage of the command.
+    fn execute(&mut self, session: &CommandSession, action: Action) -> CommandResponse;
+}
+
+impl<F> CommandHandler for F
+where
+    F: FnMut(&CommandSession, Action) -> CommandResponse,
+{
+    fn execute(&mut self, session: &CommandSession, action: Action) -> CommandResponse {
+        self(session, action)
+    }
+}
+
+struct RegisteredCommand {
+    name: String,
+    handler: Box<dyn CommandHandler>,
+}
+
+/// How many sessions a single entity can have in progress at the same time.
+const MAX_SESSIONS_PER_JID: usize = 8;
+
+/// How long a session can stay without any new stage before being dropped.
+const SESSION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// The commands registered on an agent, and their sessions in progress along with the time of
+/// their last stage.
+#[derive(Default)]
+pub(crate) struct Commands {
+    commands: Vec<(String, RegisteredCommand)>,
+    sessions: HashMap<String, (Instant, CommandSession)>,
+    next_session: u64,
+}
+
+fn error(type_: ErrorType, condition: DefinedCondition, text: &str) -> Box<StanzaError> {
+    Box::new(StanzaError::new(type_, condition, "en", text))
+}
+
+impl Commands {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub(crate) fn register(
+        &mut self,
+        node: String,
+        name: String,
+        handler: Box<dyn CommandHandler>,
+    ) {
+        self.commands.retain(|(other, _)| *other != node);
+        self.commands
+            .push((node, RegisteredCommand { name, handler }));
+    }
+
+    /// Lists the commands, for a disco#items query on the commands node.
+    pub(crate) fn disco_items(&self, jid: &Jid) -> DiscoItemsResult {
+        DiscoItemsResult {
+            node: Some(String::from(ns::COMMANDS)),
+            items: self
+                .commands
+                .iter()
+                .map(|(node, command)| DiscoItem {
+                    jid: jid.clone(),
+                    node: Some(node.clone()),
+                    name: Some(command.name.clone()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Drops the sessions whose last stage happened more than `SESSION_TIMEOUT` before `now`.
+    fn expire(&mut self, now: Instant) {
+        self.sessions
+            .retain(|_, (last, _)| now.duration_since(*last) < SESSION_TIMEOUT);
+    }
+
+    /// Runs a stage of a command requested by `from`, returning the result to send back.
+    pub(crate) fn handle(
+        &mut self,
+        from: Jid,
+        command: Command,
+    ) -> Result<Command, Box<StanzaError>> {
+        let now = Instant::now();
+        self.expire(now);
+
+        let handler = match self
+            .commands
+            .iter_mut()
+            .find(|(node, _)| *node == command.node)
+        {
+            Some((_, registered)) => &mut registered.handler,
+            None => {
+                return Err(error(
+                    ErrorType::Cancel,
+                    DefinedCondition::ItemNotFound,
+                    "No such command.",
+                ))
+            }
+        };
+
+        let mut session = match command.sessionid {
+            Some(ref id) => match self.sessions.remove(id) {
+                // Only the entity which started a session may continue it, and only with the
+                // command it started.
+                Some((_, session)) if session.from == from && session.node == command.node => {
+                    session
+                }
+                Some(session) => {
+                    self.sessions.insert(id.clone(), session);
+                    return Err(error(
+                        ErrorType::Modify,
+                        DefinedCondition::BadRequest,
+                        "Unknown session.",
+                    ));
+                }
+                None => {
+                    return Err(error(
+                        ErrorType::Modify,
+                        DefinedCondition::BadRequest,
+                        "Unknown session.",
+                    ))
+                }
+            },
+            None => {
+                let in_progress = self
+                    .sessions
+                    .values()
+                    .filter(|(_, session)| session.from == from)
+                    .count();
+                if in_progress >= MAX_SESSIONS_PER_JID {
+                    return Err(error(
+                        ErrorType::Wait,
+                        DefinedCondition::ResourceConstraint,
+                        "Too many sessions in progress.",
+                    ));
+                }
+                self.next_session += 1;
+                CommandSession {
+                    from,
+                    node: command.node.clone(),
+                    id: format!("{}:{}", command.node, self.next_session),
+                    forms: vec![],
+                }
+            }
+        };
+
+        let response = match command.action {
+            Action::Cancel => CommandResponse {
+                status: Status::Canceled,
+                actions: None,
+                notes: vec![],
+                form: None,
+            },
+            action => {
+                if action == Action::Prev {
+                    session.forms.pop();
+                } else if let Some(form) = command.form {
+                    session.forms.push(form);
+                }
+                handler.execute(&session, action)
+            }
+        };
+
+        let result = Command {
+            node: command.node,
+            sessionid: Some(session.id.clone()),
+            status: Some(response.status.clone()),
+            action: Action::Execute,
+            actions: response.actions,
+            notes: response.notes,
+            form: response.form,
+        };
+        if response.status == Status::Executing {
+            self.sessions.insert(session.id.clone(), (now, session));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xmpp_parsers::data_forms::{DataFormType, Field, FieldType};
+    use xmpp_parsers::{commands::NoteType, BareJid};
+
+    fn form(type_: DataFormType, value: Option<&str>) -> DataForm {
+        let mut field = Field::new("service", FieldType::TextSingle);
+        if let Some(value) = value {
+            field.values.push(String::from(value));
+        }
+        DataForm::new(type_, "urn:example:restart", vec![field])
+    }
+
+    fn restart(session: &CommandSession, action: Action) -> CommandResponse {
+        match (session.forms.last(), action) {
+            (None, _) => CommandResponse::form(
+                form(DataFormType::Form, None),
+                Actions {
+                    execute: Some(Action::Complete),
+                    complete: true,
+                    ..Actions::default()
+                },
+            ),
+            (Some(form), _) => CommandResponse::completed(vec![Note::new(
+                NoteType::Info,
+                format!("Restarted {}.", form.fields[0].values[0]),
+            )]),
+        }
+    }
+
+    fn jid(node: &str) -> Jid {
+        Jid::Bare(BareJid::new(node, "example.org"))
+    }
+
+    #[test]
+    fn test_disco_items() {
+        let mut commands = Commands::default();
+        assert!(commands.is_empty());
+        commands.register(
+            String::from("restart"),
+            String::from("Restart a service"),
+            Box::new(restart),
+        );
+        let items = commands.disco_items(&jid("bot"));
+        assert_eq!(items.node, Some(String::from(ns::COMMANDS)));
+        assert_eq!(items.items.len(), 1);
+        assert_eq!(items.items[0].node, Some(String::from("restart")));
+        assert_eq!(items.items[0].name, Some(String::from("Restart a service")));
+    }
+
+    #[test]
+    fn test_multi_stage() {
+        let mut commands = Commands::default();
+        commands.register(
+            String::from("restart"),
+            String::from("Restart a service"),
+            Box::new(restart),
+        );
+
+        let result = commands
+            .handle(jid("admin"), Command::new("restart"))
+            .unwrap();
+        assert_eq!(result.status, Some(Status::Executing));
+        assert!(result.form.is_some());
+        let sessionid = result.sessionid.unwrap();
+
+        // Another entity can’t continue this session.
+        let request = Command::new("restart")
+            .with_sessionid(sessionid.clone())
+            .with_action(Action::Complete)
+            .with_form(form(DataFormType::Submit, Some("httpd")));
+        let error = commands
+            .handle(jid("mallory"), request.clone())
+            .unwrap_err();
+        assert_eq!(error.defined_condition, DefinedCondition::BadRequest);
+
+        let result = commands.handle(jid("admin"), request.clone()).unwrap();
+        assert_eq!(result.status, Some(Status::Completed));
+        assert_eq!(result.notes[0].text, "Restarted httpd.");
+
+        // The session is gone once completed.
+        let error = commands.handle(jid("admin"), request).unwrap_err();
+        assert_eq!(error.defined_condition, DefinedCondition::BadRequest);
+    }
+
+    #[test]
+    fn test_other_command() {
+        let mut commands = Commands::default();
+        commands.register(
+            String::from("restart"),
+            String::from("Restart a service"),
+            Box::new(restart),
+        );
+        commands.register(
+            String::from("shutdown"),
+            String::from("Shut the server down"),
+            Box::new(|_: &CommandSession, _| {
+                CommandResponse::completed(vec![Note::new(NoteType::Info, "Shutting down.")])
+            }),
+        );
+        let result = commands
+            .handle(jid("admin"), Command::new("restart"))
+            .unwrap();
+        let sessionid = result.sessionid.unwrap();
+
+        // A session can’t be continued with another command.
+        let request = Command::new("shutdown")
+            .with_sessionid(sessionid.clone())
+            .with_action(Action::Complete)
+            .with_form(form(DataFormType::Submit, Some("httpd")));
+        let error = commands.handle(jid("admin"), request).unwrap_err();
+        assert_eq!(error.defined_condition, DefinedCondition::BadRequest);
+
+        // It is kept for the command which started it.
+        let request = Command::new("restart")
+            .with_sessionid(sessionid)
+            .with_action(Action::Complete)
+            .with_form(form(DataFormType::Submit, Some("httpd")));
+        let result = commands.handle(jid("admin"), request).unwrap();
+        assert_eq!(result.notes[0].text, "Restarted httpd.");
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut commands = Commands::default();
+        commands.register(
+            String::from("restart"),
+            String::from("Restart a service"),
+            Box::new(restart),
+        );
+        let result = commands
+            .handle(jid("admin"), Command::new("restart"))
+            .unwrap();
+        let sessionid = result.sessionid.unwrap();
+        let result = commands
+            .handle(
+                jid("admin"),
+                Command::new("restart")
+                    .with_sessionid(sessionid)
+                    .with_action(Action::Cancel),
+            )
+            .unwrap();
+        assert_eq!(result.status, Some(Status::Canceled));
+        assert!(commands.sessions.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let mut commands = Commands::default();
+        let error = commands
+            .handle(jid("admin"), Command::new("restart"))
+            .unwrap_err();
+        assert_eq!(error.defined_condition, DefinedCondition::ItemNotFound);
+    }
+
+    #[test]
+    fn test_sessions_limit() {
+        let mut commands = Commands::default();
+        commands.register(
+            String::from("restart"),
+            String::from("Restart a service"),
+            Box::new(restart),
+        );
+        for _ in 0..MAX_SESSIONS_PER_JID {
+            commands
+                .handle(jid("admin"), Command::new("restart"))
+                .unwrap();
+        }
+        let error = commands
+            .handle(jid("admin"), Command::new("restart"))
+            .unwrap_err();
+        assert_eq!(
+            error.defined_condition,
+            DefinedCondition::ResourceConstraint
+        );
+
+        // Other entities aren’t affected.
+        commands
+            .handle(jid("other"), Command::new("restart"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_session_timeout() {
+        let mut commands = Commands::default();
+        commands.register(
+            String::from("restart"),
+            String::from("Restart a service"),
+            Box::new(restart),
+        );
+        let result = commands
+            .handle(jid("admin"), Command::new("restart"))
+            .unwrap();
+        let sessionid = result.sessionid.unwrap();
+
+        commands.expire(Instant::now() + SESSION_TIMEOUT / 2);
+        assert!(commands.sessions.contains_key(&sessionid));
+        commands.expire(Instant::now() + SESSION_TIMEOUT);
+        assert!(commands.sessions.is_empty());
+    }
+}
@@ -7,17 +7,26 @@
 #![deny(bare_trait_objects)]
 
 use futures::stream::StreamExt;
+use rand::{distributions::Alphanumeric, Rng};
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::rc::Rc;
 use tokio_xmpp::{AsyncClient as TokioXmppClient, Event as TokioXmppEvent};
 use xmpp_parsers::{
     bookmarks2::Conference,
     caps::{compute_disco, hash_caps, Caps},
-    disco::{DiscoInfoQuery, DiscoInfoResult, Feature, Identity},
+    carbons::{Enable as EnableCarbons, Sent},
+    chat_markers::{Displayed, Markable},
+    commands::Command,
+    disco::{DiscoInfoQuery, DiscoInfoResult, DiscoItemsQuery, Feature, Identity},
+    fallback::Fallback,
     hashes::Algo,
+    hints::Store,
     iq::{Iq, IqType},
     message::{Body, Message, MessageType},
+    message_moderate::Moderate,
+    message_retract::Retract,
     muc::{
         user::{MucUser, Status},
         Muc,
@@ -25,14 +34,25 @@ use xmpp_parsers::{
     ns,
     presence::{Presence, Type as PresenceType},
     pubsub::pubsub::{Items, PubSub},
+    reactions::{Reaction, Reactions},
     roster::{Item as RosterItem, Roster},
     stanza_error::{DefinedCondition, ErrorType, StanzaError},
-    BareJid, FullJid, Jid,
+    BareJid, FullJid, Jid, JidParseError,
 };
+#[cfg(feature = "avatars")]
+use xmpp_parsers::{vcard::VCard, vcard_update::VCardUpdate};
 #[macro_use]
 extern crate log;
 
+mod commands;
+mod omemo;
 mod pubsub;
+#[cfg(feature = "avatars")]
+mod vcard_avatar;
+
+use commands::Commands;
+pub use commands::{CommandHandler, CommandResponse, CommandSession};
+pub use omemo::{OmemoBackend, OmemoError};
 
 pub type Error = tokio_xmpp::Error;
 
@@ -76,13 +96,60 @@ pub enum Event {
     ContactChanged(RosterItem),
     #[cfg(feature = "avatars")]
     AvatarRetrieved(Jid, String),
-    ChatMessage(BareJid, Body),
+    /// A message from this contact, along with its id as returned by
+    /// [Message::reference_id()], to react to it, retract it or mark it as displayed.
+    ChatMessage(BareJid, Option<String>, Body),
     JoinRoom(BareJid, Conference),
     LeaveRoom(BareJid),
     LeaveAllRooms,
     RoomJoined(BareJid),
     RoomLeft(BareJid),
-    RoomMessage(BareJid, RoomNick, Body),
+    /// A message from the occupant of this nick in a room, along with its id as for
+    /// `Event::ChatMessage`.
+    RoomMessage(BareJid, RoomNick, Option<String>, Body),
+    /// The reactions of a contact, or of a room occupant in which case the JID is their full
+    /// occupant JID, to the message of this [reference id](Message::reference_id()), replacing
+    /// their previous ones.
+    Reactions(Jid, String, Vec<String>),
+    /// The message of this [reference id](Message::reference_id()) got retracted by its author,
+    /// or by a moderator in which case the JID is the bare JID of the room.
+    ///
+    /// In a room, the author of the retracted message must be checked against the occupant
+    /// retracting it.
+    MessageRetracted(Jid, String),
+    /// The message of this [reference id](Message::reference_id()), and every previous one, got
+    /// displayed to this contact or room occupant, or to ourselves on another client in which
+    /// case the JID is our own bare JID.
+    MessageDisplayed(Jid, String),
+}
+
+/// The ids of the messages which one of our other clients marked as displayed, in this carbon
+/// of what it sent, as long as the carbon comes from our own bare JID.
+fn displayed_on_other_client(own_jid: Option<&Jid>, from: &Jid, sent: Sent) -> Vec<String> {
+    match own_jid {
+        Some(own_jid) if *from == Jid::Bare(BareJid::from(own_jid.clone())) => (),
+        _ => return vec![],
+    }
+    let message = match sent.forwarded.stanza {
+        Some(message) => message,
+        None => return vec![],
+    };
+    message
+        .payloads
+        .into_iter()
+        .filter(|payload| payload.is("displayed", ns::CHAT_MARKERS))
+        .filter_map(|payload| Displayed::try_from(payload).ok())
+        .map(|displayed| displayed.id)
+        .collect()
+}
+
+/// A random id for the stanzas we send, so that other entities can reference them.
+fn make_id() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
 }
 
 #[derive(Default)]
@@ -94,6 +161,8 @@ pub struct ClientBuilder<'a> {
     lang: Vec<String>,
     disco: (ClientType, String),
     features: Vec<ClientFeature>,
+    commands: Commands,
+    omemo: Option<Box<dyn OmemoBackend>>,
 }
 
 impl ClientBuilder<'_> {
@@ -106,6 +175,8 @@ impl ClientBuilder<'_> {
             lang: vec![String::from("en")],
             disco: (ClientType::default(), String::from("tokio-xmpp")),
             features: vec![],
+            commands: Commands::default(),
+            omemo: None,
         }
     }
 
@@ -134,6 +205,28 @@ impl ClientBuilder<'_> {
         self
     }
 
+    /// Registers an ad-hoc command, advertised under this node and name in the disco#items of
+    /// the commands node, replacing any command previously registered on this node.
+    pub fn register_command<H: CommandHandler + 'static>(
+        mut self,
+        node: &str,
+        name: &str,
+        handler: H,
+    ) -> Self {
+        self.commands
+            .register(String::from(node), String::from(name), Box::new(handler));
+        self
+    }
+
+    /// Encrypts and decrypts chat messages with OMEMO (XEP-0384), using this backend.
+    ///
+    /// Every chat message is then sent encrypted, and not sent at all if the backend fails to
+    /// encrypt it, for instance when no device of the recipient is known yet.
+    pub fn set_omemo_backend<B: OmemoBackend + 'static>(mut self, backend: B) -> Self {
+        self.omemo = Some(Box::new(backend));
+        self
+    }
+
     fn make_disco(&self) -> DiscoInfoResult {
         let identities = vec![Identity::new(
             "client",
@@ -151,6 +244,16 @@ impl ClientBuilder<'_> {
         if self.features.contains(&ClientFeature::JoinRooms) {
             features.push(Feature::new(format!("{}+notify", ns::BOOKMARKS2)));
         }
+        if !self.commands.is_empty() {
+            features.push(Feature::new(ns::COMMANDS));
+        }
+        if self.omemo.is_some() {
+            features.push(Feature::new(format!("{}+notify", ns::OMEMO_DEVICES)));
+        }
+        features.push(Feature::new(ns::REACTIONS));
+        features.push(Feature::new(ns::MESSAGE_RETRACT));
+        features.push(Feature::new(ns::CHAT_MARKERS));
+        features.push(Feature::new(ns::CARBONS));
         DiscoInfoResult {
             node: None,
             identities,
@@ -175,6 +278,13 @@ impl ClientBuilder<'_> {
             lang: Rc::new(self.lang),
             disco,
             node,
+            commands: self.commands,
+            omemo: self.omemo,
+            rooms: HashSet::new(),
+            #[cfg(feature = "avatars")]
+            pep_avatars: HashSet::new(),
+            #[cfg(feature = "avatars")]
+            pending_vcards: HashSet::new(),
         };
 
         Ok(agent)
@@ -187,6 +297,16 @@ pub struct Agent {
     lang: Rc<Vec<String>>,
     disco: DiscoInfoResult,
     node: String,
+    commands: Commands,
+    omemo: Option<Box<dyn OmemoBackend>>,
+    /// The rooms we are currently in.
+    rooms: HashSet<BareJid>,
+    /// The contacts who published a PEP avatar, whose vCard avatar is then ignored.
+    #[cfg(feature = "avatars")]
+    pep_avatars: HashSet<BareJid>,
+    /// The vCards requested for their avatar, by entity and hash of this avatar.
+    #[cfg(feature = "avatars")]
+    pending_vcards: HashSet<(Jid, String)>,
 }
 
 impl Agent {
@@ -197,18 +317,19 @@ impl Agent {
         password: Option<String>,
         lang: &str,
         status: &str,
-    ) {
+    ) -> Result<(), JidParseError> {
         let mut muc = Muc::new();
         if let Some(password) = password {
             muc = muc.with_password(password);
         }
 
         let nick = nick.unwrap_or_else(|| self.default_nick.borrow().clone());
-        let room_jid = room.with_resource(nick);
+        let room_jid = FullJid::from_parts(room.node(), room.domain(), &nick)?;
         let mut presence = Presence::new(PresenceType::None).with_to(Jid::Full(room_jid));
         presence.add_payload(muc);
         presence.set_status(String::from(lang), String::from(status));
         let _ = self.client.send_stanza(presence.into()).await;
+        Ok(())
     }
 
     pub async fn send_message(
@@ -218,14 +339,104 @@ impl Agent {
         lang: &str,
         text: &str,
     ) {
-        let mut message = Message::new(Some(recipient));
+        let mut message = Message::new(Some(recipient.clone()));
         message.type_ = type_;
+        // A markable message needs an id for the markers to reference it.
+        message.id = Some(make_id());
         message
             .bodies
             .insert(String::from(lang), Body(String::from(text)));
+        message.payloads.push(Markable.into());
+        if let (MessageType::Chat, Some(backend)) = (&message.type_, self.omemo.as_mut()) {
+            let recipient = BareJid::from(recipient);
+            let sender = match self.client.bound_jid() {
+                Some(jid) => BareJid::from(jid.clone()),
+                None => {
+                    error!("Couldn’t encrypt message to {}: not connected", recipient);
+                    return;
+                }
+            };
+            if let Err(err) =
+                omemo::encrypt_message(&mut **backend, &sender, &recipient, &mut message)
+            {
+                error!("Couldn’t encrypt message to {}: {}", recipient, err);
+                return;
+            }
+        }
+        let _ = self.client.send_stanza(message.into()).await;
+    }
+
+    /// Sends our reactions to the message of this [reference id](Message::reference_id()),
+    /// replacing our previous ones, an empty list removing them all.
+    pub async fn send_reactions(&mut self, to: Jid, message_id: &str, reactions: Vec<String>) {
+        let type_ = self.message_type(&to);
+        let reactions = Reactions::new(
+            message_id,
+            reactions.into_iter().map(Reaction::new).collect(),
+        );
+        let mut message = Message::new(Some(to));
+        message.type_ = type_;
+        message.payloads.push(reactions.into());
+        let _ = self.client.send_stanza(message.into()).await;
+    }
+
+    /// Retracts our message of this [reference id](Message::reference_id()).
+    pub async fn retract_message(&mut self, to: Jid, message_id: &str) {
+        let type_ = self.message_type(&to);
+        let mut message = Message::new(Some(to));
+        message.type_ = type_;
+        message.bodies.insert(
+            String::new(),
+            Body(String::from(
+                "This person attempted to retract a previous message, but it's unsupported by your client.",
+            )),
+        );
+        message.payloads.push(Retract::new(message_id).into());
+        message
+            .payloads
+            .push(Fallback::whole_body(ns::MESSAGE_RETRACT).into());
+        let _ = self.client.send_stanza(message.into()).await;
+    }
+
+    /// Marks the message of this [reference id](Message::reference_id()), and every previous
+    /// one, as displayed, which is also archived so that our other clients can sync their read
+    /// state.
+    pub async fn send_displayed(&mut self, to: Jid, message_id: &str) {
+        let type_ = self.message_type(&to);
+        let mut message = Message::new(Some(to));
+        message.type_ = type_;
+        message.payloads.push(
+            Displayed {
+                id: String::from(message_id),
+            }
+            .into(),
+        );
+        message.payloads.push(Store.into());
         let _ = self.client.send_stanza(message.into()).await;
     }
 
+    /// Asks this room to retract the message of this stanza-id, which requires being a
+    /// moderator there.
+    pub async fn moderate_message(
+        &mut self,
+        room: BareJid,
+        stanza_id: &str,
+        reason: Option<String>,
+    ) {
+        let iq = Iq::from_set("moderate", Moderate::retract(stanza_id, reason))
+            .with_to(Jid::Bare(room))
+            .into();
+        let _ = self.client.send_stanza(iq).await;
+    }
+
+    /// The type of the messages to send to this JID, groupchat for the rooms we are in.
+    fn message_type(&self, to: &Jid) -> MessageType {
+        match to {
+            Jid::Bare(room) if self.rooms.contains(room) => MessageType::Groupchat,
+            _ => MessageType::Chat,
+        }
+    }
+
     fn make_initial_presence(disco: &DiscoInfoResult, node: &str) -> Presence {
         let caps_data = compute_disco(disco);
         let hash = hash_caps(&caps_data, Algo::Sha_1).unwrap();
@@ -267,6 +478,30 @@ impl Agent {
                         let _ = self.client.send_stanza(iq).await;
                     }
                 }
+            } else if payload.is("query", ns::DISCO_ITEMS)
+                && payload.attr("node") == Some(ns::COMMANDS)
+                && DiscoItemsQuery::try_from(payload).is_ok()
+            {
+                let reply = match self.client.bound_jid() {
+                    Some(jid) => {
+                        let disco_items = self.commands.disco_items(jid);
+                        Iq::from_result(iq.id, Some(disco_items))
+                    }
+                    // Our commands can’t be addressed until a resource got bound.
+                    None => {
+                        let error = StanzaError::new(
+                            ErrorType::Wait,
+                            DefinedCondition::InternalServerError,
+                            "en",
+                            "No resource bound yet.",
+                        );
+                        Iq::from_error(iq.id, error)
+                    }
+                };
+                let _ = self
+                    .client
+                    .send_stanza(reply.with_to(iq.from.unwrap()).into())
+                    .await;
             } else {
                 // We MUST answer unhandled get iqs with a service-unavailable error.
                 let error = StanzaError::new(
@@ -289,10 +524,39 @@ impl Agent {
                     events.push(Event::ContactAdded(item));
                 }
             } else if payload.is("pubsub", ns::PUBSUB) {
-                let new_events = pubsub::handle_iq_result(&from, payload);
+                let new_events = pubsub::handle_iq_result(&from, payload, self).await;
                 events.extend(new_events);
+            } else if payload.is("vCard", ns::VCARD) {
+                #[cfg(feature = "avatars")]
+                if let Ok(vcard) = VCard::try_from(payload) {
+                    events.extend(vcard_avatar::handle_vcard_iq(&from, &iq.id, vcard, self));
+                }
+            }
+        } else if let IqType::Error(_) = iq.payload {
+            // Our device list doesn’t exist yet.
+            if iq.id == omemo::OWN_DEVICES_ID {
+                omemo::handle_device_list(&from, self, None).await;
+            }
+            #[cfg(feature = "avatars")]
+            vcard_avatar::handle_vcard_error(&from, &iq.id, self);
+        } else if let IqType::Set(payload) = iq.payload {
+            if payload.is("command", ns::COMMANDS) {
+                let result = match Command::try_from(payload) {
+                    Ok(command) => self.commands.handle(from.clone(), command),
+                    Err(err) => Err(Box::new(StanzaError::new(
+                        ErrorType::Modify,
+                        DefinedCondition::BadRequest,
+                        "en",
+                        &format!("{}", err),
+                    ))),
+                };
+                let iq = match result {
+                    Ok(command) => Iq::from_result(iq.id, Some(command)),
+                    Err(error) => Iq::from_error(iq.id, *error),
+                };
+                let _ = self.client.send_stanza(iq.with_to(from).into()).await;
+                return events;
             }
-        } else if let IqType::Set(_) = iq.payload {
             // We MUST answer unhandled set iqs with a service-unavailable error.
             let error = StanzaError::new(
                 ErrorType::Cancel,
@@ -309,22 +573,49 @@ impl Agent {
         events
     }
 
-    async fn handle_message(&mut self, message: Message) -> Vec<Event> {
+    async fn handle_message(&mut self, mut message: Message) -> Vec<Event> {
         let mut events = vec![];
         let from = message.from.clone().unwrap();
+        let recipient = message
+            .to
+            .clone()
+            .or_else(|| self.client.bound_jid().cloned());
+        if let Some(backend) = self.omemo.as_mut() {
+            let sender = BareJid::from(from.clone());
+            if let Err(err) =
+                omemo::decrypt_message(&mut **backend, &sender, recipient.as_ref(), &mut message)
+            {
+                warn!("Couldn’t decrypt message from {}: {}", sender, err);
+            }
+        }
+        // The body of a retraction is only a fallback.
+        let is_retraction = message
+            .payloads
+            .iter()
+            .any(|payload| payload.is("retract", ns::MESSAGE_RETRACT));
+        let id = message.reference_id();
         let langs: Vec<&str> = self.lang.iter().map(String::as_str).collect();
-        match message.get_best_body(langs) {
-            Some((_lang, body)) => match message.type_ {
+        // The quote of a reply is only a fallback too.
+        let body = message
+            .get_best_body(langs)
+            .map(|(_lang, body)| message.strip_fallbacks(body, &[ns::REPLY]));
+        match body {
+            Some(_) if is_retraction => (),
+            Some(body) => match message.type_ {
                 MessageType::Groupchat => {
                     let event = Event::RoomMessage(
                         from.clone().into(),
-                        FullJid::try_from(from.clone()).unwrap().resource,
-                        body.clone(),
+                        FullJid::try_from(from.clone())
+                            .unwrap()
+                            .resource()
+                            .to_owned(),
+                        id,
+                        body,
                     );
                     events.push(event)
                 }
                 MessageType::Chat | MessageType::Normal => {
-                    let event = Event::ChatMessage(from.clone().into(), body.clone());
+                    let event = Event::ChatMessage(from.clone().into(), id, body);
                     events.push(event)
                 }
                 _ => (),
@@ -335,6 +626,47 @@ impl Agent {
             if child.is("event", ns::PUBSUB_EVENT) {
                 let new_events = pubsub::handle_event(&from, child, self).await;
                 events.extend(new_events);
+            } else if child.is("reactions", ns::REACTIONS) {
+                if let Ok(reactions) = Reactions::try_from(child) {
+                    let from = match message.type_ {
+                        MessageType::Groupchat => from.clone(),
+                        _ => Jid::Bare(from.clone().into()),
+                    };
+                    let emojis = reactions
+                        .reactions
+                        .into_iter()
+                        .map(|reaction| reaction.emoji)
+                        .collect();
+                    events.push(Event::Reactions(from, reactions.id, emojis));
+                }
+            } else if child.is("displayed", ns::CHAT_MARKERS) {
+                if let Ok(displayed) = Displayed::try_from(child) {
+                    let from = match message.type_ {
+                        MessageType::Groupchat => from.clone(),
+                        _ => Jid::Bare(from.clone().into()),
+                    };
+                    events.push(Event::MessageDisplayed(from, displayed.id));
+                }
+            } else if child.is("sent", ns::CARBONS) {
+                if let Ok(sent) = Sent::try_from(child) {
+                    let own_jid = self.client.bound_jid();
+                    for id in displayed_on_other_client(own_jid, &from, sent) {
+                        events.push(Event::MessageDisplayed(from.clone(), id));
+                    }
+                }
+            } else if child.is("retract", ns::MESSAGE_RETRACT) {
+                if let Ok(retract) = Retract::try_from(child) {
+                    let from = match (&message.type_, &from) {
+                        // Only the room itself, from its bare JID, retracts on behalf of a
+                        // moderator, an occupant claiming to do so is reported as such.
+                        (MessageType::Groupchat, Jid::Bare(_)) if retract.moderated.is_some() => {
+                            from.clone()
+                        }
+                        (MessageType::Groupchat, Jid::Full(_)) => from.clone(),
+                        _ => Jid::Bare(from.clone().into()),
+                    };
+                    events.push(Event::MessageRetracted(from, retract.id));
+                }
             }
         }
 
@@ -343,17 +675,40 @@ impl Agent {
 
     async fn handle_presence(&mut self, presence: Presence) -> Vec<Event> {
         let mut events = vec![];
-        let from: BareJid = match presence.from.clone().unwrap() {
-            Jid::Full(FullJid { node, domain, .. }) => BareJid { node, domain },
-            Jid::Bare(bare) => bare,
+        let from: BareJid = presence.from.clone().unwrap().into();
+        let type_ = presence.type_.clone();
+        // The vCard of a room occupant is requested from their occupant JID.
+        #[cfg(feature = "avatars")]
+        let vcard_from = if presence
+            .payloads
+            .iter()
+            .any(|payload| payload.is("x", ns::MUC_USER))
+        {
+            presence.from.clone().unwrap()
+        } else {
+            Jid::Bare(from.clone())
         };
         for payload in presence.payloads.into_iter() {
+            #[cfg(feature = "avatars")]
+            if payload.is("x", ns::VCARD_UPDATE) {
+                if let Ok(update) = VCardUpdate::try_from(payload) {
+                    let new_events =
+                        vcard_avatar::handle_vcard_update(&vcard_from, self, update).await;
+                    events.extend(new_events);
+                }
+                continue;
+            }
             let muc_user = match MucUser::try_from(payload) {
                 Ok(muc_user) => muc_user,
                 _ => continue,
             };
             for status in muc_user.status.into_iter() {
                 if status == Status::SelfPresence {
+                    if type_ == PresenceType::Unavailable {
+                        self.rooms.remove(&from);
+                    } else {
+                        self.rooms.insert(from.clone());
+                    }
                     events.push(Event::RoomJoined(from.clone()));
                     break;
                 }
@@ -372,6 +727,15 @@ impl Agent {
                     let presence = Self::make_initial_presence(&self.disco, &self.node).into();
                     let _ = self.client.send_stanza(presence).await;
                     events.push(Event::Online);
+                    // Get a copy of what our other clients send, to sync their read state.
+                    let iq = Iq::from_set("carbons", EnableCarbons).into();
+                    let _ = self.client.send_stanza(iq).await;
+                    if let Some(backend) = self.omemo.as_ref() {
+                        let iq = omemo::publish_bundle(&**backend).into();
+                        let _ = self.client.send_stanza(iq).await;
+                        let iq = omemo::request_own_devices().into();
+                        let _ = self.client.send_stanza(iq).await;
+                    }
                     // TODO: only send this when the ContactList feature is enabled.
                     let iq = Iq::from_get(
                         "roster",
@@ -421,8 +785,19 @@ impl Agent {
 
 #[cfg(test)]
 mod tests {
-    use super::{Agent, ClientBuilder, ClientFeature, ClientType, Event};
+    use super::{
+        displayed_on_other_client, Agent, ClientBuilder, ClientFeature, ClientType, Event,
+    };
     use tokio_xmpp::AsyncClient as TokioXmppClient;
+    use xmpp_parsers::{
+        carbons::Sent,
+        chat_markers::Displayed,
+        forwarding::Forwarded,
+        message::{Message, MessageType},
+        message_moderate::Moderated,
+        message_retract::Retract,
+        BareJid, FullJid, Jid,
+    };
 
     #[tokio::test]
     async fn test_simple() {
@@ -447,4 +822,94 @@ mod tests {
             break;
         }
     }
+
+    #[tokio::test]
+    async fn test_join_room_invalid_nick() {
+        let client = TokioXmppClient::new("foo@bar", "meh").unwrap();
+        let mut agent: Agent = ClientBuilder::new("foo@bar", "meh")
+            .build_impl(client)
+            .unwrap();
+
+        let room = BareJid::new("room", "muc.bar");
+        let result = agent
+            .join_room(room, Some(String::new()), None, "en", "")
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_moderation() {
+        let client = TokioXmppClient::new("foo@bar", "meh").unwrap();
+        let mut agent: Agent = ClientBuilder::new("foo@bar", "meh")
+            .build_impl(client)
+            .unwrap();
+
+        let room = BareJid::new("coven", "chat.shakespeare.lit");
+        let occupant = Jid::Full(FullJid::new("coven", "chat.shakespeare.lit", "thirdwitch"));
+        let moderated_retraction = |from: Jid| {
+            let mut message = Message::new(Some(Jid::Bare(BareJid::new("foo", "bar"))));
+            message.from = Some(from);
+            message.type_ = MessageType::Groupchat;
+            let mut retract = Retract::new("stanza-id-1");
+            retract.moderated = Some(Moderated {
+                by: Some(occupant.clone()),
+                occupant_id: None,
+            });
+            message.payloads.push(retract.into());
+            message
+        };
+
+        let events = agent
+            .handle_message(moderated_retraction(Jid::Bare(room.clone())))
+            .await;
+        match &events[..] {
+            [Event::MessageRetracted(from, id)] => {
+                assert_eq!(*from, Jid::Bare(room.clone()));
+                assert_eq!(id, "stanza-id-1");
+            }
+            _ => panic!(),
+        }
+
+        // An occupant can’t retract the message of another one by claiming to be the room.
+        let events = agent
+            .handle_message(moderated_retraction(occupant.clone()))
+            .await;
+        match &events[..] {
+            [Event::MessageRetracted(from, _)] => assert_eq!(*from, occupant),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_displayed_on_other_client() {
+        let own_jid = Jid::Full(FullJid::new("foo", "bar", "laptop"));
+        let romeo = Jid::Bare(BareJid::new("romeo", "montague.lit"));
+        let sent = || {
+            let mut message = Message::new(Some(romeo.clone()));
+            message.payloads.push(
+                Displayed {
+                    id: String::from("message-1"),
+                }
+                .into(),
+            );
+            Sent {
+                forwarded: Forwarded {
+                    delay: None,
+                    stanza: Some(message),
+                },
+            }
+        };
+
+        let ids = displayed_on_other_client(
+            Some(&own_jid),
+            &Jid::Bare(BareJid::new("foo", "bar")),
+            sent(),
+        );
+        assert_eq!(ids, vec![String::from("message-1")]);
+
+        // Anyone else could otherwise mark our messages as read.
+        assert!(displayed_on_other_client(Some(&own_jid), &romeo, sent()).is_empty());
+        assert!(displayed_on_other_client(Some(&own_jid), &own_jid, sent()).is_empty());
+        assert!(displayed_on_other_client(None, &romeo, sent()).is_empty());
+    }
 }
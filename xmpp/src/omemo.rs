@@ -0,0 +1,423 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::Agent;
+use rand::{distributions::Alphanumeric, Rng};
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use xmpp_parsers::{
+    data_forms::{DataForm, DataFormType, Field},
+    eme::ExplicitMessageEncryption,
+    iq::Iq,
+    message::{Body, Message},
+    ns,
+    omemo::{Bundle, Device, DeviceList, Encrypted},
+    pubsub::{
+        pubsub::{Item as PubSubItem, Items, Publish, PublishOptions},
+        Item, ItemId, NodeName, PubSub,
+    },
+    sce::Envelope,
+    BareJid, Element, Jid,
+};
+
+/// The error returned by an [OmemoBackend].
+pub type OmemoError = Box<dyn StdError>;
+
+/// The body sent along an encrypted message, for clients not supporting OMEMO.
+const FALLBACK_BODY: &str =
+    "This message is encrypted with OMEMO, which your client doesn’t seem to support.";
+
+/// The maximum length of the padding of an envelope.
+const MAX_RPAD_LEN: usize = 200;
+
+/// The id of the iq retrieving our own device list.
+pub(crate) const OWN_DEVICES_ID: &str = "omemo-devices";
+
+/// The cryptography of OMEMO (XEP-0384), supplied by the user, for instance on top of a Double
+/// Ratchet implementation.
+///
+/// The agent takes care of the protocol: it publishes the bundle of this device and adds it to
+/// the device list of the account, retrieves the bundles of the devices of contacts, encrypts
+/// the chat messages it sends and decrypts the ones it receives.
+pub trait OmemoBackend {
+    /// The id of this device.
+    fn device_id(&self) -> u32;
+
+    /// The public keys of this device, which others use to start a session with it.
+    fn bundle(&self) -> Bundle;
+
+    /// Called with the device list of an account, ours included, whenever it changes.
+    ///
+    /// Returns the ids of the devices whose bundle is needed to start a session with them.
+    fn devices_updated(&mut self, jid: &BareJid, devices: &DeviceList) -> Vec<u32>;
+
+    /// Called with the bundle of a device, once retrieved.
+    fn bundle_retrieved(&mut self, jid: &BareJid, device_id: u32, bundle: Bundle);
+
+    /// Encrypts this plaintext for every device of the recipient, and for our other devices.
+    fn encrypt(&mut self, recipient: &BareJid, plaintext: &[u8]) -> Result<Encrypted, OmemoError>;
+
+    /// Decrypts a message from this sender, returning `None` for an empty message, which only
+    /// establishes or advances a session.
+    fn decrypt(
+        &mut self,
+        sender: &BareJid,
+        encrypted: &Encrypted,
+    ) -> Result<Option<Vec<u8>>, OmemoError>;
+}
+
+pub(crate) fn publish_bundle(backend: &dyn OmemoBackend) -> Iq {
+    let item = Item::new(
+        Some(ItemId(backend.device_id().to_string())),
+        None,
+        Some(backend.bundle()),
+    );
+    publish("omemo-bundle", ns::OMEMO_BUNDLES, item)
+}
+
+fn publish_device_list(devices: DeviceList) -> Iq {
+    let item = Item::new(Some(ItemId(String::from("current"))), None, Some(devices));
+    publish("omemo-devices-publish", ns::OMEMO_DEVICES, item)
+}
+
+/// Publishes this item, making the node readable by anyone so that contacts can start sessions
+/// with our devices.
+fn publish(id: &str, node: &str, item: Item) -> Iq {
+    let options = DataForm::new(
+        DataFormType::Submit,
+        ns::PUBSUB_PUBLISH_OPTIONS,
+        vec![Field::text_single("pubsub#access_model", "open")],
+    );
+    Iq::from_set(
+        id,
+        PubSub::Publish {
+            publish: Publish {
+                node: NodeName(String::from(node)),
+                items: vec![PubSubItem(item)],
+            },
+            publish_options: Some(PublishOptions {
+                form: Some(options),
+            }),
+        },
+    )
+}
+
+/// Requests our own device list, to add this device to it.
+pub(crate) fn request_own_devices() -> Iq {
+    Iq::from_get(OWN_DEVICES_ID, PubSub::Items(Items::new(ns::OMEMO_DEVICES)))
+}
+
+fn request_bundle(jid: &BareJid, device_id: u32) -> Iq {
+    let mut items = Items::new(ns::OMEMO_BUNDLES);
+    items.items.push(PubSubItem(Item {
+        id: Some(ItemId(device_id.to_string())),
+        publisher: None,
+        payload: None,
+    }));
+    Iq::from_get("omemo-bundle", PubSub::Items(items)).with_to(Jid::Bare(jid.clone()))
+}
+
+/// Handles the device list of an account, received either as a PEP event or as the result of
+/// `request_own_devices()`, or `None` if we don’t have one yet.
+pub(crate) async fn handle_device_list(from: &Jid, agent: &mut Agent, devices: Option<DeviceList>) {
+    let backend = match agent.omemo.as_mut() {
+        Some(backend) => backend,
+        None => return,
+    };
+    let from = BareJid::from(from.clone());
+    let mut devices = devices.unwrap_or_default();
+    let mut stanzas = Vec::new();
+    let own_jid = agent.client.bound_jid().cloned().map(BareJid::from);
+    if Some(&from) == own_jid.as_ref() && !devices.contains(backend.device_id()) {
+        devices.devices.push(Device {
+            id: backend.device_id(),
+            label: None,
+        });
+        stanzas.push(publish_device_list(devices.clone()));
+    }
+    for device_id in backend.devices_updated(&from, &devices) {
+        stanzas.push(request_bundle(&from, device_id));
+    }
+    for iq in stanzas {
+        let _ = agent.client.send_stanza(iq.into()).await;
+    }
+}
+
+/// Handles the bundles returned by `request_bundle()`.
+pub(crate) fn handle_bundles_iq(from: &Jid, agent: &mut Agent, items: &Items) {
+    let backend = match agent.omemo.as_mut() {
+        Some(backend) => backend,
+        None => return,
+    };
+    let from = BareJid::from(from.clone());
+    for item in &items.items {
+        let device_id = item.id.as_ref().and_then(|id| id.0.parse().ok());
+        let bundle = item
+            .payload
+            .clone()
+            .and_then(|payload| Bundle::try_from(payload).ok());
+        if let (Some(device_id), Some(bundle)) = (device_id, bundle) {
+            backend.bundle_retrieved(&from, device_id, bundle);
+        }
+    }
+}
+
+/// Random padding of random length, both taken from the thread-local CSPRNG.
+fn random_padding() -> String {
+    let mut rng = rand::thread_rng();
+    let len = rng.gen_range(1..=MAX_RPAD_LEN);
+    rng.sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Replaces the bodies of this message with their encrypted version, wrapped in a Stanza
+/// Content Encryption (XEP-0420) envelope.
+pub(crate) fn encrypt_message(
+    backend: &mut dyn OmemoBackend,
+    sender: &BareJid,
+    recipient: &BareJid,
+    message: &mut Message,
+) -> Result<(), OmemoError> {
+    if message.bodies.is_empty() {
+        return Ok(());
+    }
+    let bodies = message
+        .bodies
+        .iter()
+        .map(|(lang, body)| {
+            let mut elem = Element::from(body.clone());
+            if !lang.is_empty() {
+                elem.set_attr("xml:lang", lang);
+            }
+            elem
+        })
+        .collect();
+    let envelope = Envelope::new(bodies)
+        .with_rpad(random_padding())
+        .with_from(Jid::Bare(sender.clone()));
+    let plaintext = String::from(&Element::from(envelope));
+    let encrypted = backend.encrypt(recipient, plaintext.as_bytes())?;
+    message.bodies.clear();
+    message
+        .bodies
+        .insert(String::new(), Body(String::from(FALLBACK_BODY)));
+    message.payloads.push(encrypted.into());
+    message.payloads.push(
+        ExplicitMessageEncryption {
+            namespace: String::from(ns::OMEMO),
+            name: Some(String::from("OMEMO")),
+        }
+        .into(),
+    );
+    Ok(())
+}
+
+/// Replaces the bodies of this message with the decrypted content of its `<encrypted/>`
+/// payload, if any, after checking that its envelope comes from the sender of the message and,
+/// when known, is addressed to `recipient`.
+///
+/// The content of the envelope other than bodies is appended to the payloads of the message.
+pub(crate) fn decrypt_message(
+    backend: &mut dyn OmemoBackend,
+    sender: &BareJid,
+    recipient: Option<&Jid>,
+    message: &mut Message,
+) -> Result<(), OmemoError> {
+    let position = match message
+        .payloads
+        .iter()
+        .position(|payload| payload.is("encrypted", ns::OMEMO))
+    {
+        Some(position) => position,
+        None => return Ok(()),
+    };
+    let encrypted = Encrypted::try_from(message.payloads.remove(position))?;
+    message.bodies.clear();
+    let plaintext = match backend.decrypt(sender, &encrypted)? {
+        Some(plaintext) => String::from_utf8(plaintext)?,
+        None => return Ok(()),
+    };
+    let envelope = Envelope::try_from(plaintext.parse::<Element>()?)?;
+    if envelope.from.is_none() {
+        return Err("The envelope has no from affix.".into());
+    }
+    let sender = Jid::Bare(sender.clone());
+    match recipient {
+        Some(recipient) => envelope.validate(&sender, recipient)?,
+        None if envelope.to.is_some() => {
+            return Err("The to affix can’t be checked without a recipient.".into())
+        }
+        // Only the from affix remains to be checked.
+        None => envelope.validate(&sender, &sender)?,
+    }
+    for payload in envelope.content.payloads {
+        if payload.is("body", ns::DEFAULT_NS) {
+            let lang = payload.attr("xml:lang").unwrap_or("").to_owned();
+            message.bodies.insert(lang, Body(payload.text()));
+        } else {
+            message.payloads.push(payload);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xmpp_parsers::{
+        omemo::{
+            Header, IdentityKey, Key, Keys, Payload, PreKeyPublic, PreKeys, SignedPreKeyPublic,
+            SignedPreKeySignature,
+        },
+        Element,
+    };
+
+    /// A backend “encrypting” by reversing the bytes.
+    struct Reverse;
+
+    impl OmemoBackend for Reverse {
+        fn device_id(&self) -> u32 {
+            1
+        }
+
+        fn bundle(&self) -> Bundle {
+            Bundle {
+                spk: SignedPreKeyPublic {
+                    id: 1,
+                    data: vec![1],
+                },
+                spks: SignedPreKeySignature { data: vec![2] },
+                ik: IdentityKey { data: vec![3] },
+                prekeys: PreKeys {
+                    keys: vec![PreKeyPublic {
+                        id: 1,
+                        data: vec![4],
+                    }],
+                },
+            }
+        }
+
+        fn devices_updated(&mut self, _: &BareJid, devices: &DeviceList) -> Vec<u32> {
+            devices.devices.iter().map(|device| device.id).collect()
+        }
+
+        fn bundle_retrieved(&mut self, _: &BareJid, _: u32, _: Bundle) {}
+
+        fn encrypt(
+            &mut self,
+            recipient: &BareJid,
+            plaintext: &[u8],
+        ) -> Result<Encrypted, OmemoError> {
+            Ok(Encrypted {
+                header: Header {
+                    sid: self.device_id(),
+                    keys: vec![Keys {
+                        jid: recipient.clone(),
+                        keys: vec![Key {
+                            rid: 2,
+                            kex: Default::default(),
+                            data: vec![0],
+                        }],
+                    }],
+                },
+                payload: Some(Payload {
+                    data: plaintext.iter().rev().cloned().collect(),
+                }),
+            })
+        }
+
+        fn decrypt(
+            &mut self,
+            _: &BareJid,
+            encrypted: &Encrypted,
+        ) -> Result<Option<Vec<u8>>, OmemoError> {
+            Ok(encrypted
+                .payload
+                .as_ref()
+                .map(|payload| payload.data.iter().rev().cloned().collect()))
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let juliet = BareJid::new("juliet", "capulet.lit");
+        let mut message = Message::new(Some(Jid::Bare(juliet.clone())));
+        message.bodies.insert(
+            String::from("en"),
+            Body(String::from("Wherefore art thou?")),
+        );
+
+        encrypt_message(&mut Reverse, &juliet, &juliet, &mut message).unwrap();
+        assert_eq!(message.bodies[""].0, FALLBACK_BODY);
+        assert!(message.payloads[0].is("encrypted", ns::OMEMO));
+        assert!(message.payloads[1].is("encryption", ns::EME));
+
+        decrypt_message(&mut Reverse, &juliet, None, &mut message).unwrap();
+        assert_eq!(message.bodies.len(), 1);
+        assert_eq!(message.bodies["en"].0, "Wherefore art thou?");
+        assert_eq!(message.payloads.len(), 1);
+    }
+
+    #[test]
+    fn test_envelope() {
+        let juliet = BareJid::new("juliet", "capulet.lit");
+        let romeo = BareJid::new("romeo", "montague.lit");
+        let mut message = Message::new(Some(Jid::Bare(romeo.clone())));
+        message
+            .bodies
+            .insert(String::new(), Body(String::from("Hi")));
+        encrypt_message(&mut Reverse, &juliet, &romeo, &mut message).unwrap();
+
+        let encrypted = Encrypted::try_from(message.payloads[0].clone()).unwrap();
+        let plaintext = Reverse.decrypt(&juliet, &encrypted).unwrap().unwrap();
+        let elem: Element = String::from_utf8(plaintext).unwrap().parse().unwrap();
+        let envelope = Envelope::try_from(elem).unwrap();
+        let rpad = envelope.rpad.unwrap();
+        assert!(!rpad.is_empty() && rpad.len() <= MAX_RPAD_LEN);
+        assert_eq!(envelope.from.unwrap().jid, Jid::Bare(juliet.clone()));
+
+        // Replayed by Romeo as coming from him.
+        let mut replayed = message.clone();
+        let error = decrypt_message(&mut Reverse, &romeo, None, &mut replayed).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the from affix doesn’t match the sender of the stanza"
+        );
+        assert!(replayed.bodies.is_empty());
+    }
+
+    #[test]
+    fn test_plaintext_untouched() {
+        let juliet = BareJid::new("juliet", "capulet.lit");
+        let mut message = Message::new(None);
+        message
+            .bodies
+            .insert(String::new(), Body(String::from("Hi")));
+        decrypt_message(&mut Reverse, &juliet, None, &mut message).unwrap();
+        assert_eq!(message.bodies[""].0, "Hi");
+    }
+
+    #[test]
+    fn test_request_bundle() {
+        let juliet = BareJid::new("juliet", "capulet.lit");
+        let iq = request_bundle(&juliet, 31415);
+        assert_eq!(iq.to, Some(Jid::Bare(juliet)));
+        let elem: Element = iq.into();
+        let expected: Element = "<iq xmlns='jabber:client' type='get' id='omemo-bundle' to='juliet@capulet.lit'><pubsub xmlns='http://jabber.org/protocol/pubsub'><items node='urn:xmpp:omemo:2:bundles'><item id='31415'/></items></pubsub></iq>"
+            .parse()
+            .unwrap();
+        assert_eq!(elem, expected);
+    }
+
+    #[test]
+    fn test_publish_bundle() {
+        let elem: Element = publish_bundle(&Reverse).into();
+        let expected: Element = "<iq xmlns='jabber:client' type='set' id='omemo-bundle'><pubsub xmlns='http://jabber.org/protocol/pubsub'><publish node='urn:xmpp:omemo:2:bundles'><item id='1'><bundle xmlns='urn:xmpp:omemo:2'><spk id='1'>AQ==</spk><spks>Ag==</spks><ik>Aw==</ik><prekeys><pk id='1'>BA==</pk></prekeys></bundle></item></publish><publish-options><x xmlns='jabber:x:data' type='submit'><field var='FORM_TYPE' type='hidden'><value>http://jabber.org/protocol/pubsub#publish-options</value></field><field var='pubsub#access_model'><value>open</value></field></x></publish-options></pubsub></iq>"
+            .parse()
+            .unwrap();
+        assert_eq!(elem, expected);
+    }
+}
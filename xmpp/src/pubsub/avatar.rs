@@ -27,12 +27,14 @@ pub(crate) async fn handle_metadata_pubsub_event(
     items: Vec<Item>,
 ) -> Vec<Event> {
     let mut events = Vec::new();
+    // From now on, ignore the avatar of their vCard.
+    agent.pep_avatars.insert(from.clone().into());
     for item in items {
         let payload = item.payload.clone().unwrap();
         if payload.is("metadata", ns::AVATAR_METADATA) {
             let metadata = Metadata::try_from(payload).unwrap();
             for info in metadata.infos {
-                let filename = format!("data/{}/{}", from, &*info.id.to_hex());
+                let filename = format!("{}/{}", avatar_directory(from), &*info.id.to_hex());
                 let file_length = match fs::metadata(filename.clone()) {
                     Ok(metadata) => metadata.len(),
                     Err(_) => 0,
@@ -83,11 +85,47 @@ pub(crate) fn handle_data_pubsub_iq<'a>(
         })
 }
 
-fn save_avatar(from: &Jid, id: String, data: &[u8]) -> io::Result<String> {
-    let directory = format!("data/{}", from);
-    let filename = format!("data/{}/{}", from, id);
+/// Returns the directory storing the avatars of this entity.
+///
+/// The JID gets escaped into a single path component, as the nickname of a room occupant could
+/// otherwise point outside of this directory, for instance with `../`.
+pub(crate) fn avatar_directory(from: &Jid) -> String {
+    let mut directory = String::from("data/");
+    for (i, c) in from.to_string().char_indices() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '@' || (c == '.' && i > 0) {
+            directory.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                directory.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    directory
+}
+
+pub(crate) fn save_avatar(from: &Jid, id: String, data: &[u8]) -> io::Result<String> {
+    let directory = avatar_directory(from);
+    let filename = format!("{}/{}", directory, id);
     fs::create_dir_all(directory)?;
     let mut file = File::create(&filename)?;
     file.write_all(data)?;
     Ok(filename)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xmpp_parsers::{BareJid, FullJid};
+
+    #[test]
+    fn test_avatar_directory() {
+        let juliet = Jid::Bare(BareJid::new("juliet", "capulet.lit"));
+        assert_eq!(avatar_directory(&juliet), "data/juliet@capulet.lit");
+        let occupant = Jid::Full(FullJid::new("coven", "chat.shakespeare.lit", "../../etc"));
+        assert_eq!(
+            avatar_directory(&occupant),
+            "data/coven@chat.shakespeare.lit%2F..%2F..%2Fetc"
+        );
+    }
+}
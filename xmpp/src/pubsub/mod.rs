@@ -5,12 +5,13 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use super::Agent;
-use crate::Event;
+use crate::{omemo, Event};
 use std::convert::TryFrom;
 use std::str::FromStr;
 use xmpp_parsers::{
     bookmarks2::{Autojoin, Conference},
     ns,
+    omemo::DeviceList,
     pubsub::event::PubSubEvent,
     pubsub::pubsub::PubSub,
     BareJid, Element, Jid,
@@ -32,6 +33,16 @@ pub(crate) async fn handle_event(from: &Jid, elem: Element, agent: &mut Agent) -
                         avatar::handle_metadata_pubsub_event(&from, agent, items).await;
                     events.extend(new_events);
                 }
+                ref node if node == ns::OMEMO_DEVICES => {
+                    let devices = items
+                        .into_iter()
+                        .filter_map(|item| item.0.payload)
+                        .filter_map(|payload| DeviceList::try_from(payload).ok())
+                        .last();
+                    if devices.is_some() {
+                        omemo::handle_device_list(from, agent, devices).await;
+                    }
+                }
                 ref node if node == ns::BOOKMARKS2 => {
                     // TODO: Check that our bare JID is the sender.
                     assert_eq!(items.len(), 1);
@@ -78,7 +89,7 @@ pub(crate) async fn handle_event(from: &Jid, elem: Element, agent: &mut Agent) -
     events
 }
 
-pub(crate) fn handle_iq_result(from: &Jid, elem: Element) -> impl IntoIterator<Item = Event> {
+pub(crate) async fn handle_iq_result(from: &Jid, elem: Element, agent: &mut Agent) -> Vec<Event> {
     let mut events = Vec::new();
     let pubsub = PubSub::try_from(elem).unwrap();
     trace!("PubSub: {:#?}", pubsub);
@@ -89,6 +100,18 @@ pub(crate) fn handle_iq_result(from: &Jid, elem: Element) -> impl IntoIterator<I
                 let new_events = avatar::handle_data_pubsub_iq(&from, &items);
                 events.extend(new_events);
             }
+            ref node if node == ns::OMEMO_DEVICES => {
+                let devices = items
+                    .items
+                    .into_iter()
+                    .filter_map(|item| item.0.payload)
+                    .filter_map(|payload| DeviceList::try_from(payload).ok())
+                    .last();
+                omemo::handle_device_list(from, agent, devices).await;
+            }
+            ref node if node == ns::OMEMO_BUNDLES => {
+                omemo::handle_bundles_iq(from, agent, &items);
+            }
             ref node if node == ns::BOOKMARKS2 => {
                 events.push(Event::LeaveAllRooms);
                 for item in items.items {
@@ -0,0 +1,188 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::Agent;
+use crate::pubsub::avatar::{avatar_directory, save_avatar};
+use crate::Event;
+use sha1::{Digest, Sha1};
+use std::path::Path;
+use xmpp_parsers::{
+    hashes::{Algo, Hash},
+    iq::Iq,
+    vcard::VCard,
+    vcard_update::VCardUpdate,
+    BareJid, Jid,
+};
+
+/// The prefix of the id of the iqs requesting a vCard for its avatar, followed by the hash of
+/// this avatar.
+const ID_PREFIX: &str = "vcard-avatar:";
+
+/// Whether this is a hexadecimal SHA-1, which is then safe to use as a filename.
+fn is_valid_hash(hash: &str) -> bool {
+    hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Handles the avatar advertised in the presence of a contact, or of a room occupant in which
+/// case `from` is its full JID, downloading their vCard unless a PEP avatar has been received
+/// from them.
+pub(crate) async fn handle_vcard_update(
+    from: &Jid,
+    agent: &mut Agent,
+    update: VCardUpdate,
+) -> Vec<Event> {
+    let mut events = Vec::new();
+    let hash = match update.hash() {
+        Some(hash) if is_valid_hash(hash) => hash.to_ascii_lowercase(),
+        _ => return events,
+    };
+    if agent.pep_avatars.contains(&BareJid::from(from.clone())) {
+        return events;
+    }
+    let filename = format!("{}/{}", avatar_directory(from), hash);
+    // TODO: Also check the hash.
+    if Path::new(&filename).exists() {
+        events.push(Event::AvatarRetrieved(from.clone(), filename));
+    } else {
+        let iq = download_vcard(from, &hash);
+        agent.pending_vcards.insert((from.clone(), hash));
+        let _ = agent.client.send_stanza(iq.into()).await;
+    }
+    events
+}
+
+fn download_vcard(from: &Jid, hash: &str) -> Iq {
+    Iq::from_get(format!("{}{}", ID_PREFIX, hash), VCard::new()).with_to(from.clone())
+}
+
+/// Saves the photo of a vCard received in answer to `download_vcard()`, if it matches the hash
+/// which got advertised, ignoring any other vCard.
+pub(crate) fn handle_vcard_iq(
+    from: &Jid,
+    id: &str,
+    vcard: VCard,
+    agent: &mut Agent,
+) -> Option<Event> {
+    let hash = id.strip_prefix(ID_PREFIX)?;
+    // An unsolicited vCard could otherwise be saved as the avatar of anyone.
+    if !agent
+        .pending_vcards
+        .remove(&(from.clone(), hash.to_owned()))
+    {
+        return None;
+    }
+    let data = vcard.photo?.binval?.data;
+    if Hash::new(Algo::Sha_1, Sha1::digest(&data).to_vec()).to_hex() != hash {
+        warn!("The avatar of {} doesn’t match its hash {}", from, hash);
+        return None;
+    }
+    match save_avatar(from, hash.to_owned(), &data) {
+        Ok(filename) => Some(Event::AvatarRetrieved(from.clone(), filename)),
+        Err(err) => {
+            warn!("Couldn’t save the avatar of {}: {}", from, err);
+            None
+        }
+    }
+}
+
+/// Forgets a vCard request which failed.
+pub(crate) fn handle_vcard_error(from: &Jid, id: &str, agent: &mut Agent) {
+    if let Some(hash) = id.strip_prefix(ID_PREFIX) {
+        agent
+            .pending_vcards
+            .remove(&(from.clone(), hash.to_owned()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClientBuilder;
+    use tokio_xmpp::AsyncClient as TokioXmppClient;
+    use xmpp_parsers::{iq::IqType, ns, vcard::Photo};
+
+    const HASH: &str = "01b87fcd030b72895ff8e88db57ec525450f000d";
+
+    /// The SHA-1 of the photo used in these tests.
+    const PNG_HASH: &str = "4effda12c2611e2e4feb6f0d342feb685ccd825b";
+
+    #[test]
+    fn test_valid_hash() {
+        assert!(is_valid_hash(HASH));
+        assert!(!is_valid_hash(""));
+        assert!(!is_valid_hash("../../../../../../../../../../etc/passwd"));
+        assert!(!is_valid_hash(&HASH[1..]));
+    }
+
+    #[test]
+    fn test_download_vcard() {
+        let from = Jid::Bare(BareJid::new("juliet", "capulet.lit"));
+        let iq = download_vcard(&from, HASH);
+        assert_eq!(iq.id, format!("vcard-avatar:{}", HASH));
+        assert_eq!(iq.to, Some(from));
+        match iq.payload {
+            IqType::Get(payload) => assert!(payload.is("vCard", ns::VCARD)),
+            _ => panic!(),
+        }
+    }
+
+    fn agent() -> Agent {
+        let client = TokioXmppClient::new("foo@bar", "meh").unwrap();
+        ClientBuilder::new("foo@bar", "meh")
+            .build_impl(client)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ignored_vcards() {
+        let mut agent = agent();
+        let from = Jid::Bare(BareJid::new("juliet", "capulet.lit"));
+        let vcard = VCard {
+            photo: Some(Photo::new("image/png", b"\x89PNG".to_vec())),
+            ..VCard::new()
+        };
+        // Not requested by us.
+        let id = format!("vcard-avatar:{}", PNG_HASH);
+        assert!(handle_vcard_iq(&from, "vcard", vcard.clone(), &mut agent).is_none());
+        assert!(handle_vcard_iq(&from, &id, vcard.clone(), &mut agent).is_none());
+
+        // Requested from someone else.
+        agent
+            .pending_vcards
+            .insert((from.clone(), String::from(PNG_HASH)));
+        let romeo = Jid::Bare(BareJid::new("romeo", "montague.lit"));
+        assert!(handle_vcard_iq(&romeo, &id, vcard, &mut agent).is_none());
+
+        // Without a photo, which also ends the request.
+        assert!(handle_vcard_iq(&from, &id, VCard::new(), &mut agent).is_none());
+        assert!(agent.pending_vcards.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_hash() {
+        let mut agent = agent();
+        let from = Jid::Bare(BareJid::new("juliet", "capulet.lit"));
+        let vcard = VCard {
+            photo: Some(Photo::new("image/png", b"\x89PNG".to_vec())),
+            ..VCard::new()
+        };
+        agent
+            .pending_vcards
+            .insert((from.clone(), String::from(HASH)));
+        let id = format!("vcard-avatar:{}", HASH);
+        assert!(handle_vcard_iq(&from, &id, vcard, &mut agent).is_none());
+        assert!(agent.pending_vcards.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_vcard_error() {
+        let mut agent = agent();
+        let from = Jid::Bare(BareJid::new("juliet", "capulet.lit"));
+        agent
+            .pending_vcards
+            .insert((from.clone(), String::from(HASH)));
+        handle_vcard_error(&from, &format!("vcard-avatar:{}", HASH), &mut agent);
+        assert!(agent.pending_vcards.is_empty());
+    }
+}